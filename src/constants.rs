@@ -1,3 +1,4 @@
+use std::collections::HashSet;
 use std::error::Error;
 
 use anyhow::anyhow;
@@ -18,11 +19,14 @@ pub const JIM_ID_AND_TAG: &str = "Safety Jim#9254 (313749262687141888)";
 pub const JIM_ID: UserId = UserId::new(313749262687141888u64);
 pub const DEFAULT_WORD_FILTER_URL: &str =
     "https://raw.githubusercontent.com/Samoxive/Google-profanity-words/master/list.txt";
+pub const TOP_LEVEL_DOMAINS_URL: &str = "https://data.iana.org/TLD/tlds-alpha-by-domain.txt";
 pub const DISCORD_API_BASE: &str = "https://discord.com";
 pub const DISCORD_CDN_BASE: &str = "https://cdn.discordapp.com";
 
 pub static START_EPOCH: OnceCell<u64> = OnceCell::new();
 pub static DEFAULT_BLOCKED_WORDS: OnceCell<Vec<SmolStr>> = OnceCell::new();
+// lowercase, internationalized ones are in their punycode form
+pub static TOP_LEVEL_DOMAINS: OnceCell<HashSet<SmolStr>> = OnceCell::new();
 pub static PROGRAMMING_LANGUAGES: [(&str, &str); 25] = [
     ("None", "none"),
     ("Bash", "sh"),
@@ -69,5 +73,18 @@ pub async fn initialize_statics() -> Result<(), Box<dyn Error>> {
         )
         .map_err(|_| anyhow!("failed to set default blocked words!"))?;
 
+    let tld_body = reqwest::get(TOP_LEVEL_DOMAINS_URL).await?.text().await?;
+
+    TOP_LEVEL_DOMAINS
+        .set(
+            tld_body
+                .split('\n')
+                .map(|tld| tld.trim())
+                .filter(|tld| !tld.is_empty() && !tld.starts_with('#'))
+                .map(|tld| SmolStr::new(tld.to_lowercase()))
+                .collect(),
+        )
+        .map_err(|_| anyhow!("failed to set top level domains!"))?;
+
     Ok(())
 }
@@ -0,0 +1,112 @@
+use std::sync::Arc;
+
+use sqlx::{Error, PgPool, Row};
+
+pub const APPEAL_STATUS_PENDING: i32 = 0;
+pub const APPEAL_STATUS_ACCEPTED: i32 = 1;
+pub const APPEAL_STATUS_DENIED: i32 = 2;
+
+#[derive(sqlx::FromRow)]
+pub struct Appeal {
+    pub id: i32,
+    pub guild_id: i64,
+    pub user_id: i64,
+    pub case_number: i32,
+    pub infraction_type: i32,
+    pub message: String,
+    pub appeal_time: i64,
+    pub status: i32,
+    pub reviewer_user_id: i64,
+    pub review_time: i64,
+    pub review_message_id: i64,
+}
+
+pub struct AppealsRepository(pub Arc<PgPool>);
+
+impl AppealsRepository {
+    pub async fn initialize(&self) -> Result<(), Error> {
+        sqlx::query(include_str!("sql/appeals/create_table.sql"))
+            .execute(&*self.0)
+            .await?;
+        sqlx::query(include_str!(
+            "sql/appeals/create_guild_id_case_number_index.sql"
+        ))
+        .execute(&*self.0)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn fetch_appeal(&self, id: i32) -> Result<Option<Appeal>, Error> {
+        sqlx::query_as::<_, Appeal>(include_str!("sql/appeals/select_appeal.sql"))
+            .bind(id)
+            .fetch_optional(&*self.0)
+            .await
+    }
+
+    pub async fn fetch_guild_appeal_with_case_number(
+        &self,
+        guild_id: i64,
+        case_number: i32,
+    ) -> Result<Option<Appeal>, Error> {
+        sqlx::query_as::<_, Appeal>(include_str!(
+            "sql/appeals/select_guild_appeal_with_case_number.sql"
+        ))
+        .bind(guild_id)
+        .bind(case_number)
+        .fetch_optional(&*self.0)
+        .await
+    }
+
+    pub async fn fetch_guild_appeals(
+        &self,
+        guild_id: i64,
+        page: u32,
+    ) -> Result<Vec<Appeal>, Error> {
+        sqlx::query_as::<_, Appeal>(include_str!(
+            "sql/appeals/select_guild_appeals_paginated.sql"
+        ))
+        .bind(guild_id)
+        .bind(((page - 1) * 10) as i32)
+        .fetch_all(&*self.0)
+        .await
+    }
+
+    pub async fn fetch_guild_appeal_count(&self, guild_id: i64) -> Result<i64, Error> {
+        Ok(
+            sqlx::query(include_str!("sql/appeals/count_guild_appeals.sql"))
+                .bind(guild_id)
+                .fetch_one(&*self.0)
+                .await?
+                .get(0),
+        )
+    }
+
+    pub async fn insert_appeal(&self, appeal: Appeal) -> Result<Appeal, Error> {
+        sqlx::query_as::<_, Appeal>(include_str!("sql/appeals/insert_entity.sql"))
+            .bind(appeal.guild_id)
+            .bind(appeal.user_id)
+            .bind(appeal.case_number)
+            .bind(appeal.infraction_type)
+            .bind(appeal.message)
+            .bind(appeal.appeal_time)
+            .bind(appeal.status)
+            .bind(appeal.reviewer_user_id)
+            .bind(appeal.review_time)
+            .bind(appeal.review_message_id)
+            .fetch_one(&*self.0)
+            .await
+    }
+
+    pub async fn update_appeal(&self, appeal: &Appeal) -> Result<(), Error> {
+        sqlx::query(include_str!("sql/appeals/update_entity.sql"))
+            .bind(appeal.id)
+            .bind(appeal.status)
+            .bind(appeal.reviewer_user_id)
+            .bind(appeal.review_time)
+            .bind(appeal.review_message_id)
+            .execute(&*self.0)
+            .await?;
+
+        Ok(())
+    }
+}
@@ -4,7 +4,7 @@ use sqlx::{Error, PgPool, Row};
 
 use crate::util::now;
 
-#[derive(sqlx::FromRow, Debug)]
+#[derive(sqlx::FromRow, Debug, Clone)]
 pub struct Ban {
     pub id: i32,
     pub user_id: i64,
@@ -15,6 +15,8 @@ pub struct Ban {
     pub reason: String,
     pub expires: bool,
     pub unbanned: bool,
+    pub case_number: i32,
+    pub mod_log_message_id: i64,
 }
 
 pub struct BansRepository(pub Arc<PgPool>);
@@ -24,15 +26,29 @@ impl BansRepository {
         sqlx::query(include_str!("sql/bans/create_table.sql"))
             .execute(&*self.0)
             .await?;
+        sqlx::query(include_str!("sql/bans/add_case_number_column.sql"))
+            .execute(&*self.0)
+            .await?;
+        sqlx::query(include_str!("sql/bans/add_mod_log_message_id_column.sql"))
+            .execute(&*self.0)
+            .await?;
         sqlx::query(include_str!("sql/bans/create_ban_time_index.sql"))
             .execute(&*self.0)
             .await?;
+        sqlx::query(include_str!("sql/bans/create_case_number_index.sql"))
+            .execute(&*self.0)
+            .await?;
         Ok(())
     }
 
-    pub async fn fetch_ban(&self, id: i32) -> Result<Option<Ban>, Error> {
-        sqlx::query_as::<_, Ban>(include_str!("sql/bans/select_ban_with_id.sql"))
-            .bind(id)
+    pub async fn fetch_guild_ban_with_case_number(
+        &self,
+        guild_id: i64,
+        case_number: i32,
+    ) -> Result<Option<Ban>, Error> {
+        sqlx::query_as::<_, Ban>(include_str!("sql/bans/select_ban_with_case_number.sql"))
+            .bind(guild_id)
+            .bind(case_number)
             .fetch_optional(&*self.0)
             .await
     }
@@ -45,6 +61,18 @@ impl BansRepository {
             .await
     }
 
+    pub async fn fetch_guild_user_bans(
+        &self,
+        guild_id: i64,
+        user_id: i64,
+    ) -> Result<Vec<Ban>, Error> {
+        sqlx::query_as::<_, Ban>(include_str!("sql/bans/select_guild_user_bans.sql"))
+            .bind(guild_id)
+            .bind(user_id)
+            .fetch_all(&*self.0)
+            .await
+    }
+
     pub async fn fetch_guild_ban_count(&self, guild_id: i64) -> Result<i64, Error> {
         Ok(sqlx::query(include_str!("sql/bans/count_guild_bans.sql"))
             .bind(guild_id)
@@ -77,6 +105,7 @@ impl BansRepository {
             .bind(ban.reason)
             .bind(ban.expires)
             .bind(ban.unbanned)
+            .bind(ban.case_number)
             .fetch_one(&*self.0)
             .await
     }
@@ -98,6 +127,20 @@ impl BansRepository {
         Ok(())
     }
 
+    pub async fn update_mod_log_message_id(
+        &self,
+        id: i32,
+        mod_log_message_id: i64,
+    ) -> Result<(), Error> {
+        sqlx::query(include_str!("sql/bans/update_mod_log_message_id.sql"))
+            .bind(id)
+            .bind(mod_log_message_id)
+            .execute(&*self.0)
+            .await?;
+
+        Ok(())
+    }
+
     pub async fn invalidate_previous_user_bans(
         &self,
         guild_id: i64,
@@ -0,0 +1,49 @@
+use std::sync::Arc;
+
+use sqlx::{Error, PgPool, Row};
+
+pub struct CaseNumbersRepository(pub Arc<PgPool>);
+
+impl CaseNumbersRepository {
+    pub async fn initialize(&self) -> Result<(), Error> {
+        sqlx::query(include_str!("sql/case_numbers/create_table.sql"))
+            .execute(&*self.0)
+            .await?;
+        sqlx::query(include_str!(
+            "sql/case_numbers/assign_missing_case_numbers.sql"
+        ))
+        .execute(&*self.0)
+        .await?;
+        for query in [
+            include_str!("sql/case_numbers/set_bans_case_number_not_null.sql"),
+            include_str!("sql/case_numbers/set_hardbans_case_number_not_null.sql"),
+            include_str!("sql/case_numbers/set_kicks_case_number_not_null.sql"),
+            include_str!("sql/case_numbers/set_mutes_case_number_not_null.sql"),
+            include_str!("sql/case_numbers/set_softbans_case_number_not_null.sql"),
+            include_str!("sql/case_numbers/set_warns_case_number_not_null.sql"),
+        ] {
+            sqlx::query(query).execute(&*self.0).await?;
+        }
+        Ok(())
+    }
+
+    pub async fn next_case_number(&self, guild_id: i64) -> Result<i32, Error> {
+        Ok(sqlx::query(include_str!(
+            "sql/case_numbers/increment_guild_case_number.sql"
+        ))
+        .bind(guild_id)
+        .fetch_one(&*self.0)
+        .await?
+        .get(0))
+    }
+
+    pub async fn release_case_number(&self, guild_id: i64, case_number: i32) -> Result<(), Error> {
+        sqlx::query(include_str!("sql/case_numbers/release_case_number.sql"))
+            .bind(guild_id)
+            .bind(case_number)
+            .execute(&*self.0)
+            .await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,137 @@
+use std::sync::Arc;
+
+use sqlx::{Error, PgConnection, PgPool, Row};
+
+use crate::database::settings::{
+    ACTION_KICK, ACTION_MUTE, ACTION_SOFTBAN, ACTION_WARN, DURATION_TYPE_DAYS,
+};
+
+#[derive(sqlx::FromRow)]
+pub struct EscalationStep {
+    pub id: i32,
+    pub guild_id: i64,
+    pub infraction_type: i32,
+    pub threshold: i32,
+    pub action: i32,
+    pub action_duration: i32,
+    pub action_duration_type: i32,
+    pub lookback_duration: i32,
+    pub lookback_duration_type: i32,
+}
+
+pub struct EscalationStepsRepository(pub Arc<PgPool>);
+
+impl EscalationStepsRepository {
+    pub async fn initialize(&self) -> Result<(), Error> {
+        sqlx::query(include_str!("sql/escalation_steps/create_table.sql"))
+            .execute(&*self.0)
+            .await?;
+        sqlx::query(include_str!(
+            "sql/escalation_steps/create_guild_id_infraction_type_threshold_index.sql"
+        ))
+        .execute(&*self.0)
+        .await?;
+
+        // settings used to hold a single threshold per infraction type, move those into the ladder
+        // once, the old columns are kept for a release so rolling back doesn't lose them and only
+        // stop being required, which also marks them as migrated. a migrated threshold becomes the
+        // only and thus top step of its ladder, which keeps firing on every infraction at or past
+        // its threshold like the legacy threshold did, steps added below it only fire on reaching
+        // their own threshold
+        let has_unmigrated_legacy_columns: bool = sqlx::query(include_str!(
+            "sql/escalation_steps/has_unmigrated_legacy_threshold_columns.sql"
+        ))
+        .fetch_one(&*self.0)
+        .await?
+        .get(0);
+
+        if has_unmigrated_legacy_columns {
+            let mut transaction = self.0.begin().await?;
+            sqlx::query(include_str!(
+                "sql/escalation_steps/migrate_legacy_thresholds.sql"
+            ))
+            .bind(ACTION_SOFTBAN)
+            .bind(ACTION_KICK)
+            .bind(ACTION_MUTE)
+            .bind(ACTION_WARN)
+            .bind(DURATION_TYPE_DAYS)
+            .execute(&mut *transaction)
+            .await?;
+            sqlx::query(include_str!(
+                "sql/escalation_steps/relax_legacy_threshold_columns.sql"
+            ))
+            .execute(&mut *transaction)
+            .await?;
+            transaction.commit().await?;
+        }
+
+        Ok(())
+    }
+
+    pub async fn fetch_guild_escalation_steps(
+        &self,
+        guild_id: i64,
+    ) -> Result<Vec<EscalationStep>, Error> {
+        sqlx::query_as::<_, EscalationStep>(include_str!(
+            "sql/escalation_steps/select_guild_escalation_steps.sql"
+        ))
+        .bind(guild_id)
+        .fetch_all(&*self.0)
+        .await
+    }
+
+    pub async fn fetch_guild_escalation_steps_for_infraction_type(
+        &self,
+        guild_id: i64,
+        infraction_type: i32,
+    ) -> Result<Vec<EscalationStep>, Error> {
+        sqlx::query_as::<_, EscalationStep>(include_str!(
+            "sql/escalation_steps/select_guild_escalation_steps_for_infraction_type.sql"
+        ))
+        .bind(guild_id)
+        .bind(infraction_type)
+        .fetch_all(&*self.0)
+        .await
+    }
+
+    pub async fn delete_guild_escalation_steps(&self, guild_id: i64) -> Result<(), Error> {
+        sqlx::query(include_str!(
+            "sql/escalation_steps/delete_guild_escalation_steps.sql"
+        ))
+        .bind(guild_id)
+        .execute(&*self.0)
+        .await?;
+
+        Ok(())
+    }
+}
+
+// runs inside the caller's transaction, settings and their ladder are saved together
+pub async fn replace_guild_escalation_steps(
+    connection: &mut PgConnection,
+    guild_id: i64,
+    steps: Vec<EscalationStep>,
+) -> Result<(), Error> {
+    sqlx::query(include_str!(
+        "sql/escalation_steps/delete_guild_escalation_steps.sql"
+    ))
+    .bind(guild_id)
+    .execute(&mut *connection)
+    .await?;
+
+    for step in steps {
+        sqlx::query(include_str!("sql/escalation_steps/insert_entity.sql"))
+            .bind(guild_id)
+            .bind(step.infraction_type)
+            .bind(step.threshold)
+            .bind(step.action)
+            .bind(step.action_duration)
+            .bind(step.action_duration_type)
+            .bind(step.lookback_duration)
+            .bind(step.lookback_duration_type)
+            .execute(&mut *connection)
+            .await?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,60 @@
+use std::sync::Arc;
+
+use sqlx::{Error, PgPool};
+
+#[derive(sqlx::FromRow, Debug)]
+pub struct Evidence {
+    pub id: i32,
+    pub guild_id: i64,
+    pub case_number: i32,
+    pub channel_id: i64,
+    pub message_id: i64,
+    pub user_id: i64,
+    pub content: String,
+    pub attachments: Vec<String>,
+    pub message_time: i64,
+    pub record_time: i64,
+}
+
+pub struct EvidenceRepository(pub Arc<PgPool>);
+
+impl EvidenceRepository {
+    pub async fn initialize(&self) -> Result<(), Error> {
+        sqlx::query(include_str!("sql/evidence/create_table.sql"))
+            .execute(&*self.0)
+            .await?;
+        sqlx::query(include_str!(
+            "sql/evidence/create_guild_id_case_number_index.sql"
+        ))
+        .execute(&*self.0)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn fetch_guild_cases_evidence(
+        &self,
+        guild_id: i64,
+        case_numbers: &[i32],
+    ) -> Result<Vec<Evidence>, Error> {
+        sqlx::query_as::<_, Evidence>(include_str!("sql/evidence/select_guild_cases_evidence.sql"))
+            .bind(guild_id)
+            .bind(case_numbers)
+            .fetch_all(&*self.0)
+            .await
+    }
+
+    pub async fn insert_evidence(&self, evidence: Evidence) -> Result<Evidence, Error> {
+        sqlx::query_as::<_, Evidence>(include_str!("sql/evidence/insert_entity.sql"))
+            .bind(evidence.guild_id)
+            .bind(evidence.case_number)
+            .bind(evidence.channel_id)
+            .bind(evidence.message_id)
+            .bind(evidence.user_id)
+            .bind(evidence.content)
+            .bind(evidence.attachments)
+            .bind(evidence.message_time)
+            .bind(evidence.record_time)
+            .fetch_one(&*self.0)
+            .await
+    }
+}
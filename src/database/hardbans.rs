@@ -2,7 +2,7 @@ use std::sync::Arc;
 
 use sqlx::{Error, PgPool, Row};
 
-#[derive(sqlx::FromRow)]
+#[derive(sqlx::FromRow, Clone)]
 pub struct Hardban {
     pub id: i32,
     pub user_id: i64,
@@ -10,6 +10,8 @@ pub struct Hardban {
     pub guild_id: i64,
     pub hardban_time: i64,
     pub reason: String,
+    pub case_number: i32,
+    pub mod_log_message_id: i64,
 }
 
 pub struct HardbansRepository(pub Arc<PgPool>);
@@ -19,17 +21,35 @@ impl HardbansRepository {
         sqlx::query(include_str!("sql/hardbans/create_table.sql"))
             .execute(&*self.0)
             .await?;
+        sqlx::query(include_str!("sql/hardbans/add_case_number_column.sql"))
+            .execute(&*self.0)
+            .await?;
+        sqlx::query(include_str!(
+            "sql/hardbans/add_mod_log_message_id_column.sql"
+        ))
+        .execute(&*self.0)
+        .await?;
         sqlx::query(include_str!("sql/hardbans/create_hardban_time_index.sql"))
             .execute(&*self.0)
             .await?;
+        sqlx::query(include_str!("sql/hardbans/create_case_number_index.sql"))
+            .execute(&*self.0)
+            .await?;
         Ok(())
     }
 
-    pub async fn fetch_hardban(&self, id: i32) -> Result<Option<Hardban>, Error> {
-        sqlx::query_as::<_, Hardban>(include_str!("sql/hardbans/select_hardban_with_id.sql"))
-            .bind(id)
-            .fetch_optional(&*self.0)
-            .await
+    pub async fn fetch_guild_hardban_with_case_number(
+        &self,
+        guild_id: i64,
+        case_number: i32,
+    ) -> Result<Option<Hardban>, Error> {
+        sqlx::query_as::<_, Hardban>(include_str!(
+            "sql/hardbans/select_hardban_with_case_number.sql"
+        ))
+        .bind(guild_id)
+        .bind(case_number)
+        .fetch_optional(&*self.0)
+        .await
     }
 
     pub async fn fetch_guild_hardbans(
@@ -46,6 +66,18 @@ impl HardbansRepository {
         .await
     }
 
+    pub async fn fetch_guild_user_hardbans(
+        &self,
+        guild_id: i64,
+        user_id: i64,
+    ) -> Result<Vec<Hardban>, Error> {
+        sqlx::query_as::<_, Hardban>(include_str!("sql/hardbans/select_guild_user_hardbans.sql"))
+            .bind(guild_id)
+            .bind(user_id)
+            .fetch_all(&*self.0)
+            .await
+    }
+
     pub async fn fetch_guild_hardban_count(&self, guild_id: i64) -> Result<i64, Error> {
         Ok(
             sqlx::query(include_str!("sql/hardbans/count_guild_hardbans.sql"))
@@ -63,6 +95,7 @@ impl HardbansRepository {
             .bind(hardban.guild_id)
             .bind(hardban.hardban_time)
             .bind(hardban.reason)
+            .bind(hardban.case_number)
             .fetch_one(&*self.0)
             .await
     }
@@ -80,4 +113,18 @@ impl HardbansRepository {
 
         Ok(())
     }
+
+    pub async fn update_mod_log_message_id(
+        &self,
+        id: i32,
+        mod_log_message_id: i64,
+    ) -> Result<(), Error> {
+        sqlx::query(include_str!("sql/hardbans/update_mod_log_message_id.sql"))
+            .bind(id)
+            .bind(mod_log_message_id)
+            .execute(&*self.0)
+            .await?;
+
+        Ok(())
+    }
 }
@@ -2,7 +2,7 @@ use std::sync::Arc;
 
 use sqlx::{Error, PgPool, Row};
 
-#[derive(sqlx::FromRow)]
+#[derive(sqlx::FromRow, Clone)]
 pub struct Kick {
     pub id: i32,
     pub user_id: i64,
@@ -11,6 +11,8 @@ pub struct Kick {
     pub kick_time: i64,
     pub reason: String,
     pub pardoned: bool,
+    pub case_number: i32,
+    pub mod_log_message_id: i64,
 }
 
 pub struct KicksRepository(pub Arc<PgPool>);
@@ -20,15 +22,29 @@ impl KicksRepository {
         sqlx::query(include_str!("sql/kicks/create_table.sql"))
             .execute(&*self.0)
             .await?;
+        sqlx::query(include_str!("sql/kicks/add_case_number_column.sql"))
+            .execute(&*self.0)
+            .await?;
+        sqlx::query(include_str!("sql/kicks/add_mod_log_message_id_column.sql"))
+            .execute(&*self.0)
+            .await?;
         sqlx::query(include_str!("sql/kicks/create_kick_time_index.sql"))
             .execute(&*self.0)
             .await?;
+        sqlx::query(include_str!("sql/kicks/create_case_number_index.sql"))
+            .execute(&*self.0)
+            .await?;
         Ok(())
     }
 
-    pub async fn fetch_kick(&self, id: i32) -> Result<Option<Kick>, Error> {
-        sqlx::query_as::<_, Kick>(include_str!("sql/kicks/select_kick_with_id.sql"))
-            .bind(id)
+    pub async fn fetch_guild_kick_with_case_number(
+        &self,
+        guild_id: i64,
+        case_number: i32,
+    ) -> Result<Option<Kick>, Error> {
+        sqlx::query_as::<_, Kick>(include_str!("sql/kicks/select_kick_with_case_number.sql"))
+            .bind(guild_id)
+            .bind(case_number)
             .fetch_optional(&*self.0)
             .await
     }
@@ -41,6 +57,18 @@ impl KicksRepository {
             .await
     }
 
+    pub async fn fetch_guild_user_kicks(
+        &self,
+        guild_id: i64,
+        user_id: i64,
+    ) -> Result<Vec<Kick>, Error> {
+        sqlx::query_as::<_, Kick>(include_str!("sql/kicks/select_guild_user_kicks.sql"))
+            .bind(guild_id)
+            .bind(user_id)
+            .fetch_all(&*self.0)
+            .await
+    }
+
     pub async fn fetch_guild_kick_count(&self, guild_id: i64) -> Result<i64, Error> {
         Ok(sqlx::query(include_str!("sql/kicks/count_guild_kicks.sql"))
             .bind(guild_id)
@@ -53,12 +81,14 @@ impl KicksRepository {
         &self,
         guild_id: i64,
         user_id: i64,
+        since: i64,
     ) -> Result<i64, Error> {
         Ok(sqlx::query(include_str!(
             "sql/kicks/count_actionable_guild_user_kicks.sql"
         ))
         .bind(guild_id)
         .bind(user_id)
+        .bind(since)
         .fetch_one(&*self.0)
         .await?
         .get(0))
@@ -72,6 +102,7 @@ impl KicksRepository {
             .bind(kick.kick_time)
             .bind(kick.reason)
             .bind(kick.pardoned)
+            .bind(kick.case_number)
             .fetch_one(&*self.0)
             .await
     }
@@ -90,4 +121,18 @@ impl KicksRepository {
 
         Ok(())
     }
+
+    pub async fn update_mod_log_message_id(
+        &self,
+        id: i32,
+        mod_log_message_id: i64,
+    ) -> Result<(), Error> {
+        sqlx::query(include_str!("sql/kicks/update_mod_log_message_id.sql"))
+            .bind(id)
+            .bind(mod_log_message_id)
+            .execute(&*self.0)
+            .await?;
+
+        Ok(())
+    }
 }
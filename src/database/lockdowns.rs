@@ -0,0 +1,114 @@
+use std::sync::Arc;
+
+use sqlx::{Error, PgPool};
+
+use crate::util::now;
+
+// one row per locked channel, server wide lockdowns lock every channel of the guild
+#[derive(sqlx::FromRow, Debug)]
+pub struct Lockdown {
+    pub id: i32,
+    pub guild_id: i64,
+    pub channel_id: i64,
+    pub moderator_user_id: i64,
+    pub lock_time: i64,
+    pub expire_time: i64,
+    pub reason: String,
+    pub expires: bool,
+    pub unlocked: bool,
+    // @everyone overwrite of the channel before it was locked, restored on unlock
+    pub has_previous_overwrite: bool,
+    pub previous_allow: i64,
+    pub previous_deny: i64,
+    // the expired lockdown couldn't be lifted for lack of permissions, it's no longer retried but
+    // stays active so it can still be unlocked by hand
+    pub unlock_failed: bool,
+}
+
+pub struct LockdownsRepository(pub Arc<PgPool>);
+
+impl LockdownsRepository {
+    pub async fn initialize(&self) -> Result<(), Error> {
+        sqlx::query(include_str!("sql/lockdowns/create_table.sql"))
+            .execute(&*self.0)
+            .await?;
+        sqlx::query(include_str!("sql/lockdowns/add_unlock_failed_column.sql"))
+            .execute(&*self.0)
+            .await?;
+        sqlx::query(include_str!(
+            "sql/lockdowns/create_active_guild_id_channel_id_index.sql"
+        ))
+        .execute(&*self.0)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn fetch_active_guild_lockdowns(
+        &self,
+        guild_id: i64,
+    ) -> Result<Vec<Lockdown>, Error> {
+        sqlx::query_as::<_, Lockdown>(include_str!(
+            "sql/lockdowns/select_active_guild_lockdowns.sql"
+        ))
+        .bind(guild_id)
+        .fetch_all(&*self.0)
+        .await
+    }
+
+    pub async fn fetch_active_channel_lockdown(
+        &self,
+        guild_id: i64,
+        channel_id: i64,
+    ) -> Result<Option<Lockdown>, Error> {
+        sqlx::query_as::<_, Lockdown>(include_str!(
+            "sql/lockdowns/select_active_channel_lockdown.sql"
+        ))
+        .bind(guild_id)
+        .bind(channel_id)
+        .fetch_optional(&*self.0)
+        .await
+    }
+
+    pub async fn fetch_expired_lockdowns(&self) -> Result<Vec<Lockdown>, Error> {
+        sqlx::query_as::<_, Lockdown>(include_str!("sql/lockdowns/select_expired_lockdowns.sql"))
+            .bind(now() as i64)
+            .fetch_all(&*self.0)
+            .await
+    }
+
+    // returns None if the channel is already locked down
+    pub async fn insert_lockdown(&self, lockdown: Lockdown) -> Result<Option<Lockdown>, Error> {
+        sqlx::query_as::<_, Lockdown>(include_str!("sql/lockdowns/insert_entity.sql"))
+            .bind(lockdown.guild_id)
+            .bind(lockdown.channel_id)
+            .bind(lockdown.moderator_user_id)
+            .bind(lockdown.lock_time)
+            .bind(lockdown.expire_time)
+            .bind(lockdown.reason)
+            .bind(lockdown.expires)
+            .bind(lockdown.unlocked)
+            .bind(lockdown.has_previous_overwrite)
+            .bind(lockdown.previous_allow)
+            .bind(lockdown.previous_deny)
+            .fetch_optional(&*self.0)
+            .await
+    }
+
+    pub async fn mark_unlock_failed(&self, id: i32) -> Result<(), Error> {
+        sqlx::query(include_str!("sql/lockdowns/mark_unlock_failed.sql"))
+            .bind(id)
+            .execute(&*self.0)
+            .await?;
+
+        Ok(())
+    }
+
+    pub async fn invalidate_lockdown(&self, id: i32) -> Result<(), Error> {
+        sqlx::query(include_str!("sql/lockdowns/invalidate_entity.sql"))
+            .bind(id)
+            .execute(&*self.0)
+            .await?;
+
+        Ok(())
+    }
+}
@@ -3,17 +3,25 @@ use sqlx::{Pool, Postgres};
 
 use crate::Config;
 
+pub mod appeals;
 pub mod bans;
+pub mod case_numbers;
+pub mod escalation_steps;
+pub mod evidence;
 pub mod hardbans;
 pub mod iam_roles;
 pub mod invalid_uuids;
 pub mod joins;
 pub mod kicks;
+pub mod lockdowns;
 pub mod mutes;
+pub mod notes;
 pub mod reminders;
+pub mod reports;
 pub mod settings;
 pub mod softbans;
 pub mod tags;
+pub mod temp_roles;
 pub mod user_secrets;
 pub mod warns;
 
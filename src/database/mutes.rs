@@ -4,7 +4,7 @@ use sqlx::{Error, PgPool, Row};
 
 use crate::util::now;
 
-#[derive(sqlx::FromRow, Debug)]
+#[derive(sqlx::FromRow, Debug, Clone)]
 pub struct Mute {
     pub id: i32,
     pub user_id: i64,
@@ -16,6 +16,13 @@ pub struct Mute {
     pub expires: bool,
     pub unmuted: bool,
     pub pardoned: bool,
+    pub case_number: i32,
+    // end of the discord timeout currently applied for this mute, 0 for role based mutes and
+    // while a timed out member is away from the guild
+    pub timeout_expire_time: i64,
+    pub mod_log_message_id: i64,
+    // the backend this mute was applied with, guilds can switch backends while mutes are active
+    pub mute_backend: i32,
 }
 
 pub struct MutesRepository(pub Arc<PgPool>);
@@ -25,15 +32,35 @@ impl MutesRepository {
         sqlx::query(include_str!("sql/mutes/create_table.sql"))
             .execute(&*self.0)
             .await?;
+        sqlx::query(include_str!("sql/mutes/add_case_number_column.sql"))
+            .execute(&*self.0)
+            .await?;
+        sqlx::query(include_str!("sql/mutes/add_timeout_expire_time_column.sql"))
+            .execute(&*self.0)
+            .await?;
+        sqlx::query(include_str!("sql/mutes/add_mod_log_message_id_column.sql"))
+            .execute(&*self.0)
+            .await?;
+        sqlx::query(include_str!("sql/mutes/add_mute_backend_column.sql"))
+            .execute(&*self.0)
+            .await?;
         sqlx::query(include_str!("sql/mutes/create_mute_time_index.sql"))
             .execute(&*self.0)
             .await?;
+        sqlx::query(include_str!("sql/mutes/create_case_number_index.sql"))
+            .execute(&*self.0)
+            .await?;
         Ok(())
     }
 
-    pub async fn fetch_mute(&self, id: i32) -> Result<Option<Mute>, Error> {
-        sqlx::query_as::<_, Mute>(include_str!("sql/mutes/select_mute_with_id.sql"))
-            .bind(id)
+    pub async fn fetch_guild_mute_with_case_number(
+        &self,
+        guild_id: i64,
+        case_number: i32,
+    ) -> Result<Option<Mute>, Error> {
+        sqlx::query_as::<_, Mute>(include_str!("sql/mutes/select_mute_with_case_number.sql"))
+            .bind(guild_id)
+            .bind(case_number)
             .fetch_optional(&*self.0)
             .await
     }
@@ -46,6 +73,18 @@ impl MutesRepository {
             .await
     }
 
+    pub async fn fetch_guild_user_mutes(
+        &self,
+        guild_id: i64,
+        user_id: i64,
+    ) -> Result<Vec<Mute>, Error> {
+        sqlx::query_as::<_, Mute>(include_str!("sql/mutes/select_guild_user_mutes.sql"))
+            .bind(guild_id)
+            .bind(user_id)
+            .fetch_all(&*self.0)
+            .await
+    }
+
     pub async fn fetch_guild_mute_count(&self, guild_id: i64) -> Result<i64, Error> {
         Ok(sqlx::query(include_str!("sql/mutes/count_guild_mutes.sql"))
             .bind(guild_id)
@@ -61,6 +100,18 @@ impl MutesRepository {
             .await
     }
 
+    pub async fn fetch_mutes_with_expiring_timeout(
+        &self,
+        threshold: i64,
+    ) -> Result<Vec<Mute>, Error> {
+        sqlx::query_as::<_, Mute>(include_str!(
+            "sql/mutes/select_mutes_with_expiring_timeout.sql"
+        ))
+        .bind(threshold)
+        .fetch_all(&*self.0)
+        .await
+    }
+
     pub async fn fetch_valid_mutes(&self, guild_id: i64, user_id: i64) -> Result<Vec<Mute>, Error> {
         sqlx::query_as::<_, Mute>(include_str!("sql/mutes/select_valid_guild_user_mutes.sql"))
             .bind(guild_id)
@@ -73,12 +124,14 @@ impl MutesRepository {
         &self,
         guild_id: i64,
         user_id: i64,
+        since: i64,
     ) -> Result<i64, Error> {
         Ok(sqlx::query(include_str!(
             "sql/mutes/count_actionable_guild_user_mutes.sql"
         ))
         .bind(guild_id)
         .bind(user_id)
+        .bind(since)
         .fetch_one(&*self.0)
         .await?
         .get(0))
@@ -95,6 +148,9 @@ impl MutesRepository {
             .bind(mute.expires)
             .bind(mute.unmuted)
             .bind(mute.pardoned)
+            .bind(mute.case_number)
+            .bind(mute.timeout_expire_time)
+            .bind(mute.mute_backend)
             .fetch_one(&*self.0)
             .await
     }
@@ -117,6 +173,50 @@ impl MutesRepository {
         Ok(())
     }
 
+    pub async fn update_mod_log_message_id(
+        &self,
+        id: i32,
+        mod_log_message_id: i64,
+    ) -> Result<(), Error> {
+        sqlx::query(include_str!("sql/mutes/update_mod_log_message_id.sql"))
+            .bind(id)
+            .bind(mod_log_message_id)
+            .execute(&*self.0)
+            .await?;
+
+        Ok(())
+    }
+
+    pub async fn update_timeout_expire_time(
+        &self,
+        id: i32,
+        timeout_expire_time: i64,
+    ) -> Result<(), Error> {
+        sqlx::query(include_str!("sql/mutes/update_timeout_expire_time.sql"))
+            .bind(id)
+            .bind(timeout_expire_time)
+            .execute(&*self.0)
+            .await?;
+
+        Ok(())
+    }
+
+    pub async fn update_mute_backend(
+        &self,
+        id: i32,
+        mute_backend: i32,
+        timeout_expire_time: i64,
+    ) -> Result<(), Error> {
+        sqlx::query(include_str!("sql/mutes/update_mute_backend.sql"))
+            .bind(id)
+            .bind(mute_backend)
+            .bind(timeout_expire_time)
+            .execute(&*self.0)
+            .await?;
+
+        Ok(())
+    }
+
     pub async fn invalidate_previous_user_mutes(
         &self,
         guild_id: i64,
@@ -0,0 +1,114 @@
+use std::sync::Arc;
+
+use sqlx::{Error, PgPool, Row};
+
+#[derive(sqlx::FromRow, Debug)]
+pub struct Note {
+    pub id: i32,
+    pub guild_id: i64,
+    pub user_id: i64,
+    pub moderator_user_id: i64,
+    pub note_time: i64,
+    pub message: String,
+}
+
+pub struct NotesRepository(pub Arc<PgPool>);
+
+impl NotesRepository {
+    pub async fn initialize(&self) -> Result<(), Error> {
+        sqlx::query(include_str!("sql/notes/create_table.sql"))
+            .execute(&*self.0)
+            .await?;
+        sqlx::query(include_str!("sql/notes/create_guild_id_user_id_index.sql"))
+            .execute(&*self.0)
+            .await?;
+        Ok(())
+    }
+
+    pub async fn fetch_guild_note(&self, guild_id: i64, id: i32) -> Result<Option<Note>, Error> {
+        sqlx::query_as::<_, Note>(include_str!("sql/notes/select_guild_note.sql"))
+            .bind(id)
+            .bind(guild_id)
+            .fetch_optional(&*self.0)
+            .await
+    }
+
+    pub async fn fetch_guild_user_notes(
+        &self,
+        guild_id: i64,
+        user_id: i64,
+    ) -> Result<Vec<Note>, Error> {
+        sqlx::query_as::<_, Note>(include_str!("sql/notes/select_guild_user_notes.sql"))
+            .bind(guild_id)
+            .bind(user_id)
+            .fetch_all(&*self.0)
+            .await
+    }
+
+    pub async fn fetch_guild_notes(&self, guild_id: i64, page: u32) -> Result<Vec<Note>, Error> {
+        sqlx::query_as::<_, Note>(include_str!("sql/notes/select_guild_notes_paginated.sql"))
+            .bind(guild_id)
+            .bind(((page - 1) * 10) as i32)
+            .fetch_all(&*self.0)
+            .await
+    }
+
+    pub async fn fetch_guild_user_notes_paginated(
+        &self,
+        guild_id: i64,
+        user_id: i64,
+        page: u32,
+    ) -> Result<Vec<Note>, Error> {
+        sqlx::query_as::<_, Note>(include_str!(
+            "sql/notes/select_guild_user_notes_paginated.sql"
+        ))
+        .bind(guild_id)
+        .bind(user_id)
+        .bind(((page - 1) * 10) as i32)
+        .fetch_all(&*self.0)
+        .await
+    }
+
+    pub async fn fetch_guild_note_count(&self, guild_id: i64) -> Result<i64, Error> {
+        Ok(sqlx::query(include_str!("sql/notes/count_guild_notes.sql"))
+            .bind(guild_id)
+            .fetch_one(&*self.0)
+            .await?
+            .get(0))
+    }
+
+    pub async fn fetch_guild_user_note_count(
+        &self,
+        guild_id: i64,
+        user_id: i64,
+    ) -> Result<i64, Error> {
+        Ok(
+            sqlx::query(include_str!("sql/notes/count_guild_user_notes.sql"))
+                .bind(guild_id)
+                .bind(user_id)
+                .fetch_one(&*self.0)
+                .await?
+                .get(0),
+        )
+    }
+
+    pub async fn insert_note(&self, note: Note) -> Result<Note, Error> {
+        sqlx::query_as::<_, Note>(include_str!("sql/notes/insert_entity.sql"))
+            .bind(note.guild_id)
+            .bind(note.user_id)
+            .bind(note.moderator_user_id)
+            .bind(note.note_time)
+            .bind(note.message)
+            .fetch_one(&*self.0)
+            .await
+    }
+
+    pub async fn delete_note(&self, id: i32) -> Result<(), Error> {
+        sqlx::query(include_str!("sql/notes/delete_note.sql"))
+            .bind(id)
+            .execute(&*self.0)
+            .await?;
+
+        Ok(())
+    }
+}
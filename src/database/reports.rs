@@ -0,0 +1,172 @@
+use std::sync::Arc;
+
+use sqlx::{Error, PgPool, Row};
+
+// select_open_guild_message_report.sql and update_entity.sql rely on these values
+pub const REPORT_STATUS_OPEN: i32 = 0;
+pub const REPORT_STATUS_CLAIMED: i32 = 1;
+pub const REPORT_STATUS_RESOLVED: i32 = 2;
+pub const REPORT_STATUS_DISMISSED: i32 = 3;
+
+#[derive(sqlx::FromRow, Debug, Clone)]
+pub struct Report {
+    pub id: i32,
+    pub guild_id: i64,
+    pub channel_id: i64,
+    pub message_id: i64,
+    pub target_user_id: i64,
+    pub reporter_user_ids: Vec<i64>,
+    pub content: String,
+    pub attachments: Vec<String>,
+    pub message_time: i64,
+    pub report_time: i64,
+    pub status: i32,
+    pub moderator_user_id: i64,
+    pub update_time: i64,
+    pub action_taken: bool,
+    pub report_message_id: i64,
+}
+
+impl Report {
+    pub fn is_open(&self) -> bool {
+        self.status == REPORT_STATUS_OPEN || self.status == REPORT_STATUS_CLAIMED
+    }
+}
+
+pub struct ReportsRepository(pub Arc<PgPool>);
+
+impl ReportsRepository {
+    pub async fn initialize(&self) -> Result<(), Error> {
+        sqlx::query(include_str!("sql/reports/create_table.sql"))
+            .execute(&*self.0)
+            .await?;
+        sqlx::query(include_str!(
+            "sql/reports/create_guild_id_message_id_index.sql"
+        ))
+        .execute(&*self.0)
+        .await?;
+        sqlx::query(include_str!(
+            "sql/reports/create_open_guild_id_message_id_index.sql"
+        ))
+        .execute(&*self.0)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn fetch_report(&self, id: i32) -> Result<Option<Report>, Error> {
+        sqlx::query_as::<_, Report>(include_str!("sql/reports/select_report.sql"))
+            .bind(id)
+            .fetch_optional(&*self.0)
+            .await
+    }
+
+    pub async fn fetch_open_guild_message_report(
+        &self,
+        guild_id: i64,
+        message_id: i64,
+    ) -> Result<Option<Report>, Error> {
+        sqlx::query_as::<_, Report>(include_str!(
+            "sql/reports/select_open_guild_message_report.sql"
+        ))
+        .bind(guild_id)
+        .bind(message_id)
+        .fetch_optional(&*self.0)
+        .await
+    }
+
+    pub async fn fetch_guild_reports(
+        &self,
+        guild_id: i64,
+        status: Option<i32>,
+        target_user_id: Option<i64>,
+        page: u32,
+    ) -> Result<Vec<Report>, Error> {
+        sqlx::query_as::<_, Report>(include_str!(
+            "sql/reports/select_guild_reports_paginated.sql"
+        ))
+        .bind(guild_id)
+        .bind(status)
+        .bind(target_user_id)
+        .bind(((page - 1) * 10) as i32)
+        .fetch_all(&*self.0)
+        .await
+    }
+
+    pub async fn fetch_guild_report_count(
+        &self,
+        guild_id: i64,
+        status: Option<i32>,
+        target_user_id: Option<i64>,
+    ) -> Result<i64, Error> {
+        Ok(
+            sqlx::query(include_str!("sql/reports/count_guild_reports.sql"))
+                .bind(guild_id)
+                .bind(status)
+                .bind(target_user_id)
+                .fetch_one(&*self.0)
+                .await?
+                .get(0),
+        )
+    }
+
+    // returns None if the message already has an open report
+    pub async fn insert_report(&self, report: Report) -> Result<Option<Report>, Error> {
+        sqlx::query_as::<_, Report>(include_str!("sql/reports/insert_entity.sql"))
+            .bind(report.guild_id)
+            .bind(report.channel_id)
+            .bind(report.message_id)
+            .bind(report.target_user_id)
+            .bind(report.reporter_user_ids)
+            .bind(report.content)
+            .bind(report.attachments)
+            .bind(report.message_time)
+            .bind(report.report_time)
+            .bind(report.status)
+            .bind(report.moderator_user_id)
+            .bind(report.update_time)
+            .bind(report.action_taken)
+            .bind(report.report_message_id)
+            .fetch_optional(&*self.0)
+            .await
+    }
+
+    // returns None if the user already reported it
+    pub async fn add_reporter(
+        &self,
+        id: i32,
+        reporter_user_id: i64,
+    ) -> Result<Option<Report>, Error> {
+        sqlx::query_as::<_, Report>(include_str!("sql/reports/add_reporter.sql"))
+            .bind(id)
+            .bind(reporter_user_id)
+            .fetch_optional(&*self.0)
+            .await
+    }
+
+    // returns None if the report was already handled, handled reports can't be changed anymore
+    pub async fn update_report(&self, report: &Report) -> Result<Option<Report>, Error> {
+        sqlx::query_as::<_, Report>(include_str!("sql/reports/update_entity.sql"))
+            .bind(report.id)
+            .bind(report.status)
+            .bind(report.moderator_user_id)
+            .bind(report.update_time)
+            .bind(report.action_taken)
+            .bind(report.report_message_id)
+            .fetch_optional(&*self.0)
+            .await
+    }
+
+    // puts a report that was handled back into the queue with its previous state
+    pub async fn reopen_report(&self, report: &Report) -> Result<(), Error> {
+        sqlx::query(include_str!("sql/reports/reopen_entity.sql"))
+            .bind(report.id)
+            .bind(report.status)
+            .bind(report.moderator_user_id)
+            .bind(report.update_time)
+            .bind(report.action_taken)
+            .execute(&*self.0)
+            .await?;
+
+        Ok(())
+    }
+}
@@ -5,8 +5,13 @@ use serenity::model::id::GuildId;
 use sqlx::{Error, PgPool};
 use tracing::warn;
 
+use crate::database::escalation_steps::{replace_guild_escalation_steps, EscalationStep};
+
 const DEFAULT_WELCOME_MESSAGE: &str = "Welcome to $guild $user!";
 
+pub const MAX_DECAY_DAYS: i32 = 3650;
+pub const MAX_DURATION_DAYS: i32 = 3650;
+
 pub const WORD_FILTER_LEVEL_LOW: i32 = 0;
 pub const WORD_FILTER_LEVEL_HIGH: i32 = 1;
 pub const ACTION_NOTHING: i32 = 0;
@@ -23,33 +28,51 @@ pub const DURATION_TYPE_DAYS: i32 = 3;
 pub const PRIVACY_EVERYONE: i32 = 0;
 pub const PRIVACY_STAFF_ONLY: i32 = 1;
 pub const PRIVACY_ADMIN_ONLY: i32 = 2;
+pub const MUTE_BACKEND_ROLE: i32 = 0;
+pub const MUTE_BACKEND_TIMEOUT: i32 = 1;
+
+// largest duration accepted for the unit, MAX_DURATION_DAYS converted so it always fits in seconds
+pub fn get_max_duration(duration_type: i32) -> i32 {
+    if duration_type == DURATION_TYPE_SECONDS {
+        MAX_DURATION_DAYS * 60 * 60 * 24
+    } else if duration_type == DURATION_TYPE_MINUTES {
+        MAX_DURATION_DAYS * 60 * 24
+    } else if duration_type == DURATION_TYPE_HOURS {
+        MAX_DURATION_DAYS * 24
+    } else {
+        MAX_DURATION_DAYS
+    }
+}
+
+pub fn get_duration(duration_type: i32, duration: i32) -> Option<Duration> {
+    // widened so durations saved before they were bounded can't overflow
+    let duration = i64::from(duration).max(0);
+    let seconds = if duration_type == DURATION_TYPE_SECONDS {
+        duration
+    } else if duration_type == DURATION_TYPE_MINUTES {
+        duration * 60
+    } else if duration_type == DURATION_TYPE_HOURS {
+        duration * 60 * 60
+    } else if duration_type == DURATION_TYPE_DAYS {
+        duration * 60 * 60 * 24
+    } else {
+        warn!(
+            duration_type = duration_type,
+            "invalid state for duration type!"
+        );
+        return None;
+    };
+
+    Some(Duration::from_secs(seconds as u64))
+}
 
 pub fn get_action_duration_for_auto_mod_action(
     kind: i32,
     duration_type: i32,
     duration: i32,
 ) -> Option<Duration> {
-    if kind == ACTION_BAN || kind == ACTION_MUTE {
-        if duration == 0 {
-            None
-        } else {
-            Some(Duration::from_secs(if duration == DURATION_TYPE_SECONDS {
-                duration
-            } else if duration == DURATION_TYPE_MINUTES {
-                duration * 60
-            } else if duration == DURATION_TYPE_HOURS {
-                duration * 60 * 60
-            } else if duration == DURATION_TYPE_DAYS {
-                duration * 60 * 60 * 24
-            } else {
-                warn!(
-                    kind = kind,
-                    duration_type = duration_type,
-                    "invalid state for mod action duration type!"
-                );
-                return None;
-            } as u64))
-        }
+    if (kind == ACTION_BAN || kind == ACTION_MUTE) && duration != 0 {
+        get_duration(duration_type, duration)
     } else {
         None
     }
@@ -80,24 +103,45 @@ pub struct Setting {
     pub invite_link_remover_action_duration_type: i32,
     pub privacy_settings: i32,
     pub privacy_mod_log: i32,
-    pub softban_threshold: i32,
-    pub softban_action: i32,
-    pub softban_action_duration: i32,
-    pub softban_action_duration_type: i32,
-    pub kick_threshold: i32,
-    pub kick_action: i32,
-    pub kick_action_duration: i32,
-    pub kick_action_duration_type: i32,
-    pub mute_threshold: i32,
-    pub mute_action: i32,
-    pub mute_action_duration: i32,
-    pub mute_action_duration_type: i32,
-    pub warn_threshold: i32,
-    pub warn_action: i32,
-    pub warn_action_duration: i32,
-    pub warn_action_duration_type: i32,
     pub mods_can_edit_tags: bool,
     pub spam_filter: bool,
+    pub mute_backend: i32,
+    pub mute_role_id: Option<i64>,
+    pub warn_decay_days: i32,
+    pub mute_decay_days: i32,
+    pub kick_decay_days: i32,
+    pub softban_decay_days: i32,
+    pub appeals_channel_id: i64,
+    pub report_notify_reporter: bool,
+    pub warn_dm: bool,
+    pub warn_dm_template: Option<String>,
+    pub mute_dm: bool,
+    pub mute_dm_template: Option<String>,
+    pub kick_dm: bool,
+    pub kick_dm_template: Option<String>,
+    pub ban_dm: bool,
+    pub ban_dm_template: Option<String>,
+    pub softban_dm: bool,
+    pub softban_dm_template: Option<String>,
+    pub hardban_dm: bool,
+    pub hardban_dm_template: Option<String>,
+    pub anonymous_moderation: bool,
+    pub spam_filter_exempt_channel_ids: Vec<i64>,
+    pub spam_filter_exempt_role_ids: Vec<i64>,
+    pub invite_link_remover_exempt_channel_ids: Vec<i64>,
+    pub invite_link_remover_exempt_role_ids: Vec<i64>,
+    pub word_filter_exempt_channel_ids: Vec<i64>,
+    pub word_filter_exempt_role_ids: Vec<i64>,
+    pub link_filter: bool,
+    pub link_filter_allowlist: Option<String>,
+    pub link_filter_denylist: Option<String>,
+    pub link_filter_allowlist_only: bool,
+    pub link_filter_action: i32,
+    pub link_filter_action_duration: i32,
+    pub link_filter_action_duration_type: i32,
+    pub link_filter_exempt_channel_ids: Vec<i64>,
+    pub link_filter_exempt_role_ids: Vec<i64>,
+    pub invite_link_remover_allowlist: Option<String>,
 }
 
 impl Setting {
@@ -126,24 +170,66 @@ impl Setting {
             invite_link_remover_action_duration_type: DURATION_TYPE_MINUTES,
             privacy_settings: PRIVACY_EVERYONE,
             privacy_mod_log: PRIVACY_EVERYONE,
-            softban_threshold: 0,
-            softban_action: ACTION_NOTHING,
-            softban_action_duration: 0,
-            softban_action_duration_type: DURATION_TYPE_MINUTES,
-            kick_threshold: 0,
-            kick_action: ACTION_NOTHING,
-            kick_action_duration: 0,
-            kick_action_duration_type: DURATION_TYPE_MINUTES,
-            mute_threshold: 0,
-            mute_action: ACTION_NOTHING,
-            mute_action_duration: 0,
-            mute_action_duration_type: DURATION_TYPE_MINUTES,
-            warn_threshold: 0,
-            warn_action: ACTION_NOTHING,
-            warn_action_duration: 0,
-            warn_action_duration_type: DURATION_TYPE_MINUTES,
             mods_can_edit_tags: false,
             spam_filter: false,
+            mute_backend: MUTE_BACKEND_ROLE,
+            mute_role_id: None,
+            warn_decay_days: 0,
+            mute_decay_days: 0,
+            kick_decay_days: 0,
+            softban_decay_days: 0,
+            appeals_channel_id: 0,
+            report_notify_reporter: false,
+            warn_dm: true,
+            warn_dm_template: None,
+            mute_dm: true,
+            mute_dm_template: None,
+            kick_dm: true,
+            kick_dm_template: None,
+            ban_dm: true,
+            ban_dm_template: None,
+            softban_dm: true,
+            softban_dm_template: None,
+            hardban_dm: true,
+            hardban_dm_template: None,
+            anonymous_moderation: false,
+            spam_filter_exempt_channel_ids: vec![],
+            spam_filter_exempt_role_ids: vec![],
+            invite_link_remover_exempt_channel_ids: vec![],
+            invite_link_remover_exempt_role_ids: vec![],
+            word_filter_exempt_channel_ids: vec![],
+            word_filter_exempt_role_ids: vec![],
+            link_filter: false,
+            link_filter_allowlist: None,
+            link_filter_denylist: None,
+            link_filter_allowlist_only: false,
+            link_filter_action: ACTION_WARN,
+            link_filter_action_duration: 0,
+            link_filter_action_duration_type: DURATION_TYPE_MINUTES,
+            link_filter_exempt_channel_ids: vec![],
+            link_filter_exempt_role_ids: vec![],
+            invite_link_remover_allowlist: None,
+        }
+    }
+
+    // infractions issued before the returned unix timestamp no longer count towards escalation thresholds
+    pub fn decay_cutoff(&self, infraction_type: i32, now: u64) -> u64 {
+        let decay_days = if infraction_type == ACTION_WARN {
+            self.warn_decay_days
+        } else if infraction_type == ACTION_MUTE {
+            self.mute_decay_days
+        } else if infraction_type == ACTION_KICK {
+            self.kick_decay_days
+        } else if infraction_type == ACTION_SOFTBAN {
+            self.softban_decay_days
+        } else {
+            0
+        };
+
+        if decay_days <= 0 {
+            0
+        } else {
+            now.saturating_sub(decay_days as u64 * 24 * 60 * 60)
         }
     }
 }
@@ -155,6 +241,55 @@ impl SettingsRepository {
         sqlx::query(include_str!("sql/settings/create_table.sql"))
             .execute(&*self.0)
             .await?;
+        sqlx::query(include_str!("sql/settings/add_mute_backend_column.sql"))
+            .execute(&*self.0)
+            .await?;
+        sqlx::query(include_str!("sql/settings/add_mute_role_id_column.sql"))
+            .execute(&*self.0)
+            .await?;
+        sqlx::query(include_str!("sql/settings/add_warn_decay_days_column.sql"))
+            .execute(&*self.0)
+            .await?;
+        sqlx::query(include_str!("sql/settings/add_mute_decay_days_column.sql"))
+            .execute(&*self.0)
+            .await?;
+        sqlx::query(include_str!("sql/settings/add_kick_decay_days_column.sql"))
+            .execute(&*self.0)
+            .await?;
+        sqlx::query(include_str!(
+            "sql/settings/add_softban_decay_days_column.sql"
+        ))
+        .execute(&*self.0)
+        .await?;
+        sqlx::query(include_str!(
+            "sql/settings/add_appeals_channel_id_column.sql"
+        ))
+        .execute(&*self.0)
+        .await?;
+        sqlx::query(include_str!(
+            "sql/settings/add_report_notify_reporter_column.sql"
+        ))
+        .execute(&*self.0)
+        .await?;
+        sqlx::query(include_str!("sql/settings/add_dm_template_columns.sql"))
+            .execute(&*self.0)
+            .await?;
+        sqlx::query(include_str!(
+            "sql/settings/add_anonymous_moderation_column.sql"
+        ))
+        .execute(&*self.0)
+        .await?;
+        sqlx::query(include_str!("sql/settings/add_exemption_columns.sql"))
+            .execute(&*self.0)
+            .await?;
+        sqlx::query(include_str!("sql/settings/add_link_filter_columns.sql"))
+            .execute(&*self.0)
+            .await?;
+        sqlx::query(include_str!(
+            "sql/settings/add_invite_link_remover_allowlist_column.sql"
+        ))
+        .execute(&*self.0)
+        .await?;
         Ok(())
     }
 
@@ -190,29 +325,57 @@ impl SettingsRepository {
             .bind(setting.invite_link_remover_action_duration_type)
             .bind(setting.privacy_settings)
             .bind(setting.privacy_mod_log)
-            .bind(setting.softban_threshold)
-            .bind(setting.softban_action)
-            .bind(setting.softban_action_duration)
-            .bind(setting.softban_action_duration_type)
-            .bind(setting.kick_threshold)
-            .bind(setting.kick_action)
-            .bind(setting.kick_action_duration)
-            .bind(setting.kick_action_duration_type)
-            .bind(setting.mute_threshold)
-            .bind(setting.mute_action)
-            .bind(setting.mute_action_duration)
-            .bind(setting.mute_action_duration_type)
-            .bind(setting.warn_threshold)
-            .bind(setting.warn_action)
-            .bind(setting.warn_action_duration)
-            .bind(setting.warn_action_duration_type)
             .bind(setting.mods_can_edit_tags)
             .bind(setting.spam_filter)
+            .bind(setting.mute_backend)
+            .bind(setting.mute_role_id)
+            .bind(setting.warn_decay_days)
+            .bind(setting.mute_decay_days)
+            .bind(setting.kick_decay_days)
+            .bind(setting.softban_decay_days)
+            .bind(setting.appeals_channel_id)
+            .bind(setting.report_notify_reporter)
+            .bind(setting.warn_dm)
+            .bind(setting.warn_dm_template)
+            .bind(setting.mute_dm)
+            .bind(setting.mute_dm_template)
+            .bind(setting.kick_dm)
+            .bind(setting.kick_dm_template)
+            .bind(setting.ban_dm)
+            .bind(setting.ban_dm_template)
+            .bind(setting.softban_dm)
+            .bind(setting.softban_dm_template)
+            .bind(setting.hardban_dm)
+            .bind(setting.hardban_dm_template)
+            .bind(setting.anonymous_moderation)
+            .bind(setting.spam_filter_exempt_channel_ids)
+            .bind(setting.spam_filter_exempt_role_ids)
+            .bind(setting.invite_link_remover_exempt_channel_ids)
+            .bind(setting.invite_link_remover_exempt_role_ids)
+            .bind(setting.word_filter_exempt_channel_ids)
+            .bind(setting.word_filter_exempt_role_ids)
+            .bind(setting.link_filter)
+            .bind(setting.link_filter_allowlist)
+            .bind(setting.link_filter_denylist)
+            .bind(setting.link_filter_allowlist_only)
+            .bind(setting.link_filter_action)
+            .bind(setting.link_filter_action_duration)
+            .bind(setting.link_filter_action_duration_type)
+            .bind(setting.link_filter_exempt_channel_ids)
+            .bind(setting.link_filter_exempt_role_ids)
+            .bind(setting.invite_link_remover_allowlist)
             .fetch_one(&*self.0)
             .await
     }
 
-    pub async fn update_setting(&self, setting: Setting) -> Result<(), Error> {
+    pub async fn update_setting(
+        &self,
+        setting: Setting,
+        escalation_steps: Option<Vec<EscalationStep>>,
+    ) -> Result<(), Error> {
+        let guild_id = setting.guild_id;
+        let mut transaction = self.0.begin().await?;
+
         sqlx::query(include_str!("sql/settings/update_entity.sql"))
             .bind(setting.guild_id)
             .bind(setting.mod_log)
@@ -237,24 +400,60 @@ impl SettingsRepository {
             .bind(setting.invite_link_remover_action_duration_type)
             .bind(setting.privacy_settings)
             .bind(setting.privacy_mod_log)
-            .bind(setting.softban_threshold)
-            .bind(setting.softban_action)
-            .bind(setting.softban_action_duration)
-            .bind(setting.softban_action_duration_type)
-            .bind(setting.kick_threshold)
-            .bind(setting.kick_action)
-            .bind(setting.kick_action_duration)
-            .bind(setting.kick_action_duration_type)
-            .bind(setting.mute_threshold)
-            .bind(setting.mute_action)
-            .bind(setting.mute_action_duration)
-            .bind(setting.mute_action_duration_type)
-            .bind(setting.warn_threshold)
-            .bind(setting.warn_action)
-            .bind(setting.warn_action_duration)
-            .bind(setting.warn_action_duration_type)
             .bind(setting.mods_can_edit_tags)
             .bind(setting.spam_filter)
+            .bind(setting.mute_backend)
+            .bind(setting.mute_role_id)
+            .bind(setting.warn_decay_days)
+            .bind(setting.mute_decay_days)
+            .bind(setting.kick_decay_days)
+            .bind(setting.softban_decay_days)
+            .bind(setting.appeals_channel_id)
+            .bind(setting.report_notify_reporter)
+            .bind(setting.warn_dm)
+            .bind(setting.warn_dm_template)
+            .bind(setting.mute_dm)
+            .bind(setting.mute_dm_template)
+            .bind(setting.kick_dm)
+            .bind(setting.kick_dm_template)
+            .bind(setting.ban_dm)
+            .bind(setting.ban_dm_template)
+            .bind(setting.softban_dm)
+            .bind(setting.softban_dm_template)
+            .bind(setting.hardban_dm)
+            .bind(setting.hardban_dm_template)
+            .bind(setting.anonymous_moderation)
+            .bind(setting.spam_filter_exempt_channel_ids)
+            .bind(setting.spam_filter_exempt_role_ids)
+            .bind(setting.invite_link_remover_exempt_channel_ids)
+            .bind(setting.invite_link_remover_exempt_role_ids)
+            .bind(setting.word_filter_exempt_channel_ids)
+            .bind(setting.word_filter_exempt_role_ids)
+            .bind(setting.link_filter)
+            .bind(setting.link_filter_allowlist)
+            .bind(setting.link_filter_denylist)
+            .bind(setting.link_filter_allowlist_only)
+            .bind(setting.link_filter_action)
+            .bind(setting.link_filter_action_duration)
+            .bind(setting.link_filter_action_duration_type)
+            .bind(setting.link_filter_exempt_channel_ids)
+            .bind(setting.link_filter_exempt_role_ids)
+            .bind(setting.invite_link_remover_allowlist)
+            .execute(&mut *transaction)
+            .await?;
+
+        // None leaves the ladder alone, dashboards that don't know about it would otherwise wipe it
+        if let Some(escalation_steps) = escalation_steps {
+            replace_guild_escalation_steps(&mut transaction, guild_id, escalation_steps).await?;
+        }
+
+        transaction.commit().await
+    }
+
+    pub async fn update_mute_role_id(&self, guild_id: i64, role_id: i64) -> Result<(), Error> {
+        sqlx::query(include_str!("sql/settings/update_mute_role_id.sql"))
+            .bind(guild_id)
+            .bind(role_id)
             .execute(&*self.0)
             .await?;
 
@@ -2,7 +2,7 @@ use std::sync::Arc;
 
 use sqlx::{Error, PgPool, Row};
 
-#[derive(sqlx::FromRow)]
+#[derive(sqlx::FromRow, Clone)]
 pub struct Softban {
     pub id: i32,
     pub user_id: i64,
@@ -11,6 +11,8 @@ pub struct Softban {
     pub softban_time: i64,
     pub reason: String,
     pub pardoned: bool,
+    pub case_number: i32,
+    pub mod_log_message_id: i64,
 }
 
 pub struct SoftbansRepository(pub Arc<PgPool>);
@@ -20,17 +22,35 @@ impl SoftbansRepository {
         sqlx::query(include_str!("sql/softbans/create_table.sql"))
             .execute(&*self.0)
             .await?;
+        sqlx::query(include_str!("sql/softbans/add_case_number_column.sql"))
+            .execute(&*self.0)
+            .await?;
+        sqlx::query(include_str!(
+            "sql/softbans/add_mod_log_message_id_column.sql"
+        ))
+        .execute(&*self.0)
+        .await?;
         sqlx::query(include_str!("sql/softbans/create_softban_time_index.sql"))
             .execute(&*self.0)
             .await?;
+        sqlx::query(include_str!("sql/softbans/create_case_number_index.sql"))
+            .execute(&*self.0)
+            .await?;
         Ok(())
     }
 
-    pub async fn fetch_softban(&self, id: i32) -> Result<Option<Softban>, Error> {
-        sqlx::query_as::<_, Softban>(include_str!("sql/softbans/select_softban_with_id.sql"))
-            .bind(id)
-            .fetch_optional(&*self.0)
-            .await
+    pub async fn fetch_guild_softban_with_case_number(
+        &self,
+        guild_id: i64,
+        case_number: i32,
+    ) -> Result<Option<Softban>, Error> {
+        sqlx::query_as::<_, Softban>(include_str!(
+            "sql/softbans/select_softban_with_case_number.sql"
+        ))
+        .bind(guild_id)
+        .bind(case_number)
+        .fetch_optional(&*self.0)
+        .await
     }
 
     pub async fn fetch_guild_softbans(
@@ -47,6 +67,18 @@ impl SoftbansRepository {
         .await
     }
 
+    pub async fn fetch_guild_user_softbans(
+        &self,
+        guild_id: i64,
+        user_id: i64,
+    ) -> Result<Vec<Softban>, Error> {
+        sqlx::query_as::<_, Softban>(include_str!("sql/softbans/select_guild_user_softbans.sql"))
+            .bind(guild_id)
+            .bind(user_id)
+            .fetch_all(&*self.0)
+            .await
+    }
+
     pub async fn fetch_guild_softban_count(&self, guild_id: i64) -> Result<i64, Error> {
         Ok(
             sqlx::query(include_str!("sql/softbans/count_guild_softbans.sql"))
@@ -61,12 +93,14 @@ impl SoftbansRepository {
         &self,
         guild_id: i64,
         user_id: i64,
+        since: i64,
     ) -> Result<i64, Error> {
         Ok(sqlx::query(include_str!(
             "sql/softbans/count_actionable_guild_user_softbans.sql"
         ))
         .bind(guild_id)
         .bind(user_id)
+        .bind(since)
         .fetch_one(&*self.0)
         .await?
         .get(0))
@@ -80,6 +114,7 @@ impl SoftbansRepository {
             .bind(softban.softban_time)
             .bind(softban.reason)
             .bind(softban.pardoned)
+            .bind(softban.case_number)
             .fetch_one(&*self.0)
             .await
     }
@@ -98,4 +133,18 @@ impl SoftbansRepository {
 
         Ok(())
     }
+
+    pub async fn update_mod_log_message_id(
+        &self,
+        id: i32,
+        mod_log_message_id: i64,
+    ) -> Result<(), Error> {
+        sqlx::query(include_str!("sql/softbans/update_mod_log_message_id.sql"))
+            .bind(id)
+            .bind(mod_log_message_id)
+            .execute(&*self.0)
+            .await?;
+
+        Ok(())
+    }
 }
@@ -0,0 +1,3 @@
+select count(*)
+from appeals
+where guild_id = $1;
\ No newline at end of file
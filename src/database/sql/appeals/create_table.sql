@@ -0,0 +1,14 @@
+create table if not exists appeals
+(
+    id                serial  not null primary key,
+    guild_id          bigint  not null,
+    user_id           bigint  not null,
+    case_number       integer not null,
+    infraction_type   integer not null,
+    message           text    not null,
+    appeal_time       bigint  not null,
+    status            integer not null,
+    reviewer_user_id  bigint  not null,
+    review_time       bigint  not null,
+    review_message_id bigint  not null
+);
\ No newline at end of file
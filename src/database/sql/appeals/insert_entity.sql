@@ -0,0 +1,12 @@
+insert into appeals (guild_id,
+                     user_id,
+                     case_number,
+                     infraction_type,
+                     message,
+                     appeal_time,
+                     status,
+                     reviewer_user_id,
+                     review_time,
+                     review_message_id)
+values ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
+returning *;
\ No newline at end of file
@@ -0,0 +1,3 @@
+select *
+from appeals
+where id = $1;
\ No newline at end of file
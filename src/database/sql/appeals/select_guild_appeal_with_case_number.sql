@@ -0,0 +1,4 @@
+select *
+from appeals
+where guild_id = $1
+  and case_number = $2;
\ No newline at end of file
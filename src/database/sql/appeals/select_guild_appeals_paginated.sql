@@ -0,0 +1,5 @@
+select *
+from appeals
+where guild_id = $1
+order by appeal_time desc
+limit 10 offset $2;
\ No newline at end of file
@@ -0,0 +1,6 @@
+update appeals
+set status            = $2,
+    reviewer_user_id  = $3,
+    review_time       = $4,
+    review_message_id = $5
+where id = $1;
\ No newline at end of file
@@ -5,6 +5,7 @@ insert into bans (user_id,
                   expire_time,
                   reason,
                   expires,
-                  unbanned)
-values ($1, $2, $3, $4, $5, $6, $7, $8)
+                  unbanned,
+                  case_number)
+values ($1, $2, $3, $4, $5, $6, $7, $8, $9)
 returning *;
\ No newline at end of file
@@ -0,0 +1,5 @@
+select *
+from bans
+where guild_id = $1
+  and user_id = $2
+order by ban_time desc;
\ No newline at end of file
@@ -0,0 +1,67 @@
+with infractions as (select 'ban' as kind, id, guild_id, ban_time as action_time
+                     from bans
+                     where case_number is null
+                     union all
+                     select 'hardban', id, guild_id, hardban_time
+                     from hardbans
+                     where case_number is null
+                     union all
+                     select 'kick', id, guild_id, kick_time
+                     from kicks
+                     where case_number is null
+                     union all
+                     select 'mute', id, guild_id, mute_time
+                     from mutes
+                     where case_number is null
+                     union all
+                     select 'softban', id, guild_id, softban_time
+                     from softbans
+                     where case_number is null
+                     union all
+                     select 'warn', id, guild_id, warn_time
+                     from warns
+                     where case_number is null),
+     numbered as (select infractions.kind,
+                         infractions.id,
+                         infractions.guild_id,
+                         (coalesce(case_numbers.last_case_number, 0) +
+                          row_number() over (partition by infractions.guild_id
+                              order by infractions.action_time, infractions.kind, infractions.id))::integer as case_number
+                  from infractions
+                           left join case_numbers on case_numbers.guild_id = infractions.guild_id),
+     updated_bans as (update bans
+         set case_number = numbered.case_number
+         from numbered
+         where numbered.kind = 'ban'
+           and numbered.id = bans.id),
+     updated_hardbans as (update hardbans
+         set case_number = numbered.case_number
+         from numbered
+         where numbered.kind = 'hardban'
+           and numbered.id = hardbans.id),
+     updated_kicks as (update kicks
+         set case_number = numbered.case_number
+         from numbered
+         where numbered.kind = 'kick'
+           and numbered.id = kicks.id),
+     updated_mutes as (update mutes
+         set case_number = numbered.case_number
+         from numbered
+         where numbered.kind = 'mute'
+           and numbered.id = mutes.id),
+     updated_softbans as (update softbans
+         set case_number = numbered.case_number
+         from numbered
+         where numbered.kind = 'softban'
+           and numbered.id = softbans.id),
+     updated_warns as (update warns
+         set case_number = numbered.case_number
+         from numbered
+         where numbered.kind = 'warn'
+           and numbered.id = warns.id)
+insert
+into case_numbers (guild_id, last_case_number)
+select guild_id, max(case_number)
+from numbered
+group by guild_id
+on conflict (guild_id) do update set last_case_number = excluded.last_case_number;
@@ -0,0 +1,5 @@
+create table if not exists case_numbers
+(
+    guild_id         bigint  not null primary key,
+    last_case_number integer not null
+);
@@ -0,0 +1,4 @@
+insert into case_numbers (guild_id, last_case_number)
+values ($1, 1)
+on conflict (guild_id) do update set last_case_number = case_numbers.last_case_number + 1
+returning last_case_number;
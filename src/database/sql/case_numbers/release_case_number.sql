@@ -0,0 +1,4 @@
+update case_numbers
+set last_case_number = last_case_number - 1
+where guild_id = $1
+  and last_case_number = $2;
\ No newline at end of file
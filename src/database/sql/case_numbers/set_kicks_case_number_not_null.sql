@@ -0,0 +1,2 @@
+alter table kicks
+    alter column case_number set not null;
\ No newline at end of file
@@ -0,0 +1,2 @@
+alter table mutes
+    alter column case_number set not null;
\ No newline at end of file
@@ -0,0 +1 @@
+create unique index if not exists escalation_steps_guild_id_infraction_type_threshold_index on escalation_steps (guild_id, infraction_type, threshold);
\ No newline at end of file
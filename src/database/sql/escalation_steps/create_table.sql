@@ -0,0 +1,12 @@
+create table if not exists escalation_steps
+(
+    id                     serial  not null primary key,
+    guild_id               bigint  not null,
+    infraction_type        integer not null,
+    threshold              integer not null,
+    action                 integer not null,
+    action_duration        integer not null,
+    action_duration_type   integer not null,
+    lookback_duration      integer not null,
+    lookback_duration_type integer not null
+);
\ No newline at end of file
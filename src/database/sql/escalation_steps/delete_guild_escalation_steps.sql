@@ -0,0 +1,3 @@
+delete
+from escalation_steps
+where guild_id = $1;
\ No newline at end of file
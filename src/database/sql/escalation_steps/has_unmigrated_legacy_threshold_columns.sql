@@ -0,0 +1,5 @@
+select exists(select 1
+              from information_schema.columns
+              where table_name = 'settings'
+                and column_name = 'warn_threshold'
+                and is_nullable = 'NO');
\ No newline at end of file
@@ -0,0 +1,10 @@
+insert into escalation_steps (guild_id,
+                              infraction_type,
+                              threshold,
+                              action,
+                              action_duration,
+                              action_duration_type,
+                              lookback_duration,
+                              lookback_duration_type)
+values ($1, $2, $3, $4, $5, $6, $7, $8)
+returning *;
\ No newline at end of file
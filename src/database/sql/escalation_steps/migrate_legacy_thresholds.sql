@@ -0,0 +1,28 @@
+insert into escalation_steps (guild_id,
+                              infraction_type,
+                              threshold,
+                              action,
+                              action_duration,
+                              action_duration_type,
+                              lookback_duration,
+                              lookback_duration_type)
+select guild_id, $1, softban_threshold, softban_action, softban_action_duration, softban_action_duration_type, 0, $5
+from settings
+where softban_threshold > 0
+  and softban_action != 0
+union all
+select guild_id, $2, kick_threshold, kick_action, kick_action_duration, kick_action_duration_type, 0, $5
+from settings
+where kick_threshold > 0
+  and kick_action != 0
+union all
+select guild_id, $3, mute_threshold, mute_action, mute_action_duration, mute_action_duration_type, 0, $5
+from settings
+where mute_threshold > 0
+  and mute_action != 0
+union all
+select guild_id, $4, warn_threshold, warn_action, warn_action_duration, warn_action_duration_type, 0, $5
+from settings
+where warn_threshold > 0
+  and warn_action != 0
+on conflict do nothing;
\ No newline at end of file
@@ -0,0 +1,17 @@
+alter table settings
+    alter column softban_threshold drop not null,
+    alter column softban_action drop not null,
+    alter column softban_action_duration drop not null,
+    alter column softban_action_duration_type drop not null,
+    alter column kick_threshold drop not null,
+    alter column kick_action drop not null,
+    alter column kick_action_duration drop not null,
+    alter column kick_action_duration_type drop not null,
+    alter column mute_threshold drop not null,
+    alter column mute_action drop not null,
+    alter column mute_action_duration drop not null,
+    alter column mute_action_duration_type drop not null,
+    alter column warn_threshold drop not null,
+    alter column warn_action drop not null,
+    alter column warn_action_duration drop not null,
+    alter column warn_action_duration_type drop not null;
\ No newline at end of file
@@ -0,0 +1,4 @@
+select *
+from escalation_steps
+where guild_id = $1
+order by infraction_type, threshold;
\ No newline at end of file
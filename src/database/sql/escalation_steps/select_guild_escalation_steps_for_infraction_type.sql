@@ -0,0 +1,5 @@
+select *
+from escalation_steps
+where guild_id = $1
+  and infraction_type = $2
+order by threshold desc;
\ No newline at end of file
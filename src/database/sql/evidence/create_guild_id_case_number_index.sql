@@ -0,0 +1 @@
+create index if not exists evidence_guild_id_case_number_index on evidence (guild_id, case_number);
\ No newline at end of file
@@ -0,0 +1,13 @@
+create table if not exists evidence
+(
+    id           serial  not null primary key,
+    guild_id     bigint  not null,
+    case_number  integer not null,
+    channel_id   bigint  not null,
+    message_id   bigint  not null,
+    user_id      bigint  not null,
+    content      text    not null,
+    attachments  text[]  not null,
+    message_time bigint  not null,
+    record_time  bigint  not null
+);
\ No newline at end of file
@@ -0,0 +1,11 @@
+insert into evidence (guild_id,
+                      case_number,
+                      channel_id,
+                      message_id,
+                      user_id,
+                      content,
+                      attachments,
+                      message_time,
+                      record_time)
+values ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+returning *;
\ No newline at end of file
@@ -0,0 +1,5 @@
+select *
+from evidence
+where guild_id = $1
+  and case_number = any($2)
+order by message_time;
\ No newline at end of file
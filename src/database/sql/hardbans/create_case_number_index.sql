@@ -0,0 +1 @@
+create unique index if not exists hardbans_guild_id_case_number_index on hardbans (guild_id, case_number);
\ No newline at end of file
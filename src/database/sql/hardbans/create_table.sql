@@ -1,9 +1,11 @@
 create table if not exists hardbans
 (
-    id                serial not null primary key,
-    user_id           bigint not null,
-    moderator_user_id bigint not null,
-    guild_id          bigint not null,
-    hardban_time      bigint not null,
-    reason            text   not null
+    id                 serial not null primary key,
+    user_id            bigint not null,
+    moderator_user_id  bigint not null,
+    guild_id           bigint not null,
+    hardban_time       bigint not null,
+    reason             text   not null,
+    case_number        integer,
+    mod_log_message_id bigint not null default 0
 );
\ No newline at end of file
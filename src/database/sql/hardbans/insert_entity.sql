@@ -2,6 +2,7 @@ insert into hardbans (user_id,
                       moderator_user_id,
                       guild_id,
                       hardban_time,
-                      reason)
-values ($1, $2, $3, $4, $5)
+                      reason,
+                      case_number)
+values ($1, $2, $3, $4, $5, $6)
 returning *;
\ No newline at end of file
@@ -0,0 +1,5 @@
+select *
+from hardbans
+where guild_id = $1
+  and user_id = $2
+order by hardban_time desc;
\ No newline at end of file
@@ -0,0 +1,4 @@
+select *
+from hardbans
+where guild_id = $1
+  and case_number = $2;
\ No newline at end of file
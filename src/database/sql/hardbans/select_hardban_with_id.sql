@@ -1,3 +0,0 @@
-select *
-from hardbans
-where id = $1;
\ No newline at end of file
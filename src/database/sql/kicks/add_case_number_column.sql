@@ -0,0 +1,2 @@
+alter table kicks
+    add column if not exists case_number integer;
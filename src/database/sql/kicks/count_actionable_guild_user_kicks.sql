@@ -2,4 +2,5 @@ select count(*)
 from kicks
 where guild_id = $1
   and user_id = $2
-  and pardoned = false;
\ No newline at end of file
+  and pardoned = false
+  and kick_time >= $3;
\ No newline at end of file
@@ -0,0 +1 @@
+create unique index if not exists kicks_guild_id_case_number_index on kicks (guild_id, case_number);
\ No newline at end of file
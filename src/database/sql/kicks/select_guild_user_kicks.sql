@@ -0,0 +1,5 @@
+select *
+from kicks
+where guild_id = $1
+  and user_id = $2
+order by kick_time desc;
\ No newline at end of file
@@ -0,0 +1,2 @@
+alter table lockdowns
+    add column if not exists unlock_failed boolean not null default false;
\ No newline at end of file
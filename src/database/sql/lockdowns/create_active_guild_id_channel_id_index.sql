@@ -0,0 +1 @@
+create unique index if not exists lockdowns_active_guild_id_channel_id_index on lockdowns (guild_id, channel_id) where unlocked = false;
\ No newline at end of file
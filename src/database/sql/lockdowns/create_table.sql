@@ -0,0 +1,16 @@
+create table if not exists lockdowns
+(
+    id                     serial  not null primary key,
+    guild_id               bigint  not null,
+    channel_id             bigint  not null,
+    moderator_user_id      bigint  not null,
+    lock_time              bigint  not null,
+    expire_time            bigint  not null,
+    reason                 text    not null,
+    expires                boolean not null,
+    unlocked               boolean not null,
+    has_previous_overwrite boolean not null,
+    previous_allow         bigint  not null,
+    previous_deny          bigint  not null,
+    unlock_failed          boolean not null default false
+);
\ No newline at end of file
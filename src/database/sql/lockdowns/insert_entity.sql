@@ -0,0 +1,14 @@
+insert into lockdowns (guild_id,
+                       channel_id,
+                       moderator_user_id,
+                       lock_time,
+                       expire_time,
+                       reason,
+                       expires,
+                       unlocked,
+                       has_previous_overwrite,
+                       previous_allow,
+                       previous_deny)
+values ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)
+on conflict (guild_id, channel_id) where unlocked = false do nothing
+returning *;
\ No newline at end of file
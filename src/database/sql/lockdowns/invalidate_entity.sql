@@ -0,0 +1,3 @@
+update lockdowns
+set unlocked = true
+where id = $1;
\ No newline at end of file
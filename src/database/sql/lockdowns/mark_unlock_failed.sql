@@ -0,0 +1,3 @@
+update lockdowns
+set unlock_failed = true
+where id = $1;
\ No newline at end of file
@@ -0,0 +1,5 @@
+select *
+from lockdowns
+where guild_id = $1
+  and channel_id = $2
+  and unlocked = false;
\ No newline at end of file
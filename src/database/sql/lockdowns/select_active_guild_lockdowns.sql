@@ -0,0 +1,4 @@
+select *
+from lockdowns
+where guild_id = $1
+  and unlocked = false;
\ No newline at end of file
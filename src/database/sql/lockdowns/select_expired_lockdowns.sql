@@ -0,0 +1,6 @@
+select *
+from lockdowns
+where unlocked = false
+  and unlock_failed = false
+  and expires = true
+  and expire_time < $1;
\ No newline at end of file
@@ -0,0 +1,2 @@
+alter table mutes
+    add column if not exists case_number integer;
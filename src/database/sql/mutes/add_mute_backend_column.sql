@@ -0,0 +1,2 @@
+alter table mutes
+    add column if not exists mute_backend integer not null default 0;
\ No newline at end of file
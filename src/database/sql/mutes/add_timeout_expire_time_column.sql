@@ -0,0 +1,2 @@
+alter table mutes
+    add column if not exists timeout_expire_time bigint not null default 0;
@@ -0,0 +1 @@
+create unique index if not exists mutes_guild_id_case_number_index on mutes (guild_id, case_number);
\ No newline at end of file
@@ -6,6 +6,9 @@ insert into mutes (user_id,
                    reason,
                    expires,
                    unmuted,
-                   pardoned)
-values ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+                   pardoned,
+                   case_number,
+                   timeout_expire_time,
+                   mute_backend)
+values ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12)
 returning *;
\ No newline at end of file
@@ -0,0 +1,5 @@
+select *
+from mutes
+where guild_id = $1
+  and user_id = $2
+order by mute_time desc;
\ No newline at end of file
@@ -0,0 +1,6 @@
+select *
+from mutes
+where unmuted = false
+  and timeout_expire_time != 0
+  and timeout_expire_time < $1
+  and (expires = false or expire_time > timeout_expire_time);
\ No newline at end of file
@@ -0,0 +1,4 @@
+update mutes
+set mute_backend        = $2,
+    timeout_expire_time = $3
+where id = $1;
\ No newline at end of file
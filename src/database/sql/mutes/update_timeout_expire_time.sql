@@ -0,0 +1,3 @@
+update mutes
+set timeout_expire_time = $2
+where id = $1;
\ No newline at end of file
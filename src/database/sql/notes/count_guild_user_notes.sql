@@ -0,0 +1,4 @@
+select count(*)
+from notes
+where guild_id = $1
+  and user_id = $2;
\ No newline at end of file
@@ -0,0 +1 @@
+create index if not exists notes_guild_id_user_id_index on notes (guild_id, user_id);
\ No newline at end of file
@@ -0,0 +1,9 @@
+create table if not exists notes
+(
+    id                serial not null primary key,
+    guild_id          bigint not null,
+    user_id           bigint not null,
+    moderator_user_id bigint not null,
+    note_time         bigint not null,
+    message           text   not null
+);
\ No newline at end of file
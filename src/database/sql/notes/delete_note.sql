@@ -0,0 +1,3 @@
+delete
+from notes
+where id = $1;
\ No newline at end of file
@@ -0,0 +1,7 @@
+insert into notes (guild_id,
+                   user_id,
+                   moderator_user_id,
+                   note_time,
+                   message)
+values ($1, $2, $3, $4, $5)
+returning *;
\ No newline at end of file
@@ -0,0 +1,4 @@
+select *
+from notes
+where id = $1
+  and guild_id = $2;
\ No newline at end of file
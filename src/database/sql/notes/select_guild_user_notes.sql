@@ -0,0 +1,5 @@
+select *
+from notes
+where guild_id = $1
+  and user_id = $2
+order by note_time desc;
\ No newline at end of file
@@ -0,0 +1,6 @@
+select *
+from notes
+where guild_id = $1
+  and user_id = $2
+order by note_time desc
+limit 10 offset $3;
\ No newline at end of file
@@ -0,0 +1,5 @@
+update reports
+set reporter_user_ids = array_append(reporter_user_ids, $2)
+where id = $1
+  and not ($2 = any (reporter_user_ids))
+returning *;
\ No newline at end of file
@@ -0,0 +1,5 @@
+select count(*)
+from reports
+where guild_id = $1
+  and ($2::integer is null or status = $2)
+  and ($3::bigint is null or target_user_id = $3);
\ No newline at end of file
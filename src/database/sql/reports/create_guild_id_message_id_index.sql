@@ -0,0 +1 @@
+create index if not exists reports_guild_id_message_id_index on reports (guild_id, message_id);
\ No newline at end of file
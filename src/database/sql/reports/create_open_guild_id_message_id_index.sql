@@ -0,0 +1 @@
+create unique index if not exists reports_open_guild_id_message_id_index on reports (guild_id, message_id) where status in (0, 1);
\ No newline at end of file
@@ -0,0 +1,18 @@
+create table if not exists reports
+(
+    id                serial   not null primary key,
+    guild_id          bigint   not null,
+    channel_id        bigint   not null,
+    message_id        bigint   not null,
+    target_user_id    bigint   not null,
+    reporter_user_ids bigint[] not null,
+    content           text     not null,
+    attachments       text[]   not null,
+    message_time      bigint   not null,
+    report_time       bigint   not null,
+    status            integer  not null,
+    moderator_user_id bigint   not null,
+    update_time       bigint   not null,
+    action_taken      boolean  not null,
+    report_message_id bigint   not null
+);
\ No newline at end of file
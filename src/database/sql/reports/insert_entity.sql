@@ -0,0 +1,17 @@
+insert into reports (guild_id,
+                     channel_id,
+                     message_id,
+                     target_user_id,
+                     reporter_user_ids,
+                     content,
+                     attachments,
+                     message_time,
+                     report_time,
+                     status,
+                     moderator_user_id,
+                     update_time,
+                     action_taken,
+                     report_message_id)
+values ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14)
+on conflict (guild_id, message_id) where status in (0, 1) do nothing
+returning *;
\ No newline at end of file
@@ -0,0 +1,6 @@
+update reports
+set status            = $2,
+    moderator_user_id = $3,
+    update_time       = $4,
+    action_taken      = $5
+where id = $1;
\ No newline at end of file
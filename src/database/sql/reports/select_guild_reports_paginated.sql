@@ -0,0 +1,7 @@
+select *
+from reports
+where guild_id = $1
+  and ($2::integer is null or status = $2)
+  and ($3::bigint is null or target_user_id = $3)
+order by report_time desc
+limit 10 offset $4;
\ No newline at end of file
@@ -0,0 +1,7 @@
+select *
+from reports
+where guild_id = $1
+  and message_id = $2
+  and status in (0, 1)
+order by report_time desc
+limit 1;
\ No newline at end of file
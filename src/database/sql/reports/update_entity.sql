@@ -0,0 +1,9 @@
+update reports
+set status            = $2,
+    moderator_user_id = $3,
+    update_time       = $4,
+    action_taken      = $5,
+    report_message_id = $6
+where id = $1
+  and status in (0, 1)
+returning *;
\ No newline at end of file
@@ -0,0 +1,2 @@
+alter table settings
+    add column if not exists appeals_channel_id bigint not null default 0;
\ No newline at end of file
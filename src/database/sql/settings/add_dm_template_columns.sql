@@ -0,0 +1,13 @@
+alter table settings
+    add column if not exists warn_dm boolean not null default true,
+    add column if not exists warn_dm_template text,
+    add column if not exists mute_dm boolean not null default true,
+    add column if not exists mute_dm_template text,
+    add column if not exists kick_dm boolean not null default true,
+    add column if not exists kick_dm_template text,
+    add column if not exists ban_dm boolean not null default true,
+    add column if not exists ban_dm_template text,
+    add column if not exists softban_dm boolean not null default true,
+    add column if not exists softban_dm_template text,
+    add column if not exists hardban_dm boolean not null default true,
+    add column if not exists hardban_dm_template text;
\ No newline at end of file
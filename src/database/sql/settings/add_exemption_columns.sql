@@ -0,0 +1,7 @@
+alter table settings
+    add column if not exists spam_filter_exempt_channel_ids bigint[] not null default '{}',
+    add column if not exists spam_filter_exempt_role_ids bigint[] not null default '{}',
+    add column if not exists invite_link_remover_exempt_channel_ids bigint[] not null default '{}',
+    add column if not exists invite_link_remover_exempt_role_ids bigint[] not null default '{}',
+    add column if not exists word_filter_exempt_channel_ids bigint[] not null default '{}',
+    add column if not exists word_filter_exempt_role_ids bigint[] not null default '{}';
\ No newline at end of file
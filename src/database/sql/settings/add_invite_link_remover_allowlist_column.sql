@@ -0,0 +1,2 @@
+alter table settings
+    add column if not exists invite_link_remover_allowlist text;
\ No newline at end of file
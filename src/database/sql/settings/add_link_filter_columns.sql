@@ -0,0 +1,10 @@
+alter table settings
+    add column if not exists link_filter boolean not null default false,
+    add column if not exists link_filter_allowlist text,
+    add column if not exists link_filter_denylist text,
+    add column if not exists link_filter_allowlist_only boolean not null default false,
+    add column if not exists link_filter_action integer not null default 1,
+    add column if not exists link_filter_action_duration integer not null default 0,
+    add column if not exists link_filter_action_duration_type integer not null default 1,
+    add column if not exists link_filter_exempt_channel_ids bigint[] not null default '{}',
+    add column if not exists link_filter_exempt_role_ids bigint[] not null default '{}';
\ No newline at end of file
@@ -0,0 +1,2 @@
+alter table settings
+    add column if not exists mute_decay_days integer not null default 0;
\ No newline at end of file
@@ -0,0 +1,2 @@
+alter table settings
+    add column if not exists mute_role_id bigint;
\ No newline at end of file
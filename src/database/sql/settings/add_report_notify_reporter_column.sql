@@ -0,0 +1,2 @@
+alter table settings
+    add column if not exists report_notify_reporter boolean not null default false;
\ No newline at end of file
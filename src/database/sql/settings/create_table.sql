@@ -23,22 +23,43 @@ create table if not exists settings
     invite_link_remover_action_duration_type integer not null,
     privacy_settings                         integer not null,
     privacy_mod_log                          integer not null,
-    softban_threshold                        integer not null,
-    softban_action                           integer not null,
-    softban_action_duration                  integer not null,
-    softban_action_duration_type             integer not null,
-    kick_threshold                           integer not null,
-    kick_action                              integer not null,
-    kick_action_duration                     integer not null,
-    kick_action_duration_type                integer not null,
-    mute_threshold                           integer not null,
-    mute_action                              integer not null,
-    mute_action_duration                     integer not null,
-    mute_action_duration_type                integer not null,
-    warn_threshold                           integer not null,
-    warn_action                              integer not null,
-    warn_action_duration                     integer not null,
-    warn_action_duration_type                integer not null,
     mods_can_edit_tags                       boolean not null,
-    spam_filter                              boolean not null
+    spam_filter                              boolean not null,
+    mute_backend                             integer not null,
+    mute_role_id                             bigint,
+    warn_decay_days                          integer not null,
+    mute_decay_days                          integer not null,
+    kick_decay_days                          integer not null,
+    softban_decay_days                       integer not null,
+    appeals_channel_id                       bigint not null,
+    report_notify_reporter                   boolean not null,
+    warn_dm                                  boolean not null,
+    warn_dm_template                         text,
+    mute_dm                                  boolean not null,
+    mute_dm_template                         text,
+    kick_dm                                  boolean not null,
+    kick_dm_template                         text,
+    ban_dm                                   boolean not null,
+    ban_dm_template                          text,
+    softban_dm                               boolean not null,
+    softban_dm_template                      text,
+    hardban_dm                               boolean not null,
+    hardban_dm_template                      text,
+    anonymous_moderation                     boolean not null,
+    spam_filter_exempt_channel_ids           bigint[] not null,
+    spam_filter_exempt_role_ids              bigint[] not null,
+    invite_link_remover_exempt_channel_ids   bigint[] not null,
+    invite_link_remover_exempt_role_ids      bigint[] not null,
+    word_filter_exempt_channel_ids           bigint[] not null,
+    word_filter_exempt_role_ids              bigint[] not null,
+    link_filter                              boolean not null,
+    link_filter_allowlist                    text,
+    link_filter_denylist                     text,
+    link_filter_allowlist_only               boolean not null,
+    link_filter_action                       integer not null,
+    link_filter_action_duration              integer not null,
+    link_filter_action_duration_type         integer not null,
+    link_filter_exempt_channel_ids           bigint[] not null,
+    link_filter_exempt_role_ids              bigint[] not null,
+    invite_link_remover_allowlist            text
 );
\ No newline at end of file
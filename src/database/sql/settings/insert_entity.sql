@@ -21,24 +21,46 @@ insert into settings (guild_id,
                       invite_link_remover_action_duration_type,
                       privacy_settings,
                       privacy_mod_log,
-                      softban_threshold,
-                      softban_action,
-                      softban_action_duration,
-                      softban_action_duration_type,
-                      kick_threshold,
-                      kick_action,
-                      kick_action_duration,
-                      kick_action_duration_type,
-                      mute_threshold,
-                      mute_action,
-                      mute_action_duration,
-                      mute_action_duration_type,
-                      warn_threshold,
-                      warn_action,
-                      warn_action_duration,
-                      warn_action_duration_type,
                       mods_can_edit_tags,
-                      spam_filter)
+                      spam_filter,
+                      mute_backend,
+                      mute_role_id,
+                      warn_decay_days,
+                      mute_decay_days,
+                      kick_decay_days,
+                      softban_decay_days,
+                      appeals_channel_id,
+                      report_notify_reporter,
+                      warn_dm,
+                      warn_dm_template,
+                      mute_dm,
+                      mute_dm_template,
+                      kick_dm,
+                      kick_dm_template,
+                      ban_dm,
+                      ban_dm_template,
+                      softban_dm,
+                      softban_dm_template,
+                      hardban_dm,
+                      hardban_dm_template,
+                      anonymous_moderation,
+                      spam_filter_exempt_channel_ids,
+                      spam_filter_exempt_role_ids,
+                      invite_link_remover_exempt_channel_ids,
+                      invite_link_remover_exempt_role_ids,
+                      word_filter_exempt_channel_ids,
+                      word_filter_exempt_role_ids,
+                      link_filter,
+                      link_filter_allowlist,
+                      link_filter_denylist,
+                      link_filter_allowlist_only,
+                      link_filter_action,
+                      link_filter_action_duration,
+                      link_filter_action_duration_type,
+                      link_filter_exempt_channel_ids,
+                      link_filter_exempt_role_ids,
+                      invite_link_remover_allowlist)
 values ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17, $18, $19, $20, $21, $22, $23, $24,
-        $25, $26, $27, $28, $29, $30, $31, $32, $33, $34, $35, $36, $37, $38, $39, $40, $41)
+        $25, $26, $27, $28, $29, $30, $31, $32, $33, $34, $35, $36, $37, $38, $39, $40, $41, $42, $43, $44, $45, $46,
+        $47, $48, $49, $50, $51, $52, $53, $54, $55, $56, $57, $58, $59, $60, $61, $62)
 returning *;
\ No newline at end of file
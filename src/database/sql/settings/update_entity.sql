@@ -21,22 +21,43 @@ set mod_log                                  = $2,
     invite_link_remover_action_duration_type = $21,
     privacy_settings                         = $22,
     privacy_mod_log                          = $23,
-    softban_threshold                        = $24,
-    softban_action                           = $25,
-    softban_action_duration                  = $26,
-    softban_action_duration_type             = $27,
-    kick_threshold                           = $28,
-    kick_action                              = $29,
-    kick_action_duration                     = $30,
-    kick_action_duration_type                = $31,
-    mute_threshold                           = $32,
-    mute_action                              = $33,
-    mute_action_duration                     = $34,
-    mute_action_duration_type                = $35,
-    warn_threshold                           = $36,
-    warn_action                              = $37,
-    warn_action_duration                     = $38,
-    warn_action_duration_type                = $39,
-    mods_can_edit_tags                       = $40,
-    spam_filter                              = $41
+    mods_can_edit_tags                       = $24,
+    spam_filter                              = $25,
+    mute_backend                             = $26,
+    mute_role_id                             = $27,
+    warn_decay_days                          = $28,
+    mute_decay_days                          = $29,
+    kick_decay_days                          = $30,
+    softban_decay_days                       = $31,
+    appeals_channel_id                       = $32,
+    report_notify_reporter                   = $33,
+    warn_dm                                  = $34,
+    warn_dm_template                         = $35,
+    mute_dm                                  = $36,
+    mute_dm_template                         = $37,
+    kick_dm                                  = $38,
+    kick_dm_template                         = $39,
+    ban_dm                                   = $40,
+    ban_dm_template                          = $41,
+    softban_dm                               = $42,
+    softban_dm_template                      = $43,
+    hardban_dm                               = $44,
+    hardban_dm_template                      = $45,
+    anonymous_moderation                     = $46,
+    spam_filter_exempt_channel_ids           = $47,
+    spam_filter_exempt_role_ids              = $48,
+    invite_link_remover_exempt_channel_ids   = $49,
+    invite_link_remover_exempt_role_ids      = $50,
+    word_filter_exempt_channel_ids           = $51,
+    word_filter_exempt_role_ids              = $52,
+    link_filter                              = $53,
+    link_filter_allowlist                    = $54,
+    link_filter_denylist                     = $55,
+    link_filter_allowlist_only               = $56,
+    link_filter_action                       = $57,
+    link_filter_action_duration              = $58,
+    link_filter_action_duration_type         = $59,
+    link_filter_exempt_channel_ids           = $60,
+    link_filter_exempt_role_ids              = $61,
+    invite_link_remover_allowlist            = $62
 where guild_id = $1;
\ No newline at end of file
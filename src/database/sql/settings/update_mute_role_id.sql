@@ -0,0 +1,3 @@
+update settings
+set mute_role_id = $2
+where guild_id = $1;
\ No newline at end of file
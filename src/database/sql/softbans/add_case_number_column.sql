@@ -0,0 +1,2 @@
+alter table softbans
+    add column if not exists case_number integer;
@@ -0,0 +1,2 @@
+alter table softbans
+    add column if not exists mod_log_message_id bigint not null default 0;
\ No newline at end of file
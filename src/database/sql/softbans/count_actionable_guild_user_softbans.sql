@@ -2,4 +2,5 @@ select count(*)
 from softbans
 where guild_id = $1
   and user_id = $2
-  and pardoned = false;
\ No newline at end of file
+  and pardoned = false
+  and softban_time >= $3;
\ No newline at end of file
@@ -0,0 +1 @@
+create unique index if not exists softbans_guild_id_case_number_index on softbans (guild_id, case_number);
\ No newline at end of file
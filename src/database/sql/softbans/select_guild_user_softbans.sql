@@ -0,0 +1,5 @@
+select *
+from softbans
+where guild_id = $1
+  and user_id = $2
+order by softban_time desc;
\ No newline at end of file
@@ -0,0 +1,3 @@
+update softbans
+set mod_log_message_id = $2
+where id = $1;
\ No newline at end of file
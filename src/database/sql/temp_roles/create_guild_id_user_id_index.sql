@@ -0,0 +1 @@
+create index if not exists temp_roles_guild_id_user_id_index on temp_roles (guild_id, user_id);
\ No newline at end of file
@@ -0,0 +1,11 @@
+create table if not exists temp_roles
+(
+    id                serial  not null primary key,
+    guild_id          bigint  not null,
+    user_id           bigint  not null,
+    role_id           bigint  not null,
+    moderator_user_id bigint  not null,
+    grant_time        bigint  not null,
+    expire_time       bigint  not null,
+    removed           boolean not null
+);
\ No newline at end of file
@@ -0,0 +1,9 @@
+insert into temp_roles (guild_id,
+                        user_id,
+                        role_id,
+                        moderator_user_id,
+                        grant_time,
+                        expire_time,
+                        removed)
+values ($1, $2, $3, $4, $5, $6, $7)
+returning *;
\ No newline at end of file
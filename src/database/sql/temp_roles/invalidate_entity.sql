@@ -0,0 +1,3 @@
+update temp_roles
+set removed = true
+where id = $1;
\ No newline at end of file
@@ -0,0 +1,5 @@
+update temp_roles
+set removed = true
+where guild_id = $1
+  and user_id = $2
+  and role_id = $3;
\ No newline at end of file
@@ -0,0 +1,5 @@
+select *
+from temp_roles
+where guild_id = $1
+  and removed = false
+order by expire_time;
\ No newline at end of file
@@ -0,0 +1,6 @@
+select *
+from temp_roles
+where guild_id = $1
+  and user_id = $2
+  and removed = false
+order by expire_time;
\ No newline at end of file
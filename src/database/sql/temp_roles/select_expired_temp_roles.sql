@@ -0,0 +1,4 @@
+select *
+from temp_roles
+where removed = false
+  and expire_time < $1;
\ No newline at end of file
@@ -0,0 +1,4 @@
+select *
+from temp_roles
+where id = $1
+  and guild_id = $2;
\ No newline at end of file
@@ -0,0 +1,2 @@
+alter table warns
+    add column if not exists case_number integer;
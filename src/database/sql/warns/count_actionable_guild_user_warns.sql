@@ -2,4 +2,5 @@ select count(*)
 from warns
 where guild_id = $1
   and user_id = $2
-  and pardoned = false;
\ No newline at end of file
+  and pardoned = false
+  and warn_time >= $3;
\ No newline at end of file
@@ -0,0 +1 @@
+create unique index if not exists warns_guild_id_case_number_index on warns (guild_id, case_number);
\ No newline at end of file
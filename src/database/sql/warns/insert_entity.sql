@@ -3,6 +3,7 @@ insert into warns (user_id,
                    guild_id,
                    warn_time,
                    reason,
-                   pardoned)
-values ($1, $2, $3, $4, $5, $6)
+                   pardoned,
+                   case_number)
+values ($1, $2, $3, $4, $5, $6, $7)
 returning *;
\ No newline at end of file
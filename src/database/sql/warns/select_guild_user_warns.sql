@@ -0,0 +1,5 @@
+select *
+from warns
+where guild_id = $1
+  and user_id = $2
+order by warn_time desc;
\ No newline at end of file
@@ -0,0 +1,118 @@
+use std::sync::Arc;
+
+use sqlx::{Error, PgPool};
+
+use crate::util::now;
+
+#[derive(sqlx::FromRow, Debug)]
+pub struct TempRole {
+    pub id: i32,
+    pub guild_id: i64,
+    pub user_id: i64,
+    pub role_id: i64,
+    pub moderator_user_id: i64,
+    pub grant_time: i64,
+    pub expire_time: i64,
+    pub removed: bool,
+}
+
+pub struct TempRolesRepository(pub Arc<PgPool>);
+
+impl TempRolesRepository {
+    pub async fn initialize(&self) -> Result<(), Error> {
+        sqlx::query(include_str!("sql/temp_roles/create_table.sql"))
+            .execute(&*self.0)
+            .await?;
+        sqlx::query(include_str!(
+            "sql/temp_roles/create_guild_id_user_id_index.sql"
+        ))
+        .execute(&*self.0)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn fetch_guild_temp_role(
+        &self,
+        guild_id: i64,
+        id: i32,
+    ) -> Result<Option<TempRole>, Error> {
+        sqlx::query_as::<_, TempRole>(include_str!("sql/temp_roles/select_guild_temp_role.sql"))
+            .bind(id)
+            .bind(guild_id)
+            .fetch_optional(&*self.0)
+            .await
+    }
+
+    pub async fn fetch_active_guild_temp_roles(
+        &self,
+        guild_id: i64,
+    ) -> Result<Vec<TempRole>, Error> {
+        sqlx::query_as::<_, TempRole>(include_str!(
+            "sql/temp_roles/select_active_guild_temp_roles.sql"
+        ))
+        .bind(guild_id)
+        .fetch_all(&*self.0)
+        .await
+    }
+
+    pub async fn fetch_active_guild_user_temp_roles(
+        &self,
+        guild_id: i64,
+        user_id: i64,
+    ) -> Result<Vec<TempRole>, Error> {
+        sqlx::query_as::<_, TempRole>(include_str!(
+            "sql/temp_roles/select_active_guild_user_temp_roles.sql"
+        ))
+        .bind(guild_id)
+        .bind(user_id)
+        .fetch_all(&*self.0)
+        .await
+    }
+
+    pub async fn fetch_expired_temp_roles(&self) -> Result<Vec<TempRole>, Error> {
+        sqlx::query_as::<_, TempRole>(include_str!("sql/temp_roles/select_expired_temp_roles.sql"))
+            .bind(now() as i64)
+            .fetch_all(&*self.0)
+            .await
+    }
+
+    pub async fn insert_temp_role(&self, temp_role: TempRole) -> Result<TempRole, Error> {
+        sqlx::query_as::<_, TempRole>(include_str!("sql/temp_roles/insert_entity.sql"))
+            .bind(temp_role.guild_id)
+            .bind(temp_role.user_id)
+            .bind(temp_role.role_id)
+            .bind(temp_role.moderator_user_id)
+            .bind(temp_role.grant_time)
+            .bind(temp_role.expire_time)
+            .bind(temp_role.removed)
+            .fetch_one(&*self.0)
+            .await
+    }
+
+    pub async fn invalidate_previous_user_temp_roles(
+        &self,
+        guild_id: i64,
+        user_id: i64,
+        role_id: i64,
+    ) -> Result<(), Error> {
+        sqlx::query(include_str!(
+            "sql/temp_roles/invalidate_previous_temp_roles_of_user.sql"
+        ))
+        .bind(guild_id)
+        .bind(user_id)
+        .bind(role_id)
+        .execute(&*self.0)
+        .await?;
+
+        Ok(())
+    }
+
+    pub async fn invalidate_temp_role(&self, id: i32) -> Result<(), Error> {
+        sqlx::query(include_str!("sql/temp_roles/invalidate_entity.sql"))
+            .bind(id)
+            .execute(&*self.0)
+            .await?;
+
+        Ok(())
+    }
+}
@@ -2,7 +2,7 @@ use std::sync::Arc;
 
 use sqlx::{Error, PgPool, Row};
 
-#[derive(sqlx::FromRow)]
+#[derive(sqlx::FromRow, Clone)]
 pub struct Warn {
     pub id: i32,
     pub user_id: i64,
@@ -11,6 +11,8 @@ pub struct Warn {
     pub warn_time: i64,
     pub reason: String,
     pub pardoned: bool,
+    pub case_number: i32,
+    pub mod_log_message_id: i64,
 }
 
 pub struct WarnsRepository(pub Arc<PgPool>);
@@ -20,15 +22,29 @@ impl WarnsRepository {
         sqlx::query(include_str!("sql/warns/create_table.sql"))
             .execute(&*self.0)
             .await?;
+        sqlx::query(include_str!("sql/warns/add_case_number_column.sql"))
+            .execute(&*self.0)
+            .await?;
+        sqlx::query(include_str!("sql/warns/add_mod_log_message_id_column.sql"))
+            .execute(&*self.0)
+            .await?;
         sqlx::query(include_str!("sql/warns/create_warn_time_index.sql"))
             .execute(&*self.0)
             .await?;
+        sqlx::query(include_str!("sql/warns/create_case_number_index.sql"))
+            .execute(&*self.0)
+            .await?;
         Ok(())
     }
 
-    pub async fn fetch_warn(&self, id: i32) -> Result<Option<Warn>, Error> {
-        sqlx::query_as::<_, Warn>(include_str!("sql/warns/select_warn_with_id.sql"))
-            .bind(id)
+    pub async fn fetch_guild_warn_with_case_number(
+        &self,
+        guild_id: i64,
+        case_number: i32,
+    ) -> Result<Option<Warn>, Error> {
+        sqlx::query_as::<_, Warn>(include_str!("sql/warns/select_warn_with_case_number.sql"))
+            .bind(guild_id)
+            .bind(case_number)
             .fetch_optional(&*self.0)
             .await
     }
@@ -41,6 +57,18 @@ impl WarnsRepository {
             .await
     }
 
+    pub async fn fetch_guild_user_warns(
+        &self,
+        guild_id: i64,
+        user_id: i64,
+    ) -> Result<Vec<Warn>, Error> {
+        sqlx::query_as::<_, Warn>(include_str!("sql/warns/select_guild_user_warns.sql"))
+            .bind(guild_id)
+            .bind(user_id)
+            .fetch_all(&*self.0)
+            .await
+    }
+
     pub async fn fetch_guild_warn_count(&self, guild_id: i64) -> Result<i64, Error> {
         Ok(sqlx::query(include_str!("sql/warns/count_guild_warns.sql"))
             .bind(guild_id)
@@ -53,12 +81,14 @@ impl WarnsRepository {
         &self,
         guild_id: i64,
         user_id: i64,
+        since: i64,
     ) -> Result<i64, Error> {
         Ok(sqlx::query(include_str!(
             "sql/warns/count_actionable_guild_user_warns.sql"
         ))
         .bind(guild_id)
         .bind(user_id)
+        .bind(since)
         .fetch_one(&*self.0)
         .await?
         .get(0))
@@ -72,6 +102,7 @@ impl WarnsRepository {
             .bind(warn.warn_time)
             .bind(warn.reason)
             .bind(warn.pardoned)
+            .bind(warn.case_number)
             .fetch_one(&*self.0)
             .await
     }
@@ -90,4 +121,18 @@ impl WarnsRepository {
 
         Ok(())
     }
+
+    pub async fn update_mod_log_message_id(
+        &self,
+        id: i32,
+        mod_log_message_id: i64,
+    ) -> Result<(), Error> {
+        sqlx::query(include_str!("sql/warns/update_mod_log_message_id.sql"))
+            .bind(id)
+            .bind(mod_log_message_id)
+            .execute(&*self.0)
+            .await?;
+
+        Ok(())
+    }
 }
@@ -0,0 +1,343 @@
+use std::num::NonZeroU64;
+
+use anyhow::bail;
+use async_trait::async_trait;
+use serenity::all::{ButtonStyle, ComponentInteraction, Context};
+use serenity::builder::{
+    CreateActionRow, CreateButton, CreateInteractionResponse, CreateInteractionResponseMessage,
+    CreateMessage,
+};
+use serenity::http::Http;
+use serenity::model::id::{GuildId, UserId};
+use serenity::model::Permissions;
+use tracing::{error, warn};
+
+use crate::config::Config;
+use crate::database::appeals::{
+    Appeal, APPEAL_STATUS_ACCEPTED, APPEAL_STATUS_DENIED, APPEAL_STATUS_PENDING,
+};
+use crate::database::settings::ACTION_BAN;
+use crate::discord::components::{
+    create_custom_id, verify_guild_component, ComponentHandler, GuildComponentInteraction,
+};
+use crate::discord::util::appeal::create_appeal_embed;
+use crate::discord::util::infraction::{
+    fetch_infraction_with_case_number, log_infraction_lift, Infraction,
+};
+use crate::discord::util::mod_log::ModLogAction;
+use crate::discord::util::{reply_to_component_str, UserExt};
+use crate::service::appeal::AppealService;
+use crate::service::ban::{BanService, UnbanFailure};
+use crate::service::guild::GuildService;
+use crate::service::mute::{MuteService, UnmuteFailure};
+use crate::service::setting::SettingService;
+use crate::service::Services;
+use crate::util::now;
+
+const APPEAL_PREFIX: &str = "appeal";
+const ACCEPT: &str = "accept";
+const DENY: &str = "deny";
+
+pub struct AppealComponent;
+
+pub fn create_appeal_buttons<'a>(appeal_id: i32) -> Vec<CreateActionRow<'a>> {
+    vec![CreateActionRow::Buttons(
+        vec![
+            CreateButton::new(create_custom_id(
+                APPEAL_PREFIX,
+                &format!("{}:{}", ACCEPT, appeal_id),
+            ))
+            .label("Accept")
+            .style(ButtonStyle::Success),
+            CreateButton::new(create_custom_id(
+                APPEAL_PREFIX,
+                &format!("{}:{}", DENY, appeal_id),
+            ))
+            .label("Deny")
+            .style(ButtonStyle::Danger),
+        ]
+        .into(),
+    )]
+}
+
+fn required_permission(infraction_type: i32) -> Permissions {
+    if infraction_type == ACTION_BAN {
+        Permissions::BAN_MEMBERS
+    } else {
+        Permissions::MANAGE_ROLES
+    }
+}
+
+async fn notify_user_for_appeal_review(
+    http: &Http,
+    user_id: UserId,
+    guild_name: &str,
+    appeal: &Appeal,
+) {
+    let outcome = if appeal.status == APPEAL_STATUS_ACCEPTED {
+        "accepted, your infraction has been lifted"
+    } else {
+        "denied"
+    };
+
+    let content = format!(
+        "Your appeal for case #{} in {} has been {}.",
+        appeal.case_number, guild_name, outcome
+    );
+
+    if let Ok(channel) = user_id.create_dm_channel(http).await {
+        let _ = channel
+            .id
+            .widen()
+            .send_message(http, CreateMessage::default().content(content))
+            .await
+            .map_err(|err| {
+                error!("failed to send appeal review DM {}", err);
+                err
+            });
+    }
+}
+
+async fn lift_infraction(
+    context: &Context,
+    services: &Services,
+    guild_id: GuildId,
+    appeal: &Appeal,
+    reviewer_tag_and_id: &str,
+) -> Result<(), &'static str> {
+    let infraction = match fetch_infraction_with_case_number(services, guild_id, appeal.case_number)
+        .await
+    {
+        Ok(infraction) => infraction,
+        Err(err) => {
+            error!("failed to fetch appealed infraction {}", err);
+            return Err(
+                "Could not lift the infraction for unknown reasons, this incident has been logged.",
+            );
+        }
+    };
+
+    // someone else might have lifted the case in the meantime, appeal is still accepted
+    let (infraction, lift_action) = match infraction {
+        Some(Infraction::Ban(mut ban)) if !ban.unbanned => {
+            let ban_service = if let Some(service) = services.get::<BanService>() {
+                service
+            } else {
+                error!("couldn't get ban service!");
+                return Err(
+                    "Could not lift the ban for unknown reasons, this incident has been logged.",
+                );
+            };
+
+            match ban_service
+                .unban_case(&context.http, &ban, reviewer_tag_and_id)
+                .await
+            {
+                Ok(_) | Err(UnbanFailure::UserNotBanned) => (),
+                Err(UnbanFailure::Unauthorized) => {
+                    return Err("I don't have enough permissions to do this action!");
+                }
+                Err(UnbanFailure::Unknown) => {
+                    return Err(
+                        "Could not lift the ban for unknown reasons, this incident has been logged.",
+                    );
+                }
+            }
+
+            ban.unbanned = true;
+            (Infraction::Ban(ban), ModLogAction::Unban)
+        }
+        Some(Infraction::Mute(mut mute)) if !mute.unmuted => {
+            let mute_service = if let Some(service) = services.get::<MuteService>() {
+                service
+            } else {
+                error!("couldn't get mute service!");
+                return Err(
+                    "Could not lift the mute for unknown reasons, this incident has been logged.",
+                );
+            };
+
+            match mute_service
+                .unmute_case(&context.http, services, &mute, reviewer_tag_and_id)
+                .await
+            {
+                Ok(_) => (),
+                Err(UnmuteFailure::RoleNotFound) => {
+                    return Err(
+                        "Could not find the mute role, please pick one on the web dashboard.",
+                    );
+                }
+                Err(UnmuteFailure::Unauthorized) => {
+                    return Err("I don't have enough permissions to do this action!");
+                }
+                Err(UnmuteFailure::Unknown) => {
+                    return Err(
+                        "Could not lift the mute for unknown reasons, this incident has been logged.",
+                    );
+                }
+            }
+
+            mute.unmuted = true;
+            (Infraction::Mute(mute), ModLogAction::Unmute)
+        }
+        _ => return Ok(()),
+    };
+
+    if let Some(setting_service) = services.get::<SettingService>() {
+        let setting = setting_service.get_setting(guild_id).await;
+        let _ = log_infraction_lift(
+            &context.http,
+            &setting,
+            reviewer_tag_and_id,
+            &infraction,
+            lift_action,
+            Some("Appeal accepted"),
+        )
+        .await;
+    }
+
+    Ok(())
+}
+
+// Accept/Deny buttons under appeals posted to the appeals channel
+#[async_trait]
+impl ComponentHandler for AppealComponent {
+    fn custom_id_prefix(&self) -> &'static str {
+        APPEAL_PREFIX
+    }
+
+    async fn handle_component(
+        &self,
+        context: &Context,
+        interaction: &ComponentInteraction,
+        arguments: &str,
+        _config: &Config,
+        services: &Services,
+    ) -> anyhow::Result<()> {
+        let GuildComponentInteraction {
+            guild_id,
+            permissions,
+        } = verify_guild_component(interaction)?;
+
+        let (is_accepted, appeal_id) = match arguments.split_once(':') {
+            Some((ACCEPT, appeal_id)) => (true, appeal_id.parse::<i32>()?),
+            Some((DENY, appeal_id)) => (false, appeal_id.parse::<i32>()?),
+            _ => bail!(
+                "received an appeal component with unknown arguments {}",
+                arguments
+            ),
+        };
+
+        let appeal_service = if let Some(service) = services.get::<AppealService>() {
+            service
+        } else {
+            bail!("couldn't get appeal service!");
+        };
+
+        let mut appeal = match appeal_service.fetch_appeal(appeal_id).await {
+            Some(appeal) if appeal.guild_id == guild_id.get() as i64 => appeal,
+            _ => {
+                reply_to_component_str(
+                    &context.http,
+                    interaction,
+                    "This appeal no longer exists.",
+                    true,
+                )
+                .await;
+                return Ok(());
+            }
+        };
+
+        if appeal.status != APPEAL_STATUS_PENDING {
+            reply_to_component_str(
+                &context.http,
+                interaction,
+                "This appeal has already been reviewed.",
+                true,
+            )
+            .await;
+            return Ok(());
+        }
+
+        let required_permission = required_permission(appeal.infraction_type);
+        if !permissions.administrator() && !permissions.contains(required_permission) {
+            reply_to_component_str(
+                &context.http,
+                interaction,
+                &format!(
+                    "You don't have enough permissions to review this appeal! Required permission: {}",
+                    required_permission
+                ),
+                true,
+            )
+            .await;
+            return Ok(());
+        }
+
+        let reviewer_tag_and_id = interaction.user.tag_and_id();
+        // the review goes through without a valid user id, only the user's DM is skipped
+        let target_user_id = NonZeroU64::new(appeal.user_id as u64).map(|id| UserId::new(id.get()));
+        if target_user_id.is_none() {
+            warn!("found appeal with invalid user id! id: {}", appeal.id);
+        }
+
+        if is_accepted {
+            if let Err(message) =
+                lift_infraction(context, services, guild_id, &appeal, &reviewer_tag_and_id).await
+            {
+                reply_to_component_str(&context.http, interaction, message, true).await;
+                return Ok(());
+            }
+        }
+
+        appeal.status = if is_accepted {
+            APPEAL_STATUS_ACCEPTED
+        } else {
+            APPEAL_STATUS_DENIED
+        };
+        appeal.reviewer_user_id = interaction.user.id.get() as i64;
+        appeal.review_time = now() as i64;
+
+        if appeal_service.update_appeal(&appeal).await.is_none() {
+            reply_to_component_str(
+                &context.http,
+                interaction,
+                "Could not save the review of this appeal for unknown reasons, this incident has been logged.",
+                true,
+            )
+            .await;
+            return Ok(());
+        }
+
+        let response = CreateInteractionResponse::UpdateMessage(
+            CreateInteractionResponseMessage::new()
+                .embed(create_appeal_embed(&appeal, Some(&reviewer_tag_and_id)))
+                .components(vec![]),
+        );
+
+        let _ = interaction
+            .create_response(&context.http, response)
+            .await
+            .map_err(|err| {
+                error!("failed to update appeal message {}", err);
+                err
+            });
+
+        let guild_name = if let Some(guild_service) = services.get::<GuildService>() {
+            guild_service
+                .get_guild(guild_id)
+                .await
+                .map(|guild| guild.name.clone())
+                .unwrap_or_default()
+        } else {
+            bail!("couldn't get guild service!");
+        };
+
+        if let Some(target_user_id) = target_user_id {
+            notify_user_for_appeal_review(&context.http, target_user_id, &guild_name, &appeal)
+                .await;
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,95 @@
+use std::collections::HashMap;
+
+use anyhow::bail;
+use async_trait::async_trait;
+use serenity::all::{ComponentInteraction, Context};
+use serenity::model::id::GuildId;
+use serenity::model::Permissions;
+
+use crate::config::Config;
+use crate::service::Services;
+
+pub mod appeal;
+pub mod mod_log;
+pub mod report;
+
+// unlike collectors these keep working for messages sent before a restart, custom ids of their
+// components look like "<prefix>:<arguments>" and the prefix picks the handler
+#[async_trait]
+pub trait ComponentHandler {
+    fn custom_id_prefix(&self) -> &'static str;
+    async fn handle_component(
+        &self,
+        context: &Context,
+        interaction: &ComponentInteraction,
+        arguments: &str,
+        config: &Config,
+        services: &Services,
+    ) -> anyhow::Result<()>;
+}
+
+pub struct ComponentHandlers(pub HashMap<&'static str, Box<dyn ComponentHandler + Send + Sync>>);
+
+pub fn get_all_component_handlers() -> ComponentHandlers {
+    let mut handlers_map: HashMap<&'static str, Box<dyn ComponentHandler + Send + Sync>> =
+        HashMap::new();
+    let handlers: [Box<dyn ComponentHandler + Send + Sync>; 3] = [
+        Box::new(appeal::AppealComponent),
+        Box::new(mod_log::ModLogComponent),
+        Box::new(report::ReportComponent),
+    ];
+
+    for handler in handlers {
+        handlers_map.insert(handler.custom_id_prefix(), handler);
+    }
+
+    ComponentHandlers(handlers_map)
+}
+
+impl ComponentHandlers {
+    // returns None for components without a persistent handler, those belong to collectors
+    pub fn find<'a>(
+        &self,
+        custom_id: &'a str,
+    ) -> Option<(&(dyn ComponentHandler + Send + Sync), &'a str)> {
+        let (prefix, arguments) = custom_id.split_once(':')?;
+
+        self.0
+            .get(prefix)
+            .map(|handler| (handler.as_ref(), arguments))
+    }
+}
+
+pub fn create_custom_id(prefix: &str, arguments: &str) -> String {
+    format!("{}:{}", prefix, arguments)
+}
+
+pub struct GuildComponentInteraction {
+    pub guild_id: GuildId,
+    pub permissions: Permissions,
+}
+
+pub fn verify_guild_component(
+    interaction: &ComponentInteraction,
+) -> anyhow::Result<GuildComponentInteraction> {
+    let permissions = if let Some(permissions) = interaction
+        .member
+        .as_ref()
+        .and_then(|member| member.permissions)
+    {
+        permissions
+    } else {
+        bail!("component interaction's member has no permissions field");
+    };
+
+    let guild_id = if let Some(guild_id) = interaction.guild_id {
+        guild_id
+    } else {
+        bail!("component interaction has missing guild id");
+    };
+
+    Ok(GuildComponentInteraction {
+        guild_id,
+        permissions,
+    })
+}
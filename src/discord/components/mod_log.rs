@@ -0,0 +1,406 @@
+use std::num::NonZeroU64;
+
+use anyhow::bail;
+use async_trait::async_trait;
+use serenity::all::{ButtonStyle, ComponentInteraction, Context};
+use serenity::builder::{
+    CreateActionRow, CreateButton, CreateInteractionResponse, CreateInteractionResponseMessage,
+};
+use serenity::model::id::UserId;
+use serenity::model::Permissions;
+use tracing::error;
+
+use crate::config::Config;
+use crate::discord::components::{
+    create_custom_id, verify_guild_component, ComponentHandler, GuildComponentInteraction,
+};
+use crate::discord::slash_commands::send_infraction_history;
+use crate::discord::util::infraction::{
+    fetch_infraction_with_case_number, send_infraction_lift_entry, update_infraction, Infraction,
+};
+use crate::discord::util::mod_log::{create_updated_mod_log_embed, ModLogAction};
+use crate::discord::util::{reply_to_component_str, UserExt};
+use crate::service::ban::{BanService, UnbanFailure};
+use crate::service::mute::{MuteService, UnmuteFailure};
+use crate::service::setting::SettingService;
+use crate::service::Services;
+
+const MOD_LOG_PREFIX: &str = "mod_log";
+const UNBAN: &str = "unban";
+const UNMUTE: &str = "unmute";
+const PARDON: &str = "pardon";
+const HISTORY: &str = "history";
+
+pub struct ModLogComponent;
+
+fn create_buttons<'a>(
+    case_number: i32,
+    target_user_id: UserId,
+    lift_action: Option<&'static str>,
+    can_pardon: bool,
+) -> Vec<CreateActionRow<'a>> {
+    let mut buttons = vec![];
+
+    if let Some(lift_action) = lift_action {
+        let label = if lift_action == UNBAN {
+            "Unban"
+        } else {
+            "Unmute"
+        };
+
+        buttons.push(
+            CreateButton::new(create_custom_id(
+                MOD_LOG_PREFIX,
+                &format!("{}:{}", lift_action, case_number),
+            ))
+            .label(label)
+            .style(ButtonStyle::Primary),
+        );
+    }
+
+    if can_pardon {
+        buttons.push(
+            CreateButton::new(create_custom_id(
+                MOD_LOG_PREFIX,
+                &format!("{}:{}", PARDON, case_number),
+            ))
+            .label("Pardon")
+            .style(ButtonStyle::Primary),
+        );
+    }
+
+    buttons.push(
+        CreateButton::new(create_custom_id(
+            MOD_LOG_PREFIX,
+            &format!("{}:{}", HISTORY, target_user_id),
+        ))
+        .label("History")
+        .style(ButtonStyle::Secondary),
+    );
+
+    vec![CreateActionRow::Buttons(buttons.into())]
+}
+
+// buttons of a freshly created entry
+pub fn create_mod_log_buttons<'a>(
+    action: &ModLogAction,
+    case_number: i32,
+    target_user_id: UserId,
+) -> Vec<CreateActionRow<'a>> {
+    let (lift_action, can_pardon) = match action {
+        // permanent actions aren't meant to be lifted from the mod log
+        ModLogAction::Ban { expiration_time } => (expiration_time.and(Some(UNBAN)), false),
+        ModLogAction::Mute { expiration_time } => (expiration_time.and(Some(UNMUTE)), true),
+        ModLogAction::Kick | ModLogAction::Warn | ModLogAction::Softban => (None, true),
+        ModLogAction::Hardban
+        | ModLogAction::Unban
+        | ModLogAction::Unmute
+        | ModLogAction::Pardon
+        | ModLogAction::Expired
+        | ModLogAction::Lockdown { .. }
+        | ModLogAction::Unlock
+        | ModLogAction::UnlockFailed => (None, false),
+    };
+
+    create_buttons(case_number, target_user_id, lift_action, can_pardon)
+}
+
+// buttons of an entry after one of them was used, based on the current state of its infraction
+pub fn create_buttons_for_infraction<'a>(infraction: &Infraction) -> Vec<CreateActionRow<'a>> {
+    let (lift_action, can_pardon) = match infraction {
+        Infraction::Ban(ban) => ((ban.expires && !ban.unbanned).then_some(UNBAN), false),
+        Infraction::Mute(mute) => (
+            (mute.expires && !mute.unmuted).then_some(UNMUTE),
+            !mute.pardoned,
+        ),
+        Infraction::Kick(_) | Infraction::Softban(_) | Infraction::Warn(_) => {
+            (None, !infraction.is_pardoned())
+        }
+        Infraction::Hardban(_) => (None, false),
+    };
+
+    let target_user_id = if let Some(user_id) = infraction.user_id() {
+        user_id
+    } else {
+        return vec![];
+    };
+
+    create_buttons(
+        infraction.case_number(),
+        target_user_id,
+        lift_action,
+        can_pardon,
+    )
+}
+
+async fn unban(
+    context: &Context,
+    services: &Services,
+    permissions: Permissions,
+    infraction: &Infraction,
+    mod_user_tag_and_id: &str,
+) -> Result<(), String> {
+    if !permissions.administrator() && !permissions.ban_members() {
+        return Err(unauthorized_message(Permissions::BAN_MEMBERS));
+    }
+
+    let ban = match infraction {
+        Infraction::Ban(ban) if !ban.unbanned => ban,
+        Infraction::Ban(_) => return Err("This ban has already been lifted.".into()),
+        _ => return Err("This infraction is not a ban.".into()),
+    };
+
+    let ban_service = if let Some(service) = services.get::<BanService>() {
+        service
+    } else {
+        error!("couldn't get ban service!");
+        return Err(
+            "Could not unban specified user for unknown reasons, this incident has been logged."
+                .into(),
+        );
+    };
+
+    match ban_service
+        .unban_case(&context.http, ban, mod_user_tag_and_id)
+        .await
+    {
+        Ok(_) => Ok(()),
+        Err(UnbanFailure::UserNotBanned) => Err("Specified user is not banned!".into()),
+        Err(UnbanFailure::Unauthorized) => {
+            Err("I don't have enough permissions to do this action!".into())
+        }
+        Err(UnbanFailure::Unknown) => Err(
+            "Could not unban specified user for unknown reasons, this incident has been logged."
+                .into(),
+        ),
+    }
+}
+
+async fn unmute(
+    context: &Context,
+    services: &Services,
+    permissions: Permissions,
+    infraction: &Infraction,
+    mod_user_tag_and_id: &str,
+) -> Result<(), String> {
+    if !permissions.administrator() && !permissions.manage_roles() {
+        return Err(unauthorized_message(Permissions::MANAGE_ROLES));
+    }
+
+    let mute = match infraction {
+        Infraction::Mute(mute) if !mute.unmuted => mute,
+        Infraction::Mute(_) => return Err("This mute has already been lifted.".into()),
+        _ => return Err("This infraction is not a mute.".into()),
+    };
+
+    let mute_service = if let Some(service) = services.get::<MuteService>() {
+        service
+    } else {
+        error!("couldn't get mute service!");
+        return Err(
+            "Could not unmute specified user for unknown reasons, this incident has been logged."
+                .into(),
+        );
+    };
+
+    match mute_service
+        .unmute_case(&context.http, services, mute, mod_user_tag_and_id)
+        .await
+    {
+        Ok(_) => Ok(()),
+        Err(UnmuteFailure::RoleNotFound) => Err("Could not find the mute role, please pick one on the web dashboard or mute a user to set it up automatically.".into()),
+        Err(UnmuteFailure::Unauthorized) => {
+            Err("I don't have enough permissions to do this action!".into())
+        }
+        Err(UnmuteFailure::Unknown) => Err(
+            "Could not unmute specified user for unknown reasons, this incident has been logged."
+                .into(),
+        ),
+    }
+}
+
+async fn pardon(
+    services: &Services,
+    permissions: Permissions,
+    mut infraction: Infraction,
+) -> Result<(), String> {
+    let required_permission = infraction.required_permission();
+    if !permissions.administrator() && !permissions.contains(required_permission) {
+        return Err(unauthorized_message(required_permission));
+    }
+
+    if infraction.is_pardoned() {
+        return Err("This infraction is already pardoned.".into());
+    }
+
+    infraction.pardon()?;
+
+    if update_infraction(services, infraction).await.is_none() {
+        return Err(
+            "Could not pardon specified infraction for unknown reasons, this incident has been logged."
+                .into(),
+        );
+    }
+
+    Ok(())
+}
+
+fn unauthorized_message(required_permission: Permissions) -> String {
+    format!(
+        "You don't have enough permissions to do this action! Required permission: {}",
+        required_permission
+    )
+}
+
+// Unban/Unmute/Pardon/History buttons under mod log entries
+#[async_trait]
+impl ComponentHandler for ModLogComponent {
+    fn custom_id_prefix(&self) -> &'static str {
+        MOD_LOG_PREFIX
+    }
+
+    async fn handle_component(
+        &self,
+        context: &Context,
+        interaction: &ComponentInteraction,
+        arguments: &str,
+        _config: &Config,
+        services: &Services,
+    ) -> anyhow::Result<()> {
+        let GuildComponentInteraction {
+            guild_id,
+            permissions,
+        } = verify_guild_component(interaction)?;
+
+        let (action, argument) = if let Some(split) = arguments.split_once(':') {
+            split
+        } else {
+            bail!(
+                "received a mod log component with unknown arguments {}",
+                arguments
+            );
+        };
+
+        if action == HISTORY {
+            let target_user_id = UserId::new(argument.parse::<NonZeroU64>()?.get());
+            let target_user = target_user_id.to_user(context).await?;
+
+            return send_infraction_history(
+                context,
+                interaction,
+                guild_id,
+                permissions,
+                services,
+                &target_user,
+            )
+            .await;
+        }
+
+        let case_number = argument.parse::<i32>()?;
+
+        let infraction = if let Some(infraction) =
+            fetch_infraction_with_case_number(services, guild_id, case_number).await?
+        {
+            infraction
+        } else {
+            reply_to_component_str(
+                &context.http,
+                interaction,
+                "Infraction with given case number doesn't exist!",
+                true,
+            )
+            .await;
+            return Ok(());
+        };
+
+        let mod_user_tag_and_id = interaction.user.tag_and_id();
+
+        let (result, lift_action) = match action {
+            UNBAN => (
+                unban(
+                    context,
+                    services,
+                    permissions,
+                    &infraction,
+                    &mod_user_tag_and_id,
+                )
+                .await,
+                ModLogAction::Unban,
+            ),
+            UNMUTE => (
+                unmute(
+                    context,
+                    services,
+                    permissions,
+                    &infraction,
+                    &mod_user_tag_and_id,
+                )
+                .await,
+                ModLogAction::Unmute,
+            ),
+            PARDON => (
+                pardon(services, permissions, infraction).await,
+                ModLogAction::Pardon,
+            ),
+            _ => bail!(
+                "received a mod log component with unknown action {}",
+                action
+            ),
+        };
+
+        if let Err(message) = result {
+            reply_to_component_str(&context.http, interaction, &message, true).await;
+            return Ok(());
+        }
+
+        let infraction = fetch_infraction_with_case_number(services, guild_id, case_number).await?;
+
+        // buttons that no longer apply are dropped, so the entry reflects the updated infraction
+        let components = match &infraction {
+            Some(infraction) => create_buttons_for_infraction(infraction),
+            None => vec![],
+        };
+
+        let mut message = CreateInteractionResponseMessage::new().components(components);
+
+        // pardoned infractions still stand, only lifted ones are struck through
+        let is_lifted = !matches!(lift_action, ModLogAction::Pardon);
+        if let Some(embed) = interaction.message.embeds.first().and_then(|embed| {
+            create_updated_mod_log_embed(
+                embed,
+                &mod_user_tag_and_id,
+                lift_action.past_tense(),
+                is_lifted,
+            )
+        }) {
+            message = message.embed(embed);
+        }
+
+        let response = CreateInteractionResponse::UpdateMessage(message);
+
+        let _ = interaction
+            .create_response(&context.http, response)
+            .await
+            .map_err(|err| {
+                error!("failed to update mod log entry {}", err);
+                err
+            });
+
+        if let (Some(infraction), Some(setting_service)) =
+            (infraction, services.get::<SettingService>())
+        {
+            let setting = setting_service.get_setting(guild_id).await;
+            let _ = send_infraction_lift_entry(
+                &context.http,
+                &setting,
+                &mod_user_tag_and_id,
+                &infraction,
+                lift_action,
+                None,
+            )
+            .await;
+        }
+
+        Ok(())
+    }
+}
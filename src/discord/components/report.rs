@@ -0,0 +1,401 @@
+use std::num::NonZeroU64;
+
+use anyhow::bail;
+use async_trait::async_trait;
+use serenity::all::{ButtonStyle, ComponentInteraction, Context, GenericChannelId};
+use serenity::builder::{
+    CreateActionRow, CreateButton, CreateInteractionResponse, CreateInteractionResponseMessage,
+};
+use serenity::model::id::{GuildId, MessageId, UserId};
+use serenity::model::Permissions;
+use tracing::error;
+
+use crate::config::Config;
+use crate::database::reports::{
+    Report, REPORT_STATUS_CLAIMED, REPORT_STATUS_DISMISSED, REPORT_STATUS_OPEN,
+    REPORT_STATUS_RESOLVED,
+};
+use crate::discord::components::{
+    create_custom_id, verify_guild_component, ComponentHandler, GuildComponentInteraction,
+};
+use crate::discord::util::hierarchy::check_role_hierarchy;
+use crate::discord::util::report::{create_report_embed, notify_reporters_for_resolution};
+use crate::discord::util::{is_staff, reply_to_component_str, SerenityErrorExt, UserExt};
+use crate::service::evidence::EvidenceSnapshot;
+use crate::service::guild::GuildService;
+use crate::service::report::{ReportService, UpdateReportFailure};
+use crate::service::setting::SettingService;
+use crate::service::warn::{WarnFailure, WarnService};
+use crate::service::Services;
+use crate::util::now;
+
+const REPORT_PREFIX: &str = "report";
+const CLAIM: &str = "claim";
+const RESOLVE: &str = "resolve";
+const DISMISS: &str = "dismiss";
+const TAKE_ACTION: &str = "action";
+
+pub struct ReportComponent;
+
+fn create_button<'a>(
+    action: &str,
+    report_id: i32,
+    label: &'a str,
+    style: ButtonStyle,
+) -> CreateButton<'a> {
+    CreateButton::new(create_custom_id(
+        REPORT_PREFIX,
+        &format!("{}:{}", action, report_id),
+    ))
+    .label(label)
+    .style(style)
+}
+
+// handled reports keep their entry but lose the buttons
+pub fn create_report_buttons<'a>(report: &Report) -> Vec<CreateActionRow<'a>> {
+    if !report.is_open() {
+        return vec![];
+    }
+
+    let mut buttons = vec![];
+
+    if report.status == REPORT_STATUS_OPEN {
+        buttons.push(create_button(
+            CLAIM,
+            report.id,
+            "Claim",
+            ButtonStyle::Primary,
+        ));
+    }
+
+    buttons.push(create_button(
+        RESOLVE,
+        report.id,
+        "Resolve",
+        ButtonStyle::Success,
+    ));
+    buttons.push(create_button(
+        DISMISS,
+        report.id,
+        "Dismiss",
+        ButtonStyle::Secondary,
+    ));
+    buttons.push(create_button(
+        TAKE_ACTION,
+        report.id,
+        "Take action",
+        ButtonStyle::Danger,
+    ));
+
+    vec![CreateActionRow::Buttons(buttons.into())]
+}
+
+fn unauthorized_message(required_permission: Permissions) -> String {
+    format!(
+        "You don't have enough permissions to do this action! Required permission: {}",
+        required_permission
+    )
+}
+
+// deletes the reported message and warns its author, escalation steps take it from there
+async fn take_action(
+    context: &Context,
+    services: &Services,
+    guild_id: GuildId,
+    permissions: Permissions,
+    report: &Report,
+    interaction: &ComponentInteraction,
+) -> Result<(), String> {
+    if !permissions.administrator() && !permissions.kick_members() {
+        return Err(unauthorized_message(Permissions::KICK_MEMBERS));
+    }
+
+    let ids = (
+        NonZeroU64::new(report.target_user_id as u64),
+        NonZeroU64::new(report.channel_id as u64),
+        NonZeroU64::new(report.message_id as u64),
+    );
+
+    let (target_user_id, channel_id, message_id) =
+        if let (Some(user_id), Some(channel_id), Some(message_id)) = ids {
+            (
+                UserId::new(user_id.get()),
+                GenericChannelId::new(channel_id.get()),
+                MessageId::new(message_id.get()),
+            )
+        } else {
+            error!("found report with invalid ids! {:?}", report);
+            return Err(
+                "Could not take action for unknown reasons, this incident has been logged.".into(),
+            );
+        };
+
+    let mod_user = &interaction.user;
+
+    if target_user_id == mod_user.id {
+        return Err("You can't warn yourself, dummy!".into());
+    }
+
+    let target_user = target_user_id
+        .to_user(context)
+        .await
+        .map_err(|_err| "Could not find the reported user!".to_string())?;
+
+    let guild_service = if let Some(service) = services.get::<GuildService>() {
+        service
+    } else {
+        error!("couldn't get guild service!");
+        return Err(
+            "Could not take action for unknown reasons, this incident has been logged.".into(),
+        );
+    };
+
+    let guild = guild_service
+        .get_guild(guild_id)
+        .await
+        .map_err(|_err| "Could not fetch the server, please try again later.".to_string())?;
+
+    if let Err(failure) =
+        check_role_hierarchy(guild_service, guild_id, &guild, mod_user.id, target_user_id).await
+    {
+        return Err(failure.to_interaction_response("warn"));
+    }
+
+    match channel_id
+        .delete_message(
+            &context.http,
+            message_id,
+            Some(&format!("Reported message, report #{}", report.id)),
+        )
+        .await
+    {
+        // author or another moderator might have deleted it already
+        Ok(_) => (),
+        Err(err) if matches!(err.discord_error_code(), Some(10003) | Some(10008)) => (),
+        Err(err) if err.discord_error_code() == Some(50013) => {
+            return Err("I don't have enough permissions to do this action!".into());
+        }
+        Err(err) => {
+            error!("failed to delete reported message {}", err);
+            return Err(
+                "Could not delete the reported message for unknown reasons, this incident has been logged."
+                    .into(),
+            );
+        }
+    }
+
+    let (warn_service, setting_service) = match (
+        services.get::<WarnService>(),
+        services.get::<SettingService>(),
+    ) {
+        (Some(warn_service), Some(setting_service)) => (warn_service, setting_service),
+        _ => {
+            error!("couldn't get warn or setting service!");
+            return Err(
+                "Could not take action for unknown reasons, this incident has been logged.".into(),
+            );
+        }
+    };
+
+    let setting = setting_service.get_setting(guild_id).await;
+
+    match warn_service
+        .issue_warn(
+            &context.http,
+            guild_id,
+            &guild.name,
+            &setting,
+            services,
+            Some(channel_id),
+            mod_user.id,
+            &mod_user.tag_and_id(),
+            &target_user,
+            format!("Reported message, report #{}", report.id),
+            &[EvidenceSnapshot::from_report(report)],
+            0,
+        )
+        .await
+    {
+        // warn is issued even if its mod log entry couldn't be created
+        Ok(_) | Err(WarnFailure::ModLogError(_)) => Ok(()),
+        Err(WarnFailure::Unknown) => Err(
+            "Could not warn the reported user for unknown reasons, this incident has been logged."
+                .into(),
+        ),
+    }
+}
+
+// Claim/Resolve/Dismiss/Take action buttons under reports posted to the report channel
+#[async_trait]
+impl ComponentHandler for ReportComponent {
+    fn custom_id_prefix(&self) -> &'static str {
+        REPORT_PREFIX
+    }
+
+    async fn handle_component(
+        &self,
+        context: &Context,
+        interaction: &ComponentInteraction,
+        arguments: &str,
+        _config: &Config,
+        services: &Services,
+    ) -> anyhow::Result<()> {
+        let GuildComponentInteraction {
+            guild_id,
+            permissions,
+        } = verify_guild_component(interaction)?;
+
+        let (action, report_id) = match arguments.split_once(':') {
+            Some((action, report_id)) => (action, report_id.parse::<i32>()?),
+            None => bail!(
+                "received a report component with unknown arguments {}",
+                arguments
+            ),
+        };
+
+        if !is_staff(permissions) {
+            reply_to_component_str(
+                &context.http,
+                interaction,
+                "Only staff members can handle reports!",
+                true,
+            )
+            .await;
+            return Ok(());
+        }
+
+        let report_service = if let Some(service) = services.get::<ReportService>() {
+            service
+        } else {
+            bail!("couldn't get report service!");
+        };
+
+        let mut report = match report_service.fetch_report(report_id).await {
+            Some(report) if report.guild_id == guild_id.get() as i64 => report,
+            _ => {
+                reply_to_component_str(
+                    &context.http,
+                    interaction,
+                    "This report no longer exists.",
+                    true,
+                )
+                .await;
+                return Ok(());
+            }
+        };
+
+        if !report.is_open() {
+            reply_to_component_str(
+                &context.http,
+                interaction,
+                "This report has already been handled.",
+                true,
+            )
+            .await;
+            return Ok(());
+        }
+
+        // restored if taking action fails
+        let previous_report = report.clone();
+
+        match action {
+            CLAIM => {
+                if report.status == REPORT_STATUS_CLAIMED {
+                    reply_to_component_str(
+                        &context.http,
+                        interaction,
+                        "This report has already been claimed.",
+                        true,
+                    )
+                    .await;
+                    return Ok(());
+                }
+
+                report.status = REPORT_STATUS_CLAIMED;
+            }
+            RESOLVE => report.status = REPORT_STATUS_RESOLVED,
+            DISMISS => report.status = REPORT_STATUS_DISMISSED,
+            TAKE_ACTION => {
+                report.status = REPORT_STATUS_RESOLVED;
+                report.action_taken = true;
+            }
+            _ => bail!("received a report component with unknown action {}", action),
+        }
+
+        report.moderator_user_id = interaction.user.id.get() as i64;
+        report.update_time = now() as i64;
+        report.report_message_id = interaction.message.id.get() as i64;
+
+        // saved before taking action, so moderators pressing buttons at the same time can't both
+        // act on the report
+        let message = match report_service.update_report(&report).await {
+            Ok(()) => None,
+            Err(UpdateReportFailure::AlreadyHandled) => {
+                Some("This report has already been handled.")
+            }
+            Err(UpdateReportFailure::Unknown) => Some(
+                "Could not update this report for unknown reasons, this incident has been logged.",
+            ),
+        };
+
+        if let Some(message) = message {
+            reply_to_component_str(&context.http, interaction, message, true).await;
+            return Ok(());
+        }
+
+        if action == TAKE_ACTION {
+            if let Err(message) = take_action(
+                context,
+                services,
+                guild_id,
+                permissions,
+                &report,
+                interaction,
+            )
+            .await
+            {
+                report_service.reopen_report(&previous_report).await;
+                reply_to_component_str(&context.http, interaction, &message, true).await;
+                return Ok(());
+            }
+        }
+
+        let response = CreateInteractionResponse::UpdateMessage(
+            CreateInteractionResponseMessage::new()
+                .embed(create_report_embed(&report))
+                .components(create_report_buttons(&report)),
+        );
+
+        let _ = interaction
+            .create_response(&context.http, response)
+            .await
+            .map_err(|err| {
+                error!("failed to update report message {}", err);
+                err
+            });
+
+        if report.status == REPORT_STATUS_RESOLVED {
+            let setting = if let Some(service) = services.get::<SettingService>() {
+                service.get_setting(guild_id).await
+            } else {
+                bail!("couldn't get setting service!");
+            };
+
+            if setting.report_notify_reporter {
+                let guild_name = if let Some(guild_service) = services.get::<GuildService>() {
+                    guild_service
+                        .get_guild(guild_id)
+                        .await
+                        .map(|guild| guild.name.clone())
+                        .unwrap_or_default()
+                } else {
+                    bail!("couldn't get guild service!");
+                };
+
+                notify_reporters_for_resolution(&context.http, &guild_name, &report).await;
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -5,13 +5,13 @@ use std::time::Duration;
 
 use serenity::all::{
     Command, CommandType, ComponentInteractionCollector, ComponentInteractionDataKind, Context,
-    CreateCommand, CreateEmbed, CreateInteractionResponse, CreateInteractionResponseMessage,
+    CreateCommand, CreateInteractionResponse, CreateInteractionResponseMessage,
     CreateSelectMenuOption, Event, EventHandler, FullEvent, GenericChannelId, Interaction,
     RawEventHandler,
 };
 use serenity::async_trait;
 use serenity::builder::{
-    AutocompleteChoice, CreateAutocompleteResponse, CreateEmbedFooter,
+    AutocompleteChoice, CreateAutocompleteResponse,
     CreateMessage, CreateSelectMenu, CreateSelectMenuKind, EditInteractionResponse,
 };
 use serenity::gateway::ActivityData;
@@ -22,7 +22,6 @@ use serenity::model::gateway::{GatewayIntents, Ready};
 use serenity::model::guild::{Guild, Member, PartialGuild, Role, UnavailableGuild};
 use serenity::model::id::GuildId;
 use serenity::model::user::{CurrentUser, User};
-use serenity::model::Color;
 use serenity::prelude::Mentionable;
 use serenity::Client;
 use simsearch::{SearchOptions, SimSearch};
@@ -31,20 +30,24 @@ use tracing::{error, warn};
 use crate::config::Config;
 use crate::constants::PROGRAMMING_LANGUAGES;
 use crate::discord;
+use crate::discord::components::{get_all_component_handlers, ComponentHandlers};
 use crate::discord::message_processors::{get_all_processors, MessageProcessors};
 use crate::discord::scheduled::run_scheduled_tasks;
 use crate::discord::slash_commands::SlashCommands;
+use crate::discord::util::report::{get_report_channel_id, post_report, update_report_entry};
 use crate::discord::util::{
     defer_interaction, edit_deferred_interaction_response, reply_to_interaction_str,
     verify_guild_message_create, verify_guild_message_update, CommandDataExt, GuildMessageCreated,
-    GuildMessageUpdated, UserExt,
+    GuildMessageUpdated,
 };
 use crate::service::guild::GuildService;
 use crate::service::guild_statistic::GuildStatisticService;
 use crate::service::join::JoinService;
 use crate::service::mute::MuteService;
+use crate::service::report::{ReportService, SubmitReportFailure};
 use crate::service::setting::SettingService;
 use crate::service::tag::TagService;
+use crate::service::temp_role::TempRoleService;
 use crate::service::watchdog::WatchdogService;
 use crate::service::Services;
 use crate::util::Shutdown;
@@ -65,6 +68,7 @@ impl DiscordBot {
             config: config.clone(),
             services: services.clone(),
             slash_commands,
+            components: get_all_component_handlers(),
             message_processors: get_all_processors(),
         };
 
@@ -113,6 +117,7 @@ impl RawEventHandler for DiscordRawEventHandler {
 struct DiscordEventHandler {
     config: Arc<Config>,
     slash_commands: SlashCommands,
+    components: ComponentHandlers,
     message_processors: MessageProcessors,
     services: Arc<Services>,
 }
@@ -132,6 +137,7 @@ pub async fn initialize_slash_commands(
                 [
                     CreateCommand::new("Report").kind(CommandType::Message),
                     CreateCommand::new("Format Code").kind(CommandType::Message),
+                    CreateCommand::new("View Infractions").kind(CommandType::User),
                 ]
                 .into_iter(),
             )
@@ -143,12 +149,18 @@ pub async fn initialize_slash_commands(
 }
 
 impl DiscordEventHandler {
-    async fn channel_create(&self, new: &GuildChannel) {
+    async fn channel_create(&self, ctx: &Context, new: &GuildChannel) {
         if let Some(guild_service) = self.services.get::<GuildService>() {
             guild_service
                 .invalidate_cached_guild_channels(new.base.guild_id)
                 .await;
         }
+
+        if let Some(mute_service) = self.services.get::<MuteService>() {
+            mute_service
+                .reconcile_channel_mute_overwrite(&ctx.http, &self.services, new)
+                .await;
+        }
     }
 
     async fn channel_delete(&self, new: &GuildChannel) {
@@ -209,7 +221,13 @@ impl DiscordEventHandler {
             return;
         };
 
-        if setting.invite_link_remover && new_member.user.name.contains("discord.gg/") {
+        if setting.invite_link_remover
+            && self
+                .message_processors
+                .find_disallowed_invite(&ctx.http, guild_id, &new_member.user.name, &setting)
+                .await
+                .is_some()
+        {
             let _ = guild_id
                 .kick(
                     &ctx.http,
@@ -281,6 +299,12 @@ impl DiscordEventHandler {
             }
         }
 
+        if let Some(temp_role_service) = self.services.get::<TempRoleService>() {
+            temp_role_service
+                .reapply_temp_roles(&ctx.http, guild_id, new_member.user.id)
+                .await;
+        }
+
         let mute_service = if let Some(service) = self.services.get::<MuteService>() {
             service
         } else {
@@ -295,29 +319,40 @@ impl DiscordEventHandler {
             return;
         }
 
-        let role_id = match mute_service
-            .fetch_muted_role_id(&ctx.http, &self.services, guild_id)
+        // an indefinite record keeps the member muted indefinitely
+        let expiration_time = if mute_records.iter().any(|mute| !mute.expires) {
+            None
+        } else {
+            mute_records
+                .iter()
+                .map(|mute| mute.expire_time as u64)
+                .max()
+        };
+
+        let timeout_expire_time = match mute_service
+            .apply_mute(
+                &ctx.http,
+                &self.services,
+                &setting,
+                guild_id,
+                new_member.user.id,
+                expiration_time,
+                "Preventing mute evasion",
+            )
             .await
         {
-            Ok(role) => role,
+            Ok(timeout_expire_time) => timeout_expire_time,
             Err(_) => {
                 return;
             }
         };
 
-        let _ = ctx
-            .http
-            .add_member_role(
-                guild_id,
-                new_member.user.id,
-                role_id,
-                Some("Preventing mute evasion"),
-            )
-            .await
-            .map_err(|err| {
-                error!("failed to issue discord member role add {}", err);
-                err
-            });
+        // the guild may have switched backends since the mute was issued
+        for mute in mute_records {
+            mute_service
+                .update_mute_backend(mute.id, setting.mute_backend, timeout_expire_time as i64)
+                .await;
+        }
     }
 
     async fn guild_member_removal(&self, guild_id: GuildId, kicked: &User) {
@@ -423,29 +458,21 @@ impl DiscordEventHandler {
             return;
         };
 
-        for (i, processor) in self.message_processors.0.iter().enumerate() {
-            match processor
-                .handle_message(
-                    ctx,
-                    &message.content,
-                    guild_id,
-                    message.channel_id,
-                    message.id,
-                    &message.author,
-                    permissions,
-                    &setting,
-                    &self.services,
-                )
-                .await
-            {
-                Ok(true) => break,
-                Ok(false) => continue,
-                Err(err) => {
-                    error!("failed to run message processor id: {}, err: {}", i, err);
-                    break;
-                }
-            }
-        }
+        self.message_processors
+            .process(
+                ctx,
+                &message.content,
+                &message.attachments,
+                guild_id,
+                message.channel_id,
+                message.id,
+                &message.author,
+                &member.roles,
+                permissions,
+                &setting,
+                &self.services,
+            )
+            .await;
     }
 
     async fn message_update(&self, ctx: &Context, event: &MessageUpdateEvent) {
@@ -496,29 +523,21 @@ impl DiscordEventHandler {
             return;
         };
 
-        for (i, processor) in self.message_processors.0.iter().enumerate() {
-            match processor
-                .handle_message(
-                    ctx,
-                    content,
-                    guild_id,
-                    message.channel_id,
-                    message.id,
-                    &message.author,
-                    permissions,
-                    &setting,
-                    &self.services,
-                )
-                .await
-            {
-                Ok(true) => break,
-                Ok(false) => continue,
-                Err(err) => {
-                    error!("failed to run message processor id: {}, err: {}", i, err);
-                    break;
-                }
-            }
-        }
+        self.message_processors
+            .process(
+                ctx,
+                content,
+                &message.attachments,
+                guild_id,
+                message.channel_id,
+                message.id,
+                &message.author,
+                &member.roles,
+                permissions,
+                &setting,
+                &self.services,
+            )
+            .await;
     }
 
     async fn ready(&self, ctx: &Context, data_about_bot: &Ready) {
@@ -593,6 +612,26 @@ impl DiscordEventHandler {
             return;
         }
 
+        if let Interaction::Component(component) = interaction {
+            let custom_id = component.data.custom_id.as_str();
+            if let Some((handler, arguments)) = self.components.find(custom_id) {
+                if let Err(err) = handler
+                    .handle_component(
+                        ctx,
+                        component,
+                        arguments,
+                        &self.config,
+                        self.services.as_ref(),
+                    )
+                    .await
+                {
+                    error!(custom_id = custom_id, "failed to handle component {}", err);
+                }
+            }
+
+            return;
+        }
+
         if let Interaction::Command(command) = interaction {
             // TODO(sam): maybe remove this check later and rely on dm_permission field of command
             let (guild_id, member) = match (command.guild_id, &command.member) {
@@ -656,36 +695,61 @@ impl DiscordEventHandler {
                             return;
                         }
 
-                        let message_link = target_message
-                            .id
-                            .link(target_message.channel_id, Some(guild_id));
-
-                        let embed = CreateEmbed::default()
-                            .color(Color::new(0xFF2900))
-                            .timestamp(target_message.timestamp)
-                            .title("Message Report")
-                            .field("Reporter:", member.user.tag_and_id(), false)
-                            .field("Reported:", target_message.author.tag_and_id(), false)
-                            .field("Message Link:", format!("{}", message_link), false)
-                            .footer(CreateEmbedFooter::new("Message sent on"));
-
-                        let message = CreateMessage::default().add_embed(embed);
+                        if get_report_channel_id(&setting).is_none() {
+                            reply_to_interaction_str(
+                                &ctx.http,
+                                command,
+                                "This server doesn't have reporting enabled!",
+                                true,
+                            )
+                            .await;
+                            return;
+                        }
 
-                        let report_channel_id =
-                            if let Some(id) = NonZeroU64::new(setting.report_channel_id as u64) {
-                                GenericChannelId::new(id.get())
+                        let report_service =
+                            if let Some(service) = self.services.get::<ReportService>() {
+                                service
                             } else {
+                                return;
+                            };
+
+                        let (mut report, is_new) = match report_service
+                            .submit_report(guild_id, target_message, member.user.id)
+                            .await
+                        {
+                            Ok(result) => result,
+                            Err(SubmitReportFailure::AlreadyReported) => {
                                 reply_to_interaction_str(
                                     &ctx.http,
                                     command,
-                                    "This server doesn't have reporting enabled!",
+                                    "You have already reported this message.",
                                     true,
                                 )
                                 .await;
                                 return;
-                            };
+                            }
+                            Err(SubmitReportFailure::Unknown) => {
+                                reply_to_interaction_str(
+                                    &ctx.http,
+                                    command,
+                                    "Could not report this message for unknown reasons, this incident has been logged.",
+                                    true,
+                                )
+                                .await;
+                                return;
+                            }
+                        };
+
+                        // repeated reports only bump the existing entry, unless it was deleted
+                        if is_new || !update_report_entry(&ctx.http, &setting, &report).await {
+                            if let Some(message_id) =
+                                post_report(&ctx.http, &setting, &report).await
+                            {
+                                report.report_message_id = message_id.get() as i64;
+                                let _ = report_service.update_report(&report).await;
+                            }
+                        }
 
-                        let _ = report_channel_id.send_message(&ctx.http, message).await;
                         reply_to_interaction_str(&ctx.http, command, "Reported.", true).await;
                     } else if name == "Format Code" {
                         if target_message.content.is_empty() {
@@ -801,6 +865,34 @@ impl DiscordEventHandler {
                         );
                     }
                 }
+                CommandType::User => {
+                    let name = &command.data.name;
+                    let target_user = match command.data.resolved.users.iter().next() {
+                        Some(user) => user,
+                        None => {
+                            error!(
+                                "received a user command without targeted user {:?}",
+                                &command
+                            );
+                            return;
+                        }
+                    };
+
+                    if name == "View Infractions" {
+                        if let Err(err) = discord::slash_commands::show_infraction_history(
+                            ctx,
+                            command,
+                            self.services.as_ref(),
+                            target_user,
+                        )
+                        .await
+                        {
+                            error!("failed to handle user command {}", err);
+                        }
+                    } else {
+                        error!("received a user command with unknown name {:?}", &command);
+                    }
+                }
                 _ => {
                     error!("received an unknown command {:?}", &command);
                 }
@@ -814,7 +906,7 @@ impl EventHandler for DiscordEventHandler {
     async fn dispatch(&self, context: &Context, event: &FullEvent) {
         match event {
             FullEvent::ChannelCreate { channel } => {
-                self.channel_create(channel).await;
+                self.channel_create(context, channel).await;
             }
             FullEvent::ChannelDelete { channel, .. } => {
                 self.channel_delete(channel).await;
@@ -1,28 +1,150 @@
+use std::collections::HashSet;
+use std::sync::Arc;
+use std::time::Duration;
+
 use anyhow::bail;
 use async_trait::async_trait;
-use serenity::all::{Context, GenericChannelId};
+use moka::future::{Cache, CacheBuilder};
+use serenity::all::{Attachment, Context, GenericChannelId};
+use serenity::http::Http;
 use serenity::model::id::{GuildId, MessageId};
 use serenity::model::user::User;
 use serenity::model::Permissions;
-use tracing::error;
+use smol_str::SmolStr;
+use tracing::{error, warn};
 
 use crate::constants::{JIM_ID, JIM_ID_AND_TAG};
 use crate::database::settings::{get_action_duration_for_auto_mod_action, Setting};
-use crate::discord::message_processors::MessageProcessor;
+use crate::discord::message_processors::{MessageProcessor, ProcessorExemptions};
+use crate::discord::util::invite::find_unlisted_invite_codes;
 use crate::discord::util::{execute_mod_action, is_staff, SerenityErrorExt};
+use crate::service::evidence::EvidenceSnapshot;
 use crate::service::guild::GuildService;
 use crate::service::Services;
 
 const REASON: &str = "Sending invite links";
+// codes that aren't one of the guild's own invites are looked up one by one, spammers can put
+// dozens of them in a single message
+const MAX_INVITE_LOOKUPS: usize = 3;
+
+// clones share the caches
+#[derive(Clone)]
+pub struct InviteLinkProcessor {
+    // codes of the guild's own invites, including its vanity url
+    guild_invite_cache: Cache<GuildId, Arc<HashSet<SmolStr>>>,
+    // guild each invite code points to, None if the invite doesn't exist
+    invite_guild_cache: Cache<SmolStr, Option<GuildId>>,
+}
+
+impl InviteLinkProcessor {
+    pub fn new() -> InviteLinkProcessor {
+        InviteLinkProcessor {
+            guild_invite_cache: CacheBuilder::new(1000)
+                .time_to_live(Duration::from_secs(10 * 60))
+                .build(),
+            invite_guild_cache: CacheBuilder::new(10000)
+                .time_to_live(Duration::from_secs(60 * 60))
+                .build(),
+        }
+    }
+
+    // listing invites requires the manage server permission, without it only the vanity url and
+    // individual lookups are left
+    async fn get_guild_invites(&self, http: &Http, guild_id: GuildId) -> Arc<HashSet<SmolStr>> {
+        if let Some(codes) = self.guild_invite_cache.get(&guild_id).await {
+            return codes;
+        }
+
+        let mut codes = HashSet::new();
+
+        match guild_id.invites(http).await {
+            Ok(invites) => codes.extend(invites.iter().map(|invite| SmolStr::new(&*invite.code))),
+            Err(err) => warn!("failed to fetch invites of guild: {} {}", guild_id, err),
+        }
+
+        if let Ok(vanity_code) = guild_id.vanity_url(http).await {
+            codes.insert(SmolStr::new(vanity_code));
+        }
+
+        let codes = Arc::new(codes);
+        self.guild_invite_cache
+            .insert(guild_id, codes.clone())
+            .await;
+        codes
+    }
+
+    async fn get_invite_guild(&self, http: &Http, code: &SmolStr) -> Option<GuildId> {
+        if let Some(guild_id) = self.invite_guild_cache.get(code).await {
+            return guild_id;
+        }
+
+        // invites that can't be resolved, e.g. expired ones or dsc.gg links, are treated like
+        // invites to other guilds
+        let guild_id = http
+            .get_invite(code, false, false, None)
+            .await
+            .ok()
+            .and_then(|invite| invite.guild)
+            .map(|guild| guild.id);
 
-pub struct InviteLinkProcessor;
+        self.invite_guild_cache.insert(code.clone(), guild_id).await;
+        guild_id
+    }
+
+    // invites to the guild itself are always allowed, any other invite has to be allowlisted.
+    // codes past the lookup limit can't be verified, so they are treated like other guilds' invites
+    pub async fn find_disallowed_invite(
+        &self,
+        http: &Http,
+        guild_id: GuildId,
+        message_content: &str,
+        setting: &Setting,
+    ) -> Option<SmolStr> {
+        let codes = find_unlisted_invite_codes(
+            message_content,
+            setting.invite_link_remover_allowlist.as_deref(),
+        );
+
+        if codes.is_empty() {
+            return None;
+        }
+
+        let guild_invites = self.get_guild_invites(http, guild_id).await;
+
+        let mut lookups = 0;
+        for code in codes {
+            if guild_invites.contains(&code) {
+                continue;
+            }
+
+            if lookups >= MAX_INVITE_LOOKUPS {
+                return Some(code);
+            }
+
+            lookups += 1;
+            if self.get_invite_guild(http, &code).await != Some(guild_id) {
+                return Some(code);
+            }
+        }
+
+        None
+    }
+}
 
 #[async_trait]
 impl MessageProcessor for InviteLinkProcessor {
+    fn exemptions<'a>(&self, setting: &'a Setting) -> ProcessorExemptions<'a> {
+        ProcessorExemptions {
+            channel_ids: &setting.invite_link_remover_exempt_channel_ids,
+            role_ids: &setting.invite_link_remover_exempt_role_ids,
+        }
+    }
+
     async fn handle_message(
         &self,
         context: &Context,
         message_content: &str,
+        attachments: &[Attachment],
         guild_id: GuildId,
         channel_id: GenericChannelId,
         message_id: MessageId,
@@ -39,9 +161,14 @@ impl MessageProcessor for InviteLinkProcessor {
             return Ok(false);
         }
 
-        if !message_content.contains("discord.gg/") {
+        let code = if let Some(code) = self
+            .find_disallowed_invite(&context.http, guild_id, message_content, setting)
+            .await
+        {
+            code
+        } else {
             return Ok(false);
-        }
+        };
 
         let guild_service = if let Some(service) = services.get::<GuildService>() {
             service
@@ -77,7 +204,14 @@ impl MessageProcessor for InviteLinkProcessor {
                     JIM_ID,
                     JIM_ID_AND_TAG,
                     author,
-                    REASON.into(),
+                    format!("Sending invite link: {}", code),
+                    &[EvidenceSnapshot::from_content(
+                        channel_id,
+                        message_id,
+                        author.id,
+                        message_content,
+                        attachments,
+                    )],
                     duration,
                     0,
                 )
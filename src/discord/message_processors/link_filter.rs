@@ -0,0 +1,141 @@
+use anyhow::bail;
+use async_trait::async_trait;
+use serenity::all::{Attachment, Context, GenericChannelId};
+use serenity::model::id::{GuildId, MessageId};
+use serenity::model::user::User;
+use serenity::model::Permissions;
+use tracing::error;
+
+use crate::constants::{JIM_ID, JIM_ID_AND_TAG};
+use crate::database::settings::{get_action_duration_for_auto_mod_action, Setting};
+use crate::discord::message_processors::{MessageProcessor, ProcessorExemptions};
+use crate::discord::util::invite::remove_invite_links;
+use crate::discord::util::links::{extract_link_domains, is_domain_allowed, parse_domain_list};
+use crate::discord::util::{execute_mod_action, is_staff, SerenityErrorExt};
+use crate::service::evidence::EvidenceSnapshot;
+use crate::service::guild::GuildService;
+use crate::service::Services;
+
+const REASON: &str = "Sending disallowed links";
+
+pub struct LinkFilterProcessor;
+
+#[async_trait]
+impl MessageProcessor for LinkFilterProcessor {
+    fn exemptions<'a>(&self, setting: &'a Setting) -> ProcessorExemptions<'a> {
+        ProcessorExemptions {
+            channel_ids: &setting.link_filter_exempt_channel_ids,
+            role_ids: &setting.link_filter_exempt_role_ids,
+        }
+    }
+
+    async fn handle_message(
+        &self,
+        context: &Context,
+        message_content: &str,
+        attachments: &[Attachment],
+        guild_id: GuildId,
+        channel_id: GenericChannelId,
+        message_id: MessageId,
+        author: &User,
+        permissions: Permissions,
+        setting: &Setting,
+        services: &Services,
+    ) -> anyhow::Result<bool> {
+        if !setting.link_filter {
+            return Ok(false);
+        }
+
+        if is_staff(permissions) {
+            return Ok(false);
+        }
+
+        let domains = extract_link_domains(&remove_invite_links(message_content));
+        if domains.is_empty() {
+            return Ok(false);
+        }
+
+        let allowlist = setting
+            .link_filter_allowlist
+            .as_deref()
+            .map(parse_domain_list)
+            .unwrap_or_default();
+        let denylist = setting
+            .link_filter_denylist
+            .as_deref()
+            .map(parse_domain_list)
+            .unwrap_or_default();
+
+        let domain = if let Some(domain) = domains.iter().find(|domain| {
+            !is_domain_allowed(
+                domain,
+                &allowlist,
+                &denylist,
+                setting.link_filter_allowlist_only,
+            )
+        }) {
+            domain
+        } else {
+            return Ok(false);
+        };
+
+        let guild_service = if let Some(service) = services.get::<GuildService>() {
+            service
+        } else {
+            bail!("couldn't get guild service!");
+        };
+
+        let guild = if let Ok(guild) = guild_service.get_guild(guild_id).await {
+            guild
+        } else {
+            bail!("couldn't get guild name!");
+        };
+
+        let duration = get_action_duration_for_auto_mod_action(
+            setting.link_filter_action,
+            setting.link_filter_action_duration_type,
+            setting.link_filter_action_duration,
+        );
+
+        match channel_id
+            .delete_message(&context.http, message_id, Some(REASON))
+            .await
+        {
+            Ok(_) => {
+                execute_mod_action(
+                    setting.link_filter_action,
+                    &context.http,
+                    guild_id,
+                    &guild.name,
+                    setting,
+                    services,
+                    Some(channel_id),
+                    JIM_ID,
+                    JIM_ID_AND_TAG,
+                    author,
+                    format!("Sending disallowed link: {}", domain),
+                    &[EvidenceSnapshot::from_content(
+                        channel_id,
+                        message_id,
+                        author.id,
+                        message_content,
+                        attachments,
+                    )],
+                    duration,
+                    0,
+                )
+                .await;
+                Ok(true)
+            }
+            Err(err) => {
+                match err.discord_error_code() {
+                    Some(50013) => (),
+                    _ => {
+                        error!("failed to delete message for censorship {}", err);
+                    }
+                }
+                Ok(false)
+            }
+        }
+    }
+}
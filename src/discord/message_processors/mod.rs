@@ -1,37 +1,155 @@
 use async_trait::async_trait;
-use serenity::all::{Context, GenericChannelId};
-use serenity::model::id::{GuildId, MessageId};
+use serenity::all::{Attachment, Context, GenericChannelId};
+use serenity::http::Http;
+use serenity::model::id::{ChannelId, GuildId, MessageId, RoleId};
 use serenity::model::user::User;
 use serenity::model::Permissions;
+use smol_str::SmolStr;
+use tracing::error;
 
 use crate::database::settings::Setting;
 use crate::discord::message_processors::invite_link::InviteLinkProcessor;
+use crate::discord::message_processors::link_filter::LinkFilterProcessor;
 use crate::discord::message_processors::spam_filter::SpamFilterProcessor;
 use crate::discord::message_processors::word_filter::WordFilterProcessor;
+use crate::service::guild::GuildService;
 use crate::service::Services;
 
 mod invite_link;
+mod link_filter;
 mod spam_filter;
 mod word_filter;
 
-pub struct MessageProcessors(pub Vec<Box<dyn MessageProcessor + Send + Sync>>);
+pub struct MessageProcessors {
+    processors: Vec<Box<dyn MessageProcessor + Send + Sync>>,
+    // also checks usernames of joining members
+    invite_link: InviteLinkProcessor,
+}
 
 pub fn get_all_processors() -> MessageProcessors {
-    MessageProcessors(vec![
-        // needs to be run first to detect spam even if it contains blocklisted words
-        // or a Discord server invite link
-        Box::new(SpamFilterProcessor::new()),
-        Box::new(InviteLinkProcessor),
-        Box::new(WordFilterProcessor),
-    ])
+    let invite_link = InviteLinkProcessor::new();
+
+    MessageProcessors {
+        processors: vec![
+            // needs to be run first to detect spam even if it contains blocklisted words
+            // or a Discord server invite link
+            Box::new(SpamFilterProcessor::new()),
+            Box::new(invite_link.clone()),
+            Box::new(LinkFilterProcessor),
+            Box::new(WordFilterProcessor::new()),
+        ],
+        invite_link,
+    }
+}
+
+impl MessageProcessors {
+    // processors run in order until one of them takes action on the message
+    pub async fn process(
+        &self,
+        context: &Context,
+        message_content: &str,
+        attachments: &[Attachment],
+        guild_id: GuildId,
+        channel_id: GenericChannelId,
+        message_id: MessageId,
+        author: &User,
+        roles: &[RoleId],
+        permissions: Permissions,
+        setting: &Setting,
+        services: &Services,
+    ) {
+        // messages in threads are exempt if their parent channel is
+        let has_exempt_channels = self
+            .processors
+            .iter()
+            .any(|processor| !processor.exemptions(setting).channel_ids.is_empty());
+        let parent_channel_id = match services.get::<GuildService>() {
+            Some(guild_service) if has_exempt_channels => {
+                guild_service
+                    .get_thread_parent_id(guild_id, channel_id)
+                    .await
+            }
+            _ => None,
+        };
+
+        for (i, processor) in self.processors.iter().enumerate() {
+            if processor
+                .exemptions(setting)
+                .is_exempt(channel_id, parent_channel_id, roles)
+            {
+                continue;
+            }
+
+            match processor
+                .handle_message(
+                    context,
+                    message_content,
+                    attachments,
+                    guild_id,
+                    channel_id,
+                    message_id,
+                    author,
+                    permissions,
+                    setting,
+                    services,
+                )
+                .await
+            {
+                Ok(true) => break,
+                Ok(false) => continue,
+                Err(err) => {
+                    error!("failed to run message processor id: {}, err: {}", i, err);
+                    break;
+                }
+            }
+        }
+    }
+
+    // same rules as invites in messages, the guild's own invites and allowlisted ones are fine
+    pub async fn find_disallowed_invite(
+        &self,
+        http: &Http,
+        guild_id: GuildId,
+        content: &str,
+        setting: &Setting,
+    ) -> Option<SmolStr> {
+        self.invite_link
+            .find_disallowed_invite(http, guild_id, content, setting)
+            .await
+    }
+}
+
+// channels and roles configured to bypass a processor, on top of staff which every processor skips
+pub struct ProcessorExemptions<'a> {
+    pub channel_ids: &'a [i64],
+    pub role_ids: &'a [i64],
+}
+
+impl ProcessorExemptions<'_> {
+    pub fn is_exempt(
+        &self,
+        channel_id: GenericChannelId,
+        parent_channel_id: Option<ChannelId>,
+        roles: &[RoleId],
+    ) -> bool {
+        self.channel_ids.contains(&(channel_id.get() as i64))
+            || parent_channel_id
+                .is_some_and(|parent_id| self.channel_ids.contains(&(parent_id.get() as i64)))
+            || roles
+                .iter()
+                .any(|role_id| self.role_ids.contains(&(role_id.get() as i64)))
+    }
 }
 
 #[async_trait]
 pub trait MessageProcessor {
+    fn exemptions<'a>(&self, setting: &'a Setting) -> ProcessorExemptions<'a>;
+
     async fn handle_message(
         &self,
         context: &Context,
         message_content: &str,
+        attachments: &[Attachment],
         guild_id: GuildId,
         channel_id: GenericChannelId,
         message_id: MessageId,
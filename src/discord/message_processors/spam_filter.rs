@@ -5,7 +5,7 @@ use std::time::Duration;
 use anyhow::bail;
 use async_trait::async_trait;
 use moka::future::{Cache, CacheBuilder};
-use serenity::all::{Context, GenericChannelId};
+use serenity::all::{Attachment, Context, GenericChannelId};
 use serenity::model::id::{GuildId, MessageId, UserId};
 use serenity::model::user::User;
 use serenity::model::Permissions;
@@ -13,8 +13,9 @@ use tokio::sync::Mutex;
 
 use crate::constants::{JIM_ID, JIM_ID_AND_TAG};
 use crate::database::settings::Setting;
-use crate::discord::message_processors::MessageProcessor;
+use crate::discord::message_processors::{MessageProcessor, ProcessorExemptions};
 use crate::discord::util::is_staff;
+use crate::service::evidence::EvidenceSnapshot;
 use crate::service::guild::GuildService;
 use crate::service::hardban::HardbanService;
 use crate::service::Services;
@@ -50,13 +51,21 @@ impl SpamFilterProcessor {
 
 #[async_trait]
 impl MessageProcessor for SpamFilterProcessor {
+    fn exemptions<'a>(&self, setting: &'a Setting) -> ProcessorExemptions<'a> {
+        ProcessorExemptions {
+            channel_ids: &setting.spam_filter_exempt_channel_ids,
+            role_ids: &setting.spam_filter_exempt_role_ids,
+        }
+    }
+
     async fn handle_message(
         &self,
         context: &Context,
         message_content: &str,
+        attachments: &[Attachment],
         guild_id: GuildId,
         channel_id: GenericChannelId,
-        _message_id: MessageId,
+        message_id: MessageId,
         author: &User,
         permissions: Permissions,
         setting: &Setting,
@@ -141,11 +150,19 @@ impl MessageProcessor for SpamFilterProcessor {
                 guild_id,
                 &guild.name,
                 setting,
+                services,
                 Some(channel_id),
                 JIM_ID,
                 JIM_ID_AND_TAG,
                 author,
                 REASON.into(),
+                &[EvidenceSnapshot::from_content(
+                    channel_id,
+                    message_id,
+                    author.id,
+                    message_content,
+                    attachments,
+                )],
             )
             .await;
 
@@ -1,69 +1,114 @@
+use std::sync::Arc;
+use std::time::Duration;
+
 use anyhow::anyhow;
 use anyhow::bail;
 use async_trait::async_trait;
-use serenity::all::{Context, GenericChannelId};
+use moka::future::{Cache, CacheBuilder};
+use serenity::all::{Attachment, Context, GenericChannelId};
 use serenity::model::id::{GuildId, MessageId};
 use serenity::model::user::User;
 use serenity::model::Permissions;
-use smol_str::SmolStr;
 use tracing::{error, warn};
 
 use crate::constants::{DEFAULT_BLOCKED_WORDS, JIM_ID, JIM_ID_AND_TAG};
 use crate::database::settings::{
     get_action_duration_for_auto_mod_action, Setting, WORD_FILTER_LEVEL_HIGH, WORD_FILTER_LEVEL_LOW,
 };
-use crate::discord::message_processors::MessageProcessor;
+use crate::discord::message_processors::{MessageProcessor, ProcessorExemptions};
+use crate::discord::util::blocklist::{parse_blocklist, BlocklistEntry, BlocklistMatcher};
 use crate::discord::util::{execute_mod_action, is_staff, SerenityErrorExt};
+use crate::service::evidence::EvidenceSnapshot;
 use crate::service::guild::GuildService;
 use crate::service::Services;
 
 const REASON: &str = "Using blocklisted word(s).";
 
-pub struct WordFilterProcessor;
+// matchers are rebuilt when the guild changes its blocklist or filter level
+struct CompiledBlocklist {
+    blocklist: Option<String>,
+    level: i32,
+    matcher: BlocklistMatcher,
+}
 
-// unoptimized, maybe use Trie/Set for lookup, strings are small enough to work fast with O(n^2)
-fn filter_message_simple(content: &str, blocked_words: &[SmolStr]) -> bool {
-    for word in content.split_whitespace() {
-        for blocked_word in blocked_words {
-            if word == blocked_word {
-                return true;
-            }
-        }
+impl CompiledBlocklist {
+    fn is_compiled_from(&self, setting: &Setting) -> bool {
+        self.blocklist == setting.word_filter_blocklist && self.level == setting.word_filter_level
     }
+}
 
-    false
+pub struct WordFilterProcessor {
+    matcher_cache: Cache<GuildId, Arc<CompiledBlocklist>>,
 }
 
-// unoptimized, maybe use Aho-Corasick pattern matching instead of O(n^2)
-fn filter_message_deep(content: &str, blocked_words: &[SmolStr]) -> bool {
-    for word in content.split_whitespace() {
-        for blocked_word in blocked_words {
-            if word.contains(blocked_word.as_str()) {
-                return true;
-            }
+impl WordFilterProcessor {
+    pub fn new() -> WordFilterProcessor {
+        WordFilterProcessor {
+            matcher_cache: CacheBuilder::new(1000)
+                .time_to_idle(Duration::from_secs(60 * 10))
+                .build(),
         }
     }
 
-    false
-}
+    async fn get_matcher(
+        &self,
+        guild_id: GuildId,
+        setting: &Setting,
+    ) -> anyhow::Result<Arc<CompiledBlocklist>> {
+        if let Some(compiled) = self.matcher_cache.get(&guild_id).await {
+            if compiled.is_compiled_from(setting) {
+                return Ok(compiled);
+            }
+        }
+
+        let whole_words = if setting.word_filter_level == WORD_FILTER_LEVEL_LOW {
+            true
+        } else if setting.word_filter_level == WORD_FILTER_LEVEL_HIGH {
+            false
+        } else {
+            warn!(guild_id = setting.guild_id, "invalid word filter level");
+            true
+        };
+
+        let matcher = if let Some(blocklist) = &setting.word_filter_blocklist {
+            BlocklistMatcher::new(&parse_blocklist(blocklist), whole_words)?
+        } else {
+            let filter = DEFAULT_BLOCKED_WORDS
+                .get()
+                .ok_or_else(|| anyhow!("failed to get default blocked words!"))?;
+            let entries = filter
+                .iter()
+                .map(|word| BlocklistEntry::Default(word.as_str()))
+                .collect::<Vec<BlocklistEntry>>();
+            BlocklistMatcher::new(&entries, whole_words)?
+        };
+
+        let compiled = Arc::new(CompiledBlocklist {
+            blocklist: setting.word_filter_blocklist.clone(),
+            level: setting.word_filter_level,
+            matcher,
+        });
 
-fn filter_message(setting: &Setting, content: &str, blocked_words: &[SmolStr]) -> bool {
-    if setting.word_filter_level == WORD_FILTER_LEVEL_LOW {
-        filter_message_simple(content, blocked_words)
-    } else if setting.word_filter_level == WORD_FILTER_LEVEL_HIGH {
-        filter_message_deep(content, blocked_words)
-    } else {
-        warn!(guild_id = setting.guild_id, "invalid word filter level");
-        filter_message_simple(content, blocked_words)
+        self.matcher_cache.insert(guild_id, compiled.clone()).await;
+
+        Ok(compiled)
     }
 }
 
 #[async_trait]
 impl MessageProcessor for WordFilterProcessor {
+    fn exemptions<'a>(&self, setting: &'a Setting) -> ProcessorExemptions<'a> {
+        ProcessorExemptions {
+            channel_ids: &setting.word_filter_exempt_channel_ids,
+            role_ids: &setting.word_filter_exempt_role_ids,
+        }
+    }
+
     async fn handle_message(
         &self,
         context: &Context,
         message_content: &str,
+        attachments: &[Attachment],
         guild_id: GuildId,
         channel_id: GenericChannelId,
         message_id: MessageId,
@@ -80,23 +125,12 @@ impl MessageProcessor for WordFilterProcessor {
             return Ok(false);
         }
 
-        let result = if let Some(block_list) = &setting.word_filter_blocklist {
-            let filter = block_list
-                .split(',')
-                .map(SmolStr::new)
-                .collect::<Vec<SmolStr>>();
-
-            filter_message(setting, message_content, &filter)
+        let compiled = self.get_matcher(guild_id, setting).await?;
+        let term = if let Some(term) = compiled.matcher.find(message_content) {
+            term
         } else {
-            let filter = DEFAULT_BLOCKED_WORDS
-                .get()
-                .ok_or_else(|| anyhow!("failed to get default blocked words!"))?;
-            filter_message(setting, message_content, filter)
-        };
-
-        if !result {
             return Ok(false);
-        }
+        };
 
         let guild_service = if let Some(service) = services.get::<GuildService>() {
             service
@@ -132,7 +166,14 @@ impl MessageProcessor for WordFilterProcessor {
                     JIM_ID,
                     JIM_ID_AND_TAG,
                     author,
-                    REASON.into(),
+                    format!("Using blocklisted word: {}", term),
+                    &[EvidenceSnapshot::from_content(
+                        channel_id,
+                        message_id,
+                        author.id,
+                        message_content,
+                        attachments,
+                    )],
                     duration,
                     0,
                 )
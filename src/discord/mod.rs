@@ -1,3 +1,4 @@
+mod components;
 pub mod discord_bot;
 mod message_processors;
 mod scheduled;
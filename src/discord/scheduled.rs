@@ -1,23 +1,29 @@
 use serenity::all::{GenericChannelId, Mentionable};
+use std::collections::HashMap;
 use std::num::NonZeroU64;
 use std::sync::Arc;
 use std::time::Duration;
 
 use serenity::builder::{CreateEmbed, CreateEmbedAuthor, CreateEmbedFooter, CreateMessage};
 use serenity::http::Http;
-use serenity::model::id::{GuildId, RoleId, UserId};
+use serenity::model::id::{ChannelId, GuildId, RoleId, UserId};
 use serenity::model::Timestamp;
 use tokio::select;
 use tokio::time::interval;
 use tracing::{error, warn};
 
 use crate::constants::{AVATAR_URL, EMBED_COLOR};
+use crate::discord::util::infraction::{log_infraction_lift, Infraction};
+use crate::discord::util::mod_log::{log_lockdown, ModLogAction};
+use crate::discord::util::UserExt;
 use crate::service::ban::BanService;
 use crate::service::guild::GuildService;
 use crate::service::join::JoinService;
+use crate::service::lockdown::{LockdownService, UnlockFailure};
 use crate::service::mute::MuteService;
 use crate::service::reminder::ReminderService;
 use crate::service::setting::SettingService;
+use crate::service::temp_role::TempRoleService;
 use crate::service::Services;
 use crate::util::Shutdown;
 
@@ -70,6 +76,8 @@ pub fn run_scheduled_tasks(http: Arc<Http>, services: Arc<Services>, shutdown: S
         }
     }));
 
+    let http_4 = http.clone();
+    let services_4 = services.clone();
     let mut receiver_4 = shutdown.subscribe();
     drop(tokio::spawn(async move {
         let mut interval = interval(Duration::from_secs(5));
@@ -80,7 +88,53 @@ pub fn run_scheduled_tasks(http: Arc<Http>, services: Arc<Services>, shutdown: S
                     return;
                 }
             }
-            remind_reminders(&http, &services).await;
+            remind_reminders(&http_4, &services_4).await;
+        }
+    }));
+
+    let http_5 = http.clone();
+    let services_5 = services.clone();
+    let mut receiver_5 = shutdown.subscribe();
+    drop(tokio::spawn(async move {
+        let mut interval = interval(Duration::from_secs(600));
+        loop {
+            select! {
+                _ = interval.tick() => {}
+                _ = receiver_5.recv() => {
+                    return;
+                }
+            }
+            renew_timeouts(&http_5, &services_5).await;
+        }
+    }));
+
+    let http_6 = http.clone();
+    let services_6 = services.clone();
+    let mut receiver_6 = shutdown.subscribe();
+    drop(tokio::spawn(async move {
+        let mut interval = interval(Duration::from_secs(10));
+        loop {
+            select! {
+                _ = interval.tick() => {}
+                _ = receiver_6.recv() => {
+                    return;
+                }
+            }
+            unlock_channels(&http_6, &services_6).await;
+        }
+    }));
+
+    let mut receiver_7 = shutdown.subscribe();
+    drop(tokio::spawn(async move {
+        let mut interval = interval(Duration::from_secs(10));
+        loop {
+            select! {
+                _ = interval.tick() => {}
+                _ = receiver_7.recv() => {
+                    return;
+                }
+            }
+            remove_temp_roles(&http, &services).await;
         }
     }));
 }
@@ -164,14 +218,20 @@ pub async fn unmute_users(http: &Http, services: &Services) {
         return;
     };
 
-    let guild_service = if let Some(service) = services.get::<GuildService>() {
+    let setting_service = if let Some(service) = services.get::<SettingService>() {
         service
     } else {
         return;
     };
 
     let expired_mutes = mute_service.fetch_expired_mutes().await;
-    for expired_mute in expired_mutes {
+    if expired_mutes.is_empty() {
+        return;
+    }
+
+    let self_user_tag_and_id = fetch_self_user_tag_and_id(http).await;
+
+    for mut expired_mute in expired_mutes {
         let guild_id = if let Some(id) = NonZeroU64::new(expired_mute.guild_id as u64) {
             GuildId::new(id.get())
         } else {
@@ -183,45 +243,74 @@ pub async fn unmute_users(http: &Http, services: &Services) {
             continue;
         };
 
-        if let Some(muted_role_id) = guild_service
-            .get_roles(guild_id)
-            .await
-            .map(|roles| {
-                roles
-                    .iter()
-                    .find(|(_, role)| role.name == "Muted")
-                    .map(|(id, _)| *id)
-            })
-            .ok()
-            .flatten()
-        {
-            // these aren't likely to be zero but we need sanity checks to avoid panic
-            let user_id = if let Some(id) = NonZeroU64::new(expired_mute.user_id as u64) {
-                UserId::new(id.get())
-            } else {
-                warn!(
-                    "found expired mute with invalid user id! {:?}",
-                    expired_mute
-                );
-                mute_service.invalidate_mute(expired_mute.id).await;
-                continue;
-            };
-
-            let _ = http
-                .remove_member_role(
-                    guild_id,
-                    user_id,
-                    muted_role_id,
-                    Some("Unmuting member because duration expired"),
-                )
-                .await
-                .map_err(|err| {
-                    error!("failed to issue discord member role remove {}", err);
-                    err
-                });
-        }
+        // these aren't likely to be zero but we need sanity checks to avoid panic
+        let user_id = if let Some(id) = NonZeroU64::new(expired_mute.user_id as u64) {
+            UserId::new(id.get())
+        } else {
+            warn!(
+                "found expired mute with invalid user id! {:?}",
+                expired_mute
+            );
+            mute_service.invalidate_mute(expired_mute.id).await;
+            continue;
+        };
+
+        let mute_backend = expired_mute.mute_backend;
+
+        let setting = setting_service.get_setting(guild_id).await;
+
+        let _ = mute_service
+            .lift_mute(
+                http,
+                services,
+                &setting,
+                mute_backend,
+                guild_id,
+                user_id,
+                "Unmuting member because duration expired",
+            )
+            .await;
 
         mute_service.invalidate_mute(expired_mute.id).await;
+
+        expired_mute.unmuted = true;
+        let _ = log_infraction_lift(
+            http,
+            &setting,
+            &self_user_tag_and_id,
+            &Infraction::Mute(expired_mute),
+            ModLogAction::Expired,
+            None,
+        )
+        .await;
+    }
+}
+
+pub async fn remove_temp_roles(http: &Http, services: &Services) {
+    let temp_role_service = if let Some(service) = services.get::<TempRoleService>() {
+        service
+    } else {
+        return;
+    };
+
+    let expired_temp_roles = temp_role_service.fetch_expired_temp_roles().await;
+    for expired_temp_role in expired_temp_roles {
+        temp_role_service
+            .expire_temp_role(http, &expired_temp_role)
+            .await;
+    }
+}
+
+pub async fn renew_timeouts(http: &Http, services: &Services) {
+    let mute_service = if let Some(service) = services.get::<MuteService>() {
+        service
+    } else {
+        return;
+    };
+
+    let mutes = mute_service.fetch_mutes_with_expiring_timeout().await;
+    for mute in mutes {
+        mute_service.renew_timeout(http, services, &mute).await;
     }
 }
 
@@ -232,8 +321,20 @@ pub async fn unban_users(http: &Http, services: &Services) {
         return;
     };
 
+    let setting_service = if let Some(service) = services.get::<SettingService>() {
+        service
+    } else {
+        return;
+    };
+
     let expired_bans = ban_service.fetch_expired_bans().await;
-    for expired_ban in expired_bans {
+    if expired_bans.is_empty() {
+        return;
+    }
+
+    let self_user_tag_and_id = fetch_self_user_tag_and_id(http).await;
+
+    for mut expired_ban in expired_bans {
         // these aren't likely to be zero but we need sanity checks to avoid panic
         let guild_id = if let Some(id) = NonZeroU64::new(expired_ban.guild_id as u64) {
             GuildId::new(id.get())
@@ -264,6 +365,133 @@ pub async fn unban_users(http: &Http, services: &Services) {
             });
 
         ban_service.invalidate_ban(expired_ban.id).await;
+
+        let setting = setting_service.get_setting(guild_id).await;
+
+        expired_ban.unbanned = true;
+        let _ = log_infraction_lift(
+            http,
+            &setting,
+            &self_user_tag_and_id,
+            &Infraction::Ban(expired_ban),
+            ModLogAction::Expired,
+            None,
+        )
+        .await;
+    }
+}
+
+pub async fn unlock_channels(http: &Http, services: &Services) {
+    let lockdown_service = if let Some(service) = services.get::<LockdownService>() {
+        service
+    } else {
+        return;
+    };
+
+    let setting_service = if let Some(service) = services.get::<SettingService>() {
+        service
+    } else {
+        return;
+    };
+
+    let expired_lockdowns = lockdown_service.fetch_expired_lockdowns().await;
+    if expired_lockdowns.is_empty() {
+        return;
+    }
+
+    let self_user_tag_and_id = fetch_self_user_tag_and_id(http).await;
+
+    // server wide lockdowns expire all at once, they get a single mod log entry per guild
+    let mut unlocked_channel_ids: HashMap<GuildId, Vec<ChannelId>> = HashMap::new();
+    for expired_lockdown in expired_lockdowns {
+        let guild_id = if let Some(id) = NonZeroU64::new(expired_lockdown.guild_id as u64) {
+            GuildId::new(id.get())
+        } else {
+            warn!(
+                "found expired lockdown with invalid guild id! {:?}",
+                expired_lockdown
+            );
+            lockdown_service
+                .invalidate_lockdown(expired_lockdown.id)
+                .await;
+            continue;
+        };
+
+        match lockdown_service
+            .unlock_channel(
+                http,
+                guild_id,
+                &expired_lockdown,
+                "Lifting lockdown because duration expired",
+            )
+            .await
+        {
+            Ok(channel_id) => unlocked_channel_ids
+                .entry(guild_id)
+                .or_default()
+                .push(channel_id),
+            Err(failure) => {
+                let reason = match failure {
+                    // retrying every few seconds won't fix missing permissions, the lockdown stops
+                    // being retried but stays active so /unlock can still restore the channel
+                    UnlockFailure::Unauthorized => {
+                        lockdown_service
+                            .mark_unlock_failed(expired_lockdown.id)
+                            .await;
+                        "Lockdown expired but I don't have permission to restore the channel, it stays locked until it's unlocked by hand"
+                    }
+                    // anything else might be temporary, the lockdown stays active and is retried
+                    UnlockFailure::Unknown => {
+                        "Lockdown expired but the channel couldn't be unlocked, retrying"
+                    }
+                };
+
+                if !lockdown_service
+                    .should_report_unlock_failure(expired_lockdown.id)
+                    .await
+                {
+                    continue;
+                }
+
+                if let Some(channel_id) = NonZeroU64::new(expired_lockdown.channel_id as u64) {
+                    let setting = setting_service.get_setting(guild_id).await;
+                    let _ = log_lockdown(
+                        http,
+                        &setting,
+                        &self_user_tag_and_id,
+                        ModLogAction::UnlockFailed,
+                        &[ChannelId::new(channel_id.get())],
+                        reason,
+                    )
+                    .await;
+                }
+            }
+        }
+    }
+
+    for (guild_id, channel_ids) in unlocked_channel_ids {
+        let setting = setting_service.get_setting(guild_id).await;
+
+        let _ = log_lockdown(
+            http,
+            &setting,
+            &self_user_tag_and_id,
+            ModLogAction::Unlock,
+            &channel_ids,
+            "Lockdown duration expired",
+        )
+        .await;
+    }
+}
+
+// automatic actions show up in the mod log as issued by Jim
+async fn fetch_self_user_tag_and_id(http: &Http) -> String {
+    match http.get_current_user().await {
+        Ok(user) => user.tag_and_id(),
+        Err(err) => {
+            error!("failed to fetch current user {}", err);
+            "Jim".to_string()
+        }
     }
 }
 
@@ -17,6 +17,10 @@ use crate::discord::slash_commands::ban::BanCommandOptionFailure::{
     DurationParseError, MissingOption,
 };
 use crate::discord::slash_commands::SlashCommand;
+use crate::discord::util::evidence::{
+    create_evidence_option, fetch_evidence, EVIDENCE_OPTION_NAME,
+};
+use crate::discord::util::hierarchy::check_role_hierarchy;
 use crate::discord::util::{
     reply_to_interaction_str, unauthorized_reply, verify_guild_slash_command, CommandDataExt,
     GuildSlashCommandInteraction, UserExt,
@@ -31,6 +35,7 @@ pub struct BanCommand;
 struct BanCommandOptions<'a> {
     target_user: &'a User,
     reason: Option<String>,
+    evidence: Option<&'a str>,
     duration: Option<Duration>,
 }
 
@@ -47,6 +52,7 @@ fn generate_options(data: &CommandData) -> Result<BanCommandOptions, BanCommandO
     };
 
     let reason = data.string("reason").map(String::from);
+    let evidence = data.string(EVIDENCE_OPTION_NAME);
 
     let duration = if let Some(s) = data.string("duration") {
         if let Ok(duration) = humantime::parse_duration(s) {
@@ -61,6 +67,7 @@ fn generate_options(data: &CommandData) -> Result<BanCommandOptions, BanCommandO
     Ok(BanCommandOptions {
         target_user,
         reason,
+        evidence,
         duration,
     })
 }
@@ -98,6 +105,7 @@ impl SlashCommand for BanCommand {
                 )
                 .required(false),
             )
+            .add_option(create_evidence_option())
     }
 
     async fn handle_command(
@@ -170,11 +178,19 @@ impl SlashCommand for BanCommand {
 
         let guild = guild_service.get_guild(guild_id).await?;
 
-        if options.target_user.id == guild.owner_id {
+        if let Err(failure) = check_role_hierarchy(
+            guild_service,
+            guild_id,
+            &guild,
+            mod_user.id,
+            options.target_user.id,
+        )
+        .await
+        {
             reply_to_interaction_str(
                 &context.http,
                 interaction,
-                "You can't ban owner of the server!",
+                &failure.to_interaction_response("ban"),
                 true,
             )
             .await;
@@ -187,6 +203,22 @@ impl SlashCommand for BanCommand {
             bail!("couldn't get ban service!");
         };
 
+        let evidence = match fetch_evidence(
+            &context.http,
+            guild_service,
+            guild_id,
+            mod_user.id,
+            options.evidence,
+        )
+        .await
+        {
+            Ok(evidence) => evidence,
+            Err(message) => {
+                reply_to_interaction_str(&context.http, interaction, &message, true).await;
+                return Ok(());
+            }
+        };
+
         let setting_service = if let Some(service) = services.get::<SettingService>() {
             service
         } else {
@@ -201,6 +233,7 @@ impl SlashCommand for BanCommand {
                 guild_id,
                 &guild.name,
                 &setting,
+                services,
                 Some(channel_id),
                 mod_user.id,
                 &mod_user.tag_and_id(),
@@ -208,6 +241,7 @@ impl SlashCommand for BanCommand {
                 options
                     .reason
                     .unwrap_or_else(|| "No reason specified".into()),
+                &evidence,
                 options.duration,
             )
             .await
@@ -13,6 +13,10 @@ use crate::config::Config;
 use crate::constants::JIM_ID;
 use crate::discord::slash_commands::hardban::HardbanCommandOptionFailure::MissingOption;
 use crate::discord::slash_commands::SlashCommand;
+use crate::discord::util::evidence::{
+    create_evidence_option, fetch_evidence, EVIDENCE_OPTION_NAME,
+};
+use crate::discord::util::hierarchy::check_role_hierarchy;
 use crate::discord::util::{
     reply_to_interaction_str, unauthorized_reply, verify_guild_slash_command, CommandDataExt,
     GuildSlashCommandInteraction, UserExt,
@@ -27,6 +31,7 @@ pub struct HardbanCommand;
 struct HardbanCommandOptions<'a> {
     target_user: &'a User,
     reason: Option<String>,
+    evidence: Option<&'a str>,
 }
 
 enum HardbanCommandOptionFailure {
@@ -43,10 +48,12 @@ fn generate_options(
     };
 
     let reason = data.string("reason").map(String::from);
+    let evidence = data.string(EVIDENCE_OPTION_NAME);
 
     Ok(HardbanCommandOptions {
         target_user,
         reason,
+        evidence,
     })
 }
 
@@ -79,6 +86,7 @@ impl SlashCommand for HardbanCommand {
                 )
                 .required(false),
             )
+            .add_option(create_evidence_option())
     }
 
     async fn handle_command(
@@ -139,11 +147,19 @@ impl SlashCommand for HardbanCommand {
 
         let guild = guild_service.get_guild(guild_id).await?;
 
-        if options.target_user.id == guild.owner_id {
+        if let Err(failure) = check_role_hierarchy(
+            guild_service,
+            guild_id,
+            &guild,
+            mod_user.id,
+            options.target_user.id,
+        )
+        .await
+        {
             reply_to_interaction_str(
                 &context.http,
                 interaction,
-                "You can't hardban owner of the server!",
+                &failure.to_interaction_response("hardban"),
                 true,
             )
             .await;
@@ -156,6 +172,22 @@ impl SlashCommand for HardbanCommand {
             bail!("couldn't get hardban service!");
         };
 
+        let evidence = match fetch_evidence(
+            &context.http,
+            guild_service,
+            guild_id,
+            mod_user.id,
+            options.evidence,
+        )
+        .await
+        {
+            Ok(evidence) => evidence,
+            Err(message) => {
+                reply_to_interaction_str(&context.http, interaction, &message, true).await;
+                return Ok(());
+            }
+        };
+
         let setting_service = if let Some(service) = services.get::<SettingService>() {
             service
         } else {
@@ -170,6 +202,7 @@ impl SlashCommand for HardbanCommand {
                 guild_id,
                 &guild.name,
                 &setting,
+                services,
                 Some(channel_id),
                 mod_user.id,
                 &mod_user.tag_and_id(),
@@ -177,6 +210,7 @@ impl SlashCommand for HardbanCommand {
                 options
                     .reason
                     .unwrap_or_else(|| "No reason specified".into()),
+                &evidence,
             )
             .await
         {
@@ -0,0 +1,492 @@
+use std::time::Duration;
+
+use anyhow::bail;
+use async_trait::async_trait;
+use serenity::all::Context;
+use serenity::all::{
+    ButtonStyle, CommandData, CommandInteraction, CommandOptionType, CommandType,
+    InstallationContext, InteractionContext,
+};
+use serenity::builder::{
+    CreateActionRow, CreateButton, CreateCommand, CreateCommandOption, CreateEmbed,
+    CreateEmbedAuthor, CreateEmbedFooter, CreateInteractionResponse,
+    CreateInteractionResponseMessage, EditInteractionResponse,
+};
+use serenity::collector::ComponentInteractionCollector;
+use serenity::model::id::{GuildId, UserId};
+use serenity::model::user::User;
+use serenity::model::Permissions;
+use tracing::error;
+
+use crate::config::Config;
+use crate::constants::EMBED_COLOR;
+use crate::database::settings::{Setting, ACTION_KICK, ACTION_MUTE, ACTION_SOFTBAN, ACTION_WARN};
+use crate::discord::slash_commands::history::HistoryCommandOptionFailure::MissingOption;
+use crate::discord::slash_commands::SlashCommand;
+use crate::discord::util::{
+    can_view_mod_log, can_view_moderator, verify_guild_slash_command, CommandDataExt,
+    DeferredInteraction, GuildSlashCommandInteraction,
+};
+use crate::service::ban::BanService;
+use crate::service::hardban::HardbanService;
+use crate::service::kick::KickService;
+use crate::service::mute::MuteService;
+use crate::service::setting::SettingService;
+use crate::service::softban::SoftbanService;
+use crate::service::warn::WarnService;
+use crate::service::Services;
+use crate::util::now;
+
+const ENTRIES_PER_PAGE: usize = 5;
+const PAGINATION_TIMEOUT: Duration = Duration::from_secs(60);
+const PREVIOUS_PAGE_ID: &str = "history_previous";
+const NEXT_PAGE_ID: &str = "history_next";
+const EMBED_FIELD_VALUE_LIMIT: usize = 1024;
+
+pub struct HistoryCommand;
+
+struct HistoryCommandOptions<'a> {
+    target_user: &'a User,
+}
+
+enum HistoryCommandOptionFailure {
+    MissingOption,
+}
+
+fn generate_options(
+    data: &CommandData,
+) -> Result<HistoryCommandOptions, HistoryCommandOptionFailure> {
+    let target_user = if let Some((user, _)) = data.user("user") {
+        user
+    } else {
+        return Err(MissingOption);
+    };
+
+    Ok(HistoryCommandOptions { target_user })
+}
+
+struct InfractionEntry {
+    kind: &'static str,
+    case_number: i32,
+    moderator_user_id: i64,
+    action_time: i64,
+    expire_time: Option<i64>,
+    reason: String,
+    pardoned: bool,
+    // too old to count towards escalation thresholds, see the guild's decay settings
+    decayed: bool,
+    revoked: bool,
+}
+
+impl InfractionEntry {
+    fn title(&self) -> String {
+        let mut title = format!("#{} - {}", self.case_number, self.kind);
+        if self.pardoned {
+            title.push_str(" (pardoned)");
+        } else if self.decayed {
+            title.push_str(" (decayed)");
+        }
+
+        if self.revoked {
+            title.push_str(" (lifted)");
+        }
+
+        title
+    }
+
+    fn description(&self, show_moderator: bool) -> String {
+        let mut description = if show_moderator {
+            format!("**Moderator:** <@{}>\n", self.moderator_user_id)
+        } else {
+            "**Moderator:** Anonymous\n".to_string()
+        };
+
+        description.push_str(&format!("**Date:** <t:{}>\n", self.action_time));
+
+        if let Some(expire_time) = self.expire_time {
+            description.push_str(&format!("**Expires:** <t:{}>\n", expire_time));
+        }
+
+        description.push_str(&format!("**Reason:** {}", self.reason));
+
+        // reasons can be longer than an embed field allows, the lines before them are short
+        if description.chars().count() > EMBED_FIELD_VALUE_LIMIT {
+            description = description
+                .chars()
+                .take(EMBED_FIELD_VALUE_LIMIT - 3)
+                .collect::<String>();
+            description.push_str("...");
+        }
+
+        description
+    }
+}
+
+async fn fetch_infractions(
+    services: &Services,
+    setting: &Setting,
+    guild_id: GuildId,
+    user_id: UserId,
+) -> anyhow::Result<Vec<InfractionEntry>> {
+    let ban_service = if let Some(service) = services.get::<BanService>() {
+        service
+    } else {
+        bail!("couldn't get ban service!");
+    };
+
+    let hardban_service = if let Some(service) = services.get::<HardbanService>() {
+        service
+    } else {
+        bail!("couldn't get hardban service!");
+    };
+
+    let kick_service = if let Some(service) = services.get::<KickService>() {
+        service
+    } else {
+        bail!("couldn't get kick service!");
+    };
+
+    let mute_service = if let Some(service) = services.get::<MuteService>() {
+        service
+    } else {
+        bail!("couldn't get mute service!");
+    };
+
+    let softban_service = if let Some(service) = services.get::<SoftbanService>() {
+        service
+    } else {
+        bail!("couldn't get softban service!");
+    };
+
+    let warn_service = if let Some(service) = services.get::<WarnService>() {
+        service
+    } else {
+        bail!("couldn't get warn service!");
+    };
+
+    let now = now();
+    let kick_decay_cutoff = setting.decay_cutoff(ACTION_KICK, now) as i64;
+    let mute_decay_cutoff = setting.decay_cutoff(ACTION_MUTE, now) as i64;
+    let softban_decay_cutoff = setting.decay_cutoff(ACTION_SOFTBAN, now) as i64;
+    let warn_decay_cutoff = setting.decay_cutoff(ACTION_WARN, now) as i64;
+
+    let mut entries = vec![];
+
+    for ban in ban_service.fetch_guild_user_bans(guild_id, user_id).await {
+        entries.push(InfractionEntry {
+            kind: "Ban",
+            case_number: ban.case_number,
+            moderator_user_id: ban.moderator_user_id,
+            action_time: ban.ban_time,
+            expire_time: if ban.expires {
+                Some(ban.expire_time)
+            } else {
+                None
+            },
+            reason: ban.reason,
+            pardoned: false,
+            decayed: false,
+            revoked: ban.unbanned,
+        });
+    }
+
+    for hardban in hardban_service
+        .fetch_guild_user_hardbans(guild_id, user_id)
+        .await
+    {
+        entries.push(InfractionEntry {
+            kind: "Hardban",
+            case_number: hardban.case_number,
+            moderator_user_id: hardban.moderator_user_id,
+            action_time: hardban.hardban_time,
+            expire_time: None,
+            reason: hardban.reason,
+            pardoned: false,
+            decayed: false,
+            revoked: false,
+        });
+    }
+
+    for kick in kick_service.fetch_guild_user_kicks(guild_id, user_id).await {
+        entries.push(InfractionEntry {
+            kind: "Kick",
+            case_number: kick.case_number,
+            moderator_user_id: kick.moderator_user_id,
+            action_time: kick.kick_time,
+            expire_time: None,
+            reason: kick.reason,
+            pardoned: kick.pardoned,
+            decayed: kick.kick_time < kick_decay_cutoff,
+            revoked: false,
+        });
+    }
+
+    for mute in mute_service.fetch_guild_user_mutes(guild_id, user_id).await {
+        entries.push(InfractionEntry {
+            kind: "Mute",
+            case_number: mute.case_number,
+            moderator_user_id: mute.moderator_user_id,
+            action_time: mute.mute_time,
+            expire_time: if mute.expires {
+                Some(mute.expire_time)
+            } else {
+                None
+            },
+            reason: mute.reason,
+            pardoned: mute.pardoned,
+            decayed: mute.mute_time < mute_decay_cutoff,
+            revoked: mute.unmuted,
+        });
+    }
+
+    for softban in softban_service
+        .fetch_guild_user_softbans(guild_id, user_id)
+        .await
+    {
+        entries.push(InfractionEntry {
+            kind: "Softban",
+            case_number: softban.case_number,
+            moderator_user_id: softban.moderator_user_id,
+            action_time: softban.softban_time,
+            expire_time: None,
+            reason: softban.reason,
+            pardoned: softban.pardoned,
+            decayed: softban.softban_time < softban_decay_cutoff,
+            revoked: false,
+        });
+    }
+
+    for warn in warn_service.fetch_guild_user_warns(guild_id, user_id).await {
+        entries.push(InfractionEntry {
+            kind: "Warn",
+            case_number: warn.case_number,
+            moderator_user_id: warn.moderator_user_id,
+            action_time: warn.warn_time,
+            expire_time: None,
+            reason: warn.reason,
+            pardoned: warn.pardoned,
+            decayed: warn.warn_time < warn_decay_cutoff,
+            revoked: false,
+        });
+    }
+
+    entries.sort_by(|a, b| b.action_time.cmp(&a.action_time));
+
+    Ok(entries)
+}
+
+fn page_count(entries: &[InfractionEntry]) -> usize {
+    entries.len().div_ceil(ENTRIES_PER_PAGE).max(1)
+}
+
+fn generate_history_embed<'a>(
+    target_user: &'a User,
+    entries: &[InfractionEntry],
+    page: usize,
+    show_moderator: bool,
+) -> CreateEmbed<'a> {
+    let mut embed = CreateEmbed::default()
+        .author(CreateEmbedAuthor::new(target_user.tag()).icon_url(target_user.face()))
+        .title("Infraction History")
+        .colour(EMBED_COLOR);
+
+    if entries.is_empty() {
+        return embed.description("This user has no recorded infractions.");
+    }
+
+    for entry in entries
+        .iter()
+        .skip(page * ENTRIES_PER_PAGE)
+        .take(ENTRIES_PER_PAGE)
+    {
+        embed = embed.field(entry.title(), entry.description(show_moderator), false);
+    }
+
+    embed.footer(CreateEmbedFooter::new(format!(
+        "Page {}/{} - {} infractions in total",
+        page + 1,
+        page_count(entries),
+        entries.len()
+    )))
+}
+
+fn generate_page_buttons<'a>(page: usize, page_count: usize) -> Vec<CreateActionRow<'a>> {
+    if page_count <= 1 {
+        return vec![];
+    }
+
+    vec![CreateActionRow::Buttons(
+        vec![
+            CreateButton::new(PREVIOUS_PAGE_ID)
+                .label("Previous")
+                .style(ButtonStyle::Secondary)
+                .disabled(page == 0),
+            CreateButton::new(NEXT_PAGE_ID)
+                .label("Next")
+                .style(ButtonStyle::Secondary)
+                .disabled(page + 1 >= page_count),
+        ]
+        .into(),
+    )]
+}
+
+// shared between /history and the "View Infractions" user context menu
+pub async fn show_infraction_history(
+    context: &Context,
+    interaction: &CommandInteraction,
+    services: &Services,
+    target_user: &User,
+) -> anyhow::Result<()> {
+    let GuildSlashCommandInteraction {
+        guild_id,
+        member: _,
+        permissions,
+    } = verify_guild_slash_command(interaction)?;
+
+    send_infraction_history(
+        context,
+        interaction,
+        guild_id,
+        permissions,
+        services,
+        target_user,
+    )
+    .await
+}
+
+// also used by the History button of mod log entries
+pub async fn send_infraction_history(
+    context: &Context,
+    interaction: &impl DeferredInteraction,
+    guild_id: GuildId,
+    permissions: Permissions,
+    services: &Services,
+    target_user: &User,
+) -> anyhow::Result<()> {
+    let setting_service = if let Some(service) = services.get::<SettingService>() {
+        service
+    } else {
+        bail!("couldn't get setting service!");
+    };
+
+    interaction.defer_ephemeral(&context.http).await?;
+
+    let setting = setting_service.get_setting(guild_id).await;
+
+    if !can_view_mod_log(setting.privacy_mod_log, permissions) {
+        let _ = interaction
+            .edit_deferred_response(
+                &context.http,
+                EditInteractionResponse::default()
+                    .content("Server settings prevent you from viewing private information!"),
+            )
+            .await
+            .map_err(|err| {
+                error!("failed to edit interaction reply {}", err);
+                err
+            });
+        return Ok(());
+    }
+
+    let show_moderator = can_view_moderator(&setting, permissions);
+    let entries = fetch_infractions(services, &setting, guild_id, target_user.id).await?;
+    let page_count = page_count(&entries);
+    let mut page = 0;
+
+    let message = interaction
+        .edit_deferred_response(
+            &context.http,
+            EditInteractionResponse::default()
+                .embed(generate_history_embed(
+                    target_user,
+                    &entries,
+                    page,
+                    show_moderator,
+                ))
+                .components(generate_page_buttons(page, page_count)),
+        )
+        .await?;
+
+    if page_count <= 1 {
+        return Ok(());
+    }
+
+    while let Some(component) = ComponentInteractionCollector::new(context)
+        .timeout(PAGINATION_TIMEOUT)
+        .message_id(message.id)
+        .author_id(interaction.user_id())
+        .next()
+        .await
+    {
+        match component.data.custom_id.as_str() {
+            PREVIOUS_PAGE_ID => page = page.saturating_sub(1),
+            NEXT_PAGE_ID => page = (page + 1).min(page_count - 1),
+            _ => continue,
+        }
+
+        let response = CreateInteractionResponse::UpdateMessage(
+            CreateInteractionResponseMessage::new()
+                .embed(generate_history_embed(
+                    target_user,
+                    &entries,
+                    page,
+                    show_moderator,
+                ))
+                .components(generate_page_buttons(page, page_count)),
+        );
+
+        if let Err(err) = component.create_response(&context.http, response).await {
+            error!("failed to update history page {}", err);
+            break;
+        }
+    }
+
+    let _ = interaction
+        .edit_deferred_response(
+            &context.http,
+            EditInteractionResponse::default().components(vec![]),
+        )
+        .await
+        .map_err(|err| {
+            error!("failed to remove history buttons {}", err);
+            err
+        });
+
+    Ok(())
+}
+
+#[async_trait]
+impl SlashCommand for HistoryCommand {
+    fn command_name(&self) -> &'static str {
+        "history"
+    }
+
+    fn create_command(&self) -> CreateCommand {
+        CreateCommand::new("history")
+            .kind(CommandType::ChatInput)
+            .description("lists past infractions of given user")
+            .add_integration_type(InstallationContext::Guild)
+            .add_context(InteractionContext::Guild)
+            .add_option(
+                CreateCommandOption::new(CommandOptionType::User, "user", "target user to query")
+                    .required(true),
+            )
+    }
+
+    async fn handle_command(
+        &self,
+        context: &Context,
+        interaction: &CommandInteraction,
+        _config: &Config,
+        services: &Services,
+    ) -> anyhow::Result<()> {
+        let options = match generate_options(&interaction.data) {
+            Ok(options) => options,
+            Err(MissingOption) => {
+                bail!("interaction has missing data options")
+            }
+        };
+
+        show_infraction_history(context, interaction, services, options.target_user).await
+    }
+}
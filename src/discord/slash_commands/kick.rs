@@ -13,6 +13,10 @@ use crate::config::Config;
 use crate::constants::JIM_ID;
 use crate::discord::slash_commands::kick::KickCommandOptionFailure::MissingOption;
 use crate::discord::slash_commands::SlashCommand;
+use crate::discord::util::evidence::{
+    create_evidence_option, fetch_evidence, EVIDENCE_OPTION_NAME,
+};
+use crate::discord::util::hierarchy::check_role_hierarchy;
 use crate::discord::util::{
     reply_to_interaction_str, unauthorized_reply, verify_guild_slash_command, CommandDataExt,
     GuildSlashCommandInteraction, UserExt,
@@ -27,6 +31,7 @@ pub struct KickCommand;
 struct KickCommandOptions<'a> {
     target_user: &'a User,
     reason: Option<String>,
+    evidence: Option<&'a str>,
 }
 
 enum KickCommandOptionFailure {
@@ -41,10 +46,12 @@ fn generate_options(data: &CommandData) -> Result<KickCommandOptions, KickComman
     };
 
     let reason = data.string("reason").map(String::from);
+    let evidence = data.string(EVIDENCE_OPTION_NAME);
 
     Ok(KickCommandOptions {
         target_user,
         reason,
+        evidence,
     })
 }
 
@@ -77,6 +84,7 @@ impl SlashCommand for KickCommand {
                 )
                 .required(false),
             )
+            .add_option(create_evidence_option())
     }
 
     async fn handle_command(
@@ -137,11 +145,19 @@ impl SlashCommand for KickCommand {
 
         let guild = guild_service.get_guild(guild_id).await?;
 
-        if options.target_user.id == guild.owner_id {
+        if let Err(failure) = check_role_hierarchy(
+            guild_service,
+            guild_id,
+            &guild,
+            mod_user.id,
+            options.target_user.id,
+        )
+        .await
+        {
             reply_to_interaction_str(
                 &context.http,
                 interaction,
-                "You can't kick owner of the server!",
+                &failure.to_interaction_response("kick"),
                 true,
             )
             .await;
@@ -154,6 +170,22 @@ impl SlashCommand for KickCommand {
             bail!("couldn't get kick service!");
         };
 
+        let evidence = match fetch_evidence(
+            &context.http,
+            guild_service,
+            guild_id,
+            mod_user.id,
+            options.evidence,
+        )
+        .await
+        {
+            Ok(evidence) => evidence,
+            Err(message) => {
+                reply_to_interaction_str(&context.http, interaction, &message, true).await;
+                return Ok(());
+            }
+        };
+
         let setting_service = if let Some(service) = services.get::<SettingService>() {
             service
         } else {
@@ -176,6 +208,7 @@ impl SlashCommand for KickCommand {
                 options
                     .reason
                     .unwrap_or_else(|| "No reason specified".into()),
+                &evidence,
                 0,
             )
             .await
@@ -0,0 +1,287 @@
+use std::time::Duration;
+
+use anyhow::bail;
+use async_trait::async_trait;
+use serenity::all::Context;
+use serenity::all::{
+    CommandData, CommandInteraction, CommandOptionType, CommandType, InstallationContext,
+    InteractionContext,
+};
+use serenity::builder::{CreateCommand, CreateCommandOption};
+use serenity::model::Permissions;
+use tracing::error;
+
+use crate::config::Config;
+use crate::discord::slash_commands::lockdown::LockdownCommandOptionFailure::{
+    DurationParseError, MissingOption,
+};
+use crate::discord::slash_commands::SlashCommand;
+use crate::discord::util::mod_log::{log_lockdown, ModLogAction};
+use crate::discord::util::{
+    defer_interaction, edit_deferred_interaction_response, reply_to_interaction_str,
+    unauthorized_reply, verify_guild_slash_command, CommandDataExt, GuildSlashCommandInteraction,
+    UserExt,
+};
+use crate::service::lockdown::{LockdownFailure, LockdownService};
+use crate::service::setting::SettingService;
+use crate::service::Services;
+use crate::util::{normalize_reason, now};
+
+pub const SCOPE_CHANNEL: &str = "channel";
+pub const SCOPE_SERVER: &str = "server";
+
+pub struct LockdownCommand;
+
+struct LockdownCommandOptions {
+    is_server_wide: bool,
+    reason: String,
+    duration: Option<Duration>,
+}
+
+enum LockdownCommandOptionFailure<'a> {
+    MissingOption,
+    DurationParseError(&'a str),
+}
+
+fn generate_options(
+    data: &CommandData,
+) -> Result<LockdownCommandOptions, LockdownCommandOptionFailure> {
+    let is_server_wide = match data.string("scope") {
+        Some(SCOPE_SERVER) => true,
+        Some(_) => false,
+        None => return Err(MissingOption),
+    };
+
+    let reason = normalize_reason(data.string("reason").unwrap_or_default());
+
+    let duration = if let Some(s) = data.string("duration") {
+        if let Ok(duration) = humantime::parse_duration(s) {
+            Some(duration)
+        } else {
+            return Err(DurationParseError(s));
+        }
+    } else {
+        None
+    };
+
+    Ok(LockdownCommandOptions {
+        is_server_wide,
+        reason,
+        duration,
+    })
+}
+
+pub fn is_authorized(permissions: Permissions) -> bool {
+    permissions.administrator() || permissions.manage_channels()
+}
+
+#[async_trait]
+impl SlashCommand for LockdownCommand {
+    fn command_name(&self) -> &'static str {
+        "lockdown"
+    }
+
+    fn create_command(&self) -> CreateCommand {
+        CreateCommand::new("lockdown")
+            .kind(CommandType::ChatInput)
+            .description("stops everyone from sending messages in this channel or the whole server")
+            .add_integration_type(InstallationContext::Guild)
+            .add_context(InteractionContext::Guild)
+            .default_member_permissions(Permissions::MANAGE_CHANNELS)
+            .add_option(
+                CreateCommandOption::new(
+                    CommandOptionType::String,
+                    "scope",
+                    "whether to lock this channel or every channel of the server",
+                )
+                .add_string_choice(SCOPE_CHANNEL, SCOPE_CHANNEL)
+                .add_string_choice(SCOPE_SERVER, SCOPE_SERVER)
+                .required(true),
+            )
+            .add_option(
+                CreateCommandOption::new(
+                    CommandOptionType::String,
+                    "duration",
+                    "duration for the lockdown, channels stay locked until /unlock if not given",
+                )
+                .required(false),
+            )
+            .add_option(
+                CreateCommandOption::new(
+                    CommandOptionType::String,
+                    "reason",
+                    "reason for the lockdown",
+                )
+                .required(false),
+            )
+    }
+
+    async fn handle_command(
+        &self,
+        context: &Context,
+        interaction: &CommandInteraction,
+        _config: &Config,
+        services: &Services,
+    ) -> anyhow::Result<()> {
+        let GuildSlashCommandInteraction {
+            guild_id,
+            member: _,
+            permissions,
+        } = verify_guild_slash_command(interaction)?;
+
+        let mod_user = &interaction.user;
+
+        if !is_authorized(permissions) {
+            unauthorized_reply(&context.http, interaction, Permissions::MANAGE_CHANNELS).await;
+            return Ok(());
+        }
+
+        let options = match generate_options(&interaction.data) {
+            Ok(options) => options,
+            Err(DurationParseError(duration)) => {
+                reply_to_interaction_str(
+                    &context.http,
+                    interaction,
+                    &format!("Failed to understand duration: {}", duration),
+                    true,
+                )
+                .await;
+                return Ok(());
+            }
+            Err(MissingOption) => {
+                bail!("interaction has missing data options")
+            }
+        };
+
+        let lockdown_service = if let Some(service) = services.get::<LockdownService>() {
+            service
+        } else {
+            bail!("couldn't get lockdown service!");
+        };
+
+        let setting_service = if let Some(service) = services.get::<SettingService>() {
+            service
+        } else {
+            bail!("couldn't get setting service!");
+        };
+
+        defer_interaction(&context.http, interaction).await?;
+
+        // overwrites aren't cached, the snapshot has to reflect the current state of the channels
+        let channels = match guild_id.channels(&context.http).await {
+            Ok(channels) => channels,
+            Err(err) => {
+                error!("failed to fetch channels of guild: {} {}", guild_id, err);
+                edit_deferred_interaction_response(
+                    &context.http,
+                    interaction,
+                    "Failed to fetch channels of the server, make sure I have required permissions.",
+                )
+                .await;
+                return Ok(());
+            }
+        };
+
+        let channels = if options.is_server_wide {
+            channels
+        } else {
+            let channel = channels
+                .into_iter()
+                .find(|channel| channel.id.widen() == interaction.channel_id);
+
+            if let Some(channel) = channel {
+                vec![channel]
+            } else {
+                edit_deferred_interaction_response(
+                    &context.http,
+                    interaction,
+                    "This channel can't be locked down, try it in its parent channel.",
+                )
+                .await;
+                return Ok(());
+            }
+        };
+
+        let expire_time = options.duration.map(|duration| now() + duration.as_secs());
+
+        let total = channels.len();
+        let mut locked_channel_ids = vec![];
+        let mut already_locked = 0;
+        let mut failed = 0;
+        let mut is_unauthorized = false;
+        for channel in channels.iter() {
+            match lockdown_service
+                .lock_channel(
+                    &context.http,
+                    guild_id,
+                    channel,
+                    mod_user.id,
+                    expire_time,
+                    &options.reason,
+                )
+                .await
+            {
+                Ok(_) => locked_channel_ids.push(channel.id),
+                Err(LockdownFailure::AlreadyLocked) => already_locked += 1,
+                Err(LockdownFailure::Unauthorized) => {
+                    is_unauthorized = true;
+                    break;
+                }
+                Err(LockdownFailure::Unknown) => failed += 1,
+            }
+        }
+
+        let mut content = if !options.is_server_wide && already_locked == 1 {
+            "This channel is already locked down.".to_string()
+        } else if is_unauthorized {
+            format!(
+                "I don't have enough permissions to do this action! Locked {}/{} channel(s) before stopping.",
+                locked_channel_ids.len(),
+                total
+            )
+        } else {
+            format!(
+                "Locked down {}/{} channel(s).",
+                locked_channel_ids.len(),
+                total
+            )
+        };
+
+        if options.is_server_wide && already_locked > 0 {
+            content.push_str(&format!(
+                "\n{} channel(s) were already locked down.",
+                already_locked
+            ));
+        }
+
+        if failed > 0 {
+            content.push_str(&format!(
+                "\nCould not lock {} channel(s) for unknown reasons, this incident has been logged.",
+                failed
+            ));
+        }
+
+        if !locked_channel_ids.is_empty() {
+            let setting = setting_service.get_setting(guild_id).await;
+
+            if let Err(err) = log_lockdown(
+                &context.http,
+                &setting,
+                &mod_user.tag_and_id(),
+                ModLogAction::Lockdown {
+                    expiration_time: expire_time,
+                },
+                &locked_channel_ids,
+                &options.reason,
+            )
+            .await
+            {
+                content.push('\n');
+                content.push_str(err.to_interaction_response());
+            }
+        }
+
+        edit_deferred_interaction_response(&context.http, interaction, &content).await;
+        Ok(())
+    }
+}
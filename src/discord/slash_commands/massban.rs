@@ -1,49 +1,75 @@
 use std::num::{NonZeroU64, ParseIntError};
+use std::time::Duration;
 
 use anyhow::bail;
 use async_trait::async_trait;
+use regex::{Regex, RegexBuilder};
 use serenity::all::Context;
 use serenity::all::{
-    CommandData, CommandInteraction, CommandOptionType, CommandType, InstallationContext,
-    InteractionContext,
+    ButtonStyle, CommandData, CommandInteraction, CommandOptionType, CommandType,
+    InstallationContext, InteractionContext,
 };
-use serenity::builder::{CreateCommand, CreateCommandOption};
-use serenity::model::id::UserId;
+use serenity::builder::{
+    CreateActionRow, CreateAllowedMentions, CreateButton, CreateCommand, CreateCommandOption,
+    CreateInteractionResponse, EditInteractionResponse,
+};
+use serenity::collector::ComponentInteractionCollector;
+use serenity::futures::StreamExt;
+use serenity::http::Http;
+use serenity::model::id::{GuildId, UserId};
+use serenity::model::user::User;
 use serenity::model::Permissions;
+use tracing::error;
 
 use crate::config::Config;
 use crate::constants::JIM_ID;
 use crate::discord::slash_commands::SlashCommand;
+use crate::discord::util::hierarchy::{check_role_hierarchy, RoleHierarchyFailure};
 use crate::discord::util::{
     defer_interaction, edit_deferred_interaction_response, reply_to_interaction_str,
     unauthorized_reply, verify_guild_slash_command, CommandDataExt, GuildSlashCommandInteraction,
     SerenityErrorExt, UserExt,
 };
-use crate::service::guild::GuildService;
+use crate::service::guild::{CachedGuild, GuildService};
 use crate::service::hardban::{HardbanFailure, HardbanService};
 use crate::service::setting::SettingService;
 use crate::service::Services;
+use crate::util::now;
+
+const CONFIRM_TIMEOUT: Duration = Duration::from_secs(60);
+const CONFIRM_ID: &str = "massban_confirm";
+const CANCEL_ID: &str = "massban_cancel";
+const PREVIEW_SAMPLE_SIZE: usize = 10;
+// editing the response after every ban would run into rate limits during large raids
+const PROGRESS_INTERVAL: usize = 10;
+// patterns come from users, keep compiled regexes small
+const PATTERN_SIZE_LIMIT: usize = 1 << 16;
+// selections reaching further back than raids do are almost certainly mistakes
+const MAX_JOINED_WITHIN_MINUTES: i64 = 60 * 24 * 30;
+const MAX_NEW_ACCOUNT_DAYS: i64 = 365;
 
 pub struct MassbanCommand;
 
+enum MassbanSelection {
+    Ids(Vec<UserId>),
+    // these hold durations in seconds
+    JoinedWithin(u64),
+    NewAccounts(u64),
+    NamePattern(Regex),
+}
+
 struct MassbanCommandOptions {
-    target_users: Vec<UserId>,
+    selection: MassbanSelection,
 }
 
 enum MassbanCommandOptionFailure {
-    MissingOption,
+    InvalidSelection,
     UserIdParsingFailed,
+    InvalidPattern,
+    OutOfRange(i64, i64),
 }
 
-fn generate_options(
-    data: &CommandData,
-) -> Result<MassbanCommandOptions, MassbanCommandOptionFailure> {
-    let users_str = if let Some(s) = data.string("users") {
-        s
-    } else {
-        return Err(MassbanCommandOptionFailure::MissingOption);
-    };
-
+fn parse_user_ids(users_str: &str) -> Result<Vec<UserId>, MassbanCommandOptionFailure> {
     let user_ids = match users_str
         .split(',')
         .map(|element| element.trim())
@@ -54,21 +80,173 @@ fn generate_options(
         Err(_) => return Err(MassbanCommandOptionFailure::UserIdParsingFailed),
     };
 
-    let user_ids: Vec<UserId> = user_ids
+    Ok(user_ids
         .into_iter()
         .map(NonZeroU64::get)
         .map(UserId::new)
-        .collect();
+        .collect())
+}
+
+fn generate_options(
+    data: &CommandData,
+) -> Result<MassbanCommandOptions, MassbanCommandOptionFailure> {
+    let selection = match (
+        data.string("users"),
+        data.integer("joined_within"),
+        data.integer("new_account_days"),
+        data.string("name_pattern"),
+    ) {
+        (Some(users_str), None, None, None) => MassbanSelection::Ids(parse_user_ids(users_str)?),
+        (None, Some(minutes), None, None) => {
+            if !(1..=MAX_JOINED_WITHIN_MINUTES).contains(&minutes) {
+                return Err(MassbanCommandOptionFailure::OutOfRange(
+                    minutes,
+                    MAX_JOINED_WITHIN_MINUTES,
+                ));
+            }
+
+            MassbanSelection::JoinedWithin(minutes as u64 * 60)
+        }
+        (None, None, Some(days), None) => {
+            if !(1..=MAX_NEW_ACCOUNT_DAYS).contains(&days) {
+                return Err(MassbanCommandOptionFailure::OutOfRange(
+                    days,
+                    MAX_NEW_ACCOUNT_DAYS,
+                ));
+            }
+
+            MassbanSelection::NewAccounts(days as u64 * 24 * 60 * 60)
+        }
+        (None, None, None, Some(pattern)) => {
+            let regex = RegexBuilder::new(pattern)
+                .case_insensitive(true)
+                .size_limit(PATTERN_SIZE_LIMIT)
+                .build()
+                .map_err(|_| MassbanCommandOptionFailure::InvalidPattern)?;
+
+            MassbanSelection::NamePattern(regex)
+        }
+        _ => return Err(MassbanCommandOptionFailure::InvalidSelection),
+    };
 
-    Ok(MassbanCommandOptions {
-        target_users: user_ids,
-    })
+    Ok(MassbanCommandOptions { selection })
 }
 
 fn is_authorized(permissions: Permissions) -> bool {
     permissions.administrator() || permissions.ban_members()
 }
 
+// members are selected in bulk during raids, anyone the moderator or Jim couldn't act on by hand
+// is left out instead of failing the whole massban
+async fn fetch_target_position_limit(
+    guild_service: &GuildService,
+    guild_id: GuildId,
+    guild: &CachedGuild,
+    mod_user_id: UserId,
+) -> Option<i32> {
+    let jim_member = guild_service.get_member(guild_id, JIM_ID).await.ok()?;
+    let jim_position = guild_service
+        .get_highest_role_position(guild_id, &jim_member.roles)
+        .await
+        .ok()?;
+
+    if mod_user_id == guild.owner_id {
+        return Some(jim_position);
+    }
+
+    let mod_member = guild_service.get_member(guild_id, mod_user_id).await.ok()?;
+    let mod_position = guild_service
+        .get_highest_role_position(guild_id, &mod_member.roles)
+        .await
+        .ok()?;
+
+    Some(jim_position.min(mod_position))
+}
+
+async fn select_members(
+    http: &Http,
+    guild_service: &GuildService,
+    guild_id: GuildId,
+    guild: &CachedGuild,
+    mod_user_id: UserId,
+    position_limit: i32,
+    selection: &MassbanSelection,
+) -> anyhow::Result<Vec<User>> {
+    let now = now();
+    let mut targets = vec![];
+
+    let mut members = guild_id.members_iter(http).boxed();
+    while let Some(member) = members.next().await {
+        let member = member?;
+        let user = &member.user;
+
+        if user.bot() || user.id == mod_user_id || user.id == guild.owner_id {
+            continue;
+        }
+
+        let is_selected = match selection {
+            MassbanSelection::Ids(_) => false,
+            MassbanSelection::JoinedWithin(duration) => member
+                .joined_at
+                .map(|joined_at| joined_at.unix_timestamp() as u64 + duration >= now)
+                .unwrap_or(false),
+            MassbanSelection::NewAccounts(age) => {
+                user.avatar.is_none() && user.id.created_at().unix_timestamp() as u64 + age >= now
+            }
+            MassbanSelection::NamePattern(regex) => regex.is_match(&user.name),
+        };
+
+        if !is_selected {
+            continue;
+        }
+
+        let position = match guild_service
+            .get_highest_role_position(guild_id, &member.roles)
+            .await
+        {
+            Ok(position) => position,
+            Err(_) => bail!("failed to fetch roles of guild {}", guild_id),
+        };
+
+        if position < position_limit {
+            targets.push(member.user);
+        }
+    }
+
+    Ok(targets)
+}
+
+fn generate_preview(targets: &[User]) -> String {
+    let mut preview = format!("Selected {} user(s) to hardban:", targets.len());
+
+    for target in targets.iter().take(PREVIEW_SAMPLE_SIZE) {
+        preview.push_str(&format!("\n- {}", target.tag_and_id()));
+    }
+
+    if targets.len() > PREVIEW_SAMPLE_SIZE {
+        preview.push_str(&format!(
+            "\n...and {} more.",
+            targets.len() - PREVIEW_SAMPLE_SIZE
+        ));
+    }
+
+    preview
+}
+
+fn generate_confirm_buttons<'a>() -> Vec<CreateActionRow<'a>> {
+    vec![CreateActionRow::Buttons(
+        vec![
+            CreateButton::new(CONFIRM_ID)
+                .label("Confirm")
+                .style(ButtonStyle::Danger),
+            CreateButton::new(CANCEL_ID)
+                .label("Cancel")
+                .style(ButtonStyle::Secondary),
+        ]
+        .into(),
+    )]
+}
+
 #[async_trait]
 impl SlashCommand for MassbanCommand {
     fn command_name(&self) -> &'static str {
@@ -88,7 +266,35 @@ impl SlashCommand for MassbanCommand {
                     "users",
                     "comma separated ids of users to hardban",
                 )
-                .required(true),
+                .required(false),
+            )
+            .add_option(
+                CreateCommandOption::new(
+                    CommandOptionType::Integer,
+                    "joined_within",
+                    "hardbans everyone who joined in the last given minutes",
+                )
+                .min_int_value(1)
+                .max_int_value(MAX_JOINED_WITHIN_MINUTES as u64)
+                .required(false),
+            )
+            .add_option(
+                CreateCommandOption::new(
+                    CommandOptionType::Integer,
+                    "new_account_days",
+                    "hardbans members without an avatar whose account is younger than given days",
+                )
+                .min_int_value(1)
+                .max_int_value(MAX_NEW_ACCOUNT_DAYS as u64)
+                .required(false),
+            )
+            .add_option(
+                CreateCommandOption::new(
+                    CommandOptionType::String,
+                    "name_pattern",
+                    "hardbans members whose username matches given regex",
+                )
+                .required(false),
             )
     }
 
@@ -115,6 +321,16 @@ impl SlashCommand for MassbanCommand {
 
         let options = match generate_options(&interaction.data) {
             Ok(options) => options,
+            Err(MassbanCommandOptionFailure::InvalidSelection) => {
+                reply_to_interaction_str(
+                    &context.http,
+                    interaction,
+                    "Pick exactly one way to select users: users, joined_within, new_account_days or name_pattern.",
+                    true,
+                )
+                .await;
+                return Ok(());
+            }
             Err(MassbanCommandOptionFailure::UserIdParsingFailed) => {
                 reply_to_interaction_str(
                     &context.http,
@@ -125,32 +341,53 @@ impl SlashCommand for MassbanCommand {
                 .await;
                 return Ok(());
             }
-            Err(MassbanCommandOptionFailure::MissingOption) => {
-                bail!("interaction has missing data options")
+            Err(MassbanCommandOptionFailure::InvalidPattern) => {
+                reply_to_interaction_str(
+                    &context.http,
+                    interaction,
+                    "Given name pattern isn't a valid regex!",
+                    true,
+                )
+                .await;
+                return Ok(());
+            }
+            Err(MassbanCommandOptionFailure::OutOfRange(number, max)) => {
+                reply_to_interaction_str(
+                    &context.http,
+                    interaction,
+                    &format!(
+                        "Number is out of range, must be between 1 and {}: {}",
+                        max, number
+                    ),
+                    true,
+                )
+                .await;
+                return Ok(());
             }
         };
 
-        if options.target_users.contains(&mod_user.id)
-        {
-            reply_to_interaction_str(
-                &context.http,
-                interaction,
-                "You can't massban yourself, dummy!",
-                true,
-            )
-            .await;
-            return Ok(());
-        }
+        if let MassbanSelection::Ids(target_users) = &options.selection {
+            if target_users.contains(&mod_user.id) {
+                reply_to_interaction_str(
+                    &context.http,
+                    interaction,
+                    "You can't massban yourself, dummy!",
+                    true,
+                )
+                .await;
+                return Ok(());
+            }
 
-        if options.target_users.contains(&JIM_ID) {
-            reply_to_interaction_str(
-                &context.http,
-                interaction,
-                "I'm sorry, Dave. I'm afraid I can't do that.",
-                true,
-            )
-            .await;
-            return Ok(());
+            if target_users.contains(&JIM_ID) {
+                reply_to_interaction_str(
+                    &context.http,
+                    interaction,
+                    "I'm sorry, Dave. I'm afraid I can't do that.",
+                    true,
+                )
+                .await;
+                return Ok(());
+            }
         }
 
         let guild_service = if let Some(service) = services.get::<GuildService>() {
@@ -161,19 +398,17 @@ impl SlashCommand for MassbanCommand {
 
         let guild = guild_service.get_guild(guild_id).await?;
 
-        if options
-            .target_users
-            .iter()
-            .any(|target| *target == guild.owner_id)
-        {
-            reply_to_interaction_str(
-                &context.http,
-                interaction,
-                "You can't massban owner of the server!",
-                true,
-            )
-            .await;
-            return Ok(());
+        if let MassbanSelection::Ids(target_users) = &options.selection {
+            if target_users.iter().any(|target| *target == guild.owner_id) {
+                reply_to_interaction_str(
+                    &context.http,
+                    interaction,
+                    "You can't massban owner of the server!",
+                    true,
+                )
+                .await;
+                return Ok(());
+            }
         }
 
         let hardban_service = if let Some(service) = services.get::<HardbanService>() {
@@ -190,79 +425,219 @@ impl SlashCommand for MassbanCommand {
 
         defer_interaction(&context.http, interaction).await?;
 
-        let setting = setting_service.get_setting(guild_id).await;
+        let targets = if let MassbanSelection::Ids(target_users) = &options.selection {
+            let mut targets = vec![];
+            for target_user_id in target_users {
+                // listed users get the same checks as a regular hardban, anyone the moderator or Jim
+                // outranks can't be slipped into a massban
+                if let Err(failure) = check_role_hierarchy(
+                    guild_service,
+                    guild_id,
+                    &guild,
+                    mod_user.id,
+                    *target_user_id,
+                )
+                .await
+                {
+                    edit_deferred_interaction_response(
+                        &context.http,
+                        interaction,
+                        &format!(
+                            "{} User id: {}.",
+                            failure.to_interaction_response("massban"),
+                            target_user_id.get()
+                        ),
+                    )
+                    .await;
+                    return Ok(());
+                }
 
-        let mut mod_log_failure = None;
-        for target_user_id in options.target_users {
-            // massban is used for cases like raids, so getting the users from cache would unnecessarily
-            // inflate the cache that is mostly used for the front end, plus we are likely to send all
-            // these requests because they aren't likely to be in the cache in the first place
-            let target_user = match context.http.get_user(target_user_id).await {
-                Ok(user) => user,
-                Err(err) => match err.discord_error_code() {
-                    Some(10013) => {
-                        edit_deferred_interaction_response(
-                            &context.http,
-                            interaction,
-                            &format!("Couldn't find user for given id: {}.", target_user_id.get()),
-                        )
-                        .await;
-                        return Ok(());
-                    }
-                    _ => bail!("failed to fetch massban user!"),
-                },
+                // massban is used for cases like raids, so getting the users from cache would unnecessarily
+                // inflate the cache that is mostly used for the front end, plus we are likely to send all
+                // these requests because they aren't likely to be in the cache in the first place
+                match context.http.get_user(*target_user_id).await {
+                    Ok(user) => targets.push(user),
+                    Err(err) => match err.discord_error_code() {
+                        Some(10013) => {
+                            edit_deferred_interaction_response(
+                                &context.http,
+                                interaction,
+                                &format!(
+                                    "Couldn't find user for given id: {}.",
+                                    target_user_id.get()
+                                ),
+                            )
+                            .await;
+                            return Ok(());
+                        }
+                        _ => bail!("failed to fetch massban user!"),
+                    },
+                }
+            }
+
+            targets
+        } else {
+            let position_limit = if let Some(limit) =
+                fetch_target_position_limit(guild_service, guild_id, &guild, mod_user.id).await
+            {
+                limit
+            } else {
+                edit_deferred_interaction_response(
+                    &context.http,
+                    interaction,
+                    &RoleHierarchyFailure::FetchFailed.to_interaction_response("massban"),
+                )
+                .await;
+                return Ok(());
             };
 
+            match select_members(
+                &context.http,
+                guild_service,
+                guild_id,
+                &guild,
+                mod_user.id,
+                position_limit,
+                &options.selection,
+            )
+            .await
+            {
+                Ok(targets) => targets,
+                Err(err) => {
+                    error!("failed to select massban members {}", err);
+                    edit_deferred_interaction_response(
+                        &context.http,
+                        interaction,
+                        "Failed to list members of the server, make sure I have required permissions.",
+                    )
+                    .await;
+                    return Ok(());
+                }
+            }
+        };
+
+        if targets.is_empty() {
+            edit_deferred_interaction_response(
+                &context.http,
+                interaction,
+                "No users matched given selection.",
+            )
+            .await;
+            return Ok(());
+        }
+
+        let message = interaction
+            .edit_response(
+                &context.http,
+                EditInteractionResponse::default()
+                    .content(generate_preview(&targets))
+                    .allowed_mentions(CreateAllowedMentions::new())
+                    .components(generate_confirm_buttons()),
+            )
+            .await?;
+
+        let component = ComponentInteractionCollector::new(context)
+            .timeout(CONFIRM_TIMEOUT)
+            .message_id(message.id)
+            .author_id(mod_user.id)
+            .next()
+            .await;
+
+        let component = if let Some(component) = component {
+            component
+        } else {
+            edit_deferred_interaction_response(
+                &context.http,
+                interaction,
+                "Massban wasn't confirmed in time, nobody was hardbanned.",
+            )
+            .await;
+            return Ok(());
+        };
+
+        component
+            .create_response(&context.http, CreateInteractionResponse::Acknowledge)
+            .await?;
+
+        if component.data.custom_id != CONFIRM_ID {
+            edit_deferred_interaction_response(
+                &context.http,
+                interaction,
+                "Massban cancelled, nobody was hardbanned.",
+            )
+            .await;
+            return Ok(());
+        }
+
+        let setting = setting_service.get_setting(guild_id).await;
+
+        let total = targets.len();
+        let mut hardbanned = 0;
+        let mut failed = 0;
+        let mut mod_log_failure = None;
+        for (index, target_user) in targets.iter().enumerate() {
             match hardban_service
                 .issue_hardban(
                     &context.http,
                     guild_id,
                     &guild.name,
                     &setting,
+                    services,
                     Some(channel_id),
                     mod_user.id,
                     &mod_user.tag_and_id(),
-                    &target_user,
+                    target_user,
                     "Targeted in mass ban".into(),
+                    &[],
                 )
                 .await
             {
-                Ok(_) => (),
+                Ok(_) => hardbanned += 1,
                 Err(HardbanFailure::Unauthorized) => {
                     edit_deferred_interaction_response(
                         &context.http,
                         interaction,
-                        "I don't have enough permissions to do this action!",
+                        &format!(
+                            "I don't have enough permissions to do this action! Hardbanned {}/{} user(s) before stopping.",
+                            hardbanned, total
+                        ),
                     )
                     .await;
                     return Ok(());
                 }
                 Err(HardbanFailure::ModLogError(err)) => {
+                    hardbanned += 1;
                     mod_log_failure = Some(err);
                 }
-                Err(HardbanFailure::Unknown) => {
-                    edit_deferred_interaction_response(
-                        &context.http,
-                        interaction,
-                        "Could not massban one of specified users for unknown reasons, this incident has been logged.",
-                    )
-                        .await;
-                    return Ok(());
-                }
+                Err(HardbanFailure::Unknown) => failed += 1,
+            }
+
+            let processed = index + 1;
+            if processed % PROGRESS_INTERVAL == 0 && processed < total {
+                edit_deferred_interaction_response(
+                    &context.http,
+                    interaction,
+                    &format!("Hardbanning... {}/{} user(s) processed.", processed, total),
+                )
+                .await;
             }
         }
 
+        let mut content = format!("Hardbanned {}/{} user(s).", hardbanned, total);
+
+        if failed > 0 {
+            content.push_str(&format!(
+                "\nCould not hardban {} user(s) for unknown reasons, this incident has been logged.",
+                failed
+            ));
+        }
+
         if let Some(err) = mod_log_failure {
-            edit_deferred_interaction_response(
-                &context.http,
-                interaction,
-                err.to_interaction_response(),
-            )
-            .await;
-            return Ok(());
+            content.push('\n');
+            content.push_str(err.to_interaction_response());
         }
 
-        edit_deferred_interaction_response(&context.http, interaction, "Success.").await;
+        edit_deferred_interaction_response(&context.http, interaction, &content).await;
         Ok(())
     }
 }
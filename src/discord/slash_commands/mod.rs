@@ -8,19 +8,28 @@ use serenity::builder::CreateCommand;
 use crate::config::Config;
 use crate::service::Services;
 
+pub use history::{send_infraction_history, show_infraction_history};
+
 mod ban;
+mod case;
 mod clean;
 mod clean_bot;
 mod clean_user;
 mod hardban;
+mod history;
 mod iam;
 mod iam_not;
 mod info;
 mod invite;
 mod kick;
+mod lockdown;
 mod massban;
 mod melo;
 mod mute;
+mod note_add;
+mod note_list;
+mod note_remove;
+mod pardon;
 mod ping;
 mod remind;
 mod role_create;
@@ -32,7 +41,11 @@ mod tag_create;
 mod tag_edit;
 mod tag_list;
 mod tag_remove;
+mod temprole;
+mod temprole_cancel;
+mod temprole_list;
 mod unban;
+mod unlock;
 mod unmute;
 mod warn;
 mod weather;
@@ -57,19 +70,26 @@ pub struct SlashCommands(pub HashMap<&'static str, Box<dyn SlashCommand + Send +
 pub fn get_all_commands() -> SlashCommands {
     let mut commands_map: HashMap<&'static str, Box<dyn SlashCommand + Send + Sync>> =
         HashMap::new();
-    let commands: [Box<dyn SlashCommand + Send + Sync>; 30] = [
+    let commands: [Box<dyn SlashCommand + Send + Sync>; 41] = [
         Box::new(ban::BanCommand),
+        Box::new(case::CaseCommand),
         Box::new(clean::CleanCommand),
         Box::new(clean_bot::CleanBotCommand),
         Box::new(clean_user::CleanUserCommand),
         Box::new(hardban::HardbanCommand),
+        Box::new(history::HistoryCommand),
         Box::new(iam::IAMCommand),
         Box::new(iam_not::IAMNotCommand),
         Box::new(info::InfoCommand),
         Box::new(invite::InviteCommand),
         Box::new(kick::KickCommand),
+        Box::new(lockdown::LockdownCommand),
         Box::new(massban::MassbanCommand),
         Box::new(mute::MuteCommand),
+        Box::new(note_add::NoteAddCommand),
+        Box::new(note_list::NoteListCommand),
+        Box::new(note_remove::NoteRemoveCommand),
+        Box::new(pardon::PardonCommand),
         Box::new(ping::PingCommand),
         Box::new(remind::RemindCommand),
         Box::new(server::ServerCommand),
@@ -79,7 +99,11 @@ pub fn get_all_commands() -> SlashCommands {
         Box::new(tag_create::TagCreateCommand),
         Box::new(tag_remove::TagRemoveCommand),
         Box::new(tag_list::TagListCommand),
+        Box::new(temprole::TempRoleCommand),
+        Box::new(temprole_cancel::TempRoleCancelCommand),
+        Box::new(temprole_list::TempRoleListCommand),
         Box::new(unban::UnbanCommand),
+        Box::new(unlock::UnlockCommand),
         Box::new(unmute::UnmuteCommand),
         Box::new(warn::WarnCommand),
         Box::new(whois::WhoisCommand),
@@ -17,6 +17,10 @@ use crate::discord::slash_commands::mute::MuteCommandOptionFailure::{
     DurationParseError, MissingOption,
 };
 use crate::discord::slash_commands::SlashCommand;
+use crate::discord::util::evidence::{
+    create_evidence_option, fetch_evidence, EVIDENCE_OPTION_NAME,
+};
+use crate::discord::util::hierarchy::check_role_hierarchy;
 use crate::discord::util::{
     reply_to_interaction_str, unauthorized_reply, verify_guild_slash_command, CommandDataExt,
     GuildSlashCommandInteraction, UserExt,
@@ -31,6 +35,7 @@ pub struct MuteCommand;
 struct MuteCommandOptions<'a> {
     target_user: &'a User,
     reason: Option<String>,
+    evidence: Option<&'a str>,
     duration: Option<Duration>,
 }
 
@@ -47,6 +52,7 @@ fn generate_options(data: &CommandData) -> Result<MuteCommandOptions, MuteComman
     };
 
     let reason = data.string("reason").map(String::from);
+    let evidence = data.string(EVIDENCE_OPTION_NAME);
 
     let duration = if let Some(s) = data.string("duration") {
         if let Ok(duration) = humantime::parse_duration(s) {
@@ -61,6 +67,7 @@ fn generate_options(data: &CommandData) -> Result<MuteCommandOptions, MuteComman
     Ok(MuteCommandOptions {
         target_user,
         reason,
+        evidence,
         duration,
     })
 }
@@ -102,6 +109,7 @@ impl SlashCommand for MuteCommand {
                 )
                 .required(false),
             )
+            .add_option(create_evidence_option())
     }
 
     async fn handle_command(
@@ -167,11 +175,19 @@ impl SlashCommand for MuteCommand {
 
         let guild = guild_service.get_guild(guild_id).await?;
 
-        if options.target_user.id == guild.owner_id {
+        if let Err(failure) = check_role_hierarchy(
+            guild_service,
+            guild_id,
+            &guild,
+            mod_user.id,
+            options.target_user.id,
+        )
+        .await
+        {
             reply_to_interaction_str(
                 &context.http,
                 interaction,
-                "You can't mute owner of the server!",
+                &failure.to_interaction_response("mute"),
                 true,
             )
             .await;
@@ -184,6 +200,22 @@ impl SlashCommand for MuteCommand {
             bail!("couldn't get mute service!");
         };
 
+        let evidence = match fetch_evidence(
+            &context.http,
+            guild_service,
+            guild_id,
+            mod_user.id,
+            options.evidence,
+        )
+        .await
+        {
+            Ok(evidence) => evidence,
+            Err(message) => {
+                reply_to_interaction_str(&context.http, interaction, &message, true).await;
+                return Ok(());
+            }
+        };
+
         let setting_service = if let Some(service) = services.get::<SettingService>() {
             service
         } else {
@@ -206,6 +238,7 @@ impl SlashCommand for MuteCommand {
                 options
                     .reason
                     .unwrap_or_else(|| "No reason specified".into()),
+                &evidence,
                 options.duration,
                 0,
             )
@@ -0,0 +1,172 @@
+use anyhow::bail;
+use async_trait::async_trait;
+use serenity::all::Context;
+use serenity::all::{
+    CommandData, CommandInteraction, CommandOptionType, CommandType, InstallationContext,
+    InteractionContext,
+};
+use serenity::builder::{CreateCommand, CreateCommandOption};
+use serenity::model::user::User;
+use serenity::model::Permissions;
+
+use crate::config::Config;
+use crate::discord::slash_commands::SlashCommand;
+use crate::discord::util::{
+    reply_to_interaction_str, unauthorized_reply, verify_guild_slash_command, CommandDataExt,
+    GuildSlashCommandInteraction,
+};
+use crate::service::note::{InsertNoteFailure, NoteService, NOTE_MESSAGE_SIZE_LIMIT};
+use crate::service::Services;
+
+pub struct NoteAddCommand;
+
+struct NoteAddCommandOptions<'a> {
+    target_user: &'a User,
+    message: &'a str,
+}
+
+enum NoteAddCommandOptionFailure {
+    MissingOption,
+}
+
+fn generate_options(
+    data: &CommandData,
+) -> Result<NoteAddCommandOptions, NoteAddCommandOptionFailure> {
+    let target_user = if let Some((user, _)) = data.user("user") {
+        user
+    } else {
+        return Err(NoteAddCommandOptionFailure::MissingOption);
+    };
+
+    let message = if let Some(message) = data.string("message") {
+        message
+    } else {
+        return Err(NoteAddCommandOptionFailure::MissingOption);
+    };
+
+    Ok(NoteAddCommandOptions {
+        target_user,
+        message,
+    })
+}
+
+pub fn is_authorized(permissions: Permissions) -> bool {
+    permissions.administrator() || permissions.kick_members()
+}
+
+#[async_trait]
+impl SlashCommand for NoteAddCommand {
+    fn command_name(&self) -> &'static str {
+        "note-add"
+    }
+
+    fn create_command(&self) -> CreateCommand {
+        CreateCommand::new("note-add")
+            .kind(CommandType::ChatInput)
+            .description("adds a moderator note to given user, notes aren't sent to the user")
+            .add_integration_type(InstallationContext::Guild)
+            .add_context(InteractionContext::Guild)
+            .default_member_permissions(Permissions::KICK_MEMBERS)
+            .add_option(
+                CreateCommandOption::new(
+                    CommandOptionType::User,
+                    "user",
+                    "target user to add the note",
+                )
+                .required(true),
+            )
+            .add_option(
+                CreateCommandOption::new(
+                    CommandOptionType::String,
+                    "message",
+                    "content of the note",
+                )
+                .max_length(NOTE_MESSAGE_SIZE_LIMIT as u16)
+                .required(true),
+            )
+    }
+
+    async fn handle_command(
+        &self,
+        context: &Context,
+        interaction: &CommandInteraction,
+        _config: &Config,
+        services: &Services,
+    ) -> anyhow::Result<()> {
+        let GuildSlashCommandInteraction {
+            guild_id,
+            member: _,
+            permissions,
+        } = verify_guild_slash_command(interaction)?;
+
+        if !is_authorized(permissions) {
+            unauthorized_reply(&context.http, interaction, Permissions::KICK_MEMBERS).await;
+            return Ok(());
+        }
+
+        let options = match generate_options(&interaction.data) {
+            Ok(options) => options,
+            Err(NoteAddCommandOptionFailure::MissingOption) => {
+                bail!("interaction has missing data options")
+            }
+        };
+
+        let note_service = if let Some(service) = services.get::<NoteService>() {
+            service
+        } else {
+            bail!("couldn't get note service!");
+        };
+
+        match note_service
+            .insert_note(
+                guild_id,
+                options.target_user.id,
+                interaction.user.id,
+                options.message,
+            )
+            .await
+        {
+            Ok(note) => {
+                reply_to_interaction_str(
+                    &context.http,
+                    interaction,
+                    &format!("Success. Note id: #{}", note.id),
+                    true,
+                )
+                .await;
+            }
+            Err(InsertNoteFailure::MessageEmpty) => {
+                reply_to_interaction_str(
+                    &context.http,
+                    interaction,
+                    "Note content can't be empty!",
+                    true,
+                )
+                .await;
+            }
+            Err(InsertNoteFailure::MessageTooBig) => {
+                reply_to_interaction_str(
+                    &context.http,
+                    interaction,
+                    &format!(
+                        "Note content can't be longer than {} characters!",
+                        NOTE_MESSAGE_SIZE_LIMIT
+                    ),
+                    true,
+                )
+                .await;
+            }
+            Err(InsertNoteFailure::Unknown) => {
+                reply_to_interaction_str(
+                    &context.http,
+                    interaction,
+                    "Could not add the note for unknown reasons, this incident has been logged.",
+                    true,
+                )
+                .await;
+            }
+        }
+
+        Ok(())
+    }
+}
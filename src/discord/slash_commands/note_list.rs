@@ -0,0 +1,148 @@
+use anyhow::bail;
+use async_trait::async_trait;
+use serenity::all::Context;
+use serenity::all::{
+    CommandData, CommandInteraction, CommandOptionType, CommandType, InstallationContext,
+    InteractionContext,
+};
+use serenity::builder::{CreateCommand, CreateCommandOption, CreateEmbed, CreateEmbedAuthor};
+use serenity::model::user::User;
+use serenity::model::Permissions;
+
+use crate::config::Config;
+use crate::constants::EMBED_COLOR;
+use crate::discord::slash_commands::note_add::is_authorized;
+use crate::discord::slash_commands::SlashCommand;
+use crate::discord::util::{
+    reply_to_interaction_embed, reply_to_interaction_str, unauthorized_reply,
+    verify_guild_slash_command, CommandDataExt, GuildSlashCommandInteraction,
+};
+use crate::service::note::NoteService;
+use crate::service::Services;
+
+// notes can be up to 1000 characters, embed descriptions are limited to 4096
+const MAX_LISTED_NOTES: usize = 3;
+
+pub struct NoteListCommand;
+
+struct NoteListCommandOptions<'a> {
+    target_user: &'a User,
+}
+
+enum NoteListCommandOptionFailure {
+    MissingOption,
+}
+
+fn generate_options(
+    data: &CommandData,
+) -> Result<NoteListCommandOptions, NoteListCommandOptionFailure> {
+    let target_user = if let Some((user, _)) = data.user("user") {
+        user
+    } else {
+        return Err(NoteListCommandOptionFailure::MissingOption);
+    };
+
+    Ok(NoteListCommandOptions { target_user })
+}
+
+#[async_trait]
+impl SlashCommand for NoteListCommand {
+    fn command_name(&self) -> &'static str {
+        "note-list"
+    }
+
+    fn create_command(&self) -> CreateCommand {
+        CreateCommand::new("note-list")
+            .kind(CommandType::ChatInput)
+            .description("lists moderator notes of given user")
+            .add_integration_type(InstallationContext::Guild)
+            .add_context(InteractionContext::Guild)
+            .default_member_permissions(Permissions::KICK_MEMBERS)
+            .add_option(
+                CreateCommandOption::new(
+                    CommandOptionType::User,
+                    "user",
+                    "target user to list notes",
+                )
+                .required(true),
+            )
+    }
+
+    async fn handle_command(
+        &self,
+        context: &Context,
+        interaction: &CommandInteraction,
+        _config: &Config,
+        services: &Services,
+    ) -> anyhow::Result<()> {
+        let GuildSlashCommandInteraction {
+            guild_id,
+            member: _,
+            permissions,
+        } = verify_guild_slash_command(interaction)?;
+
+        if !is_authorized(permissions) {
+            unauthorized_reply(&context.http, interaction, Permissions::KICK_MEMBERS).await;
+            return Ok(());
+        }
+
+        let options = match generate_options(&interaction.data) {
+            Ok(options) => options,
+            Err(NoteListCommandOptionFailure::MissingOption) => {
+                bail!("interaction has missing data options")
+            }
+        };
+
+        let note_service = if let Some(service) = services.get::<NoteService>() {
+            service
+        } else {
+            bail!("couldn't get note service!");
+        };
+
+        let notes = note_service
+            .fetch_guild_user_notes(guild_id, options.target_user.id)
+            .await;
+
+        if notes.is_empty() {
+            reply_to_interaction_str(
+                &context.http,
+                interaction,
+                "This user doesn't have any notes!",
+                true,
+            )
+            .await;
+            return Ok(());
+        }
+
+        let mut notes_str = notes
+            .iter()
+            .take(MAX_LISTED_NOTES)
+            .map(|note| {
+                format!(
+                    "**#{}** - <t:{}> by <@{}>\n{}",
+                    note.id, note.note_time, note.moderator_user_id, note.message
+                )
+            })
+            .collect::<Vec<String>>()
+            .join("\n\n");
+
+        if notes.len() > MAX_LISTED_NOTES {
+            notes_str.push_str(&format!(
+                "\n\n...and {} older notes, see the dashboard for all of them.",
+                notes.len() - MAX_LISTED_NOTES
+            ));
+        }
+
+        let embed = CreateEmbed::default()
+            .author(
+                CreateEmbedAuthor::new(format!("Notes of {}", options.target_user.tag()))
+                    .icon_url(options.target_user.face()),
+            )
+            .description(notes_str)
+            .colour(EMBED_COLOR);
+
+        reply_to_interaction_embed(&context.http, interaction, embed, true).await;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,125 @@
+use anyhow::bail;
+use async_trait::async_trait;
+use serenity::all::Context;
+use serenity::all::{
+    CommandData, CommandInteraction, CommandOptionType, CommandType, InstallationContext,
+    InteractionContext,
+};
+use serenity::builder::{CreateCommand, CreateCommandOption};
+use serenity::model::Permissions;
+
+use crate::config::Config;
+use crate::discord::slash_commands::note_add::is_authorized;
+use crate::discord::slash_commands::SlashCommand;
+use crate::discord::util::{
+    reply_to_interaction_str, unauthorized_reply, verify_guild_slash_command, CommandDataExt,
+    GuildSlashCommandInteraction,
+};
+use crate::service::note::{NoteService, RemoveNoteFailure};
+use crate::service::Services;
+
+pub struct NoteRemoveCommand;
+
+struct NoteRemoveCommandOptions {
+    id: i32,
+}
+
+enum NoteRemoveCommandOptionFailure {
+    MissingOption,
+}
+
+fn generate_options(
+    data: &CommandData,
+) -> Result<NoteRemoveCommandOptions, NoteRemoveCommandOptionFailure> {
+    let id = if let Some(id) = data.integer("id") {
+        id as i32
+    } else {
+        return Err(NoteRemoveCommandOptionFailure::MissingOption);
+    };
+
+    Ok(NoteRemoveCommandOptions { id })
+}
+
+#[async_trait]
+impl SlashCommand for NoteRemoveCommand {
+    fn command_name(&self) -> &'static str {
+        "note-remove"
+    }
+
+    fn create_command(&self) -> CreateCommand {
+        CreateCommand::new("note-remove")
+            .kind(CommandType::ChatInput)
+            .description("removes a moderator note")
+            .add_integration_type(InstallationContext::Guild)
+            .add_context(InteractionContext::Guild)
+            .default_member_permissions(Permissions::KICK_MEMBERS)
+            .add_option(
+                CreateCommandOption::new(
+                    CommandOptionType::Integer,
+                    "id",
+                    "id of the note, see /note-list",
+                )
+                .min_int_value(1)
+                .max_int_value(i32::MAX as u64)
+                .required(true),
+            )
+    }
+
+    async fn handle_command(
+        &self,
+        context: &Context,
+        interaction: &CommandInteraction,
+        _config: &Config,
+        services: &Services,
+    ) -> anyhow::Result<()> {
+        let GuildSlashCommandInteraction {
+            guild_id,
+            member: _,
+            permissions,
+        } = verify_guild_slash_command(interaction)?;
+
+        if !is_authorized(permissions) {
+            unauthorized_reply(&context.http, interaction, Permissions::KICK_MEMBERS).await;
+            return Ok(());
+        }
+
+        let options = match generate_options(&interaction.data) {
+            Ok(options) => options,
+            Err(NoteRemoveCommandOptionFailure::MissingOption) => {
+                bail!("interaction has missing data options")
+            }
+        };
+
+        let note_service = if let Some(service) = services.get::<NoteService>() {
+            service
+        } else {
+            bail!("couldn't get note service!");
+        };
+
+        match note_service.remove_note(guild_id, options.id).await {
+            Ok(_) => {
+                reply_to_interaction_str(&context.http, interaction, "Success.", true).await;
+            }
+            Err(RemoveNoteFailure::NoteDoesNotExist) => {
+                reply_to_interaction_str(
+                    &context.http,
+                    interaction,
+                    "Could not find a note with given id!",
+                    true,
+                )
+                .await;
+            }
+            Err(RemoveNoteFailure::Unknown) => {
+                reply_to_interaction_str(
+                    &context.http,
+                    interaction,
+                    "Could not remove the note for unknown reasons, this incident has been logged.",
+                    true,
+                )
+                .await;
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,177 @@
+use anyhow::bail;
+use async_trait::async_trait;
+use serenity::all::Context;
+use serenity::all::{
+    CommandData, CommandInteraction, CommandOptionType, CommandType, InstallationContext,
+    InteractionContext,
+};
+use serenity::builder::{CreateCommand, CreateCommandOption};
+use serenity::model::Permissions;
+
+use crate::config::Config;
+use crate::discord::slash_commands::pardon::PardonCommandOptionFailure::MissingOption;
+use crate::discord::slash_commands::SlashCommand;
+use crate::discord::util::infraction::{
+    fetch_infraction_with_case_number, log_infraction_lift, update_infraction,
+};
+use crate::discord::util::mod_log::ModLogAction;
+use crate::discord::util::{
+    reply_to_interaction_str, unauthorized_reply, verify_guild_slash_command, CommandDataExt,
+    GuildSlashCommandInteraction, UserExt,
+};
+use crate::service::setting::SettingService;
+use crate::service::Services;
+
+pub struct PardonCommand;
+
+struct PardonCommandOptions {
+    case_number: i32,
+}
+
+enum PardonCommandOptionFailure {
+    MissingOption,
+}
+
+fn generate_options(
+    data: &CommandData,
+) -> Result<PardonCommandOptions, PardonCommandOptionFailure> {
+    let case_number = if let Some(case_number) = data.integer("case") {
+        case_number as i32
+    } else {
+        return Err(MissingOption);
+    };
+
+    Ok(PardonCommandOptions { case_number })
+}
+
+#[async_trait]
+impl SlashCommand for PardonCommand {
+    fn command_name(&self) -> &'static str {
+        "pardon"
+    }
+
+    fn create_command(&self) -> CreateCommand {
+        CreateCommand::new("pardon")
+            .kind(CommandType::ChatInput)
+            .description("pardons given infraction so it no longer counts towards thresholds")
+            .add_integration_type(InstallationContext::Guild)
+            .add_context(InteractionContext::Guild)
+            .default_member_permissions(Permissions::KICK_MEMBERS)
+            .add_option(
+                CreateCommandOption::new(
+                    CommandOptionType::Integer,
+                    "case",
+                    "case number of the infraction",
+                )
+                .min_int_value(1)
+                .max_int_value(i32::MAX as u64)
+                .required(true),
+            )
+    }
+
+    async fn handle_command(
+        &self,
+        context: &Context,
+        interaction: &CommandInteraction,
+        _config: &Config,
+        services: &Services,
+    ) -> anyhow::Result<()> {
+        let GuildSlashCommandInteraction {
+            guild_id,
+            member: _,
+            permissions,
+        } = verify_guild_slash_command(interaction)?;
+
+        let options = match generate_options(&interaction.data) {
+            Ok(options) => options,
+            Err(MissingOption) => {
+                bail!("interaction has missing data options")
+            }
+        };
+
+        let mut infraction = if let Some(infraction) =
+            fetch_infraction_with_case_number(services, guild_id, options.case_number).await?
+        {
+            infraction
+        } else {
+            reply_to_interaction_str(
+                &context.http,
+                interaction,
+                "Infraction with given case number doesn't exist!",
+                true,
+            )
+            .await;
+            return Ok(());
+        };
+
+        let required_permission = infraction.required_permission();
+        if !permissions.administrator() && !permissions.contains(required_permission) {
+            unauthorized_reply(&context.http, interaction, required_permission).await;
+            return Ok(());
+        }
+
+        if infraction.is_pardoned() {
+            reply_to_interaction_str(
+                &context.http,
+                interaction,
+                "This infraction is already pardoned.",
+                true,
+            )
+            .await;
+            return Ok(());
+        }
+
+        if let Err(message) = infraction.pardon() {
+            reply_to_interaction_str(&context.http, interaction, message, true).await;
+            return Ok(());
+        }
+
+        let setting_service = if let Some(service) = services.get::<SettingService>() {
+            service
+        } else {
+            bail!("couldn't get setting service!");
+        };
+
+        let setting = setting_service.get_setting(guild_id).await;
+
+        if update_infraction(services, infraction.clone())
+            .await
+            .is_none()
+        {
+            reply_to_interaction_str(
+                &context.http,
+                interaction,
+                "Could not pardon specified infraction for unknown reasons, this incident has been logged.",
+                true,
+            )
+            .await;
+            return Ok(());
+        }
+
+        match log_infraction_lift(
+            &context.http,
+            &setting,
+            &interaction.user.tag_and_id(),
+            &infraction,
+            ModLogAction::Pardon,
+            None,
+        )
+        .await
+        {
+            Ok(_) => {
+                reply_to_interaction_str(&context.http, interaction, "Success.", true).await;
+            }
+            Err(err) => {
+                reply_to_interaction_str(
+                    &context.http,
+                    interaction,
+                    err.to_interaction_response(),
+                    true,
+                )
+                .await;
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -15,6 +15,10 @@ use crate::discord::slash_commands::softban::SoftbanCommandOptionFailure::{
     DaysOutOfRange, MissingOption,
 };
 use crate::discord::slash_commands::SlashCommand;
+use crate::discord::util::evidence::{
+    create_evidence_option, fetch_evidence, EVIDENCE_OPTION_NAME,
+};
+use crate::discord::util::hierarchy::check_role_hierarchy;
 use crate::discord::util::{
     reply_to_interaction_str, unauthorized_reply, verify_guild_slash_command, CommandDataExt,
     GuildSlashCommandInteraction, UserExt,
@@ -29,6 +33,7 @@ pub struct SoftbanCommand;
 struct SoftbanCommandOptions<'a> {
     target_user: &'a User,
     reason: Option<String>,
+    evidence: Option<&'a str>,
     days: Option<u8>,
 }
 
@@ -47,6 +52,7 @@ fn generate_options(
     };
 
     let reason = data.string("reason").map(String::from);
+    let evidence = data.string(EVIDENCE_OPTION_NAME);
 
     let days = data.integer("days");
 
@@ -59,6 +65,7 @@ fn generate_options(
     Ok(SoftbanCommandOptions {
         target_user,
         reason,
+        evidence,
         days: days.map(|days| days as u8),
     })
 }
@@ -102,6 +109,7 @@ impl SlashCommand for SoftbanCommand {
                 .min_int_value(1)
                 .max_int_value(7),
             )
+            .add_option(create_evidence_option())
     }
 
     async fn handle_command(
@@ -175,11 +183,19 @@ impl SlashCommand for SoftbanCommand {
 
         let guild = guild_service.get_guild(guild_id).await?;
 
-        if options.target_user.id == guild.owner_id {
+        if let Err(failure) = check_role_hierarchy(
+            guild_service,
+            guild_id,
+            &guild,
+            mod_user.id,
+            options.target_user.id,
+        )
+        .await
+        {
             reply_to_interaction_str(
                 &context.http,
                 interaction,
-                "You can't softban owner of the server!",
+                &failure.to_interaction_response("softban"),
                 true,
             )
             .await;
@@ -192,6 +208,22 @@ impl SlashCommand for SoftbanCommand {
             bail!("couldn't get softban service!");
         };
 
+        let evidence = match fetch_evidence(
+            &context.http,
+            guild_service,
+            guild_id,
+            mod_user.id,
+            options.evidence,
+        )
+        .await
+        {
+            Ok(evidence) => evidence,
+            Err(message) => {
+                reply_to_interaction_str(&context.http, interaction, &message, true).await;
+                return Ok(());
+            }
+        };
+
         let setting_service = if let Some(service) = services.get::<SettingService>() {
             service
         } else {
@@ -214,6 +246,7 @@ impl SlashCommand for SoftbanCommand {
                 options
                     .reason
                     .unwrap_or_else(|| "No reason specified".into()),
+                &evidence,
                 options.days.unwrap_or(1) as u32,
                 0,
             )
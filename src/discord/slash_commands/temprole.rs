@@ -0,0 +1,331 @@
+use std::time::Duration;
+
+use anyhow::bail;
+use async_trait::async_trait;
+use serenity::all::Context;
+use serenity::all::{
+    CommandData, CommandInteraction, CommandOptionType, CommandType, InstallationContext,
+    InteractionContext,
+};
+use serenity::builder::{CreateCommand, CreateCommandOption};
+use serenity::model::guild::Role;
+use serenity::model::id::{GuildId, UserId};
+use serenity::model::user::User;
+use serenity::model::Permissions;
+
+use crate::config::Config;
+use crate::constants::JIM_ID;
+use crate::discord::slash_commands::temprole::TempRoleCommandOptionFailure::{
+    DurationParseError, MissingOption,
+};
+use crate::discord::slash_commands::SlashCommand;
+use crate::discord::util::hierarchy::check_role_hierarchy;
+use crate::discord::util::{
+    reply_to_interaction_str, unauthorized_reply, verify_guild_slash_command, CommandDataExt,
+    GuildSlashCommandInteraction,
+};
+use crate::service::guild::{CachedGuild, GuildService};
+use crate::service::temp_role::{GrantTempRoleFailure, TempRoleService};
+use crate::service::Services;
+
+pub struct TempRoleCommand;
+
+struct TempRoleCommandOptions<'a> {
+    target_user: &'a User,
+    is_member: bool,
+    has_role: bool,
+    role: &'a Role,
+    duration: Duration,
+}
+
+enum TempRoleCommandOptionFailure<'a> {
+    MissingOption,
+    DurationParseError(&'a str),
+}
+
+fn generate_options(
+    data: &CommandData,
+) -> Result<TempRoleCommandOptions, TempRoleCommandOptionFailure> {
+    let (target_user, member) = if let Some(user) = data.user("user") {
+        user
+    } else {
+        return Err(MissingOption);
+    };
+
+    let role = if let Some(role) = data.role("role") {
+        role
+    } else {
+        return Err(MissingOption);
+    };
+
+    let duration = if let Some(s) = data.string("duration") {
+        if let Ok(duration) = humantime::parse_duration(s) {
+            duration
+        } else {
+            return Err(DurationParseError(s));
+        }
+    } else {
+        return Err(MissingOption);
+    };
+
+    Ok(TempRoleCommandOptions {
+        target_user,
+        is_member: member.is_some(),
+        has_role: member.is_some_and(|member| member.roles.contains(&role.id)),
+        role,
+        duration,
+    })
+}
+
+pub fn is_authorized(permissions: Permissions) -> bool {
+    permissions.administrator() || permissions.manage_roles()
+}
+
+// Discord only checks the role against Jim's top role, moderators shouldn't be able to hand out
+// roles they couldn't assign themselves
+async fn check_role_assignable(
+    guild_service: &GuildService,
+    guild_id: GuildId,
+    guild: &CachedGuild,
+    mod_user_id: UserId,
+    role: &Role,
+) -> Result<(), &'static str> {
+    const FETCH_FAILED: &str =
+        "Could not verify role hierarchy of the server, please try again later.";
+
+    if role.id == guild_id.everyone_role() {
+        return Err("You can't grant the @everyone role!");
+    }
+
+    let roles = guild_service
+        .get_roles(guild_id)
+        .await
+        .map_err(|_err| FETCH_FAILED)?;
+
+    let role_position = if let Some(role) = roles.get(&role.id) {
+        role.position
+    } else {
+        return Err("This role no longer exists!");
+    };
+
+    if mod_user_id != guild.owner_id {
+        let mod_member = guild_service
+            .get_member(guild_id, mod_user_id)
+            .await
+            .map_err(|_err| FETCH_FAILED)?;
+
+        let mod_position = guild_service
+            .get_highest_role_position(guild_id, &mod_member.roles)
+            .await
+            .map_err(|_err| FETCH_FAILED)?;
+
+        if role_position >= mod_position {
+            return Err("You can't grant this role, it is equal to or above your highest role!");
+        }
+    }
+
+    let jim_member = guild_service
+        .get_member(guild_id, JIM_ID)
+        .await
+        .map_err(|_err| FETCH_FAILED)?;
+
+    let jim_position = guild_service
+        .get_highest_role_position(guild_id, &jim_member.roles)
+        .await
+        .map_err(|_err| FETCH_FAILED)?;
+
+    if role_position >= jim_position {
+        return Err("I can't grant this role, it is equal to or above my highest role! Move my role above it in server settings.");
+    }
+
+    Ok(())
+}
+
+#[async_trait]
+impl SlashCommand for TempRoleCommand {
+    fn command_name(&self) -> &'static str {
+        "temprole"
+    }
+
+    fn create_command(&self) -> CreateCommand {
+        CreateCommand::new("temprole")
+            .kind(CommandType::ChatInput)
+            .description("grants given role to given user, role is removed once duration expires")
+            .add_integration_type(InstallationContext::Guild)
+            .add_context(InteractionContext::Guild)
+            .default_member_permissions(Permissions::MANAGE_ROLES)
+            .add_option(
+                CreateCommandOption::new(
+                    CommandOptionType::User,
+                    "user",
+                    "target user to grant the role",
+                )
+                .required(true),
+            )
+            .add_option(
+                CreateCommandOption::new(CommandOptionType::Role, "role", "role to grant")
+                    .required(true),
+            )
+            .add_option(
+                CreateCommandOption::new(
+                    CommandOptionType::String,
+                    "duration",
+                    "how long the user keeps the role",
+                )
+                .required(true),
+            )
+    }
+
+    async fn handle_command(
+        &self,
+        context: &Context,
+        interaction: &CommandInteraction,
+        _config: &Config,
+        services: &Services,
+    ) -> anyhow::Result<()> {
+        let GuildSlashCommandInteraction {
+            guild_id,
+            member: _,
+            permissions,
+        } = verify_guild_slash_command(interaction)?;
+
+        let mod_user = &interaction.user;
+
+        if !is_authorized(permissions) {
+            unauthorized_reply(&context.http, interaction, Permissions::MANAGE_ROLES).await;
+            return Ok(());
+        }
+
+        let options = match generate_options(&interaction.data) {
+            Ok(options) => options,
+            Err(DurationParseError(duration)) => {
+                reply_to_interaction_str(
+                    &context.http,
+                    interaction,
+                    &format!("Failed to understand duration: {}", duration),
+                    true,
+                )
+                .await;
+                return Ok(());
+            }
+            Err(MissingOption) => {
+                bail!("interaction has missing data options")
+            }
+        };
+
+        if !options.is_member {
+            reply_to_interaction_str(
+                &context.http,
+                interaction,
+                "Specified user isn't a member of this server!",
+                true,
+            )
+            .await;
+            return Ok(());
+        }
+
+        let guild_service = if let Some(service) = services.get::<GuildService>() {
+            service
+        } else {
+            bail!("couldn't get guild service!");
+        };
+
+        let guild = guild_service.get_guild(guild_id).await?;
+
+        if let Err(message) =
+            check_role_assignable(guild_service, guild_id, &guild, mod_user.id, options.role).await
+        {
+            reply_to_interaction_str(&context.http, interaction, message, true).await;
+            return Ok(());
+        }
+
+        if let Err(failure) = check_role_hierarchy(
+            guild_service,
+            guild_id,
+            &guild,
+            mod_user.id,
+            options.target_user.id,
+        )
+        .await
+        {
+            reply_to_interaction_str(
+                &context.http,
+                interaction,
+                &failure.to_interaction_response("grant roles to"),
+                true,
+            )
+            .await;
+            return Ok(());
+        }
+
+        let temp_role_service = if let Some(service) = services.get::<TempRoleService>() {
+            service
+        } else {
+            bail!("couldn't get temp role service!");
+        };
+
+        match temp_role_service
+            .grant_temp_role(
+                &context.http,
+                guild_id,
+                options.target_user.id,
+                options.role.id,
+                mod_user.id,
+                options.has_role,
+                options.duration,
+            )
+            .await
+        {
+            Ok(temp_role) => {
+                reply_to_interaction_str(
+                    &context.http,
+                    interaction,
+                    &format!(
+                        "Success. Role will be removed <t:{}:R>, grant id: #{}",
+                        temp_role.expire_time, temp_role.id
+                    ),
+                    true,
+                )
+                .await;
+            }
+            Err(GrantTempRoleFailure::AlreadyHasRole) => {
+                reply_to_interaction_str(
+                    &context.http,
+                    interaction,
+                    "Specified user already has this role!",
+                    true,
+                )
+                .await;
+            }
+            Err(GrantTempRoleFailure::RoleNotFound) => {
+                reply_to_interaction_str(
+                    &context.http,
+                    interaction,
+                    "This role no longer exists!",
+                    true,
+                )
+                .await;
+            }
+            Err(GrantTempRoleFailure::Unauthorized) => {
+                reply_to_interaction_str(
+                    &context.http,
+                    interaction,
+                    "I don't have enough permissions to do this action!",
+                    true,
+                )
+                .await;
+            }
+            Err(GrantTempRoleFailure::Unknown) => {
+                reply_to_interaction_str(
+                    &context.http,
+                    interaction,
+                    "Could not grant specified role for unknown reasons, this incident has been logged.",
+                    true,
+                )
+                .await;
+            }
+        }
+
+        Ok(())
+    }
+}
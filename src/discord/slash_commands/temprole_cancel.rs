@@ -0,0 +1,145 @@
+use anyhow::bail;
+use async_trait::async_trait;
+use serenity::all::Context;
+use serenity::all::{
+    CommandData, CommandInteraction, CommandOptionType, CommandType, InstallationContext,
+    InteractionContext,
+};
+use serenity::builder::{CreateCommand, CreateCommandOption};
+use serenity::model::Permissions;
+
+use crate::config::Config;
+use crate::discord::slash_commands::temprole::is_authorized;
+use crate::discord::slash_commands::SlashCommand;
+use crate::discord::util::{
+    reply_to_interaction_str, unauthorized_reply, verify_guild_slash_command, CommandDataExt,
+    GuildSlashCommandInteraction,
+};
+use crate::service::temp_role::{CancelTempRoleFailure, TempRoleService};
+use crate::service::Services;
+
+pub struct TempRoleCancelCommand;
+
+struct TempRoleCancelCommandOptions {
+    id: i32,
+}
+
+enum TempRoleCancelCommandOptionFailure {
+    MissingOption,
+}
+
+fn generate_options(
+    data: &CommandData,
+) -> Result<TempRoleCancelCommandOptions, TempRoleCancelCommandOptionFailure> {
+    let id = if let Some(id) = data.integer("id") {
+        id as i32
+    } else {
+        return Err(TempRoleCancelCommandOptionFailure::MissingOption);
+    };
+
+    Ok(TempRoleCancelCommandOptions { id })
+}
+
+#[async_trait]
+impl SlashCommand for TempRoleCancelCommand {
+    fn command_name(&self) -> &'static str {
+        "temprole-cancel"
+    }
+
+    fn create_command(&self) -> CreateCommand {
+        CreateCommand::new("temprole-cancel")
+            .kind(CommandType::ChatInput)
+            .description("removes a temporarily granted role before it expires")
+            .add_integration_type(InstallationContext::Guild)
+            .add_context(InteractionContext::Guild)
+            .default_member_permissions(Permissions::MANAGE_ROLES)
+            .add_option(
+                CreateCommandOption::new(
+                    CommandOptionType::Integer,
+                    "id",
+                    "id of the grant, see /temprole-list",
+                )
+                .min_int_value(1)
+                .max_int_value(i32::MAX as u64)
+                .required(true),
+            )
+    }
+
+    async fn handle_command(
+        &self,
+        context: &Context,
+        interaction: &CommandInteraction,
+        _config: &Config,
+        services: &Services,
+    ) -> anyhow::Result<()> {
+        let GuildSlashCommandInteraction {
+            guild_id,
+            member: _,
+            permissions,
+        } = verify_guild_slash_command(interaction)?;
+
+        if !is_authorized(permissions) {
+            unauthorized_reply(&context.http, interaction, Permissions::MANAGE_ROLES).await;
+            return Ok(());
+        }
+
+        let options = match generate_options(&interaction.data) {
+            Ok(options) => options,
+            Err(TempRoleCancelCommandOptionFailure::MissingOption) => {
+                bail!("interaction has missing data options")
+            }
+        };
+
+        let temp_role_service = if let Some(service) = services.get::<TempRoleService>() {
+            service
+        } else {
+            bail!("couldn't get temp role service!");
+        };
+
+        let temp_role = match temp_role_service
+            .fetch_guild_temp_role(guild_id, options.id)
+            .await
+        {
+            Some(temp_role) if !temp_role.removed => temp_role,
+            _ => {
+                reply_to_interaction_str(
+                    &context.http,
+                    interaction,
+                    "Could not find an active temporary role grant with given id!",
+                    true,
+                )
+                .await;
+                return Ok(());
+            }
+        };
+
+        match temp_role_service
+            .cancel_temp_role(&context.http, &temp_role)
+            .await
+        {
+            Ok(_) => {
+                reply_to_interaction_str(&context.http, interaction, "Success.", true).await;
+            }
+            Err(CancelTempRoleFailure::Unauthorized) => {
+                reply_to_interaction_str(
+                    &context.http,
+                    interaction,
+                    "I don't have enough permissions to do this action!",
+                    true,
+                )
+                .await;
+            }
+            Err(CancelTempRoleFailure::Unknown) => {
+                reply_to_interaction_str(
+                    &context.http,
+                    interaction,
+                    "Could not remove specified role for unknown reasons, this incident has been logged.",
+                    true,
+                )
+                .await;
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,118 @@
+use anyhow::bail;
+use async_trait::async_trait;
+use serenity::all::Context;
+use serenity::all::{
+    CommandInteraction, CommandOptionType, CommandType, InstallationContext, InteractionContext,
+};
+use serenity::builder::{CreateCommand, CreateCommandOption, CreateEmbed, CreateEmbedAuthor};
+use serenity::model::Permissions;
+
+use crate::config::Config;
+use crate::constants::{AVATAR_URL, EMBED_COLOR};
+use crate::discord::slash_commands::temprole::is_authorized;
+use crate::discord::slash_commands::SlashCommand;
+use crate::discord::util::{
+    reply_to_interaction_embed, reply_to_interaction_str, unauthorized_reply,
+    verify_guild_slash_command, CommandDataExt, GuildSlashCommandInteraction,
+};
+use crate::service::temp_role::TempRoleService;
+use crate::service::Services;
+
+// keeps the description well under the embed limit
+const MAX_LISTED_GRANTS: usize = 25;
+
+pub struct TempRoleListCommand;
+
+#[async_trait]
+impl SlashCommand for TempRoleListCommand {
+    fn command_name(&self) -> &'static str {
+        "temprole-list"
+    }
+
+    fn create_command(&self) -> CreateCommand {
+        CreateCommand::new("temprole-list")
+            .kind(CommandType::ChatInput)
+            .description("lists temporary role grants that haven't expired yet")
+            .add_integration_type(InstallationContext::Guild)
+            .add_context(InteractionContext::Guild)
+            .default_member_permissions(Permissions::MANAGE_ROLES)
+            .add_option(
+                CreateCommandOption::new(
+                    CommandOptionType::User,
+                    "user",
+                    "only list grants of given user",
+                )
+                .required(false),
+            )
+    }
+
+    async fn handle_command(
+        &self,
+        context: &Context,
+        interaction: &CommandInteraction,
+        _config: &Config,
+        services: &Services,
+    ) -> anyhow::Result<()> {
+        let GuildSlashCommandInteraction {
+            guild_id,
+            member: _,
+            permissions,
+        } = verify_guild_slash_command(interaction)?;
+
+        if !is_authorized(permissions) {
+            unauthorized_reply(&context.http, interaction, Permissions::MANAGE_ROLES).await;
+            return Ok(());
+        }
+
+        let target_user_id = interaction.data.user("user").map(|(user, _)| user.id);
+
+        let temp_role_service = if let Some(service) = services.get::<TempRoleService>() {
+            service
+        } else {
+            bail!("couldn't get temp role service!");
+        };
+
+        let temp_roles = temp_role_service
+            .fetch_active_guild_temp_roles(guild_id, target_user_id)
+            .await;
+
+        if temp_roles.is_empty() {
+            reply_to_interaction_str(
+                &context.http,
+                interaction,
+                "There are no active temporary role grants!",
+                true,
+            )
+            .await;
+            return Ok(());
+        }
+
+        let mut grants_str = temp_roles
+            .iter()
+            .take(MAX_LISTED_GRANTS)
+            .map(|temp_role| {
+                format!(
+                    "\u{2022} #{} - <@{}> - <@&{}> - expires <t:{}:R>",
+                    temp_role.id, temp_role.user_id, temp_role.role_id, temp_role.expire_time
+                )
+            })
+            .collect::<Vec<String>>()
+            .join("\n");
+
+        if temp_roles.len() > MAX_LISTED_GRANTS {
+            grants_str.push_str(&format!(
+                "\n...and {} more.",
+                temp_roles.len() - MAX_LISTED_GRANTS
+            ));
+        }
+
+        let embed = CreateEmbed::default()
+            .author(CreateEmbedAuthor::new("Temporary role grants").icon_url(AVATAR_URL))
+            .description(grants_str)
+            .colour(EMBED_COLOR);
+
+        reply_to_interaction_embed(&context.http, interaction, embed, true).await;
+
+        Ok(())
+    }
+}
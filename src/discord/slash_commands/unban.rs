@@ -12,11 +12,14 @@ use serenity::model::Permissions;
 use crate::config::Config;
 use crate::discord::slash_commands::unban::UnbanCommandOptionFailure::MissingOption;
 use crate::discord::slash_commands::SlashCommand;
+use crate::discord::util::infraction::{log_infraction_lift, Infraction};
+use crate::discord::util::mod_log::ModLogAction;
 use crate::discord::util::{
     reply_to_interaction_str, unauthorized_reply, verify_guild_slash_command, CommandDataExt,
     GuildSlashCommandInteraction, UserExt,
 };
 use crate::service::ban::{BanService, UnbanFailure};
+use crate::service::setting::SettingService;
 use crate::service::Services;
 
 pub struct UnbanCommand;
@@ -95,6 +98,12 @@ impl SlashCommand for UnbanCommand {
             bail!("couldn't get ban service!");
         };
 
+        let setting_service = if let Some(service) = services.get::<SettingService>() {
+            service
+        } else {
+            bail!("couldn't get setting service!");
+        };
+
         match ban_service
             .unban(
                 &context.http,
@@ -104,8 +113,26 @@ impl SlashCommand for UnbanCommand {
             )
             .await
         {
-            Ok(_) => {
-                reply_to_interaction_str(&context.http, interaction, "Success.", true).await;
+            Ok(lifted_bans) => {
+                let setting = setting_service.get_setting(guild_id).await;
+
+                let mut response = "Success.";
+                for ban in lifted_bans {
+                    if let Err(err) = log_infraction_lift(
+                        &context.http,
+                        &setting,
+                        &mod_user.tag_and_id(),
+                        &Infraction::Ban(ban),
+                        ModLogAction::Unban,
+                        None,
+                    )
+                    .await
+                    {
+                        response = err.to_interaction_response();
+                    }
+                }
+
+                reply_to_interaction_str(&context.http, interaction, response, true).await;
             }
             Err(UnbanFailure::UserNotBanned) => {
                 reply_to_interaction_str(
@@ -0,0 +1,214 @@
+use anyhow::bail;
+use async_trait::async_trait;
+use serenity::all::Context;
+use serenity::all::{
+    CommandData, CommandInteraction, CommandOptionType, CommandType, InstallationContext,
+    InteractionContext,
+};
+use serenity::builder::{CreateCommand, CreateCommandOption};
+use serenity::model::id::ChannelId;
+use serenity::model::Permissions;
+
+use crate::config::Config;
+use crate::discord::slash_commands::lockdown::{is_authorized, SCOPE_CHANNEL, SCOPE_SERVER};
+use crate::discord::slash_commands::unlock::UnlockCommandOptionFailure::MissingOption;
+use crate::discord::slash_commands::SlashCommand;
+use crate::discord::util::mod_log::{log_lockdown, ModLogAction};
+use crate::discord::util::{
+    defer_interaction, edit_deferred_interaction_response, unauthorized_reply,
+    verify_guild_slash_command, CommandDataExt, GuildSlashCommandInteraction, UserExt,
+};
+use crate::service::lockdown::{LockdownService, UnlockFailure};
+use crate::service::setting::SettingService;
+use crate::service::Services;
+use crate::util::normalize_reason;
+
+pub struct UnlockCommand;
+
+struct UnlockCommandOptions {
+    is_server_wide: bool,
+    reason: String,
+}
+
+enum UnlockCommandOptionFailure {
+    MissingOption,
+}
+
+fn generate_options(
+    data: &CommandData,
+) -> Result<UnlockCommandOptions, UnlockCommandOptionFailure> {
+    let is_server_wide = match data.string("scope") {
+        Some(SCOPE_SERVER) => true,
+        Some(_) => false,
+        None => return Err(MissingOption),
+    };
+
+    let reason = normalize_reason(data.string("reason").unwrap_or_default());
+
+    Ok(UnlockCommandOptions {
+        is_server_wide,
+        reason,
+    })
+}
+
+#[async_trait]
+impl SlashCommand for UnlockCommand {
+    fn command_name(&self) -> &'static str {
+        "unlock"
+    }
+
+    fn create_command(&self) -> CreateCommand {
+        CreateCommand::new("unlock")
+            .kind(CommandType::ChatInput)
+            .description("lifts the lockdown of this channel or the whole server")
+            .add_integration_type(InstallationContext::Guild)
+            .add_context(InteractionContext::Guild)
+            .default_member_permissions(Permissions::MANAGE_CHANNELS)
+            .add_option(
+                CreateCommandOption::new(
+                    CommandOptionType::String,
+                    "scope",
+                    "whether to unlock this channel or every locked channel of the server",
+                )
+                .add_string_choice(SCOPE_CHANNEL, SCOPE_CHANNEL)
+                .add_string_choice(SCOPE_SERVER, SCOPE_SERVER)
+                .required(true),
+            )
+            .add_option(
+                CreateCommandOption::new(
+                    CommandOptionType::String,
+                    "reason",
+                    "reason for lifting the lockdown",
+                )
+                .required(false),
+            )
+    }
+
+    async fn handle_command(
+        &self,
+        context: &Context,
+        interaction: &CommandInteraction,
+        _config: &Config,
+        services: &Services,
+    ) -> anyhow::Result<()> {
+        let GuildSlashCommandInteraction {
+            guild_id,
+            member: _,
+            permissions,
+        } = verify_guild_slash_command(interaction)?;
+
+        let mod_user = &interaction.user;
+
+        if !is_authorized(permissions) {
+            unauthorized_reply(&context.http, interaction, Permissions::MANAGE_CHANNELS).await;
+            return Ok(());
+        }
+
+        let options = match generate_options(&interaction.data) {
+            Ok(options) => options,
+            Err(MissingOption) => {
+                bail!("interaction has missing data options")
+            }
+        };
+
+        let lockdown_service = if let Some(service) = services.get::<LockdownService>() {
+            service
+        } else {
+            bail!("couldn't get lockdown service!");
+        };
+
+        let setting_service = if let Some(service) = services.get::<SettingService>() {
+            service
+        } else {
+            bail!("couldn't get setting service!");
+        };
+
+        defer_interaction(&context.http, interaction).await?;
+
+        let lockdowns = if options.is_server_wide {
+            lockdown_service
+                .fetch_active_guild_lockdowns(guild_id)
+                .await
+        } else {
+            lockdown_service
+                .fetch_active_channel_lockdown(
+                    guild_id,
+                    ChannelId::new(interaction.channel_id.get()),
+                )
+                .await
+                .into_iter()
+                .collect()
+        };
+
+        if lockdowns.is_empty() {
+            let content = if options.is_server_wide {
+                "There are no locked down channels in this server."
+            } else {
+                "This channel isn't locked down."
+            };
+
+            edit_deferred_interaction_response(&context.http, interaction, content).await;
+            return Ok(());
+        }
+
+        let total = lockdowns.len();
+        let mut unlocked_channel_ids = vec![];
+        let mut failed = 0;
+        let mut is_unauthorized = false;
+        for lockdown in lockdowns.iter() {
+            match lockdown_service
+                .unlock_channel(&context.http, guild_id, lockdown, &options.reason)
+                .await
+            {
+                Ok(channel_id) => unlocked_channel_ids.push(channel_id),
+                Err(UnlockFailure::Unauthorized) => {
+                    is_unauthorized = true;
+                    break;
+                }
+                Err(UnlockFailure::Unknown) => failed += 1,
+            }
+        }
+
+        let mut content = if is_unauthorized {
+            format!(
+                "I don't have enough permissions to do this action! Unlocked {}/{} channel(s) before stopping.",
+                unlocked_channel_ids.len(),
+                total
+            )
+        } else {
+            format!(
+                "Unlocked {}/{} channel(s).",
+                unlocked_channel_ids.len(),
+                total
+            )
+        };
+
+        if failed > 0 {
+            content.push_str(&format!(
+                "\nCould not unlock {} channel(s) for unknown reasons, this incident has been logged.",
+                failed
+            ));
+        }
+
+        if !unlocked_channel_ids.is_empty() {
+            let setting = setting_service.get_setting(guild_id).await;
+
+            if let Err(err) = log_lockdown(
+                &context.http,
+                &setting,
+                &mod_user.tag_and_id(),
+                ModLogAction::Unlock,
+                &unlocked_channel_ids,
+                &options.reason,
+            )
+            .await
+            {
+                content.push('\n');
+                content.push_str(err.to_interaction_response());
+            }
+        }
+
+        edit_deferred_interaction_response(&context.http, interaction, &content).await;
+        Ok(())
+    }
+}
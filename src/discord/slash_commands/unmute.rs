@@ -12,11 +12,14 @@ use serenity::model::Permissions;
 use crate::config::Config;
 use crate::discord::slash_commands::unmute::UnmuteCommandOptionFailure::MissingOption;
 use crate::discord::slash_commands::SlashCommand;
+use crate::discord::util::infraction::{log_infraction_lift, Infraction};
+use crate::discord::util::mod_log::ModLogAction;
 use crate::discord::util::{
     reply_to_interaction_str, unauthorized_reply, verify_guild_slash_command, CommandDataExt,
     GuildSlashCommandInteraction, UserExt,
 };
 use crate::service::mute::{MuteService, UnmuteFailure};
+use crate::service::setting::SettingService;
 use crate::service::Services;
 
 pub struct UnmuteCommand;
@@ -97,6 +100,12 @@ impl SlashCommand for UnmuteCommand {
             bail!("couldn't get mute service!");
         };
 
+        let setting_service = if let Some(service) = services.get::<SettingService>() {
+            service
+        } else {
+            bail!("couldn't get setting service!");
+        };
+
         match mute_service
             .unmute(
                 &context.http,
@@ -107,11 +116,29 @@ impl SlashCommand for UnmuteCommand {
             )
             .await
         {
-            Ok(_) => {
-                reply_to_interaction_str(&context.http, interaction, "Success.", true).await;
+            Ok(lifted_mutes) => {
+                let setting = setting_service.get_setting(guild_id).await;
+
+                let mut response = "Success.";
+                for mute in lifted_mutes {
+                    if let Err(err) = log_infraction_lift(
+                        &context.http,
+                        &setting,
+                        &mod_user.tag_and_id(),
+                        &Infraction::Mute(mute),
+                        ModLogAction::Unmute,
+                        None,
+                    )
+                    .await
+                    {
+                        response = err.to_interaction_response();
+                    }
+                }
+
+                reply_to_interaction_str(&context.http, interaction, response, true).await;
             }
             Err(UnmuteFailure::RoleNotFound) => {
-                reply_to_interaction_str(&context.http, interaction, "Could not find a role called Muted, please create one yourself or mute a user to set it up automatically.", true).await;
+                reply_to_interaction_str(&context.http, interaction, "Could not find the mute role, please pick one on the web dashboard or mute a user to set it up automatically.", true).await;
             }
             Err(UnmuteFailure::Unauthorized) => {
                 reply_to_interaction_str(
@@ -11,15 +11,21 @@ use serenity::model::user::User;
 
 use crate::config::Config;
 use crate::constants::EMBED_COLOR;
+use crate::database::notes::Note;
 use crate::discord::slash_commands::whois::WhoisCommandOptionFailure::MissingOption;
 use crate::discord::slash_commands::SlashCommand;
 use crate::discord::util::{
-    reply_to_interaction_embed, verify_guild_slash_command, CommandDataExt,
+    is_staff, reply_to_interaction_embed, verify_guild_slash_command, CommandDataExt,
     GuildSlashCommandInteraction,
 };
 use crate::service::guild::{CachedGuild, GuildService};
+use crate::service::note::NoteService;
 use crate::service::Services;
 
+// embed field values are limited to 1024 characters
+const MAX_LISTED_NOTES: usize = 3;
+const MAX_NOTE_PREVIEW_LENGTH: usize = 200;
+
 pub struct WhoisCommand;
 
 struct WhoisCommandOptions<'a> {
@@ -83,6 +89,35 @@ fn generate_member_embed<'a>(
         .colour(EMBED_COLOR)
 }
 
+fn generate_notes_field(notes: &[Note]) -> String {
+    let mut notes_str = notes
+        .iter()
+        .take(MAX_LISTED_NOTES)
+        .map(|note| {
+            let mut preview = note
+                .message
+                .chars()
+                .take(MAX_NOTE_PREVIEW_LENGTH)
+                .collect::<String>();
+            if preview.len() < note.message.len() {
+                preview.push_str("...");
+            }
+
+            format!("**#{}** <t:{}:d> - {}", note.id, note.note_time, preview)
+        })
+        .collect::<Vec<String>>()
+        .join("\n");
+
+    if notes.len() > MAX_LISTED_NOTES {
+        notes_str.push_str(&format!(
+            "\n...and {} more, see /note-list",
+            notes.len() - MAX_LISTED_NOTES
+        ));
+    }
+
+    notes_str
+}
+
 fn generate_user_embed(user: &User) -> CreateEmbed {
     let flags = match user.public_flags {
         Some(flags) => format!("{:?}", flags),
@@ -128,7 +163,7 @@ impl SlashCommand for WhoisCommand {
         let GuildSlashCommandInteraction {
             guild_id,
             member: _,
-            permissions: _,
+            permissions,
         } = verify_guild_slash_command(interaction)?;
 
         let options = match generate_options(&interaction.data) {
@@ -140,6 +175,19 @@ impl SlashCommand for WhoisCommand {
 
         let (user, member_option) = options.target;
 
+        // notes are moderator context, regular members shouldn't learn about them
+        let notes = if is_staff(permissions) {
+            let note_service = if let Some(service) = services.get::<NoteService>() {
+                service
+            } else {
+                bail!("couldn't get note service!");
+            };
+
+            note_service.fetch_guild_user_notes(guild_id, user.id).await
+        } else {
+            vec![]
+        };
+
         if let Some(member) = member_option {
             let guild_service = if let Some(service) = services.get::<GuildService>() {
                 service
@@ -149,11 +197,17 @@ impl SlashCommand for WhoisCommand {
 
             let guild = guild_service.get_guild(guild_id).await?;
 
-            let embed = generate_member_embed(&guild, member, user);
+            let mut embed = generate_member_embed(&guild, member, user);
+            if !notes.is_empty() {
+                embed = embed.field("Notes", generate_notes_field(&notes), false);
+            }
 
             reply_to_interaction_embed(&context.http, interaction, embed, true).await;
         } else {
-            let embed = generate_user_embed(user);
+            let mut embed = generate_user_embed(user);
+            if !notes.is_empty() {
+                embed = embed.field("Notes", generate_notes_field(&notes), false);
+            }
 
             reply_to_interaction_embed(&context.http, interaction, embed, true).await;
         }
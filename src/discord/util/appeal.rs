@@ -0,0 +1,100 @@
+use std::num::NonZeroU64;
+
+use serenity::all::GenericChannelId;
+use serenity::builder::{CreateEmbed, CreateEmbedFooter, CreateMessage};
+use serenity::http::Http;
+use serenity::model::id::{MessageId, UserId};
+use serenity::model::mention::Mentionable;
+use serenity::model::{Color, Timestamp};
+use tracing::{error, warn};
+
+use crate::constants::EMBED_COLOR;
+use crate::database::appeals::{Appeal, APPEAL_STATUS_ACCEPTED, APPEAL_STATUS_DENIED};
+use crate::database::settings::{Setting, ACTION_BAN};
+use crate::discord::components::appeal::create_appeal_buttons;
+
+pub fn infraction_name(infraction_type: i32) -> &'static str {
+    if infraction_type == ACTION_BAN {
+        "Ban"
+    } else {
+        "Mute"
+    }
+}
+
+pub fn create_appeal_embed<'a>(
+    appeal: &Appeal,
+    reviewer_tag_and_id: Option<&str>,
+) -> CreateEmbed<'a> {
+    let user = if let Some(user_id) = NonZeroU64::new(appeal.user_id as u64) {
+        let user_id = UserId::new(user_id.get());
+        format!("{} ({})", user_id.mention(), user_id)
+    } else {
+        warn!("found appeal with invalid user id! id: {}", appeal.id);
+        "Unknown".to_string()
+    };
+
+    let mut embed = CreateEmbed::default()
+        .title("Infraction Appeal")
+        .field(
+            "Infraction:",
+            format!(
+                "{} - #{}",
+                infraction_name(appeal.infraction_type),
+                appeal.case_number
+            ),
+            false,
+        )
+        .field("User:", user, false)
+        .field("Appeal:", appeal.message.clone(), false)
+        .footer(CreateEmbedFooter::new("Appeal sent on"));
+
+    if let Ok(timestamp) = Timestamp::from_unix_timestamp(appeal.appeal_time) {
+        embed = embed.timestamp(timestamp);
+    }
+
+    embed = match (appeal.status, reviewer_tag_and_id) {
+        (APPEAL_STATUS_ACCEPTED, Some(reviewer)) => {
+            embed
+                .color(Color::new(0x00C853))
+                .field("Accepted by:", reviewer.to_string(), false)
+        }
+        (APPEAL_STATUS_DENIED, Some(reviewer)) => {
+            embed
+                .color(Color::new(0xFF2900))
+                .field("Denied by:", reviewer.to_string(), false)
+        }
+        _ => embed.color(EMBED_COLOR),
+    };
+
+    embed
+}
+
+pub async fn post_appeal_for_review(
+    http: &Http,
+    setting: &Setting,
+    appeal: &Appeal,
+) -> Option<MessageId> {
+    let appeals_channel_id = if let Some(id) = NonZeroU64::new(setting.appeals_channel_id as u64) {
+        GenericChannelId::new(id.get())
+    } else {
+        warn!(
+            "found setting with invalid appeals channel id! {:?}",
+            setting
+        );
+        return None;
+    };
+
+    let message = CreateMessage::default()
+        .add_embed(create_appeal_embed(appeal, None))
+        .components(create_appeal_buttons(appeal.id));
+
+    appeals_channel_id
+        .send_message(http, message)
+        .await
+        .map(|message| message.id)
+        .map_err(|err| {
+            error!("failed to post appeal for review {}", err);
+            err
+        })
+        .ok()
+}
@@ -0,0 +1,268 @@
+use aho_corasick::BuildError;
+use regex::{Regex, RegexBuilder};
+use smol_str::SmolStr;
+use tracing::warn;
+
+use crate::discord::util::links::{extract_link_domains, is_link_separator};
+use crate::discord::util::word_matcher::WordMatcher;
+
+pub const MAX_BLOCKLIST_PATTERNS: usize = 25;
+const MAX_PATTERN_LENGTH: usize = 200;
+const PATTERN_SIZE_LIMIT: usize = 1 << 18;
+const PATTERN_NEST_LIMIT: u32 = 16;
+
+const WORD_PREFIX: &str = "word:";
+const SUBSTRING_PREFIX: &str = "contains:";
+const GLOB_PREFIX: &str = "glob:";
+const REGEX_PREFIX: &str = "regex:";
+
+pub enum BlocklistEntry<'a> {
+    // untyped entries follow the guild's word filter level
+    Default(&'a str),
+    Word(&'a str),
+    Substring(&'a str),
+    Glob(&'a str),
+    Regex(&'a str),
+}
+
+impl BlocklistEntry<'_> {
+    fn is_pattern(&self) -> bool {
+        matches!(self, BlocklistEntry::Glob(_) | BlocklistEntry::Regex(_))
+    }
+}
+
+// entries are separated by commas or new lines, regex entries take up their whole line since
+// commas are valid inside of them
+pub fn parse_blocklist(blocklist: &str) -> Vec<BlocklistEntry> {
+    let mut entries = vec![];
+
+    for line in blocklist.lines() {
+        let line = line.trim();
+        if let Some(pattern) = line.strip_prefix(REGEX_PREFIX) {
+            entries.push(BlocklistEntry::Regex(pattern.trim()));
+            continue;
+        }
+
+        for entry in line.split(',').map(str::trim) {
+            if entry.is_empty() {
+                continue;
+            }
+
+            let entry = if let Some(word) = entry.strip_prefix(WORD_PREFIX) {
+                BlocklistEntry::Word(word.trim())
+            } else if let Some(substring) = entry.strip_prefix(SUBSTRING_PREFIX) {
+                BlocklistEntry::Substring(substring.trim())
+            } else if let Some(glob) = entry.strip_prefix(GLOB_PREFIX) {
+                BlocklistEntry::Glob(glob.trim())
+            } else {
+                BlocklistEntry::Default(entry)
+            };
+
+            entries.push(entry);
+        }
+    }
+
+    entries
+}
+
+pub struct BlocklistEntryError {
+    pub entry: String,
+    pub error: String,
+}
+
+// globs have to match a whole word, link or link host, so *.ru matches https://example.ru/path,
+// * matches any run of non-whitespace characters and ? a single one
+fn glob_to_regex(glob: &str) -> String {
+    let mut pattern = String::from("^");
+
+    for c in glob.chars() {
+        match c {
+            '*' => pattern.push_str(r"\S*"),
+            '?' => pattern.push_str(r"\S"),
+            c => pattern.push_str(&regex::escape(c.encode_utf8(&mut [0; 4]))),
+        }
+    }
+
+    pattern.push('$');
+    pattern
+}
+
+// the regex crate never backtracks, so matching runs in linear time, the limits only keep
+// compiled patterns small
+fn compile_pattern(pattern: &str) -> Result<Regex, String> {
+    RegexBuilder::new(pattern)
+        .case_insensitive(true)
+        .size_limit(PATTERN_SIZE_LIMIT)
+        .dfa_size_limit(PATTERN_SIZE_LIMIT)
+        .nest_limit(PATTERN_NEST_LIMIT)
+        .build()
+        .map_err(|err| match err {
+            regex::Error::CompiledTooBig(_) => "pattern is too complex".to_string(),
+            regex::Error::Syntax(message) => message
+                .lines()
+                .last()
+                .map(|line| line.trim_start_matches("error: ").to_string())
+                .unwrap_or_else(|| "invalid pattern".to_string()),
+            _ => "invalid pattern".to_string(),
+        })
+}
+
+fn compile_entry(entry: &BlocklistEntry) -> Result<Option<Regex>, String> {
+    let (source, pattern) = match entry {
+        BlocklistEntry::Glob(glob) => (*glob, glob_to_regex(glob)),
+        BlocklistEntry::Regex(pattern) => (*pattern, pattern.to_string()),
+        _ => return Ok(None),
+    };
+
+    if source.is_empty() {
+        return Err("pattern cannot be empty".to_string());
+    }
+
+    if source.len() > MAX_PATTERN_LENGTH {
+        return Err(format!(
+            "pattern cannot be longer than {} characters",
+            MAX_PATTERN_LENGTH
+        ));
+    }
+
+    compile_pattern(&pattern).map(Some)
+}
+
+fn entry_name(entry: &BlocklistEntry) -> String {
+    match entry {
+        BlocklistEntry::Default(word) => word.to_string(),
+        BlocklistEntry::Word(word) => format!("{}{}", WORD_PREFIX, word),
+        BlocklistEntry::Substring(substring) => format!("{}{}", SUBSTRING_PREFIX, substring),
+        BlocklistEntry::Glob(glob) => format!("{}{}", GLOB_PREFIX, glob),
+        BlocklistEntry::Regex(pattern) => format!("{}{}", REGEX_PREFIX, pattern),
+    }
+}
+
+// returns one error per invalid entry, glob and regex entries past the limit are all reported
+pub fn validate_blocklist(blocklist: &str) -> Result<(), Vec<BlocklistEntryError>> {
+    let entries = parse_blocklist(blocklist);
+    let mut errors = vec![];
+    let mut pattern_count = 0;
+
+    for entry in &entries {
+        if entry.is_pattern() {
+            pattern_count += 1;
+        }
+
+        let result = match entry {
+            BlocklistEntry::Word(word) | BlocklistEntry::Substring(word) if word.is_empty() => {
+                Err("word cannot be empty".to_string())
+            }
+            _ if pattern_count > MAX_BLOCKLIST_PATTERNS && entry.is_pattern() => Err(format!(
+                "blocklist cannot have more than {} glob and regex entries",
+                MAX_BLOCKLIST_PATTERNS
+            )),
+            _ => compile_entry(entry).map(|_| ()),
+        };
+
+        if let Err(error) = result {
+            errors.push(BlocklistEntryError {
+                entry: entry_name(entry),
+                error,
+            });
+        }
+    }
+
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(errors)
+    }
+}
+
+pub struct BlocklistMatcher {
+    words: WordMatcher,
+    substrings: WordMatcher,
+    // original entries paired with their compiled patterns, globs are matched against every word,
+    // link and link host, regexes against the raw content
+    globs: Vec<(SmolStr, Regex)>,
+    patterns: Vec<(SmolStr, Regex)>,
+}
+
+impl BlocklistMatcher {
+    // invalid pattern entries are skipped, blocklists saved before entries were validated
+    // shouldn't turn the whole filter off
+    pub fn new(entries: &[BlocklistEntry], whole_words: bool) -> Result<Self, BuildError> {
+        let mut words = vec![];
+        let mut substrings = vec![];
+        let mut globs = vec![];
+        let mut patterns = vec![];
+
+        for entry in entries {
+            match entry {
+                BlocklistEntry::Default(word) if whole_words => words.push(*word),
+                BlocklistEntry::Default(word) => substrings.push(*word),
+                BlocklistEntry::Word(word) => words.push(*word),
+                BlocklistEntry::Substring(substring) => substrings.push(*substring),
+                BlocklistEntry::Glob(_) | BlocklistEntry::Regex(_) => {
+                    if globs.len() + patterns.len() >= MAX_BLOCKLIST_PATTERNS {
+                        continue;
+                    }
+
+                    match compile_entry(entry) {
+                        Ok(Some(regex)) => {
+                            let compiled = (SmolStr::new(entry_name(entry)), regex);
+                            if matches!(entry, BlocklistEntry::Glob(_)) {
+                                globs.push(compiled);
+                            } else {
+                                patterns.push(compiled);
+                            }
+                        }
+                        Ok(None) => (),
+                        Err(message) => {
+                            warn!(
+                                "skipping invalid blocklist entry {}: {}",
+                                entry_name(entry),
+                                message
+                            );
+                        }
+                    }
+                }
+            }
+        }
+
+        Ok(BlocklistMatcher {
+            words: WordMatcher::new(words.into_iter(), true)?,
+            substrings: WordMatcher::new(substrings.into_iter(), false)?,
+            globs,
+            patterns,
+        })
+    }
+
+    fn find_glob(&self, content: &str) -> Option<&str> {
+        if self.globs.is_empty() {
+            return None;
+        }
+
+        let hosts = extract_link_domains(content);
+        let mut candidates = content
+            .split(is_link_separator)
+            .filter(|token| !token.is_empty())
+            .chain(hosts.iter().map(String::as_str));
+
+        candidates.find_map(|candidate| {
+            self.globs
+                .iter()
+                .find(|(_, regex)| regex.is_match(candidate))
+                .map(|(name, _)| name.as_str())
+        })
+    }
+
+    pub fn find(&self, content: &str) -> Option<&str> {
+        self.words
+            .find(content)
+            .or_else(|| self.substrings.find(content))
+            .or_else(|| self.find_glob(content))
+            .or_else(|| {
+                self.patterns
+                    .iter()
+                    .find(|(_, regex)| regex.is_match(content))
+                    .map(|(name, _)| name.as_str())
+            })
+    }
+}
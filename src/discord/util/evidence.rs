@@ -0,0 +1,221 @@
+use std::num::NonZeroU64;
+
+use serenity::all::{Channel, CommandOptionType, GenericChannelId, Permissions};
+use serenity::builder::CreateCommandOption;
+use serenity::http::Http;
+use serenity::model::channel::{GuildChannel, PermissionOverwriteType};
+use serenity::model::id::{GuildId, MessageId, RoleId, UserId};
+use tracing::error;
+
+use crate::service::evidence::EvidenceSnapshot;
+use crate::service::guild::GuildService;
+
+pub const EVIDENCE_OPTION_NAME: &str = "evidence";
+const MAX_EVIDENCE_LINKS: usize = 5;
+const MESSAGE_LINK_HOSTS: [&str; 4] = [
+    "discord.com",
+    "ptb.discord.com",
+    "canary.discord.com",
+    "discordapp.com",
+];
+
+pub fn create_evidence_option<'a>() -> CreateCommandOption<'a> {
+    CreateCommandOption::new(
+        CommandOptionType::String,
+        EVIDENCE_OPTION_NAME,
+        "message links to save as evidence, separated by spaces",
+    )
+    .required(false)
+}
+
+// https://discord.com/channels/<guild_id>/<channel_id>/<message_id>
+fn parse_message_link(link: &str) -> Option<(GuildId, GenericChannelId, MessageId)> {
+    let path = link
+        .strip_prefix("https://")
+        .or_else(|| link.strip_prefix("http://"))?;
+
+    let mut segments = path.split('/');
+    let host = segments.next()?;
+    if !MESSAGE_LINK_HOSTS.contains(&host) || segments.next()? != "channels" {
+        return None;
+    }
+
+    let guild_id = segments.next()?.parse::<NonZeroU64>().ok()?;
+    let channel_id = segments.next()?.parse::<NonZeroU64>().ok()?;
+    let message_id = segments.next()?.parse::<NonZeroU64>().ok()?;
+
+    if segments.next().is_some() {
+        return None;
+    }
+
+    Some((
+        GuildId::new(guild_id.get()),
+        GenericChannelId::new(channel_id.get()),
+        MessageId::new(message_id.get()),
+    ))
+}
+
+// overwrites are applied the same way discord does: @everyone, then all roles at once, then the member
+fn get_channel_permissions(
+    base_permissions: Permissions,
+    guild_id: GuildId,
+    member_id: UserId,
+    member_roles: &[RoleId],
+    channel: &GuildChannel,
+) -> Permissions {
+    if base_permissions.administrator() {
+        return Permissions::all();
+    }
+
+    let mut permissions = base_permissions;
+    let everyone = PermissionOverwriteType::Role(guild_id.everyone_role());
+
+    if let Some(overwrite) = channel
+        .permission_overwrites
+        .iter()
+        .find(|overwrite| overwrite.kind == everyone)
+    {
+        permissions = (permissions - overwrite.deny) | overwrite.allow;
+    }
+
+    let (role_allow, role_deny) = channel
+        .permission_overwrites
+        .iter()
+        .filter(|overwrite| match overwrite.kind {
+            PermissionOverwriteType::Role(role_id) => member_roles.contains(&role_id),
+            _ => false,
+        })
+        .fold(
+            (Permissions::empty(), Permissions::empty()),
+            |(allow, deny), overwrite| (allow | overwrite.allow, deny | overwrite.deny),
+        );
+    permissions = (permissions - role_deny) | role_allow;
+
+    if let Some(overwrite) = channel
+        .permission_overwrites
+        .iter()
+        .find(|overwrite| overwrite.kind == PermissionOverwriteType::Member(member_id))
+    {
+        permissions = (permissions - overwrite.deny) | overwrite.allow;
+    }
+
+    permissions
+}
+
+// threads inherit the permissions of their parent channel
+async fn resolve_permission_channel<'a>(
+    http: &Http,
+    channels: &'a [GuildChannel],
+    channel_id: GenericChannelId,
+) -> Option<&'a GuildChannel> {
+    if let Some(channel) = channels
+        .iter()
+        .find(|channel| channel.id.widen() == channel_id)
+    {
+        return Some(channel);
+    }
+
+    match http.get_channel(channel_id).await {
+        Ok(Channel::GuildThread(thread)) => channels
+            .iter()
+            .find(|channel| channel.id == thread.parent_id),
+        _ => None,
+    }
+}
+
+// messages are fetched before the infraction is issued, so a typo doesn't leave it without evidence
+pub async fn fetch_evidence(
+    http: &Http,
+    guild_service: &GuildService,
+    guild_id: GuildId,
+    mod_user_id: UserId,
+    links: Option<&str>,
+) -> Result<Vec<EvidenceSnapshot>, String> {
+    let links = if let Some(links) = links {
+        links.split_whitespace().collect::<Vec<&str>>()
+    } else {
+        return Ok(vec![]);
+    };
+
+    if links.len() > MAX_EVIDENCE_LINKS {
+        return Err(format!(
+            "You can attach at most {} messages as evidence!",
+            MAX_EVIDENCE_LINKS
+        ));
+    }
+
+    // the bot can see channels the moderator can't, the evidence must not leak their messages
+    let mod_roles = match guild_service.get_member(guild_id, mod_user_id).await {
+        Ok(member) => member.roles.clone(),
+        Err(_) => return Err("Failed to fetch your roles, try again later.".to_string()),
+    };
+
+    let base_permissions = match guild_service
+        .get_permissions(mod_user_id, &mod_roles, guild_id)
+        .await
+    {
+        Ok(permissions) => permissions,
+        Err(_) => return Err("Failed to fetch your permissions, try again later.".to_string()),
+    };
+
+    // overwrites aren't cached, they have to reflect the current state of the channels
+    let channels = match guild_id.channels(http).await {
+        Ok(channels) => channels,
+        Err(err) => {
+            error!("failed to fetch channels of guild: {} {}", guild_id, err);
+            return Err(
+                "Failed to fetch channels of the server, make sure I have required permissions."
+                    .to_string(),
+            );
+        }
+    };
+
+    let mut snapshots = vec![];
+    for link in links {
+        let (channel_id, message_id) = match parse_message_link(link) {
+            Some((link_guild_id, channel_id, message_id)) if link_guild_id == guild_id => {
+                (channel_id, message_id)
+            }
+            Some(_) => {
+                return Err(format!(
+                    "Evidence must be a message from this server: {}",
+                    link
+                ));
+            }
+            None => return Err(format!("Failed to understand message link: {}", link)),
+        };
+
+        let can_read = match resolve_permission_channel(http, &channels, channel_id).await {
+            Some(channel) => {
+                let permissions = get_channel_permissions(
+                    base_permissions,
+                    guild_id,
+                    mod_user_id,
+                    &mod_roles,
+                    channel,
+                );
+                permissions.view_channel() && permissions.read_message_history()
+            }
+            None => false,
+        };
+
+        if !can_read {
+            return Err(format!(
+                "You can only attach messages from channels you can read: {}",
+                link
+            ));
+        }
+
+        match channel_id.message(http, message_id).await {
+            Ok(message) => snapshots.push(EvidenceSnapshot::from_message(&message)),
+            Err(_) => {
+                return Err(format!(
+                    "Could not fetch the linked message, it may have been deleted: {}",
+                    link
+                ));
+            }
+        }
+    }
+
+    Ok(snapshots)
+}
@@ -0,0 +1,91 @@
+use serenity::all::{GuildId, UserId};
+
+use crate::constants::JIM_ID;
+use crate::service::guild::{CachedGuild, GuildService};
+
+pub enum RoleHierarchyFailure {
+    TargetIsOwner,
+    TargetOutranksModerator,
+    TargetOutranksJim,
+    FetchFailed,
+}
+
+impl RoleHierarchyFailure {
+    pub fn to_interaction_response(&self, action_name: &str) -> String {
+        match self {
+            RoleHierarchyFailure::TargetIsOwner => {
+                format!("You can't {} owner of the server!", action_name)
+            }
+            RoleHierarchyFailure::TargetOutranksModerator => format!(
+                "You can't {} this user, their highest role is equal to or above yours!",
+                action_name
+            ),
+            RoleHierarchyFailure::TargetOutranksJim => format!(
+                "I can't {} this user, their highest role is equal to or above mine! Move my role above theirs in server settings.",
+                action_name
+            ),
+            RoleHierarchyFailure::FetchFailed => {
+                "Could not verify role hierarchy of the server, please try again later.".into()
+            }
+        }
+    }
+}
+
+// Discord only enforces this for Jim's own top role, so moderators could otherwise use Jim to
+// act on members they outrank in permissions but not in roles
+pub async fn check_role_hierarchy(
+    guild_service: &GuildService,
+    guild_id: GuildId,
+    guild: &CachedGuild,
+    mod_user_id: UserId,
+    target_user_id: UserId,
+) -> Result<(), RoleHierarchyFailure> {
+    if target_user_id == guild.owner_id {
+        return Err(RoleHierarchyFailure::TargetIsOwner);
+    }
+
+    // target isn't in the server (e.g. banning by id), there is no hierarchy to respect
+    let target_member = if let Ok(member) = guild_service.get_member(guild_id, target_user_id).await
+    {
+        member
+    } else {
+        return Ok(());
+    };
+
+    let target_position = guild_service
+        .get_highest_role_position(guild_id, &target_member.roles)
+        .await
+        .map_err(|_err| RoleHierarchyFailure::FetchFailed)?;
+
+    if mod_user_id != guild.owner_id && mod_user_id != JIM_ID {
+        let mod_member = guild_service
+            .get_member(guild_id, mod_user_id)
+            .await
+            .map_err(|_err| RoleHierarchyFailure::FetchFailed)?;
+
+        let mod_position = guild_service
+            .get_highest_role_position(guild_id, &mod_member.roles)
+            .await
+            .map_err(|_err| RoleHierarchyFailure::FetchFailed)?;
+
+        if target_position >= mod_position {
+            return Err(RoleHierarchyFailure::TargetOutranksModerator);
+        }
+    }
+
+    let jim_member = guild_service
+        .get_member(guild_id, JIM_ID)
+        .await
+        .map_err(|_err| RoleHierarchyFailure::FetchFailed)?;
+
+    let jim_position = guild_service
+        .get_highest_role_position(guild_id, &jim_member.roles)
+        .await
+        .map_err(|_err| RoleHierarchyFailure::FetchFailed)?;
+
+    if target_position >= jim_position {
+        return Err(RoleHierarchyFailure::TargetOutranksJim);
+    }
+
+    Ok(())
+}
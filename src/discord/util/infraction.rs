@@ -0,0 +1,414 @@
+use std::num::NonZeroU64;
+
+use anyhow::bail;
+use serenity::all::{GuildId, Http, MessageId, Permissions, UserId};
+use tracing::warn;
+
+use crate::database::bans::Ban;
+use crate::database::hardbans::Hardban;
+use crate::database::kicks::Kick;
+use crate::database::mutes::Mute;
+use crate::database::settings::Setting;
+use crate::database::softbans::Softban;
+use crate::database::warns::Warn;
+use crate::discord::components::mod_log::create_buttons_for_infraction;
+use crate::discord::util::mod_log::{
+    create_mod_log_lift_entry, create_mod_log_update_entry, edit_mod_log_entry,
+    get_mod_log_channel_id, CreateModLogEntryError, ModLogAction,
+};
+use crate::service::ban::{validate_ban_update, BanService};
+use crate::service::hardban::HardbanService;
+use crate::service::kick::{validate_kick_update, KickService};
+use crate::service::mute::{validate_mute_update, MuteService};
+use crate::service::softban::{validate_softban_update, SoftbanService};
+use crate::service::warn::{validate_warn_update, WarnService};
+use crate::service::Services;
+use crate::util::{normalize_reason, now};
+
+// case numbers are shared by all infraction tables of a guild, so a case number alone identifies one of these
+#[derive(Clone)]
+pub enum Infraction {
+    Ban(Ban),
+    Hardban(Hardban),
+    Kick(Kick),
+    Mute(Mute),
+    Softban(Softban),
+    Warn(Warn),
+}
+
+impl Infraction {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Infraction::Ban(_) => "Ban",
+            Infraction::Hardban(_) => "Hardban",
+            Infraction::Kick(_) => "Kick",
+            Infraction::Mute(_) => "Mute",
+            Infraction::Softban(_) => "Softban",
+            Infraction::Warn(_) => "Warn",
+        }
+    }
+
+    // same permissions the web dashboard requires to modify these entries
+    pub fn required_permission(&self) -> Permissions {
+        match self {
+            Infraction::Ban(_) | Infraction::Hardban(_) | Infraction::Softban(_) => {
+                Permissions::BAN_MEMBERS
+            }
+            Infraction::Kick(_) | Infraction::Warn(_) => Permissions::KICK_MEMBERS,
+            Infraction::Mute(_) => Permissions::MANAGE_ROLES,
+        }
+    }
+
+    pub fn case_number(&self) -> i32 {
+        match self {
+            Infraction::Ban(ban) => ban.case_number,
+            Infraction::Hardban(hardban) => hardban.case_number,
+            Infraction::Kick(kick) => kick.case_number,
+            Infraction::Mute(mute) => mute.case_number,
+            Infraction::Softban(softban) => softban.case_number,
+            Infraction::Warn(warn) => warn.case_number,
+        }
+    }
+
+    // None if the stored user id is invalid, which is logged
+    pub fn user_id(&self) -> Option<UserId> {
+        let user_id = match self {
+            Infraction::Ban(ban) => ban.user_id,
+            Infraction::Hardban(hardban) => hardban.user_id,
+            Infraction::Kick(kick) => kick.user_id,
+            Infraction::Mute(mute) => mute.user_id,
+            Infraction::Softban(softban) => softban.user_id,
+            Infraction::Warn(warn) => warn.user_id,
+        };
+
+        let user_id = NonZeroU64::new(user_id as u64).map(|id| UserId::new(id.get()));
+        if user_id.is_none() {
+            warn!(
+                "found {} with invalid user id! case number: {}",
+                self.name().to_lowercase(),
+                self.case_number()
+            );
+        }
+
+        user_id
+    }
+
+    pub fn is_pardoned(&self) -> bool {
+        match self {
+            Infraction::Ban(_) | Infraction::Hardban(_) => false,
+            Infraction::Kick(kick) => kick.pardoned,
+            Infraction::Mute(mute) => mute.pardoned,
+            Infraction::Softban(softban) => softban.pardoned,
+            Infraction::Warn(warn) => warn.pardoned,
+        }
+    }
+
+    // lifted infractions are no longer in effect, their mod log entries are struck through
+    pub fn is_lifted(&self) -> bool {
+        match self {
+            Infraction::Ban(ban) => ban.unbanned,
+            Infraction::Hardban(_) => false,
+            Infraction::Kick(kick) => kick.pardoned,
+            Infraction::Mute(mute) => mute.unmuted || mute.pardoned,
+            Infraction::Softban(softban) => softban.pardoned,
+            Infraction::Warn(warn) => warn.pardoned,
+        }
+    }
+
+    pub fn mod_log_message_id(&self) -> Option<MessageId> {
+        let mod_log_message_id = match self {
+            Infraction::Ban(ban) => ban.mod_log_message_id,
+            Infraction::Hardban(hardban) => hardban.mod_log_message_id,
+            Infraction::Kick(kick) => kick.mod_log_message_id,
+            Infraction::Mute(mute) => mute.mod_log_message_id,
+            Infraction::Softban(softban) => softban.mod_log_message_id,
+            Infraction::Warn(warn) => warn.mod_log_message_id,
+        };
+
+        NonZeroU64::new(mod_log_message_id as u64).map(|id| MessageId::new(id.get()))
+    }
+
+    pub fn pardon(&mut self) -> Result<(), &'static str> {
+        match self {
+            Infraction::Ban(_) | Infraction::Hardban(_) => {
+                return Err("Bans and hardbans can't be pardoned, unban the user instead.");
+            }
+            Infraction::Kick(kick) => {
+                validate_kick_update(kick, true)?;
+                kick.pardoned = true;
+            }
+            Infraction::Mute(mute) => {
+                validate_mute_update(mute, mute.unmuted, mute.expire_time, true)?;
+                mute.pardoned = true;
+            }
+            Infraction::Softban(softban) => {
+                validate_softban_update(softban, true)?;
+                softban.pardoned = true;
+            }
+            Infraction::Warn(warn) => {
+                validate_warn_update(warn, true)?;
+                warn.pardoned = true;
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn set_reason(&mut self, reason: &str) -> Result<(), &'static str> {
+        let reason = normalize_reason(reason);
+        match self {
+            Infraction::Ban(ban) => {
+                validate_ban_update(ban, ban.unbanned, ban.expire_time)?;
+                ban.reason = reason;
+            }
+            Infraction::Hardban(hardban) => {
+                hardban.reason = reason;
+            }
+            Infraction::Kick(kick) => {
+                validate_kick_update(kick, kick.pardoned)?;
+                kick.reason = reason;
+            }
+            Infraction::Mute(mute) => {
+                validate_mute_update(mute, mute.unmuted, mute.expire_time, mute.pardoned)?;
+                mute.reason = reason;
+            }
+            Infraction::Softban(softban) => {
+                validate_softban_update(softban, softban.pardoned)?;
+                softban.reason = reason;
+            }
+            Infraction::Warn(warn) => {
+                validate_warn_update(warn, warn.pardoned)?;
+                warn.reason = reason;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+pub async fn fetch_infraction_with_case_number(
+    services: &Services,
+    guild_id: GuildId,
+    case_number: i32,
+) -> anyhow::Result<Option<Infraction>> {
+    let ban_service = if let Some(service) = services.get::<BanService>() {
+        service
+    } else {
+        bail!("couldn't get ban service!");
+    };
+
+    if let Some(ban) = ban_service
+        .fetch_guild_ban_with_case_number(guild_id, case_number)
+        .await
+    {
+        return Ok(Some(Infraction::Ban(ban)));
+    }
+
+    let hardban_service = if let Some(service) = services.get::<HardbanService>() {
+        service
+    } else {
+        bail!("couldn't get hardban service!");
+    };
+
+    if let Some(hardban) = hardban_service
+        .fetch_guild_hardban_with_case_number(guild_id, case_number)
+        .await
+    {
+        return Ok(Some(Infraction::Hardban(hardban)));
+    }
+
+    let kick_service = if let Some(service) = services.get::<KickService>() {
+        service
+    } else {
+        bail!("couldn't get kick service!");
+    };
+
+    if let Some(kick) = kick_service
+        .fetch_guild_kick_with_case_number(guild_id, case_number)
+        .await
+    {
+        return Ok(Some(Infraction::Kick(kick)));
+    }
+
+    let mute_service = if let Some(service) = services.get::<MuteService>() {
+        service
+    } else {
+        bail!("couldn't get mute service!");
+    };
+
+    if let Some(mute) = mute_service
+        .fetch_guild_mute_with_case_number(guild_id, case_number)
+        .await
+    {
+        return Ok(Some(Infraction::Mute(mute)));
+    }
+
+    let softban_service = if let Some(service) = services.get::<SoftbanService>() {
+        service
+    } else {
+        bail!("couldn't get softban service!");
+    };
+
+    if let Some(softban) = softban_service
+        .fetch_guild_softban_with_case_number(guild_id, case_number)
+        .await
+    {
+        return Ok(Some(Infraction::Softban(softban)));
+    }
+
+    let warn_service = if let Some(service) = services.get::<WarnService>() {
+        service
+    } else {
+        bail!("couldn't get warn service!");
+    };
+
+    Ok(warn_service
+        .fetch_guild_warn_with_case_number(guild_id, case_number)
+        .await
+        .map(Infraction::Warn))
+}
+
+pub async fn update_infraction(services: &Services, infraction: Infraction) -> Option<()> {
+    match infraction {
+        Infraction::Ban(ban) => services.get::<BanService>()?.update_ban(ban).await,
+        Infraction::Hardban(hardban) => {
+            services
+                .get::<HardbanService>()?
+                .update_hardban(hardban)
+                .await
+        }
+        Infraction::Kick(kick) => services.get::<KickService>()?.update_kick(kick).await,
+        Infraction::Mute(mute) => services.get::<MuteService>()?.update_mute(mute).await,
+        Infraction::Softban(softban) => {
+            services
+                .get::<SoftbanService>()?
+                .update_softban(softban)
+                .await
+        }
+        Infraction::Warn(warn) => services.get::<WarnService>()?.update_warn(warn).await,
+    }
+}
+
+// edits the original mod log entry of the infraction, or sends a new entry if that's not possible
+pub async fn log_infraction_update(
+    http: &Http,
+    setting: &Setting,
+    mod_user_tag_and_id: &str,
+    infraction: &Infraction,
+    change: &str,
+) -> Result<(), CreateModLogEntryError> {
+    let mod_log_channel_id = if let Some(channel_id) = get_mod_log_channel_id(setting) {
+        channel_id
+    } else {
+        return Ok(());
+    };
+
+    if let Some(message_id) = infraction.mod_log_message_id() {
+        if edit_mod_log_entry(
+            http,
+            mod_log_channel_id,
+            message_id,
+            mod_user_tag_and_id,
+            change,
+            infraction.is_lifted(),
+            create_buttons_for_infraction(infraction),
+        )
+        .await
+        {
+            return Ok(());
+        }
+    }
+
+    let user_id = if let Some(user_id) = infraction.user_id() {
+        user_id
+    } else {
+        return Ok(());
+    };
+
+    create_mod_log_update_entry(
+        http,
+        mod_log_channel_id,
+        mod_user_tag_and_id,
+        user_id,
+        infraction.name(),
+        infraction.case_number(),
+        change,
+        now(),
+    )
+    .await
+}
+
+// lifting an infraction gets an entry of its own, the original entry is struck through as well
+pub async fn log_infraction_lift(
+    http: &Http,
+    setting: &Setting,
+    mod_user_tag_and_id: &str,
+    infraction: &Infraction,
+    action: ModLogAction,
+    reason: Option<&str>,
+) -> Result<(), CreateModLogEntryError> {
+    let mod_log_channel_id = if let Some(channel_id) = get_mod_log_channel_id(setting) {
+        channel_id
+    } else {
+        return Ok(());
+    };
+
+    // the new entry tells about the lift anyway, no need for a fallback if this fails
+    if let Some(message_id) = infraction.mod_log_message_id() {
+        edit_mod_log_entry(
+            http,
+            mod_log_channel_id,
+            message_id,
+            mod_user_tag_and_id,
+            action.past_tense(),
+            infraction.is_lifted(),
+            create_buttons_for_infraction(infraction),
+        )
+        .await;
+    }
+
+    send_infraction_lift_entry(
+        http,
+        setting,
+        mod_user_tag_and_id,
+        infraction,
+        action,
+        reason,
+    )
+    .await
+}
+
+// for callers that already updated the original entry themselves
+pub async fn send_infraction_lift_entry(
+    http: &Http,
+    setting: &Setting,
+    mod_user_tag_and_id: &str,
+    infraction: &Infraction,
+    action: ModLogAction,
+    reason: Option<&str>,
+) -> Result<(), CreateModLogEntryError> {
+    let mod_log_channel_id = if let Some(channel_id) = get_mod_log_channel_id(setting) {
+        channel_id
+    } else {
+        return Ok(());
+    };
+
+    let user_id = if let Some(user_id) = infraction.user_id() {
+        user_id
+    } else {
+        return Ok(());
+    };
+
+    create_mod_log_lift_entry(
+        http,
+        mod_log_channel_id,
+        mod_user_tag_and_id,
+        user_id,
+        action,
+        reason,
+        infraction.case_number(),
+        now(),
+    )
+    .await
+    .map(|_| ())
+}
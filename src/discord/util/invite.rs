@@ -0,0 +1,104 @@
+use std::borrow::Cow;
+
+use lazy_static::lazy_static;
+use regex::Regex;
+use smol_str::SmolStr;
+
+use crate::discord::util::word_matcher::is_invisible;
+
+pub const INVITE_ALLOWLIST_SIZE_LIMIT: usize = 2000;
+const MAX_INVITE_CODE_LENGTH: usize = 32;
+
+lazy_static! {
+    // discord.gg/code, discord.com/invite/code, discordapp.com/invite/code and dsc.gg/code, dots
+    // can also be spelled out or spaced out like "discord . gg" and "discord (dot) gg"
+    static ref INVITE_PATTERN: Regex = Regex::new(
+        r"(?i)(?:discord(?:app)?\s*(?:\.|\(\.\)|\[\.\]|\(dot\)|\[dot\]|\bdot\b)\s*com\s*/\s*invite|(?:discord|dsc)\s*(?:\.|\(\.\)|\[\.\]|\(dot\)|\[dot\]|\bdot\b)\s*gg)\s*/\s*([a-z0-9-]+)"
+    )
+    .unwrap();
+}
+
+// markdown and invisible characters are dropped so they can't be used to split up an invite
+fn clean(text: &str) -> String {
+    text.chars()
+        .filter(|c| !is_invisible(*c) && !matches!(c, '*' | '_' | '~' | '`' | '|'))
+        .collect()
+}
+
+// returns invite codes as written, vanity codes are lowercase but regular codes are case sensitive
+fn extract_invite_codes(text: &str) -> Vec<SmolStr> {
+    let mut codes: Vec<SmolStr> = vec![];
+
+    for captures in INVITE_PATTERN.captures_iter(&clean(text)) {
+        if let Some(code) = captures.get(1) {
+            let code = SmolStr::new(code.as_str());
+            if !codes.contains(&code) {
+                codes.push(code);
+            }
+        }
+    }
+
+    codes
+}
+
+fn is_valid_code(code: &str) -> bool {
+    !code.is_empty()
+        && code.len() <= MAX_INVITE_CODE_LENGTH
+        && code.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+}
+
+// entries can be full invite links or bare codes
+fn parse_allowlist_entry(entry: &str) -> Option<SmolStr> {
+    if let Some(code) = extract_invite_codes(entry).into_iter().next() {
+        Some(code)
+    } else if is_valid_code(entry) {
+        Some(SmolStr::new(entry))
+    } else {
+        None
+    }
+}
+
+// entries are separated by commas or whitespace, invalid entries are skipped
+fn parse_invite_allowlist(list: &str) -> Vec<SmolStr> {
+    list.split(|c: char| c == ',' || c.is_whitespace())
+        .filter(|entry| !entry.is_empty())
+        .filter_map(parse_allowlist_entry)
+        .collect()
+}
+
+pub fn validate_invite_allowlist(list: &str) -> Result<(), String> {
+    if list.len() > INVITE_ALLOWLIST_SIZE_LIMIT {
+        return Err("Invite allowlist cannot be too long!".into());
+    }
+
+    for entry in list.split(|c: char| c == ',' || c.is_whitespace()) {
+        if entry.is_empty() {
+            continue;
+        }
+
+        if parse_allowlist_entry(entry).is_none() {
+            return Err(format!(
+                "Invite allowlist contains an invalid invite: {}",
+                entry
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+// invite codes in the text that aren't on the allowlist, regular codes are case sensitive so
+// they have to be listed exactly as they are
+pub fn find_unlisted_invite_codes(text: &str, allowlist: Option<&str>) -> Vec<SmolStr> {
+    let allowlist = allowlist.map(parse_invite_allowlist).unwrap_or_default();
+
+    extract_invite_codes(text)
+        .into_iter()
+        .filter(|code| !allowlist.contains(code))
+        .collect()
+}
+
+// invites are left to the invite link remover, so the link filter doesn't punish allowed ones
+pub fn remove_invite_links(text: &str) -> Cow<'_, str> {
+    INVITE_PATTERN.replace_all(text, " ")
+}
@@ -0,0 +1,172 @@
+use reqwest::Url;
+
+use crate::constants::TOP_LEVEL_DOMAINS;
+
+pub const DOMAIN_LIST_SIZE_LIMIT: usize = 2000;
+const MAX_DOMAIN_LENGTH: usize = 253;
+
+// extensions that are also valid top level domains, bare "main.rs" is far more likely to be a
+// file name than a link, links with a scheme are always checked
+const FILE_EXTENSIONS: [&str; 3] = ["rs", "py", "md"];
+
+// characters that can wrap a link in a message, e.g. markdown links, <suppressed embeds> and
+// quotes, they can't be part of a host name anyways
+pub fn is_link_separator(c: char) -> bool {
+    c.is_whitespace()
+        || matches!(
+            c,
+            '<' | '>' | '(' | ')' | '[' | ']' | '{' | '}' | '"' | '\'' | '`' | '|' | '*' | '~'
+        )
+}
+
+fn is_valid_label(label: &str) -> bool {
+    !label.is_empty()
+        && !label.starts_with('-')
+        && !label.ends_with('-')
+        && label.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+}
+
+fn is_valid_domain(domain: &str) -> bool {
+    domain.len() <= MAX_DOMAIN_LENGTH
+        && domain.contains('.')
+        && domain.split('.').all(is_valid_label)
+}
+
+// internationalized hosts are converted to punycode, so they are checked like any other host and
+// can be listed in either form
+fn to_ascii_host(host: &str) -> Option<String> {
+    if host.is_ascii() {
+        return Some(host.to_string());
+    }
+
+    let url = Url::parse(&format!("http://{}/", host)).ok()?;
+    url.host_str().map(str::to_string)
+}
+
+fn is_top_level_domain(tld: &str) -> bool {
+    TOP_LEVEL_DOMAINS
+        .get()
+        .is_some_and(|tlds| tlds.contains(tld))
+}
+
+fn extract_host(token: &str) -> Option<String> {
+    let lowercase = token.to_lowercase();
+    let (rest, has_scheme) = if let Some(rest) = lowercase
+        .strip_prefix("https://")
+        .or_else(|| lowercase.strip_prefix("http://"))
+    {
+        (rest, true)
+    } else {
+        (lowercase.as_str(), false)
+    };
+
+    let authority = rest.split(['/', '?', '#']).next()?;
+    // user:password@host
+    let host = authority.rsplit('@').next()?;
+    let host = host.split(':').next()?;
+    let host = host.trim_end_matches(['.', ',', ';', '!', '?']);
+    let host = to_ascii_host(host)?;
+
+    if !is_valid_domain(&host) {
+        return None;
+    }
+
+    if !has_scheme {
+        let tld = host.rsplit('.').next()?;
+        if !is_top_level_domain(tld) || FILE_EXTENSIONS.contains(&tld) {
+            return None;
+        }
+    }
+
+    Some(host)
+}
+
+// returns the lowercase host names of every link in the content, including bare domains without
+// a scheme
+pub fn extract_link_domains(content: &str) -> Vec<String> {
+    let mut domains: Vec<String> = vec![];
+
+    for token in content.split(is_link_separator) {
+        if let Some(host) = extract_host(token) {
+            if !domains.contains(&host) {
+                domains.push(host);
+            }
+        }
+    }
+
+    domains
+}
+
+fn normalize_domain_entry(entry: &str) -> String {
+    let entry = entry.trim().to_lowercase();
+    let entry = entry
+        .strip_prefix("https://")
+        .or_else(|| entry.strip_prefix("http://"))
+        .unwrap_or(&entry);
+    let entry = entry.split('/').next().unwrap_or_default();
+
+    // subdomains always match, so *.example.com is the same as example.com
+    let entry = entry.trim_start_matches("*.").trim_start_matches('.');
+    to_ascii_host(entry).unwrap_or_else(|| entry.to_string())
+}
+
+// entries are separated by commas or whitespace, invalid entries are skipped
+pub fn parse_domain_list(list: &str) -> Vec<String> {
+    list.split(|c: char| c == ',' || c.is_whitespace())
+        .filter(|entry| !entry.is_empty())
+        .map(normalize_domain_entry)
+        .filter(|domain| is_valid_domain(domain))
+        .collect()
+}
+
+// name is the capitalized list being validated, e.g. "Link filter allowlist"
+pub fn validate_domain_list(name: &str, list: &str) -> Result<(), String> {
+    if list.len() > DOMAIN_LIST_SIZE_LIMIT {
+        return Err(format!("{} cannot be too long!", name));
+    }
+
+    for entry in list.split(|c: char| c == ',' || c.is_whitespace()) {
+        if entry.is_empty() {
+            continue;
+        }
+
+        if !is_valid_domain(&normalize_domain_entry(entry)) {
+            return Err(format!("{} contains an invalid domain: {}", name, entry));
+        }
+    }
+
+    Ok(())
+}
+
+// length of the longest list entry the host falls under, subdomains match their parent domain
+fn longest_match(host: &str, domains: &[String]) -> Option<usize> {
+    domains
+        .iter()
+        .filter(|domain| {
+            host == domain.as_str()
+                || host
+                    .strip_suffix(domain.as_str())
+                    .is_some_and(|subdomain| subdomain.ends_with('.'))
+        })
+        .map(String::len)
+        .max()
+}
+
+// the more specific entry wins when a host is on both lists, so docs.example.com can be allowed
+// while the rest of example.com is denied
+pub fn is_domain_allowed(
+    host: &str,
+    allowlist: &[String],
+    denylist: &[String],
+    allowlist_only: bool,
+) -> bool {
+    match (
+        longest_match(host, allowlist),
+        longest_match(host, denylist),
+    ) {
+        (Some(allowed), Some(denied)) => allowed > denied,
+        (Some(_), None) => true,
+        (None, Some(_)) => false,
+        (None, None) => !allowlist_only,
+    }
+}
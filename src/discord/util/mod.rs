@@ -3,19 +3,25 @@ use std::time::Duration;
 
 use anyhow::bail;
 use async_recursion::async_recursion;
+use async_trait::async_trait;
 use serenity::all::{
-    CommandData, CommandDataOptionValue, CommandInteraction, CreateAllowedMentions, CreateEmbed,
-    CreateInteractionResponse, CreateInteractionResponseMessage, EditInteractionResponse, Error,
-    GenericChannelId, GuildId, Http, Member, Message, MessageFlags, MessageId, MessageUpdateEvent,
-    PartialMember, Permissions, Role, RoleId, User, UserId,
+    CommandData, CommandDataOptionValue, CommandInteraction, ComponentInteraction,
+    CreateAllowedMentions, CreateEmbed, CreateInteractionResponse,
+    CreateInteractionResponseMessage, EditInteractionResponse, Error, GenericChannelId, GuildId,
+    Http, Member, Message, MessageFlags, MessageId, MessageUpdateEvent, PartialMember, Permissions,
+    Role, RoleId, User, UserId,
 };
 // PartialChannel is no longer available in the updated serenity API
 use tracing::{error, warn};
 
 use crate::database::settings::{
-    Setting, ACTION_BAN, ACTION_HARDBAN, ACTION_KICK, ACTION_MUTE, ACTION_SOFTBAN, ACTION_WARN,
+    get_action_duration_for_auto_mod_action, get_duration, Setting, ACTION_BAN, ACTION_HARDBAN,
+    ACTION_KICK, ACTION_MUTE, ACTION_SOFTBAN, ACTION_WARN, PRIVACY_ADMIN_ONLY, PRIVACY_EVERYONE,
+    PRIVACY_STAFF_ONLY,
 };
 use crate::service::ban::BanService;
+use crate::service::escalation::EscalationService;
+use crate::service::evidence::EvidenceSnapshot;
 use crate::service::guild::CachedRole;
 use crate::service::hardban::HardbanService;
 use crate::service::kick::KickService;
@@ -25,8 +31,17 @@ use crate::service::warn::WarnService;
 use crate::service::Services;
 use crate::util::now;
 
+pub mod appeal;
+pub mod blocklist;
+pub mod evidence;
+pub mod hierarchy;
+pub mod infraction;
+pub mod invite;
+pub mod links;
 pub mod mod_log;
+pub mod report;
 pub mod user_dm;
+pub mod word_matcher;
 
 pub trait CommandDataExt {
     fn option(&self, option_name: &str) -> Option<&CommandDataOptionValue>;
@@ -132,6 +147,25 @@ pub fn is_staff(permissions: Permissions) -> bool {
         || permissions.manage_messages()
 }
 
+pub fn can_view_mod_log(privacy_mod_log: i32, permissions: Permissions) -> bool {
+    if privacy_mod_log == PRIVACY_EVERYONE {
+        true
+    } else if privacy_mod_log == PRIVACY_STAFF_ONLY {
+        is_staff(permissions)
+    } else if privacy_mod_log == PRIVACY_ADMIN_ONLY {
+        permissions.administrator()
+    } else {
+        false
+    }
+}
+
+// anonymous moderation only applies to viewers, the mod log channel always shows who acted.
+// it is independent of privacy_mod_log: even in staff only guilds, non-admin staff who can see
+// the mod log still see moderators hidden, only admins see who acted
+pub fn can_view_moderator(setting: &Setting, permissions: Permissions) -> bool {
+    !setting.anonymous_moderation || permissions.administrator()
+}
+
 pub fn get_permissions(
     member_id: UserId,
     member_roles: &[RoleId],
@@ -290,6 +324,80 @@ pub async fn reply_to_interaction_embed(
     reply_to_interaction(http, interaction, response_data).await;
 }
 
+pub async fn reply_to_component_str(
+    http: &Http,
+    interaction: &ComponentInteraction,
+    content: &str,
+    is_ephemeral: bool,
+) {
+    let response_data =
+        create_interaction_response_message(is_ephemeral).content(content.to_string());
+
+    let _ = interaction
+        .create_response(http, CreateInteractionResponse::Message(response_data))
+        .await
+        .map_err(|err| {
+            error!(
+                "failed to reply to component interaction {:?} {}",
+                interaction, err
+            );
+            err
+        });
+}
+
+// lets replies that are deferred and filled in later be shared by slash commands and message components
+#[async_trait]
+pub trait DeferredInteraction: Sync {
+    fn user_id(&self) -> UserId;
+    async fn defer_ephemeral(&self, http: &Http) -> Result<(), Error>;
+    async fn edit_deferred_response(
+        &self,
+        http: &Http,
+        builder: EditInteractionResponse<'_>,
+    ) -> Result<Message, Error>;
+}
+
+#[async_trait]
+impl DeferredInteraction for CommandInteraction {
+    fn user_id(&self) -> UserId {
+        self.user.id
+    }
+
+    async fn defer_ephemeral(&self, http: &Http) -> Result<(), Error> {
+        defer_ephemeral_interaction(http, self).await
+    }
+
+    async fn edit_deferred_response(
+        &self,
+        http: &Http,
+        builder: EditInteractionResponse<'_>,
+    ) -> Result<Message, Error> {
+        self.edit_response(http, builder).await
+    }
+}
+
+#[async_trait]
+impl DeferredInteraction for ComponentInteraction {
+    fn user_id(&self) -> UserId {
+        self.user.id
+    }
+
+    async fn defer_ephemeral(&self, http: &Http) -> Result<(), Error> {
+        let interaction_response =
+            CreateInteractionResponse::Defer(create_interaction_response_message(true));
+
+        self.create_response(http, interaction_response).await
+    }
+
+    async fn edit_deferred_response(
+        &self,
+        http: &Http,
+        builder: EditInteractionResponse<'_>,
+    ) -> Result<Message, Error> {
+        self.edit_response(http, builder).await
+    }
+}
+
 pub async fn defer_interaction(http: &Http, interaction: &CommandInteraction) -> Result<(), Error> {
     let interaction_response =
         CreateInteractionResponse::Defer(CreateInteractionResponseMessage::new());
@@ -301,6 +409,20 @@ pub async fn defer_interaction(http: &Http, interaction: &CommandInteraction) ->
     Ok(())
 }
 
+pub async fn defer_ephemeral_interaction(
+    http: &Http,
+    interaction: &CommandInteraction,
+) -> Result<(), Error> {
+    let interaction_response =
+        CreateInteractionResponse::Defer(create_interaction_response_message(true));
+
+    interaction
+        .create_response(http, interaction_response)
+        .await?;
+
+    Ok(())
+}
+
 pub async fn edit_deferred_interaction_response(
     http: &Http,
     interaction: &CommandInteraction,
@@ -410,6 +532,8 @@ pub async fn clean_messages(
 
 pub trait SerenityErrorExt {
     fn discord_error_code(&self) -> Option<u32>;
+    // unknown channels, members, messages and so on all come back as 404
+    fn is_not_found(&self) -> bool;
 }
 
 impl SerenityErrorExt for Error {
@@ -429,6 +553,15 @@ impl SerenityErrorExt for Error {
             _ => None,
         }
     }
+
+    fn is_not_found(&self) -> bool {
+        match self {
+            Error::Http(http_error) => http_error
+                .status_code()
+                .is_some_and(|status| status.as_u16() == 404),
+            _ => false,
+        }
+    }
 }
 
 #[async_recursion]
@@ -444,6 +577,7 @@ pub async fn execute_mod_action(
     mod_user_tag_and_id: &str,
     target_user: &User,
     reason: String,
+    evidence: &[EvidenceSnapshot],
     duration: Option<Duration>,
     call_depth: i32,
 ) {
@@ -506,6 +640,7 @@ pub async fn execute_mod_action(
                 mod_user_tag_and_id,
                 target_user,
                 reason,
+                evidence,
                 call_depth + 1,
             )
             .await;
@@ -522,6 +657,7 @@ pub async fn execute_mod_action(
                 mod_user_tag_and_id,
                 target_user,
                 reason,
+                evidence,
                 duration,
                 call_depth + 1,
             )
@@ -539,6 +675,7 @@ pub async fn execute_mod_action(
                 mod_user_tag_and_id,
                 target_user,
                 reason,
+                evidence,
                 call_depth + 1,
             )
             .await;
@@ -549,11 +686,13 @@ pub async fn execute_mod_action(
                 guild_id,
                 guild_name,
                 setting,
+                services,
                 channel_id,
                 mod_user_id,
                 mod_user_tag_and_id,
                 target_user,
                 reason,
+                evidence,
                 duration,
             )
             .await;
@@ -570,6 +709,7 @@ pub async fn execute_mod_action(
                 mod_user_tag_and_id,
                 target_user,
                 reason,
+                evidence,
                 1,
                 call_depth + 1,
             )
@@ -581,11 +721,13 @@ pub async fn execute_mod_action(
                 guild_id,
                 guild_name,
                 setting,
+                services,
                 channel_id,
                 mod_user_id,
                 mod_user_tag_and_id,
                 target_user,
                 reason,
+                evidence,
             )
             .await;
     } else {
@@ -596,3 +738,140 @@ pub async fn execute_mod_action(
         );
     }
 }
+
+async fn fetch_actionable_infraction_count(
+    infraction_type: i32,
+    setting: &Setting,
+    services: &Services,
+    guild_id: GuildId,
+    user_id: UserId,
+    since: u64,
+) -> Option<i64> {
+    if infraction_type == ACTION_WARN {
+        Some(
+            services
+                .get::<WarnService>()?
+                .fetch_actionable_warn_count(setting, guild_id, user_id, since)
+                .await,
+        )
+    } else if infraction_type == ACTION_MUTE {
+        Some(
+            services
+                .get::<MuteService>()?
+                .fetch_actionable_mute_count(setting, guild_id, user_id, since)
+                .await,
+        )
+    } else if infraction_type == ACTION_KICK {
+        Some(
+            services
+                .get::<KickService>()?
+                .fetch_actionable_kick_count(setting, guild_id, user_id, since)
+                .await,
+        )
+    } else if infraction_type == ACTION_SOFTBAN {
+        Some(
+            services
+                .get::<SoftbanService>()?
+                .fetch_actionable_softban_count(setting, guild_id, user_id, since)
+                .await,
+        )
+    } else {
+        None
+    }
+}
+
+// walks the guild's escalation ladder for given infraction type from the highest threshold down,
+// a step only fires on the infraction that reaches its threshold within its lookback window, so
+// infractions past the threshold don't repeat the step. the top step is the exception, it fires on
+// every infraction at or past its threshold since there's nothing left to escalate to
+pub async fn escalate_mod_action(
+    infraction_type: i32,
+    http: &Http,
+    guild_id: GuildId,
+    guild_name: &str,
+    setting: &Setting,
+    services: &Services,
+    channel_id: Option<GenericChannelId>,
+    mod_user_id: UserId,
+    mod_user_tag_and_id: &str,
+    target_user: &User,
+    reason: String,
+    call_depth: i32,
+) {
+    let escalation_service = if let Some(service) = services.get::<EscalationService>() {
+        service
+    } else {
+        error!("couldn't get escalation service!");
+        return;
+    };
+
+    let steps = escalation_service
+        .fetch_guild_escalation_steps_for_infraction_type(guild_id, infraction_type)
+        .await;
+
+    let now = now();
+    for (i, step) in steps.into_iter().enumerate() {
+        let since = if step.lookback_duration == 0 {
+            0
+        } else if let Some(lookback) =
+            get_duration(step.lookback_duration_type, step.lookback_duration)
+        {
+            now.saturating_sub(lookback.as_secs())
+        } else {
+            continue;
+        };
+
+        let count = if let Some(count) = fetch_actionable_infraction_count(
+            infraction_type,
+            setting,
+            services,
+            guild_id,
+            target_user.id,
+            since,
+        )
+        .await
+        {
+            count
+        } else {
+            warn!(
+                infraction_type = infraction_type,
+                guild_id = guild_id.get(),
+                "invalid state for escalation step infraction type!"
+            );
+            return;
+        };
+
+        let is_top_step = i == 0;
+        if count < i64::from(step.threshold)
+            || (!is_top_step && count != i64::from(step.threshold))
+        {
+            continue;
+        }
+
+        let duration = get_action_duration_for_auto_mod_action(
+            step.action,
+            step.action_duration_type,
+            step.action_duration,
+        );
+
+        execute_mod_action(
+            step.action,
+            http,
+            guild_id,
+            guild_name,
+            setting,
+            services,
+            channel_id,
+            mod_user_id,
+            mod_user_tag_and_id,
+            target_user,
+            reason,
+            &[],
+            duration,
+            call_depth,
+        )
+        .await;
+
+        return;
+    }
+}
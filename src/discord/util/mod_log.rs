@@ -1,12 +1,20 @@
+use std::num::NonZeroU64;
+
 use serenity::all::GenericChannelId;
-use serenity::builder::{CreateEmbed, CreateMessage};
+use serenity::builder::{CreateActionRow, CreateEmbed, CreateMessage, EditMessage};
 use serenity::http::Http;
+use serenity::model::channel::Embed;
+use serenity::model::id::{ChannelId, MessageId, UserId};
 use serenity::model::mention::Mentionable;
 use serenity::model::user::User;
 use serenity::model::{Color, Timestamp};
 use tracing::{error, warn};
 
+use crate::constants::EMBED_COLOR;
+use crate::database::settings::Setting;
+use crate::discord::components::mod_log::create_mod_log_buttons;
 use crate::discord::util::{SerenityErrorExt, UserExt};
+use crate::util::now;
 
 pub enum ModLogAction {
     Ban { expiration_time: Option<u64> },
@@ -15,6 +23,13 @@ pub enum ModLogAction {
     Mute { expiration_time: Option<u64> },
     Softban,
     Hardban,
+    Unban,
+    Unmute,
+    Pardon,
+    Expired,
+    Lockdown { expiration_time: Option<u64> },
+    Unlock,
+    UnlockFailed,
 }
 
 impl ModLogAction {
@@ -26,6 +41,13 @@ impl ModLogAction {
             ModLogAction::Mute { .. } => Color::new(0xFFFFFF),
             ModLogAction::Softban => Color::new(0xFF55DD),
             ModLogAction::Hardban => Color::new(0x700000),
+            ModLogAction::Unban => Color::new(0x00C853),
+            ModLogAction::Unmute => Color::new(0x64DD17),
+            ModLogAction::Pardon => Color::new(0x2979FF),
+            ModLogAction::Expired => Color::new(0x9E9E9E),
+            ModLogAction::Lockdown { .. } => Color::new(0xD50000),
+            ModLogAction::Unlock => Color::new(0x00BFA5),
+            ModLogAction::UnlockFailed => Color::new(0xFF6D00),
         }
     }
 
@@ -37,6 +59,24 @@ impl ModLogAction {
             ModLogAction::Mute { .. } => "Mute",
             ModLogAction::Softban => "Softban",
             ModLogAction::Hardban => "Hardban",
+            ModLogAction::Unban => "Unban",
+            ModLogAction::Unmute => "Unmute",
+            ModLogAction::Pardon => "Pardon",
+            ModLogAction::Expired => "Expired",
+            ModLogAction::Lockdown { .. } => "Lockdown",
+            ModLogAction::Unlock => "Unlock",
+            ModLogAction::UnlockFailed => "Unlock Failed",
+        }
+    }
+
+    // how the action shows up on the entry of the infraction it lifts
+    pub fn past_tense(&self) -> &'static str {
+        match self {
+            ModLogAction::Unban => "Unbanned",
+            ModLogAction::Unmute => "Unmuted",
+            ModLogAction::Pardon => "Pardoned",
+            ModLogAction::Expired => "Expired",
+            _ => self.name(),
         }
     }
 
@@ -54,6 +94,12 @@ impl ModLogAction {
                     .unwrap_or_else(|| "Indefinitely".into());
                 embed.field("Muted until", value, false)
             }
+            ModLogAction::Lockdown { expiration_time } => {
+                let value = expiration_time
+                    .map(|time| format!("<t:{}>", time))
+                    .unwrap_or_else(|| "Until unlocked".into());
+                embed.field("Locked until", value, false)
+            }
             _ => embed,
         }
     }
@@ -75,6 +121,22 @@ impl CreateModLogEntryError {
     }
 }
 
+pub fn get_mod_log_channel_id(setting: &Setting) -> Option<GenericChannelId> {
+    if !setting.mod_log {
+        return None;
+    }
+
+    if let Some(id) = NonZeroU64::new(setting.mod_log_channel_id as u64) {
+        Some(GenericChannelId::new(id.get()))
+    } else {
+        warn!(
+            "found setting with invalid mod log channel id! {:?}",
+            setting
+        );
+        None
+    }
+}
+
 pub async fn create_mod_log_entry(
     http: &Http,
     mod_log_channel_id: GenericChannelId,
@@ -83,9 +145,9 @@ pub async fn create_mod_log_entry(
     target_user: &User,
     action: ModLogAction,
     reason: &str,
-    entity_id: i32,
+    case_number: i32,
     action_time: u64,
-) -> Result<(), CreateModLogEntryError> {
+) -> Result<MessageId, CreateModLogEntryError> {
     let timestamp = match Timestamp::from_unix_timestamp(action_time as i64) {
         Ok(t) => t,
         Err(_) => {
@@ -102,7 +164,7 @@ pub async fn create_mod_log_entry(
         .timestamp(timestamp)
         .field(
             "Action",
-            format!("{} - #{}", action.name(), entity_id),
+            format!("{} - #{}", action.name(), case_number),
             false,
         )
         .field("User:", target_user.tag_and_id(), false)
@@ -115,12 +177,268 @@ pub async fn create_mod_log_entry(
 
     embed = action.create_expiration_date_field(embed);
 
-    let message = CreateMessage::default().add_embed(embed);
+    let components = create_mod_log_buttons(&action, case_number, target_user.id);
+
+    send_mod_log_entry(http, mod_log_channel_id, embed, components).await
+}
+
+// lifting actions refer to the case number of the infraction they lift
+pub async fn create_mod_log_lift_entry(
+    http: &Http,
+    mod_log_channel_id: GenericChannelId,
+    mod_user_tag_and_id: &str,
+    target_user_id: UserId,
+    action: ModLogAction,
+    reason: Option<&str>,
+    case_number: i32,
+    action_time: u64,
+) -> Result<MessageId, CreateModLogEntryError> {
+    let timestamp = match Timestamp::from_unix_timestamp(action_time as i64) {
+        Ok(t) => t,
+        Err(_) => {
+            warn!(
+                "attempted to create mod log entry with invalid timestamp! {}",
+                action_time
+            );
+            return Err(CreateModLogEntryError::Unknown);
+        }
+    };
+
+    let mut embed = CreateEmbed::default()
+        .color(action.color())
+        .timestamp(timestamp)
+        .field(
+            "Action",
+            format!("{} - #{}", action.name(), case_number),
+            false,
+        )
+        .field(
+            "User:",
+            format!("{} ({})", target_user_id.mention(), target_user_id),
+            false,
+        );
+
+    if let Some(reason) = reason {
+        embed = embed.field("Reason:", reason, false);
+    }
+
+    embed = embed.field("Responsible Moderator:", mod_user_tag_and_id, false);
+
+    let components = create_mod_log_buttons(&action, case_number, target_user_id);
+
+    send_mod_log_entry(http, mod_log_channel_id, embed, components).await
+}
+
+// lockdowns target channels instead of users, so they have neither case numbers nor buttons
+async fn create_mod_log_lockdown_entry(
+    http: &Http,
+    mod_log_channel_id: GenericChannelId,
+    mod_user_tag_and_id: &str,
+    action: ModLogAction,
+    channel_ids: &[ChannelId],
+    reason: &str,
+    action_time: u64,
+) -> Result<MessageId, CreateModLogEntryError> {
+    let timestamp = match Timestamp::from_unix_timestamp(action_time as i64) {
+        Ok(t) => t,
+        Err(_) => {
+            warn!(
+                "attempted to create mod log entry with invalid timestamp! {}",
+                action_time
+            );
+            return Err(CreateModLogEntryError::Unknown);
+        }
+    };
+
+    // server wide lockdowns would overflow the field with mentions
+    let channels = if channel_ids.len() > 10 {
+        format!("{} channels", channel_ids.len())
+    } else {
+        channel_ids
+            .iter()
+            .map(|channel_id| channel_id.mention().to_string())
+            .collect::<Vec<String>>()
+            .join(", ")
+    };
+
+    let mut embed = CreateEmbed::default()
+        .color(action.color())
+        .timestamp(timestamp)
+        .field("Action", action.name(), false)
+        .field("Channels:", channels, false)
+        .field("Reason:", reason, false)
+        .field("Responsible Moderator:", mod_user_tag_and_id, false);
+
+    embed = action.create_expiration_date_field(embed);
+
+    send_mod_log_entry(http, mod_log_channel_id, embed, vec![]).await
+}
+
+pub async fn log_lockdown(
+    http: &Http,
+    setting: &Setting,
+    mod_user_tag_and_id: &str,
+    action: ModLogAction,
+    channel_ids: &[ChannelId],
+    reason: &str,
+) -> Result<(), CreateModLogEntryError> {
+    let mod_log_channel_id = if let Some(channel_id) = get_mod_log_channel_id(setting) {
+        channel_id
+    } else {
+        return Ok(());
+    };
+
+    create_mod_log_lockdown_entry(
+        http,
+        mod_log_channel_id,
+        mod_user_tag_and_id,
+        action,
+        channel_ids,
+        reason,
+        now(),
+    )
+    .await
+    .map(|_| ())
+}
+
+pub async fn create_mod_log_update_entry(
+    http: &Http,
+    mod_log_channel_id: GenericChannelId,
+    mod_user_tag_and_id: &str,
+    target_user_id: UserId,
+    infraction_name: &str,
+    case_number: i32,
+    change: &str,
+    action_time: u64,
+) -> Result<(), CreateModLogEntryError> {
+    let timestamp = match Timestamp::from_unix_timestamp(action_time as i64) {
+        Ok(t) => t,
+        Err(_) => {
+            warn!(
+                "attempted to create mod log entry with invalid timestamp! {}",
+                action_time
+            );
+            return Err(CreateModLogEntryError::Unknown);
+        }
+    };
+
+    let embed = CreateEmbed::default()
+        .color(EMBED_COLOR)
+        .timestamp(timestamp)
+        .field(
+            "Action",
+            format!("Infraction updated - {} #{}", infraction_name, case_number),
+            false,
+        )
+        .field(
+            "User:",
+            format!("{} ({})", target_user_id.mention(), target_user_id),
+            false,
+        )
+        .field("Change:", change, false)
+        .field("Responsible Moderator:", mod_user_tag_and_id, false);
+
+    send_mod_log_entry(http, mod_log_channel_id, embed, vec![])
+        .await
+        .map(|_| ())
+}
+
+// rebuilds the embed of an entry with the change appended, entries of infractions that are no
+// longer in effect get their action struck through
+pub fn create_updated_mod_log_embed<'a>(
+    original: &Embed,
+    mod_user_tag_and_id: &str,
+    change: &str,
+    strike_through: bool,
+) -> Option<CreateEmbed<'a>> {
+    // discord allows 25 fields per embed
+    if original.fields.len() >= 25 {
+        return None;
+    }
+
+    let mut embed = CreateEmbed::default();
+
+    if let Some(color) = original.colour {
+        embed = embed.color(color);
+    }
+
+    if let Some(timestamp) = original.timestamp {
+        embed = embed.timestamp(timestamp);
+    }
+
+    for field in original.fields.iter() {
+        let value = if strike_through && &*field.name == "Action" && !field.value.starts_with("~~")
+        {
+            format!("~~{}~~", field.value)
+        } else {
+            field.value.to_string()
+        };
+
+        embed = embed.field(field.name.to_string(), value, field.inline);
+    }
+
+    Some(embed.field(
+        "Updated by:",
+        format!("{} - {}", mod_user_tag_and_id, change),
+        false,
+    ))
+}
+
+// returns false if the entry couldn't be edited, e.g. it was deleted, a new entry should be sent instead
+pub async fn edit_mod_log_entry(
+    http: &Http,
+    mod_log_channel_id: GenericChannelId,
+    message_id: MessageId,
+    mod_user_tag_and_id: &str,
+    change: &str,
+    strike_through: bool,
+    components: Vec<CreateActionRow<'_>>,
+) -> bool {
+    let message = match mod_log_channel_id.message(http, message_id).await {
+        Ok(message) => message,
+        Err(err) => {
+            // unknown channel or message, mod log channel was changed or the entry was deleted
+            if !matches!(err.discord_error_code(), Some(10003) | Some(10008)) {
+                error!("failed to fetch mod log entry {}", err);
+            }
+            return false;
+        }
+    };
+
+    let embed = if let Some(embed) = message.embeds.first().and_then(|embed| {
+        create_updated_mod_log_embed(embed, mod_user_tag_and_id, change, strike_through)
+    }) {
+        embed
+    } else {
+        return false;
+    };
+
+    let builder = EditMessage::new().embed(embed).components(components);
+
+    mod_log_channel_id
+        .edit_message(http, message_id, builder)
+        .await
+        .map_err(|err| {
+            error!("failed to edit mod log entry {}", err);
+            err
+        })
+        .is_ok()
+}
+
+async fn send_mod_log_entry(
+    http: &Http,
+    mod_log_channel_id: GenericChannelId,
+    embed: CreateEmbed<'_>,
+    components: Vec<CreateActionRow<'_>>,
+) -> Result<MessageId, CreateModLogEntryError> {
+    let message = CreateMessage::default()
+        .add_embed(embed)
+        .components(components);
 
     mod_log_channel_id
         .send_message(http, message)
         .await
-        .map(|_| ())
+        .map(|message| message.id)
         .map_err(|err| match err.discord_error_code() {
             Some(10003) => CreateModLogEntryError::ModLogChannelDoesNotExist,
             Some(50013) => CreateModLogEntryError::Unauthorized,
@@ -0,0 +1,219 @@
+use std::num::NonZeroU64;
+
+use serenity::all::GenericChannelId;
+use serenity::builder::{CreateEmbed, CreateEmbedFooter, CreateMessage, EditMessage};
+use serenity::http::Http;
+use serenity::model::id::{MessageId, UserId};
+use serenity::model::{Color, Timestamp};
+use tracing::{error, warn};
+
+use crate::database::reports::{
+    Report, REPORT_STATUS_CLAIMED, REPORT_STATUS_DISMISSED, REPORT_STATUS_RESOLVED,
+};
+use crate::database::settings::Setting;
+use crate::discord::components::report::create_report_buttons;
+use crate::discord::util::SerenityErrorExt;
+
+const MAX_LISTED_REPORTERS: usize = 10;
+const EMBED_FIELD_VALUE_LIMIT: usize = 1024;
+
+fn truncate_field_value(value: &str) -> String {
+    if value.chars().count() <= EMBED_FIELD_VALUE_LIMIT {
+        value.to_string()
+    } else {
+        let mut truncated = value
+            .chars()
+            .take(EMBED_FIELD_VALUE_LIMIT - 3)
+            .collect::<String>();
+        truncated.push_str("...");
+        truncated
+    }
+}
+
+pub fn get_report_channel_id(setting: &Setting) -> Option<GenericChannelId> {
+    NonZeroU64::new(setting.report_channel_id as u64).map(|id| GenericChannelId::new(id.get()))
+}
+
+pub fn report_status_name(report: &Report) -> &'static str {
+    match report.status {
+        REPORT_STATUS_CLAIMED => "Claimed",
+        REPORT_STATUS_RESOLVED => "Resolved",
+        REPORT_STATUS_DISMISSED => "Dismissed",
+        _ => "Open",
+    }
+}
+
+pub fn create_report_embed<'a>(report: &Report) -> CreateEmbed<'a> {
+    let reporters = report
+        .reporter_user_ids
+        .iter()
+        .take(MAX_LISTED_REPORTERS)
+        .map(|id| format!("<@{}> ({})", id, id))
+        .collect::<Vec<String>>()
+        .join("\n");
+    let reporters = if report.reporter_user_ids.len() > MAX_LISTED_REPORTERS {
+        format!(
+            "{}\n...and {} more",
+            reporters,
+            report.reporter_user_ids.len() - MAX_LISTED_REPORTERS
+        )
+    } else {
+        reporters
+    };
+
+    let message_link = format!(
+        "https://discord.com/channels/{}/{}/{}",
+        report.guild_id, report.channel_id, report.message_id
+    );
+
+    let content = if report.content.is_empty() {
+        "<no content>".to_string()
+    } else {
+        truncate_field_value(&report.content)
+    };
+
+    let mut embed = CreateEmbed::default()
+        .title(format!("Message Report #{}", report.id))
+        .field(
+            "Reported:",
+            format!("<@{}> ({})", report.target_user_id, report.target_user_id),
+            false,
+        )
+        .field(
+            if report.reporter_user_ids.len() > 1 {
+                "Reporters:"
+            } else {
+                "Reporter:"
+            },
+            reporters,
+            false,
+        )
+        .field("Channel:", format!("<#{}>", report.channel_id), true)
+        .field("Message Link:", message_link, true)
+        .field("Content:", content, false)
+        .footer(CreateEmbedFooter::new("Message sent on"));
+
+    if !report.attachments.is_empty() {
+        embed = embed.field(
+            "Attachments:",
+            truncate_field_value(&report.attachments.join("\n")),
+            false,
+        );
+    }
+
+    if let Ok(timestamp) = Timestamp::from_unix_timestamp(report.message_time) {
+        embed = embed.timestamp(timestamp);
+    }
+
+    let moderator = format!(
+        "<@{}> ({})",
+        report.moderator_user_id, report.moderator_user_id
+    );
+
+    embed = match report.status {
+        REPORT_STATUS_CLAIMED => {
+            embed
+                .color(Color::new(0xFFAB00))
+                .field("Claimed by:", moderator, false)
+        }
+        REPORT_STATUS_RESOLVED if report.action_taken => embed.color(Color::new(0x00C853)).field(
+            "Resolved by:",
+            format!("{}\nMessage deleted and author warned", moderator),
+            false,
+        ),
+        REPORT_STATUS_RESOLVED => {
+            embed
+                .color(Color::new(0x00C853))
+                .field("Resolved by:", moderator, false)
+        }
+        REPORT_STATUS_DISMISSED => {
+            embed
+                .color(Color::new(0x9E9E9E))
+                .field("Dismissed by:", moderator, false)
+        }
+        _ => embed.color(Color::new(0xFF2900)),
+    };
+
+    embed
+}
+
+pub async fn post_report(http: &Http, setting: &Setting, report: &Report) -> Option<MessageId> {
+    let report_channel_id = if let Some(id) = get_report_channel_id(setting) {
+        id
+    } else {
+        warn!(
+            "found setting with invalid report channel id! {:?}",
+            setting
+        );
+        return None;
+    };
+
+    let message = CreateMessage::default()
+        .add_embed(create_report_embed(report))
+        .components(create_report_buttons(report));
+
+    report_channel_id
+        .send_message(http, message)
+        .await
+        .map(|message| message.id)
+        .map_err(|err| {
+            error!("failed to post report {}", err);
+            err
+        })
+        .ok()
+}
+
+// returns false if the report message couldn't be edited, e.g. it was deleted, it should be posted again
+pub async fn update_report_entry(http: &Http, setting: &Setting, report: &Report) -> bool {
+    let (report_channel_id, message_id) = match (
+        get_report_channel_id(setting),
+        NonZeroU64::new(report.report_message_id as u64),
+    ) {
+        (Some(channel_id), Some(message_id)) => (channel_id, MessageId::new(message_id.get())),
+        _ => return false,
+    };
+
+    let builder = EditMessage::new()
+        .embed(create_report_embed(report))
+        .components(create_report_buttons(report));
+
+    report_channel_id
+        .edit_message(http, message_id, builder)
+        .await
+        .map_err(|err| {
+            // unknown channel or message, report channel was changed or the entry was deleted
+            if !matches!(err.discord_error_code(), Some(10003) | Some(10008)) {
+                error!("failed to edit report entry {}", err);
+            }
+            err
+        })
+        .is_ok()
+}
+
+// reporters only learn that their report was handled, not by whom or how
+pub async fn notify_reporters_for_resolution(http: &Http, guild_name: &str, report: &Report) {
+    let content = format!(
+        "A message you reported in {} has been reviewed and resolved by the moderators. Thank you for your report!",
+        guild_name
+    );
+
+    for reporter_user_id in &report.reporter_user_ids {
+        let user_id = if let Some(id) = NonZeroU64::new(*reporter_user_id as u64) {
+            UserId::new(id.get())
+        } else {
+            continue;
+        };
+
+        if let Ok(channel) = user_id.create_dm_channel(http).await {
+            let _ = channel
+                .id
+                .widen()
+                .send_message(http, CreateMessage::default().content(content.as_str()))
+                .await
+                .map_err(|err| {
+                    error!("failed to send report resolution DM {}", err);
+                    err
+                });
+        }
+    }
+}
@@ -5,6 +5,11 @@ use serenity::model::Timestamp;
 use tracing::{error, warn};
 
 use crate::constants::EMBED_COLOR;
+use crate::database::settings::Setting;
+
+pub const DM_TEMPLATE_SIZE_LIMIT: usize = 1500;
+const EMBED_DESCRIPTION_LIMIT: usize = 4096;
+const DM_TEMPLATE_PLACEHOLDERS: [&str; 5] = ["guild", "reason", "moderator", "expires", "case"];
 
 pub enum ModActionKind {
     Ban { expiration_time: Option<u64> },
@@ -16,7 +21,7 @@ pub enum ModActionKind {
 }
 
 impl ModActionKind {
-    fn title(&self, guild_name: &str) -> String {
+    pub fn title(&self, guild_name: &str) -> String {
         match self {
             ModActionKind::Ban { .. } => format!("Banned from {}", guild_name),
             ModActionKind::Kick => format!("Kicked from {}", guild_name),
@@ -38,6 +43,40 @@ impl ModActionKind {
         }
     }
 
+    fn is_dm_enabled(&self, setting: &Setting) -> bool {
+        match self {
+            ModActionKind::Ban { .. } => setting.ban_dm,
+            ModActionKind::Kick => setting.kick_dm,
+            ModActionKind::Warn => setting.warn_dm,
+            ModActionKind::Mute { .. } => setting.mute_dm,
+            ModActionKind::Softban => setting.softban_dm,
+            ModActionKind::Hardban => setting.hardban_dm,
+        }
+    }
+
+    fn dm_template<'a>(&self, setting: &'a Setting) -> Option<&'a str> {
+        match self {
+            ModActionKind::Ban { .. } => setting.ban_dm_template.as_deref(),
+            ModActionKind::Kick => setting.kick_dm_template.as_deref(),
+            ModActionKind::Warn => setting.warn_dm_template.as_deref(),
+            ModActionKind::Mute { .. } => setting.mute_dm_template.as_deref(),
+            ModActionKind::Softban => setting.softban_dm_template.as_deref(),
+            ModActionKind::Hardban => setting.hardban_dm_template.as_deref(),
+        }
+    }
+
+    fn expires(&self) -> String {
+        match self {
+            ModActionKind::Ban {
+                expiration_time: Some(time),
+            }
+            | ModActionKind::Mute {
+                expiration_time: Some(time),
+            } => format!("<t:{}>", time),
+            _ => "Never".into(),
+        }
+    }
+
     fn create_expiration_date_field<'a>(&'a self, embed: CreateEmbed<'a>) -> CreateEmbed<'a> {
         match self {
             ModActionKind::Ban { expiration_time } => {
@@ -57,15 +96,157 @@ impl ModActionKind {
     }
 }
 
+pub fn moderator_name(hide_moderator: bool, guild_name: &str, mod_user_tag_and_id: &str) -> String {
+    if hide_moderator {
+        format!("a moderator of {}", guild_name)
+    } else {
+        mod_user_tag_and_id.to_string()
+    }
+}
+
+fn placeholder_name_len(s: &str) -> usize {
+    s.find(|c: char| !c.is_ascii_alphabetic())
+        .unwrap_or(s.len())
+}
+
+// name is the capitalized action the template belongs to, e.g. "Ban"
+pub fn validate_dm_template(name: &str, template: &str) -> Result<(), String> {
+    if template.chars().count() > DM_TEMPLATE_SIZE_LIMIT {
+        return Err(format!("{} DM template cannot be too long!", name));
+    }
+
+    let mut rest = template;
+    while let Some(index) = rest.find('$') {
+        rest = &rest[index + 1..];
+        let placeholder = &rest[..placeholder_name_len(rest)];
+
+        if !placeholder.is_empty() && !DM_TEMPLATE_PLACEHOLDERS.contains(&placeholder) {
+            return Err(format!(
+                "{} DM template contains unknown placeholder ${}!",
+                name, placeholder
+            ));
+        }
+
+        rest = &rest[placeholder.len()..];
+    }
+
+    Ok(())
+}
+
+fn truncate_description(description: String) -> String {
+    if description.chars().count() <= EMBED_DESCRIPTION_LIMIT {
+        description
+    } else {
+        let mut truncated = description
+            .chars()
+            .take(EMBED_DESCRIPTION_LIMIT - 3)
+            .collect::<String>();
+        truncated.push_str("...");
+        truncated
+    }
+}
+
+// placeholders are substituted in a single pass, so values like the reason can't inject other ones.
+// the reason alone can be long enough to push the rendered template past the description limit
+pub fn render_dm_template(
+    template: &str,
+    kind: &ModActionKind,
+    reason: &str,
+    case_number: i32,
+    guild_name: &str,
+    moderator: &str,
+) -> String {
+    let mut rendered = String::with_capacity(template.len());
+
+    let mut rest = template;
+    while let Some(index) = rest.find('$') {
+        rendered.push_str(&rest[..index]);
+        rest = &rest[index + 1..];
+        let placeholder = &rest[..placeholder_name_len(rest)];
+
+        match placeholder {
+            "guild" => rendered.push_str(guild_name),
+            "reason" => rendered.push_str(reason),
+            "moderator" => rendered.push_str(moderator),
+            "expires" => rendered.push_str(&kind.expires()),
+            "case" => rendered.push_str(&format!("#{}", case_number)),
+            _ => {
+                rendered.push('$');
+                rendered.push_str(placeholder);
+            }
+        }
+
+        rest = &rest[placeholder.len()..];
+    }
+
+    rendered.push_str(rest);
+    truncate_description(rendered)
+}
+
+fn create_mod_action_embed<'a>(
+    setting: &Setting,
+    kind: &'a ModActionKind,
+    reason: &str,
+    case_number: i32,
+    guild_name: &str,
+    mod_user_tag_and_id: &str,
+    appeal_url: Option<&str>,
+) -> CreateEmbed<'a> {
+    let moderator = moderator_name(
+        setting.anonymous_moderation,
+        guild_name,
+        mod_user_tag_and_id,
+    );
+
+    let mut embed = CreateEmbed::default()
+        .title(kind.title(guild_name))
+        .colour(EMBED_COLOR);
+
+    if let Some(template) = kind.dm_template(setting) {
+        embed = embed.description(render_dm_template(
+            template,
+            kind,
+            reason,
+            case_number,
+            guild_name,
+            &moderator,
+        ));
+    } else {
+        embed = embed
+            .footer(CreateEmbedFooter::new(kind.by_moderator(&moderator)))
+            .field("Reason", reason.to_string(), false)
+            .field("Case", format!("#{}", case_number), false);
+
+        embed = kind.create_expiration_date_field(embed);
+    }
+
+    if let Some(appeal_url) = appeal_url {
+        embed = embed.field(
+            "Appeal",
+            format!("You can appeal this decision [here]({}).", appeal_url),
+            false,
+        );
+    }
+
+    embed
+}
+
 pub async fn notify_user_for_mod_action(
     http: &Http,
+    setting: &Setting,
     user: UserId,
     kind: ModActionKind,
     reason: &str,
     action_time: u64,
+    case_number: i32,
     guild_name: &str,
     mod_user_tag_and_id: &str,
+    appeal_url: Option<&str>,
 ) {
+    if !kind.is_dm_enabled(setting) {
+        return;
+    }
+
     let dm_channel_result = user.create_dm_channel(http).await.map_err(|err| {
         error!("failed to create DM channel {}", err);
         err
@@ -83,16 +264,16 @@ pub async fn notify_user_for_mod_action(
             }
         };
 
-        let mut embed = CreateEmbed::default()
-            .title(kind.title(guild_name))
-            .colour(EMBED_COLOR)
-            .footer(CreateEmbedFooter::new(
-                kind.by_moderator(mod_user_tag_and_id),
-            ))
-            .timestamp(timestamp)
-            .field("Reason", reason, false);
-
-        embed = kind.create_expiration_date_field(embed);
+        let embed = create_mod_action_embed(
+            setting,
+            &kind,
+            reason,
+            case_number,
+            guild_name,
+            mod_user_tag_and_id,
+            appeal_url,
+        )
+        .timestamp(timestamp);
 
         let message = CreateMessage::default().add_embed(embed);
 
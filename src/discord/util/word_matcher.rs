@@ -0,0 +1,169 @@
+use aho_corasick::{AhoCorasick, BuildError, MatchKind};
+use smol_str::SmolStr;
+use unicode_normalization::char::is_combining_mark;
+use unicode_normalization::UnicodeNormalization;
+
+// characters that render as nothing, commonly put between letters to get past filters
+pub fn is_invisible(c: char) -> bool {
+    matches!(
+        c,
+        '\u{00AD}'
+            | '\u{034F}'
+            | '\u{061C}'
+            | '\u{115F}'
+            | '\u{1160}'
+            | '\u{17B4}'
+            | '\u{17B5}'
+            | '\u{180E}'
+            | '\u{200B}'..='\u{200F}'
+            | '\u{202A}'..='\u{202E}'
+            | '\u{2060}'..='\u{2064}'
+            | '\u{FE00}'..='\u{FE0F}'
+            | '\u{FEFF}'
+    )
+}
+
+// markdown formatting is dropped instead of being treated as a separator, so **b**ad is still bad
+fn is_markdown(c: char) -> bool {
+    matches!(c, '*' | '_' | '~' | '`' | '|')
+}
+
+// maps lookalike letters from other scripts and common leetspeak substitutions to latin letters,
+// expects lowercase input
+fn fold_lookalike(c: char) -> char {
+    match c {
+        'а' | 'α' | '4' | '@' => 'a',
+        'в' | 'β' | '8' => 'b',
+        'с' | 'ϲ' => 'c',
+        'ԁ' => 'd',
+        'е' | 'ε' | '3' | '€' => 'e',
+        'ɡ' | '9' => 'g',
+        'һ' | 'н' => 'h',
+        'і' | 'ι' | '1' => 'i',
+        'ј' => 'j',
+        'к' | 'κ' => 'k',
+        'ӏ' => 'l',
+        'м' => 'm',
+        'η' => 'n',
+        'о' | 'ο' | '0' => 'o',
+        'р' | 'ρ' => 'p',
+        'ԛ' => 'q',
+        'ѕ' | '5' | '$' => 's',
+        'т' | 'τ' | '7' => 't',
+        'υ' => 'u',
+        'ν' => 'v',
+        'ѡ' | 'ω' => 'w',
+        'х' | 'χ' => 'x',
+        'у' | 'ү' => 'y',
+        c => c,
+    }
+}
+
+// lowercases, strips diacritics and invisible characters, folds lookalikes and turns every run of
+// other characters into a single space
+fn normalize(text: &str) -> String {
+    let mut normalized = String::with_capacity(text.len());
+    let mut pending_separator = false;
+
+    for c in text.nfkd() {
+        if is_combining_mark(c) || is_invisible(c) || is_markdown(c) {
+            continue;
+        }
+
+        for c in c.to_lowercase().map(fold_lookalike) {
+            if c.is_alphanumeric() {
+                if pending_separator && !normalized.is_empty() {
+                    normalized.push(' ');
+                }
+                pending_separator = false;
+                normalized.push(c);
+            } else {
+                pending_separator = true;
+            }
+        }
+    }
+
+    normalized
+}
+
+// "b a d" is joined back into "bad", single letters on their own are left alone
+fn join_spaced_letters(normalized: &str) -> String {
+    let mut joined = String::with_capacity(normalized.len());
+    let mut previous_is_letter = false;
+
+    for word in normalized.split(' ') {
+        let is_letter = word.chars().count() == 1;
+        if !joined.is_empty() && !(is_letter && previous_is_letter) {
+            joined.push(' ');
+        }
+        joined.push_str(word);
+        previous_is_letter = is_letter;
+    }
+
+    joined
+}
+
+pub struct WordMatcher {
+    // original terms indexed by pattern id, reported back when a message matches
+    terms: Vec<SmolStr>,
+    automaton: AhoCorasick,
+    whole_words: bool,
+}
+
+impl WordMatcher {
+    // whole word matching requires terms to be surrounded by word boundaries, otherwise terms are
+    // also found inside of other words. spaces are kept in both modes, so "was sad" doesn't contain
+    // "ass", only runs of single letters like "a s s" are joined
+    pub fn new<'a>(
+        terms: impl Iterator<Item = &'a str>,
+        whole_words: bool,
+    ) -> Result<WordMatcher, BuildError> {
+        let mut original_terms = vec![];
+        let mut patterns = vec![];
+
+        for term in terms {
+            let normalized = normalize(term);
+            if normalized.is_empty() {
+                continue;
+            }
+
+            let joined = join_spaced_letters(&normalized);
+            let pattern = if whole_words {
+                format!(" {} ", joined)
+            } else {
+                joined
+            };
+
+            original_terms.push(SmolStr::new(term.trim()));
+            patterns.push(pattern);
+        }
+
+        let automaton = AhoCorasick::builder()
+            .match_kind(MatchKind::LeftmostFirst)
+            .build(&patterns)?;
+
+        Ok(WordMatcher {
+            terms: original_terms,
+            automaton,
+            whole_words,
+        })
+    }
+
+    pub fn find(&self, content: &str) -> Option<&str> {
+        let normalized = normalize(content);
+        if normalized.is_empty() {
+            return None;
+        }
+
+        let joined = join_spaced_letters(&normalized);
+        let haystack = if self.whole_words {
+            format!(" {} ", joined)
+        } else {
+            joined
+        };
+
+        self.automaton
+            .find(&haystack)
+            .map(|found| self.terms[found.pattern().as_usize()].as_str())
+    }
+}
@@ -0,0 +1,229 @@
+use std::sync::Arc;
+
+use axum::extract::{Path, Query, State};
+use axum::http::StatusCode;
+use axum::response::{Html, IntoResponse, Response};
+use axum::{Form, Json};
+use serde::{Deserialize, Serialize};
+use serenity::all::Permissions;
+use tracing::error;
+
+use crate::database::appeals::{Appeal, APPEAL_STATUS_PENDING};
+use crate::database::settings::{ACTION_BAN, ACTION_MUTE};
+use crate::discord::util::appeal::post_appeal_for_review;
+use crate::discord::util::infraction::{fetch_infraction_with_case_number, Infraction};
+use crate::server::endpoint::{CaseNumberParam, ModLogPaginationParams};
+use crate::server::model::appeal::AppealModel;
+use crate::server::{extract_service, ModLogEndpointParams, ModPermission};
+use crate::service::appeal::AppealService;
+use crate::service::guild::GuildService;
+use crate::service::setting::SettingService;
+use crate::service::Services;
+use crate::util::now;
+
+const APPEAL_TEMPLATE: &str = include_str!("appeal.html");
+// leaves room for the rest of the review embed, discord caps embed fields at 1024 characters
+const MAX_APPEAL_MESSAGE_LENGTH: usize = 1000;
+
+pub struct AppealModPermission;
+
+impl ModPermission for AppealModPermission {
+    fn permission() -> Permissions {
+        Permissions::BAN_MEMBERS
+    }
+}
+
+#[derive(Deserialize)]
+pub struct AppealTokenParam {
+    token: String,
+}
+
+// /appeal/:token
+pub async fn get_appeal_page(_path: Path<AppealTokenParam>) -> Html<&'static str> {
+    Html(APPEAL_TEMPLATE)
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct AppealFormModel {
+    message: String,
+}
+
+// /appeal/:token
+pub async fn submit_appeal(
+    State(services): State<Arc<Services>>,
+    Path(AppealTokenParam { token }): Path<AppealTokenParam>,
+    Form(form): Form<AppealFormModel>,
+) -> Result<Json<&'static str>, Response> {
+    let appeal_service =
+        extract_service::<AppealService>(&services).map_err(|err| err.into_response())?;
+
+    let target = if let Some(target) = appeal_service.verify_appeal_token(&token) {
+        target
+    } else {
+        return Err((
+            StatusCode::FORBIDDEN,
+            Json("This appeal link is invalid or has expired!"),
+        )
+            .into_response());
+    };
+
+    let message = form.message.trim();
+    if message.is_empty() {
+        return Err((StatusCode::BAD_REQUEST, Json("Appeal can't be empty!")).into_response());
+    }
+
+    if message.chars().count() > MAX_APPEAL_MESSAGE_LENGTH {
+        return Err((StatusCode::BAD_REQUEST, Json("Appeal is too long!")).into_response());
+    }
+
+    let setting_service =
+        extract_service::<SettingService>(&services).map_err(|err| err.into_response())?;
+    let setting = setting_service.get_setting(target.guild_id).await;
+
+    if setting.appeals_channel_id == 0 {
+        return Err((
+            StatusCode::FORBIDDEN,
+            Json("This server doesn't accept appeals anymore!"),
+        )
+            .into_response());
+    }
+
+    let infraction =
+        match fetch_infraction_with_case_number(&services, target.guild_id, target.case_number)
+            .await
+        {
+            Ok(infraction) => infraction,
+            Err(err) => {
+                error!("failed to fetch infraction for appeal {:?}", err);
+                return Err(StatusCode::INTERNAL_SERVER_ERROR.into_response());
+            }
+        };
+
+    let infraction_type = match infraction {
+        Some(Infraction::Ban(ban))
+            if ban.user_id == target.user_id.get() as i64 && !ban.unbanned =>
+        {
+            ACTION_BAN
+        }
+        Some(Infraction::Mute(mute))
+            if mute.user_id == target.user_id.get() as i64 && !mute.unmuted =>
+        {
+            ACTION_MUTE
+        }
+        _ => {
+            return Err((
+                StatusCode::BAD_REQUEST,
+                Json("This infraction has already been lifted!"),
+            )
+                .into_response());
+        }
+    };
+
+    if appeal_service
+        .fetch_guild_appeal_with_case_number(target.guild_id, target.case_number)
+        .await
+        .is_some()
+    {
+        return Err((
+            StatusCode::CONFLICT,
+            Json("You have already appealed this infraction!"),
+        )
+            .into_response());
+    }
+
+    let mut appeal = if let Some(appeal) = appeal_service
+        .insert_appeal(Appeal {
+            id: 0,
+            guild_id: target.guild_id.get() as i64,
+            user_id: target.user_id.get() as i64,
+            case_number: target.case_number,
+            infraction_type,
+            message: message.to_string(),
+            appeal_time: now() as i64,
+            status: APPEAL_STATUS_PENDING,
+            reviewer_user_id: 0,
+            review_time: 0,
+            review_message_id: 0,
+        })
+        .await
+    {
+        appeal
+    } else {
+        return Err(StatusCode::INTERNAL_SERVER_ERROR.into_response());
+    };
+
+    let guild_service =
+        extract_service::<GuildService>(&services).map_err(|err| err.into_response())?;
+
+    // appeal is saved either way, moderators can still see it on the dashboard
+    if let Some(message_id) =
+        post_appeal_for_review(&guild_service.http().await, &setting, &appeal).await
+    {
+        appeal.review_message_id = message_id.get() as i64;
+        appeal_service.update_appeal(&appeal).await;
+    }
+
+    Ok(Json(
+        "Your appeal has been sent to the moderators! You can close this window.",
+    ))
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GetAppealsResponse {
+    current_page: u32,
+    total_pages: u32,
+    entries: Vec<AppealModel>,
+}
+
+// /guilds/:guild_id/appeals
+pub async fn get_appeals(
+    State(services): State<Arc<Services>>,
+    Query(mod_log_params): Query<ModLogPaginationParams>,
+    ModLogEndpointParams(guild_id, show_moderator, _): ModLogEndpointParams<AppealModPermission>,
+) -> Result<Json<GetAppealsResponse>, StatusCode> {
+    let appeal_service = extract_service::<AppealService>(&services).map_err(|err| err.0)?;
+
+    let mut appeals = vec![];
+    let fetched_appeals = appeal_service
+        .fetch_guild_appeals(guild_id, mod_log_params.page)
+        .await;
+    for appeal in fetched_appeals {
+        appeals.push(AppealModel::from_appeal(&services, &appeal, show_moderator).await);
+    }
+
+    let page_count = appeal_service.fetch_guild_appeal_count(guild_id).await / 10 + 1;
+
+    Ok(Json(GetAppealsResponse {
+        current_page: mod_log_params.page.get(),
+        total_pages: page_count as u32,
+        entries: appeals,
+    }))
+}
+
+// /guilds/:guild_id/appeals/:case_number
+pub async fn get_appeal(
+    State(services): State<Arc<Services>>,
+    ModLogEndpointParams(guild_id, show_moderator, _): ModLogEndpointParams<AppealModPermission>,
+    Path(CaseNumberParam { case_number }): Path<CaseNumberParam>,
+) -> Result<Json<AppealModel>, Response> {
+    let appeal_service =
+        extract_service::<AppealService>(&services).map_err(|err| err.into_response())?;
+
+    let appeal = if let Some(appeal) = appeal_service
+        .fetch_guild_appeal_with_case_number(guild_id, case_number)
+        .await
+    {
+        appeal
+    } else {
+        return Err((
+            StatusCode::NOT_FOUND,
+            Json("Appeal for given case number doesn't exist!"),
+        )
+            .into_response());
+    };
+
+    Ok(Json(
+        AppealModel::from_appeal(&services, &appeal, show_moderator).await,
+    ))
+}
@@ -7,11 +7,16 @@ use axum::Json;
 use serde::{Deserialize, Serialize};
 use serenity::all::Permissions;
 
-use crate::server::endpoint::ModLogPaginationParams;
+use crate::discord::util::infraction::Infraction;
+use crate::server::endpoint::{
+    describe_expiration_change, log_infraction_changes, CaseNumberParam, ModLogPaginationParams,
+};
 use crate::server::model::ban::BanModel;
-use crate::server::{extract_service, ModLogEndpointParams, ModPermission};
-use crate::service::ban::BanService;
+use crate::server::model::evidence::EvidenceModel;
+use crate::server::{extract_service, ModLogEndpointParams, ModPermission, User};
+use crate::service::ban::{validate_ban_update, BanService};
 use crate::service::Services;
+use crate::util::normalize_reason;
 
 pub struct BanModPermission;
 
@@ -33,7 +38,7 @@ pub struct GetBansResponse {
 pub async fn get_bans(
     State(services): State<Arc<Services>>,
     Query(mod_log_params): Query<ModLogPaginationParams>,
-    ModLogEndpointParams(guild_id, _): ModLogEndpointParams<BanModPermission>,
+    ModLogEndpointParams(guild_id, show_moderator, _): ModLogEndpointParams<BanModPermission>,
 ) -> Result<Json<GetBansResponse>, StatusCode> {
     let ban_service = extract_service::<BanService>(&services).map_err(|err| err.0)?;
 
@@ -41,8 +46,14 @@ pub async fn get_bans(
     let fetched_bans = ban_service
         .fetch_guild_bans(guild_id, mod_log_params.page)
         .await;
+    let case_numbers = fetched_bans
+        .iter()
+        .map(|ban| ban.case_number)
+        .collect::<Vec<i32>>();
+    let mut evidence = EvidenceModel::from_cases(&services, guild_id, &case_numbers).await;
     for ban in fetched_bans {
-        bans.push(BanModel::from_ban(&services, &ban).await);
+        let ban_evidence = evidence.remove(&ban.case_number).unwrap_or_default();
+        bans.push(BanModel::from_ban(&services, &ban, show_moderator, ban_evidence).await);
     }
 
     let page_count = ban_service.fetch_guild_ban_count(guild_id).await / 10 + 1;
@@ -54,77 +65,63 @@ pub async fn get_bans(
     }))
 }
 
-#[derive(Deserialize)]
-pub struct BanIdParam {
-    pub ban_id: i32,
-}
-
-// /guilds/:guild_id/bans/:ban_id
+// /guilds/:guild_id/bans/:case_number
 pub async fn get_ban(
     State(services): State<Arc<Services>>,
-    ModLogEndpointParams(guild_id, _): ModLogEndpointParams<BanModPermission>,
-    Path(BanIdParam { ban_id }): Path<BanIdParam>,
+    ModLogEndpointParams(guild_id, show_moderator, _): ModLogEndpointParams<BanModPermission>,
+    Path(CaseNumberParam { case_number }): Path<CaseNumberParam>,
 ) -> Result<Json<BanModel>, Response> {
     let ban_service =
         extract_service::<BanService>(&services).map_err(|err| err.into_response())?;
 
-    let ban = if let Some(ban) = ban_service.fetch_ban(ban_id).await {
+    let ban = if let Some(ban) = ban_service
+        .fetch_guild_ban_with_case_number(guild_id, case_number)
+        .await
+    {
         ban
     } else {
         return Err((
             StatusCode::NOT_FOUND,
-            Json("Ban with given id doesn't exist!"),
+            Json("Ban with given case number doesn't exist!"),
         )
             .into_response());
     };
 
-    if ban.guild_id != guild_id.get() as i64 {
-        return Err((
-            StatusCode::FORBIDDEN,
-            Json("Given ban id doesn't belong to your guild!"),
-        )
-            .into_response());
-    }
-
-    let ban_model = BanModel::from_ban(&services, &ban).await;
+    let evidence = EvidenceModel::from_case(&services, guild_id, ban.case_number).await;
+    let ban_model = BanModel::from_ban(&services, &ban, show_moderator, evidence).await;
 
     Ok(Json(ban_model))
 }
 
-// /guilds/:guild_id/bans/:ban_id
+// /guilds/:guild_id/bans/:case_number
 pub async fn update_ban(
     State(services): State<Arc<Services>>,
-    ModLogEndpointParams(guild_id, _): ModLogEndpointParams<BanModPermission>,
-    Path(BanIdParam { ban_id }): Path<BanIdParam>,
-    Json(mut new_ban): Json<BanModel>,
+    ModLogEndpointParams(guild_id, show_moderator, _): ModLogEndpointParams<BanModPermission>,
+    User(user_id): User,
+    Path(CaseNumberParam { case_number }): Path<CaseNumberParam>,
+    Json(new_ban): Json<BanModel>,
 ) -> Result<(), Response> {
-    new_ban.reason = new_ban.reason.trim().to_string();
-
     let ban_service =
         extract_service::<BanService>(&services).map_err(|err| err.into_response())?;
 
-    let mut ban = if let Some(ban) = ban_service.fetch_ban(ban_id).await {
+    let mut ban = if let Some(ban) = ban_service
+        .fetch_guild_ban_with_case_number(guild_id, case_number)
+        .await
+    {
         ban
     } else {
         return Err((
             StatusCode::NOT_FOUND,
-            Json("Ban with given id doesn't exist!"),
+            Json("Ban with given case number doesn't exist!"),
         )
             .into_response());
     };
 
-    if ban.guild_id != guild_id.get() as i64 {
-        return Err((
-            StatusCode::FORBIDDEN,
-            Json("Given ban id doesn't belong to your guild!"),
-        )
-            .into_response());
-    }
-
     if ban.id != new_ban.id
+        || ban.case_number != new_ban.case_number
         || ban.user_id.to_string() != new_ban.user.id
         || ban.ban_time != new_ban.action_time
-        || ban.moderator_user_id.to_string() != new_ban.moderator_user.id
+        || (show_moderator && ban.moderator_user_id.to_string() != new_ban.moderator_user.id)
     {
         return Err((
             StatusCode::BAD_REQUEST,
@@ -133,30 +130,37 @@ pub async fn update_ban(
             .into_response());
     }
 
-    if ban.unbanned {
-        // expired
-        if !new_ban.unbanned || // un-expiring the ban
-            ban.expire_time != new_ban.expiration_time
-        {
-            // changing expiration time
-            return Err((
-                StatusCode::BAD_REQUEST,
-                Json("You can't change expiration property after user has been unbanned."),
-            )
-                .into_response());
-        }
+    if let Err(message) = validate_ban_update(&ban, new_ban.unbanned, new_ban.expiration_time) {
+        return Err((StatusCode::BAD_REQUEST, Json(message)).into_response());
+    }
+
+    let reason = normalize_reason(&new_ban.reason);
+    let mut changes = vec![];
+    if ban.reason != reason {
+        changes.push(format!("Reason changed to: {}", reason));
+    }
+    if ban.expire_time != new_ban.expiration_time {
+        changes.push(describe_expiration_change(new_ban.expiration_time));
+    }
+    if !ban.unbanned && new_ban.unbanned {
+        changes.push("Unbanned".to_string());
     }
 
     ban.expire_time = new_ban.expiration_time;
     ban.expires = new_ban.expiration_time != 0;
     ban.unbanned = new_ban.unbanned;
-    ban.reason = if new_ban.reason.is_empty() {
-        "No reason specified".to_string()
-    } else {
-        new_ban.reason.clone()
-    };
-
-    ban_service.update_ban(ban).await;
+    ban.reason = reason;
+
+    if ban_service.update_ban(ban.clone()).await.is_some() {
+        log_infraction_changes(
+            &services,
+            guild_id,
+            user_id,
+            &Infraction::Ban(ban),
+            &changes,
+        )
+        .await;
+    }
 
     Ok(())
 }
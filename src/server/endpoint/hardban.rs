@@ -7,11 +7,14 @@ use axum::Json;
 use serde::{Deserialize, Serialize};
 use serenity::all::Permissions;
 
-use crate::server::endpoint::ModLogPaginationParams;
+use crate::discord::util::infraction::Infraction;
+use crate::server::endpoint::{log_infraction_changes, CaseNumberParam, ModLogPaginationParams};
+use crate::server::model::evidence::EvidenceModel;
 use crate::server::model::hardban::HardbanModel;
-use crate::server::{extract_service, ModLogEndpointParams, ModPermission};
+use crate::server::{extract_service, ModLogEndpointParams, ModPermission, User};
 use crate::service::hardban::HardbanService;
 use crate::service::Services;
+use crate::util::normalize_reason;
 
 pub struct HardbanModPermission;
 
@@ -33,7 +36,7 @@ pub struct GetHardbansResponse {
 pub async fn get_hardbans(
     State(services): State<Arc<Services>>,
     Query(mod_log_params): Query<ModLogPaginationParams>,
-    ModLogEndpointParams(guild_id, _): ModLogEndpointParams<HardbanModPermission>,
+    ModLogEndpointParams(guild_id, show_moderator, _): ModLogEndpointParams<HardbanModPermission>,
 ) -> Result<Json<GetHardbansResponse>, StatusCode> {
     let hardban_service = extract_service::<HardbanService>(&services).map_err(|err| err.0)?;
 
@@ -41,8 +44,16 @@ pub async fn get_hardbans(
     let fetched_hardbans = hardban_service
         .fetch_guild_hardbans(guild_id, mod_log_params.page)
         .await;
+    let case_numbers = fetched_hardbans
+        .iter()
+        .map(|hardban| hardban.case_number)
+        .collect::<Vec<i32>>();
+    let mut evidence = EvidenceModel::from_cases(&services, guild_id, &case_numbers).await;
     for hardban in fetched_hardbans {
-        hardbans.push(HardbanModel::from_hardban(&services, &hardban).await);
+        let hardban_evidence = evidence.remove(&hardban.case_number).unwrap_or_default();
+        hardbans.push(
+            HardbanModel::from_hardban(&services, &hardban, show_moderator, hardban_evidence).await,
+        );
     }
 
     let page_count = hardban_service.fetch_guild_hardban_count(guild_id).await / 10 + 1;
@@ -54,77 +65,65 @@ pub async fn get_hardbans(
     }))
 }
 
-#[derive(Deserialize)]
-pub struct HardbanIdParam {
-    pub hardban_id: i32,
-}
-
-// /guilds/:guild_id/hardbans/:hardban_id
+// /guilds/:guild_id/hardbans/:case_number
 pub async fn get_hardban(
     State(services): State<Arc<Services>>,
-    ModLogEndpointParams(guild_id, _): ModLogEndpointParams<HardbanModPermission>,
-    Path(HardbanIdParam { hardban_id }): Path<HardbanIdParam>,
+    ModLogEndpointParams(guild_id, show_moderator, _): ModLogEndpointParams<HardbanModPermission>,
+    Path(CaseNumberParam { case_number }): Path<CaseNumberParam>,
 ) -> Result<Json<HardbanModel>, Response> {
     let hardban_service =
         extract_service::<HardbanService>(&services).map_err(|err| err.into_response())?;
 
-    let hardban = if let Some(hardban) = hardban_service.fetch_hardban(hardban_id).await {
+    let hardban = if let Some(hardban) = hardban_service
+        .fetch_guild_hardban_with_case_number(guild_id, case_number)
+        .await
+    {
         hardban
     } else {
         return Err((
             StatusCode::NOT_FOUND,
-            Json("Hardban with given id doesn't exist!"),
+            Json("Hardban with given case number doesn't exist!"),
         )
             .into_response());
     };
 
-    if hardban.guild_id != guild_id.get() as i64 {
-        return Err((
-            StatusCode::FORBIDDEN,
-            Json("Given hardban id doesn't belong to your guild!"),
-        )
-            .into_response());
-    }
-
-    let hardban_model = HardbanModel::from_hardban(&services, &hardban).await;
+    let evidence = EvidenceModel::from_case(&services, guild_id, hardban.case_number).await;
+    let hardban_model =
+        HardbanModel::from_hardban(&services, &hardban, show_moderator, evidence).await;
 
     Ok(Json(hardban_model))
 }
 
-// /guilds/:guild_id/hardbans/:hardban_id
+// /guilds/:guild_id/hardbans/:case_number
 pub async fn update_hardban(
     State(services): State<Arc<Services>>,
-    ModLogEndpointParams(guild_id, _): ModLogEndpointParams<HardbanModPermission>,
-    Path(HardbanIdParam { hardban_id }): Path<HardbanIdParam>,
-    Json(mut new_hardban): Json<HardbanModel>,
+    ModLogEndpointParams(guild_id, show_moderator, _): ModLogEndpointParams<HardbanModPermission>,
+    User(user_id): User,
+    Path(CaseNumberParam { case_number }): Path<CaseNumberParam>,
+    Json(new_hardban): Json<HardbanModel>,
 ) -> Result<(), Response> {
-    new_hardban.reason = new_hardban.reason.trim().to_string();
-
     let hardban_service =
         extract_service::<HardbanService>(&services).map_err(|err| err.into_response())?;
 
-    let mut hardban = if let Some(hardban) = hardban_service.fetch_hardban(hardban_id).await {
+    let mut hardban = if let Some(hardban) = hardban_service
+        .fetch_guild_hardban_with_case_number(guild_id, case_number)
+        .await
+    {
         hardban
     } else {
         return Err((
             StatusCode::NOT_FOUND,
-            Json("Hardban with given id doesn't exist!"),
+            Json("Hardban with given case number doesn't exist!"),
         )
             .into_response());
     };
 
-    if hardban.guild_id != guild_id.get() as i64 {
-        return Err((
-            StatusCode::FORBIDDEN,
-            Json("Given hardban id doesn't belong to your guild!"),
-        )
-            .into_response());
-    }
-
     if hardban.id != new_hardban.id
+        || hardban.case_number != new_hardban.case_number
         || hardban.user_id.to_string() != new_hardban.user.id
         || hardban.hardban_time != new_hardban.action_time
-        || hardban.moderator_user_id.to_string() != new_hardban.moderator_user.id
+        || (show_moderator
+            && hardban.moderator_user_id.to_string() != new_hardban.moderator_user.id)
     {
         return Err((
             StatusCode::BAD_REQUEST,
@@ -133,13 +132,28 @@ pub async fn update_hardban(
             .into_response());
     }
 
-    hardban.reason = if new_hardban.reason.is_empty() {
-        "No reason specified".to_string()
-    } else {
-        new_hardban.reason.clone()
-    };
+    let reason = normalize_reason(&new_hardban.reason);
+    let mut changes = vec![];
+    if hardban.reason != reason {
+        changes.push(format!("Reason changed to: {}", reason));
+    }
 
-    hardban_service.update_hardban(hardban).await;
+    hardban.reason = reason;
+
+    if hardban_service
+        .update_hardban(hardban.clone())
+        .await
+        .is_some()
+    {
+        log_infraction_changes(
+            &services,
+            guild_id,
+            user_id,
+            &Infraction::Hardban(hardban),
+            &changes,
+        )
+        .await;
+    }
 
     Ok(())
 }
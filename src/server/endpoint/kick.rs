@@ -7,11 +7,14 @@ use axum::Json;
 use serde::{Deserialize, Serialize};
 use serenity::all::Permissions;
 
-use crate::server::endpoint::ModLogPaginationParams;
+use crate::discord::util::infraction::Infraction;
+use crate::server::endpoint::{log_infraction_changes, CaseNumberParam, ModLogPaginationParams};
+use crate::server::model::evidence::EvidenceModel;
 use crate::server::model::kick::KickModel;
-use crate::server::{extract_service, ModLogEndpointParams, ModPermission};
-use crate::service::kick::KickService;
+use crate::server::{extract_service, ModLogEndpointParams, ModPermission, User};
+use crate::service::kick::{validate_kick_update, KickService};
 use crate::service::Services;
+use crate::util::normalize_reason;
 
 pub struct KickModPermission;
 
@@ -33,7 +36,7 @@ pub struct GetKicksResponse {
 pub async fn get_kicks(
     State(services): State<Arc<Services>>,
     Query(mod_log_params): Query<ModLogPaginationParams>,
-    ModLogEndpointParams(guild_id, _): ModLogEndpointParams<KickModPermission>,
+    ModLogEndpointParams(guild_id, show_moderator, _): ModLogEndpointParams<KickModPermission>,
 ) -> Result<Json<GetKicksResponse>, StatusCode> {
     let kick_service = extract_service::<KickService>(&services).map_err(|err| err.0)?;
 
@@ -41,8 +44,14 @@ pub async fn get_kicks(
     let fetched_kicks = kick_service
         .fetch_guild_kicks(guild_id, mod_log_params.page)
         .await;
+    let case_numbers = fetched_kicks
+        .iter()
+        .map(|kick| kick.case_number)
+        .collect::<Vec<i32>>();
+    let mut evidence = EvidenceModel::from_cases(&services, guild_id, &case_numbers).await;
     for kick in fetched_kicks {
-        kicks.push(KickModel::from_kick(&services, &kick).await);
+        let kick_evidence = evidence.remove(&kick.case_number).unwrap_or_default();
+        kicks.push(KickModel::from_kick(&services, &kick, show_moderator, kick_evidence).await);
     }
 
     let page_count = kick_service.fetch_guild_kick_count(guild_id).await / 10 + 1;
@@ -54,77 +63,63 @@ pub async fn get_kicks(
     }))
 }
 
-#[derive(Deserialize)]
-pub struct KickIdParam {
-    pub kick_id: i32,
-}
-
-// /guilds/:guild_id/kicks/:kick_id
+// /guilds/:guild_id/kicks/:case_number
 pub async fn get_kick(
     State(services): State<Arc<Services>>,
-    ModLogEndpointParams(guild_id, _): ModLogEndpointParams<KickModPermission>,
-    Path(KickIdParam { kick_id }): Path<KickIdParam>,
+    ModLogEndpointParams(guild_id, show_moderator, _): ModLogEndpointParams<KickModPermission>,
+    Path(CaseNumberParam { case_number }): Path<CaseNumberParam>,
 ) -> Result<Json<KickModel>, Response> {
     let kick_service =
         extract_service::<KickService>(&services).map_err(|err| err.into_response())?;
 
-    let kick = if let Some(kick) = kick_service.fetch_kick(kick_id).await {
+    let kick = if let Some(kick) = kick_service
+        .fetch_guild_kick_with_case_number(guild_id, case_number)
+        .await
+    {
         kick
     } else {
         return Err((
             StatusCode::NOT_FOUND,
-            Json("Kick with given id doesn't exist!"),
+            Json("Kick with given case number doesn't exist!"),
         )
             .into_response());
     };
 
-    if kick.guild_id != guild_id.get() as i64 {
-        return Err((
-            StatusCode::FORBIDDEN,
-            Json("Given kick id doesn't belong to your guild!"),
-        )
-            .into_response());
-    }
-
-    let kick_model = KickModel::from_kick(&services, &kick).await;
+    let evidence = EvidenceModel::from_case(&services, guild_id, kick.case_number).await;
+    let kick_model = KickModel::from_kick(&services, &kick, show_moderator, evidence).await;
 
     Ok(Json(kick_model))
 }
 
-// /guilds/:guild_id/kicks/:kick_id
+// /guilds/:guild_id/kicks/:case_number
 pub async fn update_kick(
     State(services): State<Arc<Services>>,
-    ModLogEndpointParams(guild_id, _): ModLogEndpointParams<KickModPermission>,
-    Path(KickIdParam { kick_id }): Path<KickIdParam>,
-    Json(mut new_kick): Json<KickModel>,
+    ModLogEndpointParams(guild_id, show_moderator, _): ModLogEndpointParams<KickModPermission>,
+    User(user_id): User,
+    Path(CaseNumberParam { case_number }): Path<CaseNumberParam>,
+    Json(new_kick): Json<KickModel>,
 ) -> Result<(), Response> {
-    new_kick.reason = new_kick.reason.trim().to_string();
-
     let kick_service =
         extract_service::<KickService>(&services).map_err(|err| err.into_response())?;
 
-    let mut kick = if let Some(kick) = kick_service.fetch_kick(kick_id).await {
+    let mut kick = if let Some(kick) = kick_service
+        .fetch_guild_kick_with_case_number(guild_id, case_number)
+        .await
+    {
         kick
     } else {
         return Err((
             StatusCode::NOT_FOUND,
-            Json("Kick with given id doesn't exist!"),
+            Json("Kick with given case number doesn't exist!"),
         )
             .into_response());
     };
 
-    if kick.guild_id != guild_id.get() as i64 {
-        return Err((
-            StatusCode::FORBIDDEN,
-            Json("Given kick id doesn't belong to your guild!"),
-        )
-            .into_response());
-    }
-
     if kick.id != new_kick.id
+        || kick.case_number != new_kick.case_number
         || kick.user_id.to_string() != new_kick.user.id
         || kick.kick_time != new_kick.action_time
-        || kick.moderator_user_id.to_string() != new_kick.moderator_user.id
+        || (show_moderator && kick.moderator_user_id.to_string() != new_kick.moderator_user.id)
     {
         return Err((
             StatusCode::BAD_REQUEST,
@@ -133,18 +128,32 @@ pub async fn update_kick(
             .into_response());
     }
 
-    if kick.pardoned && !new_kick.pardoned {
-        return Err((StatusCode::BAD_REQUEST, Json("You can't un-pardon a kick!")).into_response());
+    if let Err(message) = validate_kick_update(&kick, new_kick.pardoned) {
+        return Err((StatusCode::BAD_REQUEST, Json(message)).into_response());
     }
 
-    kick.reason = if new_kick.reason.is_empty() {
-        "No reason specified".to_string()
-    } else {
-        new_kick.reason.clone()
-    };
+    let reason = normalize_reason(&new_kick.reason);
+    let mut changes = vec![];
+    if kick.reason != reason {
+        changes.push(format!("Reason changed to: {}", reason));
+    }
+    if !kick.pardoned && new_kick.pardoned {
+        changes.push("Pardoned".to_string());
+    }
+
+    kick.reason = reason;
     kick.pardoned = new_kick.pardoned;
 
-    kick_service.update_kick(kick).await;
+    if kick_service.update_kick(kick.clone()).await.is_some() {
+        log_infraction_changes(
+            &services,
+            guild_id,
+            user_id,
+            &Infraction::Kick(kick),
+            &changes,
+        )
+        .await;
+    }
 
     Ok(())
 }
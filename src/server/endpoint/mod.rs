@@ -1,19 +1,29 @@
 use std::num::NonZeroU32;
 
 use serde::Deserialize;
+use serenity::all::{GuildId, UserId};
+use tracing::error;
 
+use crate::discord::util::infraction::{log_infraction_update, Infraction};
+use crate::service::guild::GuildService;
+use crate::service::setting::SettingService;
+use crate::service::Services;
+
+pub mod appeal;
 pub mod ban;
 pub mod captcha;
 pub mod hardban;
 pub mod kick;
 pub mod login;
 pub mod mute;
+pub mod note;
+pub mod report;
 pub mod self_user;
 pub mod settings;
 pub mod softban;
 pub mod warn;
 
-fn default_page() -> NonZeroU32 {
+pub fn default_page() -> NonZeroU32 {
     NonZeroU32::new(1).unwrap()
 }
 
@@ -22,3 +32,58 @@ pub struct ModLogPaginationParams {
     #[serde(default = "default_page")]
     pub page: NonZeroU32,
 }
+
+#[derive(Deserialize)]
+pub struct CaseNumberParam {
+    pub case_number: i32,
+}
+
+pub fn describe_expiration_change(expire_time: i64) -> String {
+    if expire_time == 0 {
+        "Expiration removed".to_string()
+    } else {
+        format!("Expiration changed to <t:{}>", expire_time)
+    }
+}
+
+// edits made on the dashboard are reflected on the mod log entry of the infraction too
+pub async fn log_infraction_changes(
+    services: &Services,
+    guild_id: GuildId,
+    user_id: UserId,
+    infraction: &Infraction,
+    changes: &[String],
+) {
+    if changes.is_empty() {
+        return;
+    }
+
+    let setting_service = if let Some(service) = services.get::<SettingService>() {
+        service
+    } else {
+        error!("couldn't get setting service!");
+        return;
+    };
+
+    let guild_service = if let Some(service) = services.get::<GuildService>() {
+        service
+    } else {
+        error!("couldn't get guild service!");
+        return;
+    };
+
+    let setting = setting_service.get_setting(guild_id).await;
+    let mod_user_tag_and_id = match guild_service.get_user(user_id).await {
+        Ok(user) => format!("{} ({})", user.tag, user_id.get()),
+        Err(_) => user_id.get().to_string(),
+    };
+
+    let _ = log_infraction_update(
+        &guild_service.http().await,
+        &setting,
+        &mod_user_tag_and_id,
+        infraction,
+        &changes.join("\n"),
+    )
+    .await;
+}
@@ -7,11 +7,16 @@ use axum::Json;
 use serde::{Deserialize, Serialize};
 use serenity::all::Permissions;
 
-use crate::server::endpoint::ModLogPaginationParams;
+use crate::discord::util::infraction::Infraction;
+use crate::server::endpoint::{
+    describe_expiration_change, log_infraction_changes, CaseNumberParam, ModLogPaginationParams,
+};
+use crate::server::model::evidence::EvidenceModel;
 use crate::server::model::mute::MuteModel;
-use crate::server::{extract_service, ModLogEndpointParams, ModPermission};
-use crate::service::mute::MuteService;
+use crate::server::{extract_service, ModLogEndpointParams, ModPermission, User};
+use crate::service::mute::{validate_mute_update, MuteService};
 use crate::service::Services;
+use crate::util::normalize_reason;
 
 pub struct MuteModPermission;
 
@@ -33,7 +38,7 @@ pub struct GetMutesResponse {
 pub async fn get_mutes(
     State(services): State<Arc<Services>>,
     Query(mod_log_params): Query<ModLogPaginationParams>,
-    ModLogEndpointParams(guild_id, _): ModLogEndpointParams<MuteModPermission>,
+    ModLogEndpointParams(guild_id, show_moderator, _): ModLogEndpointParams<MuteModPermission>,
 ) -> Result<Json<GetMutesResponse>, StatusCode> {
     let mute_service = extract_service::<MuteService>(&services).map_err(|err| err.0)?;
 
@@ -41,8 +46,14 @@ pub async fn get_mutes(
     let fetched_mutes = mute_service
         .fetch_guild_mutes(guild_id, mod_log_params.page)
         .await;
+    let case_numbers = fetched_mutes
+        .iter()
+        .map(|mute| mute.case_number)
+        .collect::<Vec<i32>>();
+    let mut evidence = EvidenceModel::from_cases(&services, guild_id, &case_numbers).await;
     for mute in fetched_mutes {
-        mutes.push(MuteModel::from_mute(&services, &mute).await);
+        let mute_evidence = evidence.remove(&mute.case_number).unwrap_or_default();
+        mutes.push(MuteModel::from_mute(&services, &mute, show_moderator, mute_evidence).await);
     }
 
     let page_count = mute_service.fetch_guild_mute_count(guild_id).await / 10 + 1;
@@ -54,77 +65,63 @@ pub async fn get_mutes(
     }))
 }
 
-#[derive(Deserialize)]
-pub struct MuteIdParam {
-    pub mute_id: i32,
-}
-
-// /guilds/:guild_id/mutes/:mute_id
+// /guilds/:guild_id/mutes/:case_number
 pub async fn get_mute(
     State(services): State<Arc<Services>>,
-    ModLogEndpointParams(guild_id, _): ModLogEndpointParams<MuteModPermission>,
-    Path(MuteIdParam { mute_id }): Path<MuteIdParam>,
+    ModLogEndpointParams(guild_id, show_moderator, _): ModLogEndpointParams<MuteModPermission>,
+    Path(CaseNumberParam { case_number }): Path<CaseNumberParam>,
 ) -> Result<Json<MuteModel>, Response> {
     let mute_service =
         extract_service::<MuteService>(&services).map_err(|err| err.into_response())?;
 
-    let mute = if let Some(mute) = mute_service.fetch_mute(mute_id).await {
+    let mute = if let Some(mute) = mute_service
+        .fetch_guild_mute_with_case_number(guild_id, case_number)
+        .await
+    {
         mute
     } else {
         return Err((
             StatusCode::NOT_FOUND,
-            Json("Mute with given id doesn't exist!"),
+            Json("Mute with given case number doesn't exist!"),
         )
             .into_response());
     };
 
-    if mute.guild_id != guild_id.get() as i64 {
-        return Err((
-            StatusCode::FORBIDDEN,
-            Json("Given mute id doesn't belong to your guild!"),
-        )
-            .into_response());
-    }
-
-    let mute_model = MuteModel::from_mute(&services, &mute).await;
+    let evidence = EvidenceModel::from_case(&services, guild_id, mute.case_number).await;
+    let mute_model = MuteModel::from_mute(&services, &mute, show_moderator, evidence).await;
 
     Ok(Json(mute_model))
 }
 
-// /guilds/:guild_id/mutes/:mute_id
+// /guilds/:guild_id/mutes/:case_number
 pub async fn update_mute(
     State(services): State<Arc<Services>>,
-    ModLogEndpointParams(guild_id, _): ModLogEndpointParams<MuteModPermission>,
-    Path(MuteIdParam { mute_id }): Path<MuteIdParam>,
-    Json(mut new_mute): Json<MuteModel>,
+    ModLogEndpointParams(guild_id, show_moderator, _): ModLogEndpointParams<MuteModPermission>,
+    User(user_id): User,
+    Path(CaseNumberParam { case_number }): Path<CaseNumberParam>,
+    Json(new_mute): Json<MuteModel>,
 ) -> Result<(), Response> {
-    new_mute.reason = new_mute.reason.trim().to_string();
-
     let mute_service =
         extract_service::<MuteService>(&services).map_err(|err| err.into_response())?;
 
-    let mut mute = if let Some(mute) = mute_service.fetch_mute(mute_id).await {
+    let mut mute = if let Some(mute) = mute_service
+        .fetch_guild_mute_with_case_number(guild_id, case_number)
+        .await
+    {
         mute
     } else {
         return Err((
             StatusCode::NOT_FOUND,
-            Json("Mute with given id doesn't exist!"),
+            Json("Mute with given case number doesn't exist!"),
         )
             .into_response());
     };
 
-    if mute.guild_id != guild_id.get() as i64 {
-        return Err((
-            StatusCode::FORBIDDEN,
-            Json("Given mute id doesn't belong to your guild!"),
-        )
-            .into_response());
-    }
-
     if mute.id != new_mute.id
+        || mute.case_number != new_mute.case_number
         || mute.user_id.to_string() != new_mute.user.id
         || mute.mute_time != new_mute.action_time
-        || mute.moderator_user_id.to_string() != new_mute.moderator_user.id
+        || (show_moderator && mute.moderator_user_id.to_string() != new_mute.moderator_user.id)
     {
         return Err((
             StatusCode::BAD_REQUEST,
@@ -133,35 +130,46 @@ pub async fn update_mute(
             .into_response());
     }
 
-    if mute.unmuted {
-        // expired
-        if !new_mute.unmuted || // un-expiring the mute
-            mute.expire_time != new_mute.expiration_time
-        {
-            // changing expiration time
-            return Err((
-                StatusCode::BAD_REQUEST,
-                Json("You can't change expiration property after user has been unmuted."),
-            )
-                .into_response());
-        }
+    if let Err(message) = validate_mute_update(
+        &mute,
+        new_mute.unmuted,
+        new_mute.expiration_time,
+        new_mute.pardoned,
+    ) {
+        return Err((StatusCode::BAD_REQUEST, Json(message)).into_response());
     }
 
-    if mute.pardoned && !new_mute.pardoned {
-        return Err((StatusCode::BAD_REQUEST, Json("You can't un-pardon a mute!")).into_response());
+    let reason = normalize_reason(&new_mute.reason);
+    let mut changes = vec![];
+    if mute.reason != reason {
+        changes.push(format!("Reason changed to: {}", reason));
+    }
+    if mute.expire_time != new_mute.expiration_time {
+        changes.push(describe_expiration_change(new_mute.expiration_time));
+    }
+    if !mute.unmuted && new_mute.unmuted {
+        changes.push("Unmuted".to_string());
+    }
+    if !mute.pardoned && new_mute.pardoned {
+        changes.push("Pardoned".to_string());
     }
 
     mute.expire_time = new_mute.expiration_time;
     mute.expires = new_mute.expiration_time != 0;
     mute.unmuted = new_mute.unmuted;
-    mute.reason = if new_mute.reason.is_empty() {
-        "No reason specified".to_string()
-    } else {
-        new_mute.reason.clone()
-    };
+    mute.reason = reason;
     mute.pardoned = new_mute.pardoned;
 
-    mute_service.update_mute(mute).await;
+    if mute_service.update_mute(mute.clone()).await.is_some() {
+        log_infraction_changes(
+            &services,
+            guild_id,
+            user_id,
+            &Infraction::Mute(mute),
+            &changes,
+        )
+        .await;
+    }
 
     Ok(())
 }
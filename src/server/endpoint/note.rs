@@ -0,0 +1,123 @@
+use std::num::{NonZeroU32, NonZeroU64};
+use std::sync::Arc;
+
+use axum::extract::{Path, Query, State};
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::Json;
+use serde::{Deserialize, Serialize};
+use serenity::all::UserId;
+
+use crate::server::endpoint::default_page;
+use crate::server::model::note::{CreateNoteModel, NoteModel};
+use crate::server::{extract_service, StaffEndpointParams, User};
+use crate::service::note::{
+    InsertNoteFailure, NoteService, RemoveNoteFailure, NOTE_MESSAGE_SIZE_LIMIT,
+};
+use crate::service::Services;
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NotePaginationParams {
+    #[serde(default = "default_page")]
+    pub page: NonZeroU32,
+    pub user_id: Option<NonZeroU64>,
+}
+
+#[derive(Deserialize)]
+pub struct NoteIdParam {
+    pub id: i32,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GetNotesResponse {
+    current_page: u32,
+    total_pages: u32,
+    entries: Vec<NoteModel>,
+}
+
+// /guilds/:guild_id/notes
+pub async fn get_notes(
+    State(services): State<Arc<Services>>,
+    Query(params): Query<NotePaginationParams>,
+    StaffEndpointParams(guild_id): StaffEndpointParams,
+) -> Result<Json<GetNotesResponse>, StatusCode> {
+    let note_service = extract_service::<NoteService>(&services).map_err(|err| err.0)?;
+
+    let user_id = params.user_id.map(|id| UserId::new(id.get()));
+
+    let mut notes = vec![];
+    let fetched_notes = note_service
+        .fetch_guild_notes(guild_id, user_id, params.page)
+        .await;
+    for note in fetched_notes {
+        notes.push(NoteModel::from_note(&services, &note).await);
+    }
+
+    let page_count = note_service.fetch_guild_note_count(guild_id, user_id).await / 10 + 1;
+
+    Ok(Json(GetNotesResponse {
+        current_page: params.page.get(),
+        total_pages: page_count as u32,
+        entries: notes,
+    }))
+}
+
+// /guilds/:guild_id/notes
+pub async fn create_note(
+    State(services): State<Arc<Services>>,
+    StaffEndpointParams(guild_id): StaffEndpointParams,
+    User(mod_user_id): User,
+    Json(new_note): Json<CreateNoteModel>,
+) -> Result<Json<NoteModel>, Response> {
+    let note_service =
+        extract_service::<NoteService>(&services).map_err(|err| err.into_response())?;
+
+    let user_id = if let Ok(id) = new_note.user_id.parse::<NonZeroU64>() {
+        UserId::new(id.get())
+    } else {
+        return Err((StatusCode::BAD_REQUEST, Json("Invalid user id!")).into_response());
+    };
+
+    match note_service
+        .insert_note(guild_id, user_id, mod_user_id, &new_note.message)
+        .await
+    {
+        Ok(note) => Ok(Json(NoteModel::from_note(&services, &note).await)),
+        Err(InsertNoteFailure::MessageEmpty) => Err((
+            StatusCode::BAD_REQUEST,
+            Json("Note content can't be empty!"),
+        )
+            .into_response()),
+        Err(InsertNoteFailure::MessageTooBig) => Err((
+            StatusCode::BAD_REQUEST,
+            Json(format!(
+                "Note content can't be longer than {} characters!",
+                NOTE_MESSAGE_SIZE_LIMIT
+            )),
+        )
+            .into_response()),
+        Err(InsertNoteFailure::Unknown) => Err(StatusCode::INTERNAL_SERVER_ERROR.into_response()),
+    }
+}
+
+// /guilds/:guild_id/notes/:id
+pub async fn delete_note(
+    State(services): State<Arc<Services>>,
+    StaffEndpointParams(guild_id): StaffEndpointParams,
+    Path(NoteIdParam { id }): Path<NoteIdParam>,
+) -> Result<(), Response> {
+    let note_service =
+        extract_service::<NoteService>(&services).map_err(|err| err.into_response())?;
+
+    match note_service.remove_note(guild_id, id).await {
+        Ok(_) => Ok(()),
+        Err(RemoveNoteFailure::NoteDoesNotExist) => Err((
+            StatusCode::NOT_FOUND,
+            Json("Note with given id doesn't exist!"),
+        )
+            .into_response()),
+        Err(RemoveNoteFailure::Unknown) => Err(StatusCode::INTERNAL_SERVER_ERROR.into_response()),
+    }
+}
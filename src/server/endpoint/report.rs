@@ -0,0 +1,91 @@
+use std::num::{NonZeroU32, NonZeroU64};
+use std::sync::Arc;
+
+use axum::extract::{Path, Query, State};
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::Json;
+use serde::{Deserialize, Serialize};
+use serenity::all::UserId;
+
+use crate::server::endpoint::default_page;
+use crate::server::model::report::ReportModel;
+use crate::server::{extract_service, StaffEndpointParams};
+use crate::service::report::ReportService;
+use crate::service::Services;
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReportPaginationParams {
+    #[serde(default = "default_page")]
+    pub page: NonZeroU32,
+    pub status: Option<i32>,
+    pub target_user_id: Option<NonZeroU64>,
+}
+
+#[derive(Deserialize)]
+pub struct ReportIdParam {
+    pub id: i32,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GetReportsResponse {
+    current_page: u32,
+    total_pages: u32,
+    entries: Vec<ReportModel>,
+}
+
+// /guilds/:guild_id/reports
+pub async fn get_reports(
+    State(services): State<Arc<Services>>,
+    Query(params): Query<ReportPaginationParams>,
+    StaffEndpointParams(guild_id): StaffEndpointParams,
+) -> Result<Json<GetReportsResponse>, StatusCode> {
+    let report_service = extract_service::<ReportService>(&services).map_err(|err| err.0)?;
+
+    let target_user_id = params.target_user_id.map(|id| UserId::new(id.get()));
+
+    let mut reports = vec![];
+    let fetched_reports = report_service
+        .fetch_guild_reports(guild_id, params.status, target_user_id, params.page)
+        .await;
+    for report in fetched_reports {
+        reports.push(ReportModel::from_report(&services, &report).await);
+    }
+
+    let page_count = report_service
+        .fetch_guild_report_count(guild_id, params.status, target_user_id)
+        .await
+        / 10
+        + 1;
+
+    Ok(Json(GetReportsResponse {
+        current_page: params.page.get(),
+        total_pages: page_count as u32,
+        entries: reports,
+    }))
+}
+
+// /guilds/:guild_id/reports/:id
+pub async fn get_report(
+    State(services): State<Arc<Services>>,
+    StaffEndpointParams(guild_id): StaffEndpointParams,
+    Path(ReportIdParam { id }): Path<ReportIdParam>,
+) -> Result<Json<ReportModel>, Response> {
+    let report_service =
+        extract_service::<ReportService>(&services).map_err(|err| err.into_response())?;
+
+    let report = match report_service.fetch_report(id).await {
+        Some(report) if report.guild_id == guild_id.get() as i64 => report,
+        _ => {
+            return Err((
+                StatusCode::NOT_FOUND,
+                Json("Report with given id doesn't exist!"),
+            )
+                .into_response());
+        }
+    };
+
+    Ok(Json(ReportModel::from_report(&services, &report).await))
+}
@@ -1,3 +1,4 @@
+use std::collections::HashMap;
 use std::num::NonZeroU64;
 use std::sync::Arc;
 
@@ -8,21 +9,44 @@ use axum::response::{IntoResponse, Response};
 use axum::Json;
 use serenity::all::{ChannelId, GuildId, RoleId};
 use serenity::model::Permissions;
+use tracing::{error, warn};
 
+use crate::constants::JIM_ID;
+use crate::database::escalation_steps::EscalationStep;
 use crate::database::settings::{
-    Setting, ACTION_HARDBAN, ACTION_NOTHING, DURATION_TYPE_DAYS, DURATION_TYPE_SECONDS,
-    PRIVACY_ADMIN_ONLY, PRIVACY_EVERYONE, PRIVACY_STAFF_ONLY, WORD_FILTER_LEVEL_HIGH,
-    WORD_FILTER_LEVEL_LOW,
+    get_max_duration, Setting, ACTION_BAN, ACTION_HARDBAN, ACTION_KICK, ACTION_MUTE,
+    ACTION_NOTHING, ACTION_SOFTBAN, ACTION_WARN, DURATION_TYPE_DAYS, DURATION_TYPE_SECONDS,
+    MAX_DECAY_DAYS, MUTE_BACKEND_ROLE, MUTE_BACKEND_TIMEOUT, PRIVACY_ADMIN_ONLY, PRIVACY_EVERYONE,
+    PRIVACY_STAFF_ONLY, WORD_FILTER_LEVEL_HIGH, WORD_FILTER_LEVEL_LOW,
 };
+use crate::discord::util::blocklist::validate_blocklist;
+use crate::discord::util::invite::validate_invite_allowlist;
 use crate::discord::util::is_staff;
+use crate::discord::util::links::validate_domain_list;
+use crate::discord::util::user_dm::{
+    moderator_name, render_dm_template, validate_dm_template, ModActionKind,
+};
 use crate::server::model::channel::ChannelModel;
+use crate::server::model::escalation_step::EscalationStepModel;
 use crate::server::model::guild::GuildModel;
 use crate::server::model::role::RoleModel;
-use crate::server::model::setting::SettingModel;
+use crate::server::model::setting::{
+    BlocklistEntryErrorModel, DmPreviewModel, DmTemplatePreviewModel, SettingModel,
+};
 use crate::server::{extract_service, AxumState, GuildPathParams, User};
-use crate::service::guild::GuildService;
+use crate::service::escalation::EscalationService;
+use crate::service::guild::{CachedChannel, CachedRole, GuildService};
+use crate::service::mute::{MuteFailure, MuteService};
 use crate::service::setting::SettingService;
 use crate::service::Services;
+use crate::util::now;
+
+const MAX_ESCALATION_STEPS: usize = 20;
+const MAX_EXEMPTIONS: usize = 50;
+const PREVIEW_MODERATOR: &str = "Moderator (123456789012345678)";
+const PREVIEW_REASON: &str = "Example reason";
+const PREVIEW_CASE_NUMBER: i32 = 42;
+const PREVIEW_EXPIRATION_SECONDS: u64 = 60 * 60 * 24;
 
 pub struct SettingEndpointParams(GuildId);
 
@@ -115,6 +139,145 @@ impl FromRequestParts<AxumState> for SettingEndpointParams {
     }
 }
 
+fn validate_escalation_step(step: &EscalationStepModel) -> Result<(), &'static str> {
+    if step.infraction_type != ACTION_WARN
+        && step.infraction_type != ACTION_MUTE
+        && step.infraction_type != ACTION_KICK
+        && step.infraction_type != ACTION_SOFTBAN
+    {
+        return Err("Invalid value for escalation step infraction type!");
+    }
+
+    if step.threshold < 1 {
+        return Err("Invalid value for escalation step threshold!");
+    }
+
+    if step.action < ACTION_WARN || step.action > ACTION_HARDBAN {
+        return Err("Invalid value for escalation step action!");
+    }
+
+    if step.action_duration_type < DURATION_TYPE_SECONDS
+        || step.action_duration_type > DURATION_TYPE_DAYS
+    {
+        return Err("Invalid value for escalation step action duration type!");
+    }
+
+    if step.action_duration < 0
+        || step.action_duration > get_max_duration(step.action_duration_type)
+    {
+        return Err("Invalid value for escalation step action duration!");
+    }
+
+    if step.lookback_duration_type < DURATION_TYPE_SECONDS
+        || step.lookback_duration_type > DURATION_TYPE_DAYS
+    {
+        return Err("Invalid value for escalation step lookback duration type!");
+    }
+
+    if step.lookback_duration < 0
+        || step.lookback_duration > get_max_duration(step.lookback_duration_type)
+    {
+        return Err("Invalid value for escalation step lookback duration!");
+    }
+
+    Ok(())
+}
+
+fn exempt_channel_models(
+    channel_ids: &[i64],
+    channels: &HashMap<ChannelId, CachedChannel>,
+) -> Vec<ChannelModel> {
+    channel_ids
+        .iter()
+        .filter_map(|id| NonZeroU64::new(*id as u64))
+        .map(|id| ChannelId::new(id.get()))
+        .filter_map(|channel_id| {
+            channels
+                .get(&channel_id)
+                .map(|channel| ChannelModel::from_guild_channel(channel_id, channel))
+        })
+        .collect()
+}
+
+fn exempt_role_models(role_ids: &[i64], roles: &HashMap<RoleId, CachedRole>) -> Vec<RoleModel> {
+    role_ids
+        .iter()
+        .filter_map(|id| NonZeroU64::new(*id as u64))
+        .map(|id| RoleId::new(id.get()))
+        .filter_map(|role_id| {
+            roles
+                .get(&role_id)
+                .map(|role| RoleModel::from_role(role_id, role))
+        })
+        .collect()
+}
+
+// name is the auto-mod feature the channels are exempt from, e.g. "spam filter"
+fn parse_exempt_channels(
+    name: &str,
+    exempt_channels: &[ChannelModel],
+    channels: &HashMap<ChannelId, CachedChannel>,
+) -> Result<Vec<i64>, String> {
+    if exempt_channels.len() > MAX_EXEMPTIONS {
+        return Err(format!(
+            "You can't exempt more than {} channels from the {}!",
+            MAX_EXEMPTIONS, name
+        ));
+    }
+
+    let mut channel_ids = vec![];
+    for channel in exempt_channels {
+        let channel_id = match channel.id.parse::<NonZeroU64>() {
+            Ok(id) => ChannelId::new(id.get()),
+            Err(_) => return Err(format!("Selected {} exempt channel id is invalid!", name)),
+        };
+
+        if channels.get(&channel_id).is_none() {
+            return Err(format!("Selected {} exempt channel doesn't exist!", name));
+        }
+
+        let channel_id = channel_id.get() as i64;
+        if !channel_ids.contains(&channel_id) {
+            channel_ids.push(channel_id);
+        }
+    }
+
+    Ok(channel_ids)
+}
+
+// name is the auto-mod feature the roles are exempt from, e.g. "spam filter"
+fn parse_exempt_roles(
+    name: &str,
+    exempt_roles: &[RoleModel],
+    roles: &HashMap<RoleId, CachedRole>,
+) -> Result<Vec<i64>, String> {
+    if exempt_roles.len() > MAX_EXEMPTIONS {
+        return Err(format!(
+            "You can't exempt more than {} roles from the {}!",
+            MAX_EXEMPTIONS, name
+        ));
+    }
+
+    let mut role_ids = vec![];
+    for role in exempt_roles {
+        let role_id = match role.id.parse::<NonZeroU64>() {
+            Ok(id) => RoleId::new(id.get()),
+            Err(_) => return Err(format!("Selected {} exempt role id is invalid!", name)),
+        };
+
+        if roles.get(&role_id).is_none() {
+            return Err(format!("Selected {} exempt role doesn't exist!", name));
+        }
+
+        let role_id = role_id.get() as i64;
+        if !role_ids.contains(&role_id) {
+            role_ids.push(role_id);
+        }
+    }
+
+    Ok(role_ids)
+}
+
 // /guilds/:guild_id/settings
 pub async fn get_setting(
     State(services): State<Arc<Services>>,
@@ -162,6 +325,13 @@ pub async fn get_setting(
 
     let setting = setting_service.get_setting(guild_id).await;
 
+    let escalation_service =
+        extract_service::<EscalationService>(&services).map_err(|err| err.into_response())?;
+
+    let escalation_steps = escalation_service
+        .fetch_guild_escalation_steps(guild_id)
+        .await;
+
     let mod_log_channel = NonZeroU64::new(setting.mod_log_channel_id as u64)
         .map(NonZeroU64::get)
         .map(ChannelId::new)
@@ -182,6 +352,16 @@ pub async fn get_setting(
         })
         .map(|(id, channel)| ChannelModel::from_guild_channel(id, channel));
 
+    let appeals_channel = NonZeroU64::new(setting.appeals_channel_id as u64)
+        .map(NonZeroU64::get)
+        .map(ChannelId::new)
+        .and_then(|channel_id| {
+            channels
+                .get(&channel_id)
+                .map(|channel| (channel_id, channel))
+        })
+        .map(|(id, channel)| ChannelModel::from_guild_channel(id, channel));
+
     let holding_room_role = setting
         .holding_room_role_id
         .and_then(|id| NonZeroU64::new(id as u64))
@@ -190,6 +370,14 @@ pub async fn get_setting(
         .and_then(|role_id| roles.get(&role_id).map(|role| (role_id, role)))
         .map(|(role_id, role)| RoleModel::from_role(role_id, role));
 
+    let mute_role = setting
+        .mute_role_id
+        .and_then(|id| NonZeroU64::new(id as u64))
+        .map(NonZeroU64::get)
+        .map(RoleId::new)
+        .and_then(|role_id| roles.get(&role_id).map(|role| (role_id, role)))
+        .map(|(role_id, role)| RoleModel::from_role(role_id, role));
+
     let welcome_channel = NonZeroU64::new(setting.welcome_message_channel_id as u64)
         .map(NonZeroU64::get)
         .map(ChannelId::new)
@@ -230,26 +418,77 @@ pub async fn get_setting(
         invite_link_remover_action: setting.invite_link_remover_action,
         invite_link_remover_action_duration: setting.invite_link_remover_action_duration,
         invite_link_remover_action_duration_type: setting.invite_link_remover_action_duration_type,
+        invite_link_remover_allowlist: Some(setting.invite_link_remover_allowlist.clone()),
         privacy_settings: setting.privacy_settings,
         privacy_mod_log: setting.privacy_mod_log,
-        softban_threshold: setting.softban_threshold,
-        softban_action: setting.softban_action,
-        softban_action_duration: setting.softban_action_duration,
-        softban_action_duration_type: setting.softban_action_duration_type,
-        kick_threshold: setting.kick_threshold,
-        kick_action: setting.kick_action,
-        kick_action_duration: setting.kick_action_duration,
-        kick_action_duration_type: setting.kick_action_duration_type,
-        mute_threshold: setting.mute_threshold,
-        mute_action: setting.mute_action,
-        mute_action_duration: setting.mute_action_duration,
-        mute_action_duration_type: setting.mute_action_duration_type,
-        warn_threshold: setting.warn_threshold,
-        warn_action: setting.warn_action,
-        warn_action_duration: setting.warn_action_duration,
-        warn_action_duration_type: setting.warn_action_duration_type,
         mods_can_edit_tags: setting.mods_can_edit_tags,
         spam_filter: setting.spam_filter,
+        mute_backend: Some(setting.mute_backend),
+        mute_role: Some(mute_role),
+        warn_decay_days: Some(setting.warn_decay_days),
+        mute_decay_days: Some(setting.mute_decay_days),
+        kick_decay_days: Some(setting.kick_decay_days),
+        softban_decay_days: Some(setting.softban_decay_days),
+        appeals_channel: Some(appeals_channel),
+        report_notify_reporter: Some(setting.report_notify_reporter),
+        warn_dm: Some(setting.warn_dm),
+        warn_dm_template: Some(setting.warn_dm_template.clone()),
+        mute_dm: Some(setting.mute_dm),
+        mute_dm_template: Some(setting.mute_dm_template.clone()),
+        kick_dm: Some(setting.kick_dm),
+        kick_dm_template: Some(setting.kick_dm_template.clone()),
+        ban_dm: Some(setting.ban_dm),
+        ban_dm_template: Some(setting.ban_dm_template.clone()),
+        softban_dm: Some(setting.softban_dm),
+        softban_dm_template: Some(setting.softban_dm_template.clone()),
+        hardban_dm: Some(setting.hardban_dm),
+        hardban_dm_template: Some(setting.hardban_dm_template.clone()),
+        anonymous_moderation: Some(setting.anonymous_moderation),
+        spam_filter_exempt_channels: Some(exempt_channel_models(
+            &setting.spam_filter_exempt_channel_ids,
+            &channels,
+        )),
+        spam_filter_exempt_roles: Some(exempt_role_models(
+            &setting.spam_filter_exempt_role_ids,
+            &roles,
+        )),
+        invite_link_remover_exempt_channels: Some(exempt_channel_models(
+            &setting.invite_link_remover_exempt_channel_ids,
+            &channels,
+        )),
+        invite_link_remover_exempt_roles: Some(exempt_role_models(
+            &setting.invite_link_remover_exempt_role_ids,
+            &roles,
+        )),
+        word_filter_exempt_channels: Some(exempt_channel_models(
+            &setting.word_filter_exempt_channel_ids,
+            &channels,
+        )),
+        word_filter_exempt_roles: Some(exempt_role_models(
+            &setting.word_filter_exempt_role_ids,
+            &roles,
+        )),
+        link_filter: Some(setting.link_filter),
+        link_filter_allowlist: Some(setting.link_filter_allowlist.clone()),
+        link_filter_denylist: Some(setting.link_filter_denylist.clone()),
+        link_filter_allowlist_only: Some(setting.link_filter_allowlist_only),
+        link_filter_action: Some(setting.link_filter_action),
+        link_filter_action_duration: Some(setting.link_filter_action_duration),
+        link_filter_action_duration_type: Some(setting.link_filter_action_duration_type),
+        link_filter_exempt_channels: Some(exempt_channel_models(
+            &setting.link_filter_exempt_channel_ids,
+            &channels,
+        )),
+        link_filter_exempt_roles: Some(exempt_role_models(
+            &setting.link_filter_exempt_role_ids,
+            &roles,
+        )),
+        escalation_steps: Some(
+            escalation_steps
+                .iter()
+                .map(EscalationStepModel::from_escalation_step)
+                .collect(),
+        ),
     }))
 }
 
@@ -284,6 +523,11 @@ pub async fn update_setting(
         }
     };
 
+    let setting_service =
+        extract_service::<SettingService>(&services).map_err(|err| err.into_response())?;
+
+    let stored_setting = setting_service.get_setting(guild_id).await;
+
     new_setting.message = new_setting.message.trim().to_string();
     new_setting.word_filter_blocklist = new_setting
         .word_filter_blocklist
@@ -296,6 +540,37 @@ pub async fn update_setting(
         }
     }
 
+    for list in [
+        &mut new_setting.invite_link_remover_allowlist,
+        &mut new_setting.link_filter_allowlist,
+        &mut new_setting.link_filter_denylist,
+    ]
+    .into_iter()
+    .flatten()
+    {
+        *list = list
+            .as_ref()
+            .map(|s| s.trim().to_string())
+            .filter(|s| !s.is_empty());
+    }
+
+    for template in [
+        &mut new_setting.warn_dm_template,
+        &mut new_setting.mute_dm_template,
+        &mut new_setting.kick_dm_template,
+        &mut new_setting.ban_dm_template,
+        &mut new_setting.softban_dm_template,
+        &mut new_setting.hardban_dm_template,
+    ]
+    .into_iter()
+    .flatten()
+    {
+        *template = template
+            .as_ref()
+            .map(|s| s.trim().to_string())
+            .filter(|s| !s.is_empty());
+    }
+
     let mod_log_channel_id = if let Some(channel) = new_setting.mod_log_channel.as_ref() {
         let channel_id = match channel.id.parse::<NonZeroU64>() {
             Ok(id) => ChannelId::new(id.get()),
@@ -346,6 +621,33 @@ pub async fn update_setting(
         0
     };
 
+    let appeals_channel_id = if let Some(Some(channel)) = new_setting.appeals_channel.as_ref() {
+        let channel_id = match channel.id.parse::<NonZeroU64>() {
+            Ok(id) => ChannelId::new(id.get()),
+            Err(_) => {
+                return Err((
+                    StatusCode::BAD_REQUEST,
+                    Json("Selected appeals channel id is invalid!"),
+                )
+                    .into_response());
+            }
+        };
+
+        if channels.get(&channel_id).is_none() {
+            return Err((
+                StatusCode::BAD_REQUEST,
+                Json("Selected appeals channel doesn't exist!"),
+            )
+                .into_response());
+        }
+
+        channel_id.get() as i64
+    } else if new_setting.appeals_channel.is_some() {
+        0
+    } else {
+        stored_setting.appeals_channel_id
+    };
+
     let welcome_message_channel_id =
         if let Some(channel) = new_setting.welcome_message_channel.as_ref() {
             let channel_id = match channel.id.parse::<NonZeroU64>() {
@@ -401,6 +703,139 @@ pub async fn update_setting(
         None
     };
 
+    let mute_role_id = if let Some(Some(role)) = new_setting.mute_role.as_ref() {
+        let role_id = match role.id.parse::<NonZeroU64>() {
+            Ok(id) => RoleId::new(id.get()),
+            Err(_) => {
+                return Err((
+                    StatusCode::BAD_REQUEST,
+                    Json("Selected mute role id is invalid!"),
+                )
+                    .into_response());
+            }
+        };
+
+        if role_id == guild_id.everyone_role() {
+            return Err((
+                StatusCode::BAD_REQUEST,
+                Json("You can't use the @everyone role as the mute role!"),
+            )
+                .into_response());
+        }
+
+        let role = if let Some(role) = roles.get(&role_id) {
+            role
+        } else {
+            return Err((
+                StatusCode::BAD_REQUEST,
+                Json("Selected mute role doesn't exist!"),
+            )
+                .into_response());
+        };
+
+        if role.managed {
+            return Err((
+                StatusCode::BAD_REQUEST,
+                Json("You can't use a role managed by an integration as the mute role!"),
+            )
+                .into_response());
+        }
+
+        let jim_position = match guild_service.get_member(guild_id, JIM_ID).await {
+            Ok(jim_member) => guild_service
+                .get_highest_role_position(guild_id, &jim_member.roles)
+                .await
+                .ok(),
+            Err(_) => None,
+        };
+
+        let jim_position = if let Some(position) = jim_position {
+            position
+        } else {
+            return Err((
+                StatusCode::BAD_REQUEST,
+                Json("Failed to fetch guild data, is Jim in this server?"),
+            )
+                .into_response());
+        };
+
+        if role.position >= jim_position {
+            return Err((
+                StatusCode::BAD_REQUEST,
+                Json("I can't assign the selected mute role, it is equal to or above my highest role! Move my role above it in server settings."),
+            )
+                .into_response());
+        }
+
+        Some(role_id.get() as i64)
+    } else if new_setting.mute_role.is_some() {
+        None
+    } else {
+        stored_setting.mute_role_id
+    };
+
+    let spam_filter_exempt_channel_ids = match new_setting.spam_filter_exempt_channels.as_deref() {
+        Some(exempt_channels) => {
+            parse_exempt_channels("spam filter", exempt_channels, &channels)
+                .map_err(|message| (StatusCode::BAD_REQUEST, Json(message)).into_response())?
+        }
+        None => stored_setting.spam_filter_exempt_channel_ids.clone(),
+    };
+
+    let spam_filter_exempt_role_ids = match new_setting.spam_filter_exempt_roles.as_deref() {
+        Some(exempt_roles) => parse_exempt_roles("spam filter", exempt_roles, &roles)
+            .map_err(|message| (StatusCode::BAD_REQUEST, Json(message)).into_response())?,
+        None => stored_setting.spam_filter_exempt_role_ids.clone(),
+    };
+
+    let invite_link_remover_exempt_channel_ids =
+        match new_setting.invite_link_remover_exempt_channels.as_deref() {
+            Some(exempt_channels) => {
+                parse_exempt_channels("invite link remover", exempt_channels, &channels)
+                    .map_err(|message| (StatusCode::BAD_REQUEST, Json(message)).into_response())?
+            }
+            None => stored_setting
+                .invite_link_remover_exempt_channel_ids
+                .clone(),
+        };
+
+    let invite_link_remover_exempt_role_ids = match new_setting
+        .invite_link_remover_exempt_roles
+        .as_deref()
+    {
+        Some(exempt_roles) => parse_exempt_roles("invite link remover", exempt_roles, &roles)
+            .map_err(|message| (StatusCode::BAD_REQUEST, Json(message)).into_response())?,
+        None => stored_setting.invite_link_remover_exempt_role_ids.clone(),
+    };
+
+    let word_filter_exempt_channel_ids = match new_setting.word_filter_exempt_channels.as_deref() {
+        Some(exempt_channels) => {
+            parse_exempt_channels("word filter", exempt_channels, &channels)
+                .map_err(|message| (StatusCode::BAD_REQUEST, Json(message)).into_response())?
+        }
+        None => stored_setting.word_filter_exempt_channel_ids.clone(),
+    };
+
+    let word_filter_exempt_role_ids = match new_setting.word_filter_exempt_roles.as_deref() {
+        Some(exempt_roles) => parse_exempt_roles("word filter", exempt_roles, &roles)
+            .map_err(|message| (StatusCode::BAD_REQUEST, Json(message)).into_response())?,
+        None => stored_setting.word_filter_exempt_role_ids.clone(),
+    };
+
+    let link_filter_exempt_channel_ids = match new_setting.link_filter_exempt_channels.as_deref() {
+        Some(exempt_channels) => {
+            parse_exempt_channels("link filter", exempt_channels, &channels)
+                .map_err(|message| (StatusCode::BAD_REQUEST, Json(message)).into_response())?
+        }
+        None => stored_setting.link_filter_exempt_channel_ids.clone(),
+    };
+
+    let link_filter_exempt_role_ids = match new_setting.link_filter_exempt_roles.as_deref() {
+        Some(exempt_roles) => parse_exempt_roles("link filter", exempt_roles, &roles)
+            .map_err(|message| (StatusCode::BAD_REQUEST, Json(message)).into_response())?,
+        None => stored_setting.link_filter_exempt_role_ids.clone(),
+    };
+
     if new_setting.join_captcha && new_setting.holding_room {
         return Err((
             StatusCode::BAD_REQUEST,
@@ -439,6 +874,17 @@ pub async fn update_setting(
             )
                 .into_response());
         }
+
+        if let Err(errors) = validate_blocklist(blocklist) {
+            let errors = errors
+                .into_iter()
+                .map(|error| BlocklistEntryErrorModel {
+                    entry: error.entry,
+                    error: error.error,
+                })
+                .collect::<Vec<BlocklistEntryErrorModel>>();
+            return Err((StatusCode::BAD_REQUEST, Json(errors)).into_response());
+        }
     }
 
     if new_setting.word_filter_level != WORD_FILTER_LEVEL_LOW
@@ -507,170 +953,210 @@ pub async fn update_setting(
             .into_response());
     }
 
-    if new_setting.softban_threshold < 0 {
-        return Err((
-            StatusCode::BAD_REQUEST,
-            Json("Invalid value for softban threshold!"),
-        )
-            .into_response());
+    if let Some(Some(allowlist)) = new_setting.invite_link_remover_allowlist.as_ref() {
+        if let Err(message) = validate_invite_allowlist(allowlist) {
+            return Err((StatusCode::BAD_REQUEST, Json(message)).into_response());
+        }
     }
 
-    if new_setting.softban_action < ACTION_NOTHING || new_setting.softban_action > ACTION_HARDBAN {
-        return Err((
-            StatusCode::BAD_REQUEST,
-            Json("Invalid value for softban action!"),
-        )
-            .into_response());
-    }
+    let domain_lists = [
+        ("Link filter allowlist", &new_setting.link_filter_allowlist),
+        ("Link filter denylist", &new_setting.link_filter_denylist),
+    ];
 
-    if new_setting.softban_action_duration < 0 {
-        return Err((
-            StatusCode::BAD_REQUEST,
-            Json("Invalid value for softban action duration!"),
-        )
-            .into_response());
+    for (name, domain_list) in domain_lists {
+        if let Some(Some(domain_list)) = domain_list {
+            if let Err(message) = validate_domain_list(name, domain_list) {
+                return Err((StatusCode::BAD_REQUEST, Json(message)).into_response());
+            }
+        }
     }
 
-    if new_setting.softban_action_duration_type < DURATION_TYPE_SECONDS
-        || new_setting.softban_action_duration_type > DURATION_TYPE_DAYS
-    {
+    let link_filter_action = new_setting
+        .link_filter_action
+        .unwrap_or(stored_setting.link_filter_action);
+    let link_filter_action_duration = new_setting
+        .link_filter_action_duration
+        .unwrap_or(stored_setting.link_filter_action_duration);
+    let link_filter_action_duration_type = new_setting
+        .link_filter_action_duration_type
+        .unwrap_or(stored_setting.link_filter_action_duration_type);
+
+    if link_filter_action < ACTION_NOTHING || link_filter_action > ACTION_HARDBAN {
         return Err((
             StatusCode::BAD_REQUEST,
-            Json("Invalid value for softban action duration type!"),
+            Json("Invalid value for link filter action!"),
         )
             .into_response());
     }
 
-    if new_setting.kick_threshold < 0 {
+    if link_filter_action_duration < 0 {
         return Err((
             StatusCode::BAD_REQUEST,
-            Json("Invalid value for kick threshold!"),
+            Json("Invalid value for link filter action duration!"),
         )
             .into_response());
     }
 
-    if new_setting.kick_action < ACTION_NOTHING || new_setting.kick_action > ACTION_HARDBAN {
+    if link_filter_action_duration_type < DURATION_TYPE_SECONDS
+        || link_filter_action_duration_type > DURATION_TYPE_DAYS
+    {
         return Err((
             StatusCode::BAD_REQUEST,
-            Json("Invalid value for kick action!"),
+            Json("Invalid value for link filter action duration type!"),
         )
             .into_response());
     }
 
-    if new_setting.kick_action_duration < 0 {
+    if new_setting.privacy_settings < PRIVACY_EVERYONE
+        || new_setting.privacy_settings > PRIVACY_ADMIN_ONLY
+    {
         return Err((
             StatusCode::BAD_REQUEST,
-            Json("Invalid value for kick action duration!"),
+            Json("Invalid value for moderator log privacy!"),
         )
             .into_response());
     }
 
-    if new_setting.kick_action_duration_type < DURATION_TYPE_SECONDS
-        || new_setting.kick_action_duration_type > DURATION_TYPE_DAYS
+    if new_setting.privacy_mod_log < PRIVACY_EVERYONE
+        || new_setting.privacy_mod_log > PRIVACY_ADMIN_ONLY
     {
         return Err((
             StatusCode::BAD_REQUEST,
-            Json("Invalid value for kick action duration type!"),
+            Json("Invalid value for moderator log privacy!"),
         )
             .into_response());
     }
 
-    if new_setting.mute_threshold < 0 {
-        return Err((
-            StatusCode::BAD_REQUEST,
-            Json("Invalid value for mute threshold!"),
-        )
-            .into_response());
-    }
+    let mute_backend = new_setting
+        .mute_backend
+        .unwrap_or(stored_setting.mute_backend);
 
-    if new_setting.mute_action < ACTION_NOTHING || new_setting.mute_action > ACTION_HARDBAN {
+    if mute_backend != MUTE_BACKEND_ROLE && mute_backend != MUTE_BACKEND_TIMEOUT {
         return Err((
             StatusCode::BAD_REQUEST,
-            Json("Invalid value for mute action!"),
+            Json("Invalid value for mute backend!"),
         )
             .into_response());
     }
 
-    if new_setting.mute_action_duration < 0 {
-        return Err((
-            StatusCode::BAD_REQUEST,
-            Json("Invalid value for mute action duration!"),
-        )
-            .into_response());
-    }
+    let warn_decay_days = new_setting
+        .warn_decay_days
+        .unwrap_or(stored_setting.warn_decay_days);
 
-    if new_setting.mute_action_duration_type < DURATION_TYPE_SECONDS
-        || new_setting.mute_action_duration_type > DURATION_TYPE_DAYS
-    {
+    if warn_decay_days < 0 || warn_decay_days > MAX_DECAY_DAYS {
         return Err((
             StatusCode::BAD_REQUEST,
-            Json("Invalid value for mute action duration type!"),
+            Json("Invalid value for warn decay days!"),
         )
             .into_response());
     }
 
-    if new_setting.warn_threshold < 0 {
-        return Err((
-            StatusCode::BAD_REQUEST,
-            Json("Invalid value for warn threshold!"),
-        )
-            .into_response());
-    }
+    let mute_decay_days = new_setting
+        .mute_decay_days
+        .unwrap_or(stored_setting.mute_decay_days);
 
-    if new_setting.warn_action < ACTION_NOTHING || new_setting.warn_action > ACTION_HARDBAN {
+    if mute_decay_days < 0 || mute_decay_days > MAX_DECAY_DAYS {
         return Err((
             StatusCode::BAD_REQUEST,
-            Json("Invalid value for warn action!"),
+            Json("Invalid value for mute decay days!"),
         )
             .into_response());
     }
 
-    if new_setting.warn_action_duration < 0 {
+    let kick_decay_days = new_setting
+        .kick_decay_days
+        .unwrap_or(stored_setting.kick_decay_days);
+
+    if kick_decay_days < 0 || kick_decay_days > MAX_DECAY_DAYS {
         return Err((
             StatusCode::BAD_REQUEST,
-            Json("Invalid value for warn action duration!"),
+            Json("Invalid value for kick decay days!"),
         )
             .into_response());
     }
 
-    if new_setting.warn_action_duration_type < DURATION_TYPE_SECONDS
-        || new_setting.warn_action_duration_type > DURATION_TYPE_DAYS
-    {
+    let softban_decay_days = new_setting
+        .softban_decay_days
+        .unwrap_or(stored_setting.softban_decay_days);
+
+    if softban_decay_days < 0 || softban_decay_days > MAX_DECAY_DAYS {
         return Err((
             StatusCode::BAD_REQUEST,
-            Json("Invalid value for warn action duration type!"),
+            Json("Invalid value for softban decay days!"),
         )
             .into_response());
     }
 
-    if new_setting.privacy_settings < PRIVACY_EVERYONE
-        || new_setting.privacy_settings > PRIVACY_ADMIN_ONLY
-    {
-        return Err((
-            StatusCode::BAD_REQUEST,
-            Json("Invalid value for moderator log privacy!"),
-        )
-            .into_response());
+    let dm_templates = [
+        ("Warn", &new_setting.warn_dm_template),
+        ("Mute", &new_setting.mute_dm_template),
+        ("Kick", &new_setting.kick_dm_template),
+        ("Ban", &new_setting.ban_dm_template),
+        ("Softban", &new_setting.softban_dm_template),
+        ("Hardban", &new_setting.hardban_dm_template),
+    ];
+
+    for (name, template) in dm_templates {
+        if let Some(Some(template)) = template {
+            if let Err(message) = validate_dm_template(name, template) {
+                return Err((StatusCode::BAD_REQUEST, Json(message)).into_response());
+            }
+        }
     }
 
-    if new_setting.privacy_mod_log < PRIVACY_EVERYONE
-        || new_setting.privacy_mod_log > PRIVACY_ADMIN_ONLY
-    {
-        return Err((
-            StatusCode::BAD_REQUEST,
-            Json("Invalid value for moderator log privacy!"),
-        )
-            .into_response());
+    if let Some(steps) = new_setting.escalation_steps.as_ref() {
+        if steps.len() > MAX_ESCALATION_STEPS {
+            return Err((
+                StatusCode::BAD_REQUEST,
+                Json("Escalation ladder cannot have more than 20 steps!"),
+            )
+                .into_response());
+        }
+
+        for (index, step) in steps.iter().enumerate() {
+            if let Err(message) = validate_escalation_step(step) {
+                return Err((StatusCode::BAD_REQUEST, Json(message)).into_response());
+            }
+
+            let is_duplicate = steps[..index].iter().any(|other| {
+                other.infraction_type == step.infraction_type && other.threshold == step.threshold
+            });
+
+            if is_duplicate {
+                return Err((
+                    StatusCode::BAD_REQUEST,
+                    Json(
+                        "Escalation steps of the same infraction type must have different thresholds!",
+                    ),
+                )
+                    .into_response());
+            }
+        }
     }
 
     if new_setting.guild.id != guild_id.to_string() {
         return Err((StatusCode::BAD_REQUEST, Json("Invalid guild id!")).into_response());
     }
 
-    let setting_service =
-        extract_service::<SettingService>(&services).map_err(|err| err.into_response())?;
-
-    setting_service
+    let escalation_steps = new_setting.escalation_steps.as_ref().map(|steps| {
+        steps
+            .iter()
+            .map(|step| EscalationStep {
+                id: 0,
+                guild_id: guild_id.get() as i64,
+                infraction_type: step.infraction_type,
+                threshold: step.threshold,
+                action: step.action,
+                action_duration: step.action_duration,
+                action_duration_type: step.action_duration_type,
+                lookback_duration: step.lookback_duration,
+                lookback_duration_type: step.lookback_duration_type,
+            })
+            .collect()
+    });
+
+    if setting_service
         .update_setting(
             guild_id,
             Setting {
@@ -697,33 +1183,190 @@ pub async fn update_setting(
                     .invite_link_remover_action_duration,
                 invite_link_remover_action_duration_type: new_setting
                     .invite_link_remover_action_duration_type,
+                invite_link_remover_allowlist: new_setting
+                    .invite_link_remover_allowlist
+                    .clone()
+                    .unwrap_or_else(|| stored_setting.invite_link_remover_allowlist.clone()),
                 privacy_settings: new_setting.privacy_settings,
                 privacy_mod_log: new_setting.privacy_mod_log,
-                softban_threshold: new_setting.softban_threshold,
-                softban_action: new_setting.softban_action,
-                softban_action_duration: new_setting.softban_action_duration,
-                softban_action_duration_type: new_setting.softban_action_duration_type,
-                kick_threshold: new_setting.kick_threshold,
-                kick_action: new_setting.kick_action,
-                kick_action_duration: new_setting.kick_action_duration,
-                kick_action_duration_type: new_setting.kick_action_duration_type,
-                mute_threshold: new_setting.mute_threshold,
-                mute_action: new_setting.mute_action,
-                mute_action_duration: new_setting.mute_action_duration,
-                mute_action_duration_type: new_setting.mute_action_duration_type,
-                warn_threshold: new_setting.warn_threshold,
-                warn_action: new_setting.warn_action,
-                warn_action_duration: new_setting.warn_action_duration,
-                warn_action_duration_type: new_setting.warn_action_duration_type,
                 mods_can_edit_tags: new_setting.mods_can_edit_tags,
                 spam_filter: new_setting.spam_filter,
+                mute_backend,
+                mute_role_id,
+                warn_decay_days,
+                mute_decay_days,
+                kick_decay_days,
+                softban_decay_days,
+                appeals_channel_id,
+                report_notify_reporter: new_setting
+                    .report_notify_reporter
+                    .unwrap_or(stored_setting.report_notify_reporter),
+                warn_dm: new_setting.warn_dm.unwrap_or(stored_setting.warn_dm),
+                warn_dm_template: new_setting
+                    .warn_dm_template
+                    .clone()
+                    .unwrap_or_else(|| stored_setting.warn_dm_template.clone()),
+                mute_dm: new_setting.mute_dm.unwrap_or(stored_setting.mute_dm),
+                mute_dm_template: new_setting
+                    .mute_dm_template
+                    .clone()
+                    .unwrap_or_else(|| stored_setting.mute_dm_template.clone()),
+                kick_dm: new_setting.kick_dm.unwrap_or(stored_setting.kick_dm),
+                kick_dm_template: new_setting
+                    .kick_dm_template
+                    .clone()
+                    .unwrap_or_else(|| stored_setting.kick_dm_template.clone()),
+                ban_dm: new_setting.ban_dm.unwrap_or(stored_setting.ban_dm),
+                ban_dm_template: new_setting
+                    .ban_dm_template
+                    .clone()
+                    .unwrap_or_else(|| stored_setting.ban_dm_template.clone()),
+                softban_dm: new_setting.softban_dm.unwrap_or(stored_setting.softban_dm),
+                softban_dm_template: new_setting
+                    .softban_dm_template
+                    .clone()
+                    .unwrap_or_else(|| stored_setting.softban_dm_template.clone()),
+                hardban_dm: new_setting.hardban_dm.unwrap_or(stored_setting.hardban_dm),
+                hardban_dm_template: new_setting
+                    .hardban_dm_template
+                    .clone()
+                    .unwrap_or_else(|| stored_setting.hardban_dm_template.clone()),
+                anonymous_moderation: new_setting
+                    .anonymous_moderation
+                    .unwrap_or(stored_setting.anonymous_moderation),
+                spam_filter_exempt_channel_ids,
+                spam_filter_exempt_role_ids,
+                invite_link_remover_exempt_channel_ids,
+                invite_link_remover_exempt_role_ids,
+                word_filter_exempt_channel_ids,
+                word_filter_exempt_role_ids,
+                link_filter: new_setting
+                    .link_filter
+                    .unwrap_or(stored_setting.link_filter),
+                link_filter_allowlist: new_setting
+                    .link_filter_allowlist
+                    .clone()
+                    .unwrap_or_else(|| stored_setting.link_filter_allowlist.clone()),
+                link_filter_denylist: new_setting
+                    .link_filter_denylist
+                    .clone()
+                    .unwrap_or_else(|| stored_setting.link_filter_denylist.clone()),
+                link_filter_allowlist_only: new_setting
+                    .link_filter_allowlist_only
+                    .unwrap_or(stored_setting.link_filter_allowlist_only),
+                link_filter_action,
+                link_filter_action_duration,
+                link_filter_action_duration_type,
+                link_filter_exempt_channel_ids,
+                link_filter_exempt_role_ids,
             },
+            escalation_steps,
         )
-        .await;
+        .await
+        .is_none()
+    {
+        return Err((
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json("Failed to save settings!"),
+        )
+            .into_response());
+    }
+
+    // a newly picked mute role doesn't stop anyone from talking until channels deny it, that's a
+    // request per channel so it runs in the background once the settings are saved
+    if let Some(role_id) = mute_role_id.filter(|id| Some(*id) != stored_setting.mute_role_id) {
+        if mute_backend == MUTE_BACKEND_ROLE {
+            let http = guild_service.http().await;
+            let services = services.clone();
+            drop(tokio::spawn(async move {
+                let mute_service = if let Some(service) = services.get::<MuteService>() {
+                    service
+                } else {
+                    error!("couldn't get mute service!");
+                    return;
+                };
+
+                if let Err(MuteFailure::UnauthorizedChannelOverride) = mute_service
+                    .apply_mute_role_overwrites(&http, guild_id, RoleId::new(role_id as u64))
+                    .await
+                {
+                    warn!(
+                        "missing permission to create mute role permission overrides in guild: {}",
+                        guild_id
+                    );
+                }
+            }));
+        }
+    }
 
     Ok(())
 }
 
+// /guilds/:guild_id/settings/dm-preview
+pub async fn preview_dm_template(
+    State(services): State<Arc<Services>>,
+    SettingEndpointParams(guild_id): SettingEndpointParams,
+    Json(preview): Json<DmTemplatePreviewModel>,
+) -> Result<Json<DmPreviewModel>, Response> {
+    let expiration_time = Some(now() + PREVIEW_EXPIRATION_SECONDS);
+    let kind = if preview.action == ACTION_WARN {
+        ModActionKind::Warn
+    } else if preview.action == ACTION_MUTE {
+        ModActionKind::Mute { expiration_time }
+    } else if preview.action == ACTION_KICK {
+        ModActionKind::Kick
+    } else if preview.action == ACTION_BAN {
+        ModActionKind::Ban { expiration_time }
+    } else if preview.action == ACTION_SOFTBAN {
+        ModActionKind::Softban
+    } else if preview.action == ACTION_HARDBAN {
+        ModActionKind::Hardban
+    } else {
+        return Err((StatusCode::BAD_REQUEST, Json("Invalid value for action!")).into_response());
+    };
+
+    let template = preview.template.trim();
+    if template.is_empty() {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            Json("DM template cannot be empty!"),
+        )
+            .into_response());
+    }
+
+    if let Err(message) = validate_dm_template("This", template) {
+        return Err((StatusCode::BAD_REQUEST, Json(message)).into_response());
+    }
+
+    let guild_service =
+        extract_service::<GuildService>(&services).map_err(|err| err.into_response())?;
+
+    let guild = match guild_service.get_guild(guild_id).await {
+        Ok(guild) => guild,
+        Err(_) => {
+            return Err((
+                StatusCode::BAD_REQUEST,
+                Json("Failed to fetch guild data, is Jim in this server?"),
+            )
+                .into_response());
+        }
+    };
+
+    let moderator = moderator_name(preview.hide_moderator, &guild.name, PREVIEW_MODERATOR);
+
+    Ok(Json(DmPreviewModel {
+        title: kind.title(&guild.name),
+        description: render_dm_template(
+            template,
+            &kind,
+            PREVIEW_REASON,
+            PREVIEW_CASE_NUMBER,
+            &guild.name,
+            &moderator,
+        ),
+    }))
+}
+
 // /guilds/:guild_id/settings
 pub async fn reset_setting(
     State(services): State<Arc<Services>>,
@@ -732,7 +1375,13 @@ pub async fn reset_setting(
     let setting_service =
         extract_service::<SettingService>(&services).map_err(|err| err.into_response())?;
 
+    let escalation_service =
+        extract_service::<EscalationService>(&services).map_err(|err| err.into_response())?;
+
     setting_service.reset_setting(guild_id).await;
+    escalation_service
+        .delete_guild_escalation_steps(guild_id)
+        .await;
 
     Ok(())
 }
@@ -7,11 +7,14 @@ use axum::Json;
 use serde::{Deserialize, Serialize};
 use serenity::model::Permissions;
 
-use crate::server::endpoint::ModLogPaginationParams;
+use crate::discord::util::infraction::Infraction;
+use crate::server::endpoint::{log_infraction_changes, CaseNumberParam, ModLogPaginationParams};
+use crate::server::model::evidence::EvidenceModel;
 use crate::server::model::softban::SoftbanModel;
-use crate::server::{extract_service, ModLogEndpointParams, ModPermission};
-use crate::service::softban::SoftbanService;
+use crate::server::{extract_service, ModLogEndpointParams, ModPermission, User};
+use crate::service::softban::{validate_softban_update, SoftbanService};
 use crate::service::Services;
+use crate::util::normalize_reason;
 
 pub struct SoftbanModPermission;
 
@@ -33,7 +36,7 @@ pub struct GetSoftbansResponse {
 pub async fn get_softbans(
     State(services): State<Arc<Services>>,
     Query(mod_log_params): Query<ModLogPaginationParams>,
-    ModLogEndpointParams(guild_id, _): ModLogEndpointParams<SoftbanModPermission>,
+    ModLogEndpointParams(guild_id, show_moderator, _): ModLogEndpointParams<SoftbanModPermission>,
 ) -> Result<Json<GetSoftbansResponse>, StatusCode> {
     let softban_service = extract_service::<SoftbanService>(&services).map_err(|err| err.0)?;
 
@@ -41,8 +44,16 @@ pub async fn get_softbans(
     let fetched_softbans = softban_service
         .fetch_guild_softbans(guild_id, mod_log_params.page)
         .await;
+    let case_numbers = fetched_softbans
+        .iter()
+        .map(|softban| softban.case_number)
+        .collect::<Vec<i32>>();
+    let mut evidence = EvidenceModel::from_cases(&services, guild_id, &case_numbers).await;
     for softban in fetched_softbans {
-        softbans.push(SoftbanModel::from_softban(&services, &softban).await);
+        let softban_evidence = evidence.remove(&softban.case_number).unwrap_or_default();
+        softbans.push(
+            SoftbanModel::from_softban(&services, &softban, show_moderator, softban_evidence).await,
+        );
     }
 
     let page_count = softban_service.fetch_guild_softban_count(guild_id).await / 10 + 1;
@@ -54,77 +65,65 @@ pub async fn get_softbans(
     }))
 }
 
-#[derive(Deserialize)]
-pub struct SoftbanIdParam {
-    pub softban_id: i32,
-}
-
-// /guilds/:guild_id/softbans/:softban_id
+// /guilds/:guild_id/softbans/:case_number
 pub async fn get_softban(
     State(services): State<Arc<Services>>,
-    ModLogEndpointParams(guild_id, _): ModLogEndpointParams<SoftbanModPermission>,
-    Path(SoftbanIdParam { softban_id }): Path<SoftbanIdParam>,
+    ModLogEndpointParams(guild_id, show_moderator, _): ModLogEndpointParams<SoftbanModPermission>,
+    Path(CaseNumberParam { case_number }): Path<CaseNumberParam>,
 ) -> Result<Json<SoftbanModel>, Response> {
     let softban_service =
         extract_service::<SoftbanService>(&services).map_err(|err| err.into_response())?;
 
-    let softban = if let Some(softban) = softban_service.fetch_softban(softban_id).await {
+    let softban = if let Some(softban) = softban_service
+        .fetch_guild_softban_with_case_number(guild_id, case_number)
+        .await
+    {
         softban
     } else {
         return Err((
             StatusCode::NOT_FOUND,
-            Json("Softban with given id doesn't exist!"),
+            Json("Softban with given case number doesn't exist!"),
         )
             .into_response());
     };
 
-    if softban.guild_id != guild_id.get() as i64 {
-        return Err((
-            StatusCode::FORBIDDEN,
-            Json("Given softban id doesn't belong to your guild!"),
-        )
-            .into_response());
-    }
-
-    let softban_model = SoftbanModel::from_softban(&services, &softban).await;
+    let evidence = EvidenceModel::from_case(&services, guild_id, softban.case_number).await;
+    let softban_model =
+        SoftbanModel::from_softban(&services, &softban, show_moderator, evidence).await;
 
     Ok(Json(softban_model))
 }
 
-// /guilds/:guild_id/softbans/:softban_id
+// /guilds/:guild_id/softbans/:case_number
 pub async fn update_softban(
     State(services): State<Arc<Services>>,
-    ModLogEndpointParams(guild_id, _): ModLogEndpointParams<SoftbanModPermission>,
-    Path(SoftbanIdParam { softban_id }): Path<SoftbanIdParam>,
-    Json(mut new_softban): Json<SoftbanModel>,
+    ModLogEndpointParams(guild_id, show_moderator, _): ModLogEndpointParams<SoftbanModPermission>,
+    User(user_id): User,
+    Path(CaseNumberParam { case_number }): Path<CaseNumberParam>,
+    Json(new_softban): Json<SoftbanModel>,
 ) -> Result<(), Response> {
-    new_softban.reason = new_softban.reason.trim().to_string();
-
     let softban_service =
         extract_service::<SoftbanService>(&services).map_err(|err| err.into_response())?;
 
-    let mut softban = if let Some(softban) = softban_service.fetch_softban(softban_id).await {
+    let mut softban = if let Some(softban) = softban_service
+        .fetch_guild_softban_with_case_number(guild_id, case_number)
+        .await
+    {
         softban
     } else {
         return Err((
             StatusCode::NOT_FOUND,
-            Json("Softban with given id doesn't exist!"),
+            Json("Softban with given case number doesn't exist!"),
         )
             .into_response());
     };
 
-    if softban.guild_id != guild_id.get() as i64 {
-        return Err((
-            StatusCode::FORBIDDEN,
-            Json("Given softban id doesn't belong to your guild!"),
-        )
-            .into_response());
-    }
-
     if softban.id != new_softban.id
+        || softban.case_number != new_softban.case_number
         || softban.user_id.to_string() != new_softban.user.id
         || softban.softban_time != new_softban.action_time
-        || softban.moderator_user_id.to_string() != new_softban.moderator_user.id
+        || (show_moderator
+            && softban.moderator_user_id.to_string() != new_softban.moderator_user.id)
     {
         return Err((
             StatusCode::BAD_REQUEST,
@@ -133,22 +132,36 @@ pub async fn update_softban(
             .into_response());
     }
 
-    if softban.pardoned && !new_softban.pardoned {
-        return Err((
-            StatusCode::BAD_REQUEST,
-            Json("You can't un-pardon a softban!"),
-        )
-            .into_response());
+    if let Err(message) = validate_softban_update(&softban, new_softban.pardoned) {
+        return Err((StatusCode::BAD_REQUEST, Json(message)).into_response());
     }
 
-    softban.reason = if new_softban.reason.is_empty() {
-        "No reason specified".to_string()
-    } else {
-        new_softban.reason.clone()
-    };
+    let reason = normalize_reason(&new_softban.reason);
+    let mut changes = vec![];
+    if softban.reason != reason {
+        changes.push(format!("Reason changed to: {}", reason));
+    }
+    if !softban.pardoned && new_softban.pardoned {
+        changes.push("Pardoned".to_string());
+    }
+
+    softban.reason = reason;
     softban.pardoned = new_softban.pardoned;
 
-    softban_service.update_softban(softban).await;
+    if softban_service
+        .update_softban(softban.clone())
+        .await
+        .is_some()
+    {
+        log_infraction_changes(
+            &services,
+            guild_id,
+            user_id,
+            &Infraction::Softban(softban),
+            &changes,
+        )
+        .await;
+    }
 
     Ok(())
 }
@@ -7,11 +7,14 @@ use axum::Json;
 use serde::{Deserialize, Serialize};
 use serenity::model::Permissions;
 
-use crate::server::endpoint::ModLogPaginationParams;
+use crate::discord::util::infraction::Infraction;
+use crate::server::endpoint::{log_infraction_changes, CaseNumberParam, ModLogPaginationParams};
+use crate::server::model::evidence::EvidenceModel;
 use crate::server::model::warn::WarnModel;
-use crate::server::{extract_service, ModLogEndpointParams, ModPermission};
-use crate::service::warn::WarnService;
+use crate::server::{extract_service, ModLogEndpointParams, ModPermission, User};
+use crate::service::warn::{validate_warn_update, WarnService};
 use crate::service::Services;
+use crate::util::normalize_reason;
 
 pub struct WarnModPermission;
 
@@ -33,7 +36,7 @@ pub struct GetWarnsResponse {
 pub async fn get_warns(
     State(services): State<Arc<Services>>,
     Query(mod_log_params): Query<ModLogPaginationParams>,
-    ModLogEndpointParams(guild_id, _): ModLogEndpointParams<WarnModPermission>,
+    ModLogEndpointParams(guild_id, show_moderator, _): ModLogEndpointParams<WarnModPermission>,
 ) -> Result<Json<GetWarnsResponse>, StatusCode> {
     let warn_service = extract_service::<WarnService>(&services).map_err(|err| err.0)?;
 
@@ -41,8 +44,14 @@ pub async fn get_warns(
     let fetched_warns = warn_service
         .fetch_guild_warns(guild_id, mod_log_params.page)
         .await;
+    let case_numbers = fetched_warns
+        .iter()
+        .map(|warn| warn.case_number)
+        .collect::<Vec<i32>>();
+    let mut evidence = EvidenceModel::from_cases(&services, guild_id, &case_numbers).await;
     for warn in fetched_warns {
-        warns.push(WarnModel::from_warn(&services, &warn).await);
+        let warn_evidence = evidence.remove(&warn.case_number).unwrap_or_default();
+        warns.push(WarnModel::from_warn(&services, &warn, show_moderator, warn_evidence).await);
     }
 
     let page_count = warn_service.fetch_guild_warn_count(guild_id).await / 10 + 1;
@@ -54,77 +63,63 @@ pub async fn get_warns(
     }))
 }
 
-#[derive(Deserialize)]
-pub struct WarnIdParam {
-    pub warn_id: i32,
-}
-
-// /guilds/:guild_id/warns/:warn_id
+// /guilds/:guild_id/warns/:case_number
 pub async fn get_warn(
     State(services): State<Arc<Services>>,
-    ModLogEndpointParams(guild_id, _): ModLogEndpointParams<WarnModPermission>,
-    Path(WarnIdParam { warn_id }): Path<WarnIdParam>,
+    ModLogEndpointParams(guild_id, show_moderator, _): ModLogEndpointParams<WarnModPermission>,
+    Path(CaseNumberParam { case_number }): Path<CaseNumberParam>,
 ) -> Result<Json<WarnModel>, Response> {
     let warn_service =
         extract_service::<WarnService>(&services).map_err(|err| err.into_response())?;
 
-    let warn = if let Some(warn) = warn_service.fetch_warn(warn_id).await {
+    let warn = if let Some(warn) = warn_service
+        .fetch_guild_warn_with_case_number(guild_id, case_number)
+        .await
+    {
         warn
     } else {
         return Err((
             StatusCode::NOT_FOUND,
-            Json("Warn with given id doesn't exist!"),
+            Json("Warn with given case number doesn't exist!"),
         )
             .into_response());
     };
 
-    if warn.guild_id != guild_id.get() as i64 {
-        return Err((
-            StatusCode::FORBIDDEN,
-            Json("Given warn id doesn't belong to your guild!"),
-        )
-            .into_response());
-    }
-
-    let warn_model = WarnModel::from_warn(&services, &warn).await;
+    let evidence = EvidenceModel::from_case(&services, guild_id, warn.case_number).await;
+    let warn_model = WarnModel::from_warn(&services, &warn, show_moderator, evidence).await;
 
     Ok(Json(warn_model))
 }
 
-// /guilds/:guild_id/warns/:warn_id
+// /guilds/:guild_id/warns/:case_number
 pub async fn update_warn(
     State(services): State<Arc<Services>>,
-    ModLogEndpointParams(guild_id, _): ModLogEndpointParams<WarnModPermission>,
-    Path(WarnIdParam { warn_id }): Path<WarnIdParam>,
-    Json(mut new_warn): Json<WarnModel>,
+    ModLogEndpointParams(guild_id, show_moderator, _): ModLogEndpointParams<WarnModPermission>,
+    User(user_id): User,
+    Path(CaseNumberParam { case_number }): Path<CaseNumberParam>,
+    Json(new_warn): Json<WarnModel>,
 ) -> Result<(), Response> {
-    new_warn.reason = new_warn.reason.trim().to_string();
-
     let warn_service =
         extract_service::<WarnService>(&services).map_err(|err| err.into_response())?;
 
-    let mut warn = if let Some(warn) = warn_service.fetch_warn(warn_id).await {
+    let mut warn = if let Some(warn) = warn_service
+        .fetch_guild_warn_with_case_number(guild_id, case_number)
+        .await
+    {
         warn
     } else {
         return Err((
             StatusCode::NOT_FOUND,
-            Json("Warn with given id doesn't exist!"),
+            Json("Warn with given case number doesn't exist!"),
         )
             .into_response());
     };
 
-    if warn.guild_id != guild_id.get() as i64 {
-        return Err((
-            StatusCode::FORBIDDEN,
-            Json("Given warn id doesn't belong to your guild!"),
-        )
-            .into_response());
-    }
-
     if warn.id != new_warn.id
+        || warn.case_number != new_warn.case_number
         || warn.user_id.to_string() != new_warn.user.id
         || warn.warn_time != new_warn.action_time
-        || warn.moderator_user_id.to_string() != new_warn.moderator_user.id
+        || (show_moderator && warn.moderator_user_id.to_string() != new_warn.moderator_user.id)
     {
         return Err((
             StatusCode::BAD_REQUEST,
@@ -133,18 +128,32 @@ pub async fn update_warn(
             .into_response());
     }
 
-    if warn.pardoned && !new_warn.pardoned {
-        return Err((StatusCode::BAD_REQUEST, Json("You can't un-pardon a warn!")).into_response());
+    if let Err(message) = validate_warn_update(&warn, new_warn.pardoned) {
+        return Err((StatusCode::BAD_REQUEST, Json(message)).into_response());
     }
 
-    warn.reason = if new_warn.reason.is_empty() {
-        "No reason specified".to_string()
-    } else {
-        new_warn.reason.clone()
-    };
+    let reason = normalize_reason(&new_warn.reason);
+    let mut changes = vec![];
+    if warn.reason != reason {
+        changes.push(format!("Reason changed to: {}", reason));
+    }
+    if !warn.pardoned && new_warn.pardoned {
+        changes.push("Pardoned".to_string());
+    }
+
+    warn.reason = reason;
     warn.pardoned = new_warn.pardoned;
 
-    warn_service.update_warn(warn).await;
+    if warn_service.update_warn(warn.clone()).await.is_some() {
+        log_infraction_changes(
+            &services,
+            guild_id,
+            user_id,
+            &Infraction::Warn(warn),
+            &changes,
+        )
+        .await;
+    }
 
     Ok(())
 }
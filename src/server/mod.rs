@@ -29,16 +29,20 @@ use tower_http::trace::TraceLayer;
 use tracing::error;
 use uuid::Uuid;
 
-use crate::database::settings::{PRIVACY_ADMIN_ONLY, PRIVACY_EVERYONE, PRIVACY_STAFF_ONLY};
-use crate::discord::util::is_staff;
+use crate::discord::util::{can_view_mod_log, can_view_moderator, is_staff};
+use crate::server::endpoint::appeal::{get_appeal, get_appeal_page, get_appeals, submit_appeal};
 use crate::server::endpoint::ban::{get_ban, get_bans, update_ban};
 use crate::server::endpoint::captcha::{get_captcha_page, submit_captcha};
 use crate::server::endpoint::hardban::{get_hardban, get_hardbans, update_hardban};
 use crate::server::endpoint::kick::{get_kick, get_kicks, update_kick};
 use crate::server::endpoint::login::login;
 use crate::server::endpoint::mute::{get_mute, get_mutes, update_mute};
+use crate::server::endpoint::note::{create_note, delete_note, get_notes};
+use crate::server::endpoint::report::{get_report, get_reports};
 use crate::server::endpoint::self_user::get_self;
-use crate::server::endpoint::settings::{get_setting, reset_setting, update_setting};
+use crate::server::endpoint::settings::{
+    get_setting, preview_dm_template, reset_setting, update_setting,
+};
 use crate::server::endpoint::softban::{get_softban, get_softbans, update_softban};
 use crate::server::endpoint::warn::{get_warn, get_warns, update_warn};
 use crate::service::guild::GuildService;
@@ -160,7 +164,8 @@ impl FromRequestParts<AxumState> for User {
     }
 }
 
-pub struct ModLogEndpointParams<T: ModPermission>(GuildId, PhantomData<T>);
+// the flag tells whether moderators of infractions can be shown to the requesting user
+pub struct ModLogEndpointParams<T: ModPermission>(GuildId, bool, PhantomData<T>);
 
 #[derive(Deserialize)]
 pub struct GuildPathParams {
@@ -224,19 +229,8 @@ impl<T: ModPermission> FromRequestParts<AxumState> for ModLogEndpointParams<T> {
             .map_err(|err| err.into_response())?;
         let setting = setting_service.get_setting(guild_id).await;
 
-        let privacy_setting = setting.privacy_mod_log;
         if is_read {
-            let is_authorized = if privacy_setting == PRIVACY_EVERYONE {
-                true
-            } else if privacy_setting == PRIVACY_STAFF_ONLY {
-                is_staff(permissions)
-            } else if privacy_setting == PRIVACY_ADMIN_ONLY {
-                permissions.administrator()
-            } else {
-                false
-            };
-
-            if !is_authorized {
+            if !can_view_mod_log(setting.privacy_mod_log, permissions) {
                 return Err((
                     StatusCode::FORBIDDEN,
                     Json("Server settings prevent you from viewing private information!"),
@@ -257,7 +251,72 @@ impl<T: ModPermission> FromRequestParts<AxumState> for ModLogEndpointParams<T> {
             }
         }
 
-        Ok(ModLogEndpointParams(guild_id, PhantomData))
+        let show_moderator = can_view_moderator(&setting, permissions);
+
+        Ok(ModLogEndpointParams(guild_id, show_moderator, PhantomData))
+    }
+}
+
+// for endpoints that aren't covered by privacy settings, e.g. moderator notes and reports
+pub struct StaffEndpointParams(GuildId);
+
+impl FromRequestParts<AxumState> for StaffEndpointParams {
+    type Rejection = Response;
+
+    async fn from_request_parts(
+        parts: &mut Parts,
+        state: &AxumState,
+    ) -> Result<Self, Self::Rejection> {
+        match parts.method {
+            Method::GET | Method::POST | Method::DELETE => (),
+            _ => return Err(StatusCode::METHOD_NOT_ALLOWED.into_response()),
+        }
+
+        let Path(GuildPathParams { guild_id }) =
+            Path::<GuildPathParams>::from_request_parts(parts, state)
+                .await
+                .map_err(|_err| StatusCode::INTERNAL_SERVER_ERROR.into_response())?;
+
+        let guild_id = GuildId::new(guild_id.get());
+
+        let User(user_id) = User::from_request_parts(parts, state)
+            .await
+            .map_err(|err| err.into_response())?;
+
+        let guild_service =
+            extract_service::<GuildService>(&state.services).map_err(|err| err.into_response())?;
+
+        let member = guild_service
+            .get_member(guild_id, user_id)
+            .await
+            .map_err(|_err| {
+                (
+                    StatusCode::FORBIDDEN,
+                    Json("This server either doesn't exist or you aren't in it!"),
+                )
+                    .into_response()
+            })?;
+
+        let permissions = guild_service
+            .get_permissions(user_id, &member.roles, guild_id)
+            .await
+            .map_err(|_err| {
+                (
+                    StatusCode::FORBIDDEN,
+                    Json("This server either doesn't exist or you aren't in it!"),
+                )
+                    .into_response()
+            })?;
+
+        if !is_staff(permissions) {
+            return Err((
+                StatusCode::FORBIDDEN,
+                Json("Only staff members can access this information!"),
+            )
+                .into_response());
+        }
+
+        Ok(StaffEndpointParams(guild_id))
     }
 }
 
@@ -299,32 +358,51 @@ pub async fn run_server(
         .route("/guilds/{guild_id}/settings", get(get_setting))
         .route("/guilds/{guild_id}/settings", post(update_setting))
         .route("/guilds/{guild_id}/settings", delete(reset_setting))
+        .route(
+            "/guilds/{guild_id}/settings/dm-preview",
+            post(preview_dm_template),
+        )
         .route("/captcha/{guild_id}/{user_id}", get(get_captcha_page))
         .route("/captcha/{guild_id}/{user_id}", post(submit_captcha))
+        .route("/appeal/{token}", get(get_appeal_page))
+        .route("/appeal/{token}", post(submit_appeal))
+        .route("/guilds/{guild_id}/appeals", get(get_appeals))
+        .route("/guilds/{guild_id}/appeals/{case_number}", get(get_appeal))
         .route("/guilds/{guild_id}/bans", get(get_bans))
-        .route("/guilds/{guild_id}/bans/{ban_id}", get(get_ban))
-        .route("/guilds/{guild_id}/bans/{ban_id}", post(update_ban))
+        .route("/guilds/{guild_id}/bans/{case_number}", get(get_ban))
+        .route("/guilds/{guild_id}/bans/{case_number}", post(update_ban))
         .route("/guilds/{guild_id}/hardbans", get(get_hardbans))
-        .route("/guilds/{guild_id}/hardbans/{hardban_id}", get(get_hardban))
         .route(
-            "/guilds/{guild_id}/hardbans/{hardban_id}",
+            "/guilds/{guild_id}/hardbans/{case_number}",
+            get(get_hardban),
+        )
+        .route(
+            "/guilds/{guild_id}/hardbans/{case_number}",
             post(update_hardban),
         )
         .route("/guilds/{guild_id}/kicks", get(get_kicks))
-        .route("/guilds/{guild_id}/kicks/{kick_id}", get(get_kick))
-        .route("/guilds/{guild_id}/kicks/{kick_id}", post(update_kick))
+        .route("/guilds/{guild_id}/kicks/{case_number}", get(get_kick))
+        .route("/guilds/{guild_id}/kicks/{case_number}", post(update_kick))
         .route("/guilds/{guild_id}/mutes", get(get_mutes))
-        .route("/guilds/{guild_id}/mutes/{mute_id}", get(get_mute))
-        .route("/guilds/{guild_id}/mutes/{mute_id}", post(update_mute))
+        .route("/guilds/{guild_id}/mutes/{case_number}", get(get_mute))
+        .route("/guilds/{guild_id}/mutes/{case_number}", post(update_mute))
+        .route("/guilds/{guild_id}/notes", get(get_notes))
+        .route("/guilds/{guild_id}/notes", post(create_note))
+        .route("/guilds/{guild_id}/notes/{id}", delete(delete_note))
+        .route("/guilds/{guild_id}/reports", get(get_reports))
+        .route("/guilds/{guild_id}/reports/{id}", get(get_report))
         .route("/guilds/{guild_id}/softbans", get(get_softbans))
-        .route("/guilds/{guild_id}/softbans/{softban_id}", get(get_softban))
         .route(
-            "/guilds/{guild_id}/softbans/{softban_id}",
+            "/guilds/{guild_id}/softbans/{case_number}",
+            get(get_softban),
+        )
+        .route(
+            "/guilds/{guild_id}/softbans/{case_number}",
             post(update_softban),
         )
         .route("/guilds/{guild_id}/warns", get(get_warns))
-        .route("/guilds/{guild_id}/warns/{warn_id}", get(get_warn))
-        .route("/guilds/{guild_id}/warns/{warn_id}", post(update_warn))
+        .route("/guilds/{guild_id}/warns/{case_number}", get(get_warn))
+        .route("/guilds/{guild_id}/warns/{case_number}", post(update_warn))
         .layer(
             CorsLayer::new()
                 .allow_origin(cors_origin)
@@ -0,0 +1,62 @@
+use std::num::NonZeroU64;
+
+use serde::{Deserialize, Serialize};
+use serenity::model::id::UserId;
+
+use crate::database::appeals::Appeal;
+use crate::server::model::user::UserModel;
+use crate::service::Services;
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AppealModel {
+    pub id: i32,
+    pub case_number: i32,
+    pub infraction_type: i32,
+    pub user: UserModel,
+    pub message: String,
+    pub appeal_time: i64,
+    pub status: i32,
+    pub reviewer_user: Option<UserModel>,
+    pub review_time: i64,
+}
+
+impl AppealModel {
+    pub async fn from_appeal(
+        services: &Services,
+        appeal: &Appeal,
+        show_moderator: bool,
+    ) -> AppealModel {
+        let user = if let Some(id) = NonZeroU64::new(appeal.user_id as u64) {
+            let user_id = UserId::new(id.get());
+            UserModel::from_id(services, user_id).await
+        } else {
+            // should never happen since user ids come from Discord
+            Default::default()
+        };
+
+        // pending appeals don't have a reviewer yet
+        let reviewer_user = if let Some(id) = NonZeroU64::new(appeal.reviewer_user_id as u64) {
+            if show_moderator {
+                let user_id = UserId::new(id.get());
+                Some(UserModel::from_id(services, user_id).await)
+            } else {
+                Some(UserModel::anonymous())
+            }
+        } else {
+            None
+        };
+
+        AppealModel {
+            id: appeal.id,
+            case_number: appeal.case_number,
+            infraction_type: appeal.infraction_type,
+            user,
+            message: appeal.message.clone(),
+            appeal_time: appeal.appeal_time,
+            status: appeal.status,
+            reviewer_user,
+            review_time: appeal.review_time,
+        }
+    }
+}
@@ -4,6 +4,7 @@ use serde::{Deserialize, Serialize};
 use serenity::model::id::UserId;
 
 use crate::database::bans::Ban;
+use crate::server::model::evidence::EvidenceModel;
 use crate::server::model::user::UserModel;
 use crate::service::Services;
 
@@ -11,16 +12,24 @@ use crate::service::Services;
 #[serde(rename_all = "camelCase")]
 pub struct BanModel {
     pub id: i32,
+    pub case_number: i32,
     pub user: UserModel,
     pub moderator_user: UserModel,
     pub action_time: i64,
     pub expiration_time: i64,
     pub unbanned: bool,
     pub reason: String,
+    #[serde(default)]
+    pub evidence: Vec<EvidenceModel>,
 }
 
 impl BanModel {
-    pub async fn from_ban(services: &Services, ban: &Ban) -> BanModel {
+    pub async fn from_ban(
+        services: &Services,
+        ban: &Ban,
+        show_moderator: bool,
+        evidence: Vec<EvidenceModel>,
+    ) -> BanModel {
         let user = if let Some(id) = NonZeroU64::new(ban.user_id as u64) {
             let user_id = UserId::new(id.get());
             UserModel::from_id(services, user_id).await
@@ -29,7 +38,9 @@ impl BanModel {
             Default::default()
         };
 
-        let moderator_user = if let Some(id) = NonZeroU64::new(ban.moderator_user_id as u64) {
+        let moderator_user = if !show_moderator {
+            UserModel::anonymous()
+        } else if let Some(id) = NonZeroU64::new(ban.moderator_user_id as u64) {
             let user_id = UserId::new(id.get());
             UserModel::from_id(services, user_id).await
         } else {
@@ -38,12 +49,14 @@ impl BanModel {
 
         BanModel {
             id: ban.id,
+            case_number: ban.case_number,
             user,
             moderator_user,
             action_time: ban.ban_time,
             expiration_time: ban.expire_time,
             unbanned: ban.unbanned,
             reason: ban.reason.clone(),
+            evidence,
         }
     }
 }
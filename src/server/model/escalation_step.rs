@@ -0,0 +1,29 @@
+use serde::{Deserialize, Serialize};
+
+use crate::database::escalation_steps::EscalationStep;
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EscalationStepModel {
+    pub infraction_type: i32,
+    pub threshold: i32,
+    pub action: i32,
+    pub action_duration: i32,
+    pub action_duration_type: i32,
+    pub lookback_duration: i32,
+    pub lookback_duration_type: i32,
+}
+
+impl EscalationStepModel {
+    pub fn from_escalation_step(step: &EscalationStep) -> EscalationStepModel {
+        EscalationStepModel {
+            infraction_type: step.infraction_type,
+            threshold: step.threshold,
+            action: step.action,
+            action_duration: step.action_duration,
+            action_duration_type: step.action_duration_type,
+            lookback_duration: step.lookback_duration,
+            lookback_duration_type: step.lookback_duration_type,
+        }
+    }
+}
@@ -0,0 +1,82 @@
+use std::collections::HashMap;
+use std::num::NonZeroU64;
+
+use serde::{Deserialize, Serialize};
+use serenity::model::id::{GuildId, UserId};
+
+use crate::database::evidence::Evidence;
+use crate::server::model::user::UserModel;
+use crate::service::evidence::EvidenceService;
+use crate::service::Services;
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EvidenceModel {
+    pub id: i32,
+    pub user: UserModel,
+    pub channel_id: String,
+    pub message_id: String,
+    pub content: String,
+    pub attachments: Vec<String>,
+    pub message_time: i64,
+    pub record_time: i64,
+}
+
+impl EvidenceModel {
+    pub async fn from_evidence(services: &Services, evidence: &Evidence) -> EvidenceModel {
+        let user = if let Some(id) = NonZeroU64::new(evidence.user_id as u64) {
+            let user_id = UserId::new(id.get());
+            UserModel::from_id(services, user_id).await
+        } else {
+            Default::default()
+        };
+
+        EvidenceModel {
+            id: evidence.id,
+            user,
+            channel_id: evidence.channel_id.to_string(),
+            message_id: evidence.message_id.to_string(),
+            content: evidence.content.clone(),
+            attachments: evidence.attachments.clone(),
+            message_time: evidence.message_time,
+            record_time: evidence.record_time,
+        }
+    }
+
+    pub async fn from_case(
+        services: &Services,
+        guild_id: GuildId,
+        case_number: i32,
+    ) -> Vec<EvidenceModel> {
+        EvidenceModel::from_cases(services, guild_id, &[case_number])
+            .await
+            .remove(&case_number)
+            .unwrap_or_default()
+    }
+
+    pub async fn from_cases(
+        services: &Services,
+        guild_id: GuildId,
+        case_numbers: &[i32],
+    ) -> HashMap<i32, Vec<EvidenceModel>> {
+        let evidence_service = if let Some(service) = services.get::<EvidenceService>() {
+            service
+        } else {
+            return HashMap::new();
+        };
+
+        let mut models = HashMap::new();
+        for (case_number, evidence) in evidence_service
+            .fetch_guild_cases_evidence(guild_id, case_numbers)
+            .await
+        {
+            let mut case_models = vec![];
+            for evidence in evidence {
+                case_models.push(EvidenceModel::from_evidence(services, &evidence).await);
+            }
+            models.insert(case_number, case_models);
+        }
+
+        models
+    }
+}
@@ -4,6 +4,7 @@ use serde::{Deserialize, Serialize};
 use serenity::model::id::UserId;
 
 use crate::database::hardbans::Hardban;
+use crate::server::model::evidence::EvidenceModel;
 use crate::server::model::user::UserModel;
 use crate::service::Services;
 
@@ -11,14 +12,22 @@ use crate::service::Services;
 #[serde(rename_all = "camelCase")]
 pub struct HardbanModel {
     pub id: i32,
+    pub case_number: i32,
     pub user: UserModel,
     pub moderator_user: UserModel,
     pub action_time: i64,
     pub reason: String,
+    #[serde(default)]
+    pub evidence: Vec<EvidenceModel>,
 }
 
 impl HardbanModel {
-    pub async fn from_hardban(services: &Services, hardban: &Hardban) -> HardbanModel {
+    pub async fn from_hardban(
+        services: &Services,
+        hardban: &Hardban,
+        show_moderator: bool,
+        evidence: Vec<EvidenceModel>,
+    ) -> HardbanModel {
         let user = if let Some(id) = NonZeroU64::new(hardban.user_id as u64) {
             let user_id = UserId::new(id.get());
             UserModel::from_id(services, user_id).await
@@ -26,7 +35,9 @@ impl HardbanModel {
             Default::default()
         };
 
-        let moderator_user = if let Some(id) = NonZeroU64::new(hardban.moderator_user_id as u64) {
+        let moderator_user = if !show_moderator {
+            UserModel::anonymous()
+        } else if let Some(id) = NonZeroU64::new(hardban.moderator_user_id as u64) {
             let user_id = UserId::new(id.get());
             UserModel::from_id(services, user_id).await
         } else {
@@ -35,10 +46,12 @@ impl HardbanModel {
 
         HardbanModel {
             id: hardban.id,
+            case_number: hardban.case_number,
             user,
             moderator_user,
             action_time: hardban.hardban_time,
             reason: hardban.reason.clone(),
+            evidence,
         }
     }
 }
@@ -4,6 +4,7 @@ use serde::{Deserialize, Serialize};
 use serenity::model::id::UserId;
 
 use crate::database::kicks::Kick;
+use crate::server::model::evidence::EvidenceModel;
 use crate::server::model::user::UserModel;
 use crate::service::Services;
 
@@ -11,6 +12,7 @@ use crate::service::Services;
 #[serde(rename_all = "camelCase")]
 pub struct KickModel {
     pub id: i32,
+    pub case_number: i32,
     pub user: UserModel,
     pub moderator_user: UserModel,
     pub action_time: i64,
@@ -19,7 +21,12 @@ pub struct KickModel {
 }
 
 impl KickModel {
-    pub async fn from_kick(services: &Services, kick: &Kick) -> KickModel {
+    pub async fn from_kick(
+        services: &Services,
+        kick: &Kick,
+        show_moderator: bool,
+        evidence: Vec<EvidenceModel>,
+    ) -> KickModel {
         let user = if let Some(id) = NonZeroU64::new(kick.user_id as u64) {
             let user_id = UserId::new(id.get());
             UserModel::from_id(services, user_id).await
@@ -27,7 +34,9 @@ impl KickModel {
             Default::default()
         };
 
-        let moderator_user = if let Some(id) = NonZeroU64::new(kick.moderator_user_id as u64) {
+        let moderator_user = if !show_moderator {
+            UserModel::anonymous()
+        } else if let Some(id) = NonZeroU64::new(kick.moderator_user_id as u64) {
             let user_id = UserId::new(id.get());
             UserModel::from_id(services, user_id).await
         } else {
@@ -36,10 +45,12 @@ impl KickModel {
 
         KickModel {
             id: kick.id,
+            case_number: kick.case_number,
             user,
             moderator_user,
             action_time: kick.kick_time,
             reason: kick.reason.clone(),
+            evidence,
             pardoned: kick.pardoned,
         }
     }
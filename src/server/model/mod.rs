@@ -1,9 +1,14 @@
+pub mod appeal;
 pub mod ban;
 pub mod channel;
+pub mod escalation_step;
+pub mod evidence;
 pub mod guild;
 pub mod hardban;
 pub mod kick;
 pub mod mute;
+pub mod note;
+pub mod report;
 pub mod role;
 pub mod self_user;
 pub mod setting;
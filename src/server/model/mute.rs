@@ -4,6 +4,7 @@ use serde::{Deserialize, Serialize};
 use serenity::model::id::UserId;
 
 use crate::database::mutes::Mute;
+use crate::server::model::evidence::EvidenceModel;
 use crate::server::model::user::UserModel;
 use crate::service::Services;
 
@@ -11,6 +12,7 @@ use crate::service::Services;
 #[serde(rename_all = "camelCase")]
 pub struct MuteModel {
     pub id: i32,
+    pub case_number: i32,
     pub user: UserModel,
     pub moderator_user: UserModel,
     pub action_time: i64,
@@ -21,7 +23,12 @@ pub struct MuteModel {
 }
 
 impl MuteModel {
-    pub async fn from_mute(services: &Services, mute: &Mute) -> MuteModel {
+    pub async fn from_mute(
+        services: &Services,
+        mute: &Mute,
+        show_moderator: bool,
+        evidence: Vec<EvidenceModel>,
+    ) -> MuteModel {
         let user = if let Some(id) = NonZeroU64::new(mute.user_id as u64) {
             let user_id = UserId::new(id.get());
             UserModel::from_id(services, user_id).await
@@ -29,7 +36,9 @@ impl MuteModel {
             Default::default()
         };
 
-        let moderator_user = if let Some(id) = NonZeroU64::new(mute.moderator_user_id as u64) {
+        let moderator_user = if !show_moderator {
+            UserModel::anonymous()
+        } else if let Some(id) = NonZeroU64::new(mute.moderator_user_id as u64) {
             let user_id = UserId::new(id.get());
             UserModel::from_id(services, user_id).await
         } else {
@@ -38,12 +47,14 @@ impl MuteModel {
 
         MuteModel {
             id: mute.id,
+            case_number: mute.case_number,
             user,
             moderator_user,
             action_time: mute.mute_time,
             expiration_time: mute.expire_time,
             unmuted: mute.unmuted,
             reason: mute.reason.clone(),
+            evidence,
             pardoned: mute.pardoned,
         }
     }
@@ -0,0 +1,51 @@
+use std::num::NonZeroU64;
+
+use serde::{Deserialize, Serialize};
+use serenity::model::id::UserId;
+
+use crate::database::notes::Note;
+use crate::server::model::user::UserModel;
+use crate::service::Services;
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NoteModel {
+    pub id: i32,
+    pub user: UserModel,
+    pub moderator_user: UserModel,
+    pub note_time: i64,
+    pub message: String,
+}
+
+impl NoteModel {
+    pub async fn from_note(services: &Services, note: &Note) -> NoteModel {
+        let user = if let Some(id) = NonZeroU64::new(note.user_id as u64) {
+            let user_id = UserId::new(id.get());
+            UserModel::from_id(services, user_id).await
+        } else {
+            Default::default()
+        };
+
+        let moderator_user = if let Some(id) = NonZeroU64::new(note.moderator_user_id as u64) {
+            let user_id = UserId::new(id.get());
+            UserModel::from_id(services, user_id).await
+        } else {
+            Default::default()
+        };
+
+        NoteModel {
+            id: note.id,
+            user,
+            moderator_user,
+            note_time: note.note_time,
+            message: note.message.clone(),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateNoteModel {
+    pub user_id: String,
+    pub message: String,
+}
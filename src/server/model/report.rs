@@ -0,0 +1,65 @@
+use std::num::NonZeroU64;
+
+use serde::{Deserialize, Serialize};
+use serenity::model::id::UserId;
+
+use crate::database::reports::Report;
+use crate::server::model::user::UserModel;
+use crate::service::Services;
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReportModel {
+    pub id: i32,
+    pub user: UserModel,
+    pub reporters: Vec<UserModel>,
+    pub channel_id: String,
+    pub message_id: String,
+    pub content: String,
+    pub attachments: Vec<String>,
+    pub message_time: i64,
+    pub report_time: i64,
+    pub status: i32,
+    pub moderator_user: Option<UserModel>,
+    pub update_time: i64,
+    pub action_taken: bool,
+}
+
+async fn user_model_from_id(services: &Services, user_id: i64) -> Option<UserModel> {
+    if let Some(id) = NonZeroU64::new(user_id as u64) {
+        Some(UserModel::from_id(services, UserId::new(id.get())).await)
+    } else {
+        None
+    }
+}
+
+impl ReportModel {
+    pub async fn from_report(services: &Services, report: &Report) -> ReportModel {
+        let user = user_model_from_id(services, report.target_user_id)
+            .await
+            .unwrap_or_default();
+
+        let mut reporters = vec![];
+        for reporter_user_id in &report.reporter_user_ids {
+            if let Some(reporter) = user_model_from_id(services, *reporter_user_id).await {
+                reporters.push(reporter);
+            }
+        }
+
+        ReportModel {
+            id: report.id,
+            user,
+            reporters,
+            channel_id: report.channel_id.to_string(),
+            message_id: report.message_id.to_string(),
+            content: report.content.clone(),
+            attachments: report.attachments.clone(),
+            message_time: report.message_time,
+            report_time: report.report_time,
+            status: report.status,
+            moderator_user: user_model_from_id(services, report.moderator_user_id).await,
+            update_time: report.update_time,
+            action_taken: report.action_taken,
+        }
+    }
+}
@@ -1,9 +1,21 @@
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Deserializer, Serialize};
 
 use crate::server::model::channel::ChannelModel;
+use crate::server::model::escalation_step::EscalationStepModel;
 use crate::server::model::guild::GuildModel;
 use crate::server::model::role::RoleModel;
 
+// tells a field that was sent as null apart from one that wasn't sent at all, the latter is None
+fn deserialize_present<'de, T, D>(deserializer: D) -> Result<Option<T>, D::Error>
+where
+    T: Deserialize<'de>,
+    D: Deserializer<'de>,
+{
+    T::deserialize(deserializer).map(Some)
+}
+
+// fields added after the dashboard shipped are optional, saves that don't send them keep the
+// stored values instead of resetting them
 #[derive(Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct SettingModel {
@@ -30,24 +42,108 @@ pub struct SettingModel {
     pub invite_link_remover_action: i32,
     pub invite_link_remover_action_duration: i32,
     pub invite_link_remover_action_duration_type: i32,
+    #[serde(default, deserialize_with = "deserialize_present")]
+    pub invite_link_remover_allowlist: Option<Option<String>>,
     pub privacy_settings: i32,
     pub privacy_mod_log: i32,
-    pub softban_threshold: i32,
-    pub softban_action: i32,
-    pub softban_action_duration: i32,
-    pub softban_action_duration_type: i32,
-    pub kick_threshold: i32,
-    pub kick_action: i32,
-    pub kick_action_duration: i32,
-    pub kick_action_duration_type: i32,
-    pub mute_threshold: i32,
-    pub mute_action: i32,
-    pub mute_action_duration: i32,
-    pub mute_action_duration_type: i32,
-    pub warn_threshold: i32,
-    pub warn_action: i32,
-    pub warn_action_duration: i32,
-    pub warn_action_duration_type: i32,
     pub mods_can_edit_tags: bool,
     pub spam_filter: bool,
+    #[serde(default)]
+    pub mute_backend: Option<i32>,
+    #[serde(default, deserialize_with = "deserialize_present")]
+    pub mute_role: Option<Option<RoleModel>>,
+    #[serde(default)]
+    pub warn_decay_days: Option<i32>,
+    #[serde(default)]
+    pub mute_decay_days: Option<i32>,
+    #[serde(default)]
+    pub kick_decay_days: Option<i32>,
+    #[serde(default)]
+    pub softban_decay_days: Option<i32>,
+    #[serde(default, deserialize_with = "deserialize_present")]
+    pub appeals_channel: Option<Option<ChannelModel>>,
+    #[serde(default)]
+    pub report_notify_reporter: Option<bool>,
+    #[serde(default)]
+    pub warn_dm: Option<bool>,
+    #[serde(default, deserialize_with = "deserialize_present")]
+    pub warn_dm_template: Option<Option<String>>,
+    #[serde(default)]
+    pub mute_dm: Option<bool>,
+    #[serde(default, deserialize_with = "deserialize_present")]
+    pub mute_dm_template: Option<Option<String>>,
+    #[serde(default)]
+    pub kick_dm: Option<bool>,
+    #[serde(default, deserialize_with = "deserialize_present")]
+    pub kick_dm_template: Option<Option<String>>,
+    #[serde(default)]
+    pub ban_dm: Option<bool>,
+    #[serde(default, deserialize_with = "deserialize_present")]
+    pub ban_dm_template: Option<Option<String>>,
+    #[serde(default)]
+    pub softban_dm: Option<bool>,
+    #[serde(default, deserialize_with = "deserialize_present")]
+    pub softban_dm_template: Option<Option<String>>,
+    #[serde(default)]
+    pub hardban_dm: Option<bool>,
+    #[serde(default, deserialize_with = "deserialize_present")]
+    pub hardban_dm_template: Option<Option<String>>,
+    // hides moderators from targets and from every mod log viewer except admins, including
+    // non-admin staff when the mod log is staff only
+    #[serde(default)]
+    pub anonymous_moderation: Option<bool>,
+    #[serde(default)]
+    pub spam_filter_exempt_channels: Option<Vec<ChannelModel>>,
+    #[serde(default)]
+    pub spam_filter_exempt_roles: Option<Vec<RoleModel>>,
+    #[serde(default)]
+    pub invite_link_remover_exempt_channels: Option<Vec<ChannelModel>>,
+    #[serde(default)]
+    pub invite_link_remover_exempt_roles: Option<Vec<RoleModel>>,
+    #[serde(default)]
+    pub word_filter_exempt_channels: Option<Vec<ChannelModel>>,
+    #[serde(default)]
+    pub word_filter_exempt_roles: Option<Vec<RoleModel>>,
+    #[serde(default)]
+    pub link_filter: Option<bool>,
+    #[serde(default, deserialize_with = "deserialize_present")]
+    pub link_filter_allowlist: Option<Option<String>>,
+    #[serde(default, deserialize_with = "deserialize_present")]
+    pub link_filter_denylist: Option<Option<String>>,
+    #[serde(default)]
+    pub link_filter_allowlist_only: Option<bool>,
+    #[serde(default)]
+    pub link_filter_action: Option<i32>,
+    #[serde(default)]
+    pub link_filter_action_duration: Option<i32>,
+    #[serde(default)]
+    pub link_filter_action_duration_type: Option<i32>,
+    #[serde(default)]
+    pub link_filter_exempt_channels: Option<Vec<ChannelModel>>,
+    #[serde(default)]
+    pub link_filter_exempt_roles: Option<Vec<RoleModel>>,
+    #[serde(default)]
+    pub escalation_steps: Option<Vec<EscalationStepModel>>,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DmTemplatePreviewModel {
+    pub action: i32,
+    pub template: String,
+    pub hide_moderator: bool,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DmPreviewModel {
+    pub title: String,
+    pub description: String,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BlocklistEntryErrorModel {
+    pub entry: String,
+    pub error: String,
 }
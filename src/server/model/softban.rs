@@ -4,6 +4,7 @@ use serde::{Deserialize, Serialize};
 use serenity::model::id::UserId;
 
 use crate::database::softbans::Softban;
+use crate::server::model::evidence::EvidenceModel;
 use crate::server::model::user::UserModel;
 use crate::service::Services;
 
@@ -11,6 +12,7 @@ use crate::service::Services;
 #[serde(rename_all = "camelCase")]
 pub struct SoftbanModel {
     pub id: i32,
+    pub case_number: i32,
     pub user: UserModel,
     pub moderator_user: UserModel,
     pub action_time: i64,
@@ -19,7 +21,12 @@ pub struct SoftbanModel {
 }
 
 impl SoftbanModel {
-    pub async fn from_softban(services: &Services, softban: &Softban) -> SoftbanModel {
+    pub async fn from_softban(
+        services: &Services,
+        softban: &Softban,
+        show_moderator: bool,
+        evidence: Vec<EvidenceModel>,
+    ) -> SoftbanModel {
         let user = if let Some(id) = NonZeroU64::new(softban.user_id as u64) {
             let user_id = UserId::new(id.get());
             UserModel::from_id(services, user_id).await
@@ -27,7 +34,9 @@ impl SoftbanModel {
             Default::default()
         };
 
-        let moderator_user = if let Some(id) = NonZeroU64::new(softban.moderator_user_id as u64) {
+        let moderator_user = if !show_moderator {
+            UserModel::anonymous()
+        } else if let Some(id) = NonZeroU64::new(softban.moderator_user_id as u64) {
             let user_id = UserId::new(id.get());
             UserModel::from_id(services, user_id).await
         } else {
@@ -36,10 +45,12 @@ impl SoftbanModel {
 
         SoftbanModel {
             id: softban.id,
+            case_number: softban.case_number,
             user,
             moderator_user,
             action_time: softban.softban_time,
             reason: softban.reason.clone(),
+            evidence,
             pardoned: softban.pardoned,
         }
     }
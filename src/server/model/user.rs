@@ -23,6 +23,15 @@ impl Default for UserModel {
 }
 
 impl UserModel {
+    // stands in for moderators when the guild has anonymous moderation enabled
+    pub fn anonymous() -> UserModel {
+        UserModel {
+            id: "0".to_string(),
+            username: "Anonymous Moderator".to_string(),
+            avatar_url: "https://cdn.discordapp.com/embed/avatars/0.png".to_string(),
+        }
+    }
+
     pub async fn from_id(services: &Services, user_id: UserId) -> UserModel {
         let user_model = if let Some(guild_service) = services.get::<GuildService>() {
             guild_service
@@ -0,0 +1,190 @@
+use std::num::{NonZeroU32, NonZeroU64};
+use std::sync::Arc;
+
+use jsonwebtoken::errors::ErrorKind;
+use jsonwebtoken::{decode, encode, Algorithm, DecodingKey, EncodingKey, Header, Validation};
+use serde::{Deserialize, Serialize};
+use serenity::model::id::{GuildId, UserId};
+use tracing::error;
+use typemap_rev::TypeMapKey;
+
+use crate::database::appeals::{Appeal, AppealsRepository};
+use crate::database::settings::Setting;
+use crate::util::now;
+use crate::Config;
+
+// long enough to cover the bans and mutes people usually appeal, links don't have to live forever
+const APPEAL_TOKEN_LIFETIME_SECONDS: u64 = 30 * 24 * 60 * 60;
+
+impl TypeMapKey for AppealService {
+    type Value = AppealService;
+}
+
+pub struct AppealService {
+    pub config: Arc<Config>,
+    pub repository: AppealsRepository,
+}
+
+#[derive(Deserialize, Serialize)]
+struct AppealClaims {
+    #[serde(rename = "guildId")]
+    guild_id: String,
+    #[serde(rename = "userId")]
+    user_id: String,
+    #[serde(rename = "caseNumber")]
+    case_number: i32,
+    exp: u64,
+}
+
+pub struct AppealTarget {
+    pub guild_id: GuildId,
+    pub user_id: UserId,
+    pub case_number: i32,
+}
+
+impl AppealService {
+    pub fn new(config: Arc<Config>, repository: AppealsRepository) -> AppealService {
+        AppealService { config, repository }
+    }
+
+    // returns None if the guild doesn't accept appeals
+    pub fn create_appeal_url(
+        &self,
+        setting: &Setting,
+        guild_id: GuildId,
+        user_id: UserId,
+        case_number: i32,
+    ) -> Option<String> {
+        if setting.appeals_channel_id == 0 {
+            return None;
+        }
+
+        let claims = AppealClaims {
+            guild_id: guild_id.to_string(),
+            user_id: user_id.to_string(),
+            case_number,
+            exp: now() + APPEAL_TOKEN_LIFETIME_SECONDS,
+        };
+
+        match encode(
+            &Header::new(Algorithm::HS512),
+            &claims,
+            &EncodingKey::from_secret(self.config.server_secret.as_bytes()),
+        ) {
+            Ok(token) => Some(format!("{}/appeal/{}", self.config.self_url, token)),
+            Err(err) => {
+                error!("failed to create appeal token {:?}", err);
+                None
+            }
+        }
+    }
+
+    pub fn verify_appeal_token(&self, token: &str) -> Option<AppealTarget> {
+        let claims = match decode::<AppealClaims>(
+            token,
+            &DecodingKey::from_secret(self.config.server_secret.as_bytes()),
+            &Validation::new(Algorithm::HS512),
+        ) {
+            Ok(data) => data.claims,
+            Err(error) => {
+                return match error.kind() {
+                    ErrorKind::InvalidSignature | ErrorKind::ExpiredSignature => None,
+                    _ => {
+                        error!("failed to decode appeal token {:?}", error.kind());
+                        None
+                    }
+                };
+            }
+        };
+
+        match (
+            claims.guild_id.parse::<NonZeroU64>(),
+            claims.user_id.parse::<NonZeroU64>(),
+        ) {
+            (Ok(guild_id), Ok(user_id)) => Some(AppealTarget {
+                guild_id: GuildId::new(guild_id.get()),
+                user_id: UserId::new(user_id.get()),
+                case_number: claims.case_number,
+            }),
+            _ => {
+                // secret leaked or we have a problem with token generation
+                error!("received an appeal token with valid signature with invalid ids");
+                None
+            }
+        }
+    }
+
+    pub async fn fetch_appeal(&self, id: i32) -> Option<Appeal> {
+        self.repository
+            .fetch_appeal(id)
+            .await
+            .map_err(|err| {
+                error!("failed to fetch appeal {:?}", err);
+                err
+            })
+            .ok()
+            .flatten()
+    }
+
+    pub async fn fetch_guild_appeal_with_case_number(
+        &self,
+        guild_id: GuildId,
+        case_number: i32,
+    ) -> Option<Appeal> {
+        self.repository
+            .fetch_guild_appeal_with_case_number(guild_id.get() as i64, case_number)
+            .await
+            .map_err(|err| {
+                error!("failed to fetch appeal with case number {:?}", err);
+                err
+            })
+            .ok()
+            .flatten()
+    }
+
+    pub async fn fetch_guild_appeals(&self, guild_id: GuildId, page: NonZeroU32) -> Vec<Appeal> {
+        self.repository
+            .fetch_guild_appeals(guild_id.get() as i64, page.get())
+            .await
+            .map_err(|err| {
+                error!("failed to fetch guild appeals {:?}", err);
+                err
+            })
+            .ok()
+            .unwrap_or_default()
+    }
+
+    pub async fn fetch_guild_appeal_count(&self, guild_id: GuildId) -> i64 {
+        self.repository
+            .fetch_guild_appeal_count(guild_id.get() as i64)
+            .await
+            .map_err(|err| {
+                error!("failed to fetch guild appeal count {:?}", err);
+                err
+            })
+            .ok()
+            .unwrap_or(0)
+    }
+
+    pub async fn insert_appeal(&self, appeal: Appeal) -> Option<Appeal> {
+        self.repository
+            .insert_appeal(appeal)
+            .await
+            .map_err(|err| {
+                error!("failed to insert appeal {:?}", err);
+                err
+            })
+            .ok()
+    }
+
+    pub async fn update_appeal(&self, appeal: &Appeal) -> Option<()> {
+        self.repository
+            .update_appeal(appeal)
+            .await
+            .map_err(|err| {
+                error!("failed to update appeal {:?}", err);
+                err
+            })
+            .ok()
+    }
+}
@@ -12,6 +12,10 @@ use crate::database::settings::Setting;
 use crate::discord::util::mod_log::{create_mod_log_entry, CreateModLogEntryError, ModLogAction};
 use crate::discord::util::user_dm::{notify_user_for_mod_action, ModActionKind};
 use crate::discord::util::SerenityErrorExt;
+use crate::service::appeal::AppealService;
+use crate::service::case_number::CaseNumberService;
+use crate::service::evidence::{record_evidence, EvidenceSnapshot};
+use crate::service::Services;
 use crate::util::now;
 
 impl TypeMapKey for BanService {
@@ -34,6 +38,19 @@ pub enum UnbanFailure {
     Unknown,
 }
 
+// once a ban is lifted its expiration can neither be undone nor moved
+pub fn validate_ban_update(
+    ban: &Ban,
+    unbanned: bool,
+    expire_time: i64,
+) -> Result<(), &'static str> {
+    if ban.unbanned && (!unbanned || ban.expire_time != expire_time) {
+        return Err("You can't change expiration property after user has been unbanned.");
+    }
+
+    Ok(())
+}
+
 impl BanService {
     pub async fn issue_ban(
         &self,
@@ -41,11 +58,13 @@ impl BanService {
         guild_id: GuildId,
         guild_name: &str,
         setting: &Setting,
+        services: &Services,
         channel_id: Option<GenericChannelId>,
         mod_user_id: UserId,
         mod_user_tag_and_id: &str,
         target_user: &User,
         reason: String,
+        evidence: &[EvidenceSnapshot],
         duration: Option<Duration>,
     ) -> Result<(), BanFailure> {
         let now = now();
@@ -65,14 +84,38 @@ impl BanService {
             None
         };
 
+        let case_number_service = if let Some(service) = services.get::<CaseNumberService>() {
+            service
+        } else {
+            error!("couldn't get case number service!");
+            return Err(BanFailure::Unknown);
+        };
+
+        let case_number =
+            if let Some(case_number) = case_number_service.next_case_number(guild_id).await {
+                case_number
+            } else {
+                return Err(BanFailure::Unknown);
+            };
+
+        let appeal_url = if let Some(service) = services.get::<AppealService>() {
+            service.create_appeal_url(setting, guild_id, target_user.id, case_number)
+        } else {
+            error!("couldn't get appeal service!");
+            None
+        };
+
         notify_user_for_mod_action(
             http,
+            setting,
             target_user.id,
             ModActionKind::Ban { expiration_time },
             &reason,
             now,
+            case_number,
             guild_name,
             mod_user_tag_and_id,
+            appeal_url.as_deref(),
         )
         .await;
 
@@ -83,6 +126,9 @@ impl BanService {
         {
             Ok(_) => (),
             Err(err) => {
+                case_number_service
+                    .release_case_number(guild_id, case_number)
+                    .await;
                 return match err.discord_error_code() {
                     Some(50013) => Err(BanFailure::Unauthorized),
                     _ => {
@@ -103,17 +149,19 @@ impl BanService {
             reason: reason.clone(),
             expires: duration.is_some(),
             unbanned: false,
+            case_number,
+            mod_log_message_id: 0,
         };
 
         // we already issued the ban, we can ignore whether inserting to database failed or not
         // chances are it will not fail anyways.
         self.invalidate_previous_user_bans(guild_id, target_user.id)
             .await;
-        let ban_id = self.insert_ban(ban_entry).await.map(|ban| ban.id);
+        if let Some(ban) = self.insert_ban(ban_entry).await {
+            record_evidence(services, guild_id, case_number, evidence).await;
 
-        if let Some(ban_id) = ban_id {
             if let Some(mod_log_channel_id) = mod_log_channel_id {
-                if let Err(err) = create_mod_log_entry(
+                match create_mod_log_entry(
                     http,
                     mod_log_channel_id,
                     channel_id,
@@ -121,12 +169,16 @@ impl BanService {
                     target_user,
                     ModLogAction::Ban { expiration_time },
                     &reason,
-                    ban_id,
+                    case_number,
                     now,
                 )
                 .await
                 {
-                    return Err(BanFailure::ModLogError(err));
+                    Ok(message_id) => {
+                        self.update_mod_log_message_id(ban.id, message_id.get() as i64)
+                            .await;
+                    }
+                    Err(err) => return Err(BanFailure::ModLogError(err)),
                 }
             }
         }
@@ -134,7 +186,7 @@ impl BanService {
         Ok(())
     }
 
-    pub async fn unban(
+    async fn remove_ban(
         &self,
         http: &Http,
         guild_id: GuildId,
@@ -146,30 +198,89 @@ impl BanService {
             .remove_ban(guild_id, target_user_id, Some(&audit_log_reason))
             .await
         {
-            Ok(_) => (),
-            Err(err) => {
-                return match err.discord_error_code() {
-                    Some(10026) => Err(UnbanFailure::UserNotBanned),
-                    Some(50013) => Err(UnbanFailure::Unauthorized),
-                    _ => {
-                        error!("failed issue discord unban {}", err);
-                        Err(UnbanFailure::Unknown)
-                    }
-                };
-            }
+            Ok(_) => Ok(()),
+            Err(err) => match err.discord_error_code() {
+                Some(10026) => Err(UnbanFailure::UserNotBanned),
+                Some(50013) => Err(UnbanFailure::Unauthorized),
+                _ => {
+                    error!("failed issue discord unban {}", err);
+                    Err(UnbanFailure::Unknown)
+                }
+            },
         }
+    }
+
+    pub async fn unban(
+        &self,
+        http: &Http,
+        guild_id: GuildId,
+        target_user_id: UserId,
+        mod_user_tag_and_id: &str,
+    ) -> Result<Vec<Ban>, UnbanFailure> {
+        self.remove_ban(http, guild_id, target_user_id, mod_user_tag_and_id)
+            .await?;
+
+        // returned so callers can log the cases that were lifted
+        let mut lifted_bans = self
+            .fetch_guild_user_bans(guild_id, target_user_id)
+            .await
+            .into_iter()
+            .filter(|ban| !ban.unbanned)
+            .collect::<Vec<Ban>>();
 
         self.invalidate_previous_user_bans(guild_id, target_user_id)
             .await;
-        Ok(())
+
+        for ban in lifted_bans.iter_mut() {
+            ban.unbanned = true;
+        }
+
+        Ok(lifted_bans)
+    }
+
+    // lifts the given case only, callers check that it's still active
+    pub async fn unban_case(
+        &self,
+        http: &Http,
+        ban: &Ban,
+        mod_user_tag_and_id: &str,
+    ) -> Result<(), UnbanFailure> {
+        let (guild_id, user_id) = match (
+            NonZeroU64::new(ban.guild_id as u64),
+            NonZeroU64::new(ban.user_id as u64),
+        ) {
+            (Some(guild_id), Some(user_id)) => {
+                (GuildId::new(guild_id.get()), UserId::new(user_id.get()))
+            }
+            _ => {
+                warn!("found ban with invalid guild or user id! {:?}", ban);
+                self.invalidate_ban(ban.id).await;
+                return Err(UnbanFailure::Unknown);
+            }
+        };
+
+        let result = self
+            .remove_ban(http, guild_id, user_id, mod_user_tag_and_id)
+            .await;
+
+        // a user that is no longer banned was unbanned outside of the bot, the case is over either way
+        if let Ok(_) | Err(UnbanFailure::UserNotBanned) = result {
+            self.invalidate_ban(ban.id).await;
+        }
+
+        result
     }
 
-    pub async fn fetch_ban(&self, id: i32) -> Option<Ban> {
+    pub async fn fetch_guild_ban_with_case_number(
+        &self,
+        guild_id: GuildId,
+        case_number: i32,
+    ) -> Option<Ban> {
         self.repository
-            .fetch_ban(id)
+            .fetch_guild_ban_with_case_number(guild_id.get() as i64, case_number)
             .await
             .map_err(|err| {
-                error!("failed to fetch ban {:?}", err);
+                error!("failed to fetch ban with case number {:?}", err);
                 err
             })
             .ok()
@@ -188,6 +299,18 @@ impl BanService {
             .unwrap()
     }
 
+    pub async fn fetch_guild_user_bans(&self, guild_id: GuildId, user_id: UserId) -> Vec<Ban> {
+        self.repository
+            .fetch_guild_user_bans(guild_id.get() as i64, user_id.get() as i64)
+            .await
+            .map_err(|err| {
+                error!("failed to fetch guild user bans {:?}", err);
+                err
+            })
+            .ok()
+            .unwrap_or_default()
+    }
+
     pub async fn fetch_guild_ban_count(&self, guild_id: GuildId) -> i64 {
         self.repository
             .fetch_guild_ban_count(guild_id.get() as i64)
@@ -246,6 +369,17 @@ impl BanService {
             .ok()
     }
 
+    pub async fn update_mod_log_message_id(&self, id: i32, mod_log_message_id: i64) {
+        let _ = self
+            .repository
+            .update_mod_log_message_id(id, mod_log_message_id)
+            .await
+            .map_err(|err| {
+                error!("failed to update ban mod log message id {:?}", err);
+                err
+            });
+    }
+
     pub async fn invalidate_previous_user_bans(&self, guild_id: GuildId, user_id: UserId) {
         let _ = self
             .repository
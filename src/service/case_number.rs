@@ -0,0 +1,39 @@
+use serenity::model::id::GuildId;
+use tracing::error;
+use typemap_rev::TypeMapKey;
+
+use crate::database::case_numbers::CaseNumbersRepository;
+
+impl TypeMapKey for CaseNumberService {
+    type Value = CaseNumberService;
+}
+
+pub struct CaseNumberService {
+    pub repository: CaseNumbersRepository,
+}
+
+impl CaseNumberService {
+    pub async fn next_case_number(&self, guild_id: GuildId) -> Option<i32> {
+        self.repository
+            .next_case_number(guild_id.get() as i64)
+            .await
+            .map_err(|err| {
+                error!("failed to fetch next case number {:?}", err);
+                err
+            })
+            .ok()
+    }
+
+    // hands the number back when the action it was taken for failed, numbers taken by other
+    // infractions in the meantime are left alone
+    pub async fn release_case_number(&self, guild_id: GuildId, case_number: i32) {
+        let _ = self
+            .repository
+            .release_case_number(guild_id.get() as i64, case_number)
+            .await
+            .map_err(|err| {
+                error!("failed to release case number {:?}", err);
+                err
+            });
+    }
+}
@@ -0,0 +1,61 @@
+use serenity::model::id::GuildId;
+use tracing::error;
+use typemap_rev::TypeMapKey;
+
+use crate::database::escalation_steps::{EscalationStep, EscalationStepsRepository};
+
+impl TypeMapKey for EscalationService {
+    type Value = EscalationService;
+}
+
+pub struct EscalationService {
+    pub repository: EscalationStepsRepository,
+}
+
+impl EscalationService {
+    pub async fn fetch_guild_escalation_steps(&self, guild_id: GuildId) -> Vec<EscalationStep> {
+        self.repository
+            .fetch_guild_escalation_steps(guild_id.get() as i64)
+            .await
+            .map_err(|err| {
+                error!("failed to fetch guild escalation steps {:?}", err);
+                err
+            })
+            .ok()
+            .unwrap_or_default()
+    }
+
+    // sorted by threshold, highest first
+    pub async fn fetch_guild_escalation_steps_for_infraction_type(
+        &self,
+        guild_id: GuildId,
+        infraction_type: i32,
+    ) -> Vec<EscalationStep> {
+        self.repository
+            .fetch_guild_escalation_steps_for_infraction_type(
+                guild_id.get() as i64,
+                infraction_type,
+            )
+            .await
+            .map_err(|err| {
+                error!(
+                    "failed to fetch guild escalation steps for infraction type {:?}",
+                    err
+                );
+                err
+            })
+            .ok()
+            .unwrap_or_default()
+    }
+
+    pub async fn delete_guild_escalation_steps(&self, guild_id: GuildId) {
+        let _ = self
+            .repository
+            .delete_guild_escalation_steps(guild_id.get() as i64)
+            .await
+            .map_err(|err| {
+                error!("failed to delete guild escalation steps {:?}", err);
+                err
+            });
+    }
+}
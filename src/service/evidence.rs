@@ -0,0 +1,162 @@
+use std::collections::HashMap;
+
+use serenity::all::GenericChannelId;
+use serenity::model::channel::{Attachment, Message};
+use serenity::model::id::{GuildId, MessageId, UserId};
+use tracing::error;
+use typemap_rev::TypeMapKey;
+
+use crate::database::evidence::{Evidence, EvidenceRepository};
+use crate::database::reports::Report;
+use crate::service::Services;
+use crate::util::now;
+
+impl TypeMapKey for EvidenceService {
+    type Value = EvidenceService;
+}
+
+pub struct EvidenceService {
+    pub repository: EvidenceRepository,
+}
+
+// copy of a message taken before it is deleted, it is only persisted once the infraction is issued
+pub struct EvidenceSnapshot {
+    pub channel_id: i64,
+    pub message_id: i64,
+    pub user_id: i64,
+    pub content: String,
+    pub attachments: Vec<String>,
+    pub message_time: i64,
+}
+
+impl EvidenceSnapshot {
+    pub fn from_message(message: &Message) -> Self {
+        EvidenceSnapshot {
+            channel_id: message.channel_id.get() as i64,
+            message_id: message.id.get() as i64,
+            user_id: message.author.id.get() as i64,
+            content: message.content.to_string(),
+            attachments: message
+                .attachments
+                .iter()
+                .map(|attachment| attachment.url.to_string())
+                .collect(),
+            message_time: message.timestamp.unix_timestamp(),
+        }
+    }
+
+    // message processors receive the parts of the message they act on instead of the message itself
+    pub fn from_content(
+        channel_id: GenericChannelId,
+        message_id: MessageId,
+        user_id: UserId,
+        content: &str,
+        attachments: &[Attachment],
+    ) -> Self {
+        EvidenceSnapshot {
+            channel_id: channel_id.get() as i64,
+            message_id: message_id.get() as i64,
+            user_id: user_id.get() as i64,
+            content: content.to_string(),
+            attachments: attachments
+                .iter()
+                .map(|attachment| attachment.url.to_string())
+                .collect(),
+            message_time: message_id.created_at().unix_timestamp(),
+        }
+    }
+
+    pub fn from_report(report: &Report) -> Self {
+        EvidenceSnapshot {
+            channel_id: report.channel_id,
+            message_id: report.message_id,
+            user_id: report.target_user_id,
+            content: report.content.clone(),
+            attachments: report.attachments.clone(),
+            message_time: report.message_time,
+        }
+    }
+}
+
+impl EvidenceService {
+    pub async fn insert_evidence(
+        &self,
+        guild_id: GuildId,
+        case_number: i32,
+        snapshots: &[EvidenceSnapshot],
+    ) {
+        let record_time = now() as i64;
+
+        for snapshot in snapshots {
+            let evidence = Evidence {
+                id: 0,
+                guild_id: guild_id.get() as i64,
+                case_number,
+                channel_id: snapshot.channel_id,
+                message_id: snapshot.message_id,
+                user_id: snapshot.user_id,
+                content: snapshot.content.clone(),
+                attachments: snapshot.attachments.clone(),
+                message_time: snapshot.message_time,
+                record_time,
+            };
+
+            let _ = self
+                .repository
+                .insert_evidence(evidence)
+                .await
+                .map_err(|err| {
+                    error!("failed to insert evidence {:?}", err);
+                    err
+                });
+        }
+    }
+
+    // mod log pages fetch the evidence of all of their cases at once
+    pub async fn fetch_guild_cases_evidence(
+        &self,
+        guild_id: GuildId,
+        case_numbers: &[i32],
+    ) -> HashMap<i32, Vec<Evidence>> {
+        let evidence = self
+            .repository
+            .fetch_guild_cases_evidence(guild_id.get() as i64, case_numbers)
+            .await
+            .map_err(|err| {
+                error!("failed to fetch guild cases evidence {:?}", err);
+                err
+            })
+            .ok()
+            .unwrap_or_default();
+
+        let mut evidence_by_case: HashMap<i32, Vec<Evidence>> = HashMap::new();
+        for evidence in evidence {
+            evidence_by_case
+                .entry(evidence.case_number)
+                .or_default()
+                .push(evidence);
+        }
+
+        evidence_by_case
+    }
+}
+
+// infractions are saved even if their evidence can't be, so failures are only logged
+pub async fn record_evidence(
+    services: &Services,
+    guild_id: GuildId,
+    case_number: i32,
+    snapshots: &[EvidenceSnapshot],
+) {
+    if snapshots.is_empty() {
+        return;
+    }
+
+    if let Some(evidence_service) = services.get::<EvidenceService>() {
+        evidence_service
+            .insert_evidence(guild_id, case_number, snapshots)
+            .await;
+    } else {
+        error!("couldn't get evidence service!");
+    }
+}
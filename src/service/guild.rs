@@ -3,7 +3,9 @@ use std::sync::Arc;
 use std::time::Duration;
 
 use moka::future::{Cache, CacheBuilder};
+use serenity::all::GenericChannelId;
 use serenity::http::Http;
+use serenity::model::channel::Channel;
 use serenity::model::id::{ChannelId, GuildId, RoleId, UserId};
 use serenity::model::Permissions;
 use thiserror::Error;
@@ -29,6 +31,9 @@ pub struct CachedMember {
 pub struct CachedRole {
     pub name: String,
     pub permissions: Permissions,
+    pub position: i32,
+    // roles managed by integrations, like bot roles, can't be assigned to members
+    pub managed: bool,
 }
 
 #[derive(Debug)]
@@ -48,6 +53,8 @@ pub struct GuildService {
     member_cache: Cache<(GuildId, UserId), Arc<CachedMember>>,
     user_cache: Cache<UserId, Arc<CachedUser>>,
     channel_cache: Cache<GuildId, Arc<HashMap<ChannelId, CachedChannel>>>,
+    // threads can't be moved to another channel, so their parent never goes stale
+    thread_parent_cache: Cache<GenericChannelId, ChannelId>,
 }
 
 pub enum GetPermissionsFailure {
@@ -103,6 +110,9 @@ impl GuildService {
                 .time_to_idle(Duration::from_secs(2 * 60))
                 .time_to_live(Duration::from_secs(5 * 60))
                 .build(),
+            thread_parent_cache: CacheBuilder::new(4096)
+                .time_to_idle(Duration::from_secs(60 * 60))
+                .build(),
         }
     }
 
@@ -166,6 +176,22 @@ impl GuildService {
         .ok_or(GetPermissionsFailure::Calculation)
     }
 
+    // members without any roles only have @everyone, which is always at position 0
+    pub async fn get_highest_role_position(
+        &self,
+        guild_id: GuildId,
+        member_roles: &[RoleId],
+    ) -> Result<i32, GetRolesFailure> {
+        let guild_roles = self.get_roles(guild_id).await?;
+
+        Ok(member_roles
+            .iter()
+            .filter_map(|role_id| guild_roles.get(role_id))
+            .map(|role| role.position)
+            .max()
+            .unwrap_or(0))
+    }
+
     pub async fn get_member(
         &self,
         guild_id: GuildId,
@@ -206,6 +232,8 @@ impl GuildService {
                             CachedRole {
                                 name: role.name.to_string(),
                                 permissions: role.permissions,
+                                position: role.position.into(),
+                                managed: role.managed(),
                             },
                         )
                     })
@@ -254,6 +282,32 @@ impl GuildService {
         Ok(channels)
     }
 
+    // threads aren't in the guild's channel list, None if the channel isn't a thread
+    pub async fn get_thread_parent_id(
+        &self,
+        guild_id: GuildId,
+        channel_id: GenericChannelId,
+    ) -> Option<ChannelId> {
+        if let Some(parent_id) = self.thread_parent_cache.get(&channel_id).await {
+            return Some(parent_id);
+        }
+
+        if let Ok(channels) = self.get_channels(guild_id).await {
+            if channels.keys().any(|id| id.widen() == channel_id) {
+                return None;
+            }
+        }
+
+        if let Ok(Channel::GuildThread(thread)) = self.http().await.get_channel(channel_id).await {
+            self.thread_parent_cache
+                .insert(channel_id, thread.parent_id)
+                .await;
+            Some(thread.parent_id)
+        } else {
+            None
+        }
+    }
+
     pub async fn get_user(&self, user_id: UserId) -> Result<Arc<CachedUser>, GetUserFailure> {
         let user = if let Some(cached_user) = self.user_cache.get(&user_id).await {
             cached_user
@@ -11,6 +11,9 @@ use crate::database::settings::Setting;
 use crate::discord::util::mod_log::{create_mod_log_entry, CreateModLogEntryError, ModLogAction};
 use crate::discord::util::user_dm::{notify_user_for_mod_action, ModActionKind};
 use crate::discord::util::SerenityErrorExt;
+use crate::service::case_number::CaseNumberService;
+use crate::service::evidence::{record_evidence, EvidenceSnapshot};
+use crate::service::Services;
 use crate::util::now;
 
 impl TypeMapKey for HardbanService {
@@ -34,11 +37,13 @@ impl HardbanService {
         guild_id: GuildId,
         guild_name: &str,
         setting: &Setting,
+        services: &Services,
         channel_id: Option<GenericChannelId>,
         mod_user_id: UserId,
         mod_user_tag_and_id: &str,
         target_user: &User,
         reason: String,
+        evidence: &[EvidenceSnapshot],
     ) -> Result<(), HardbanFailure> {
         let now = now();
         let mod_log_channel_id = if setting.mod_log {
@@ -55,14 +60,31 @@ impl HardbanService {
             None
         };
 
+        let case_number_service = if let Some(service) = services.get::<CaseNumberService>() {
+            service
+        } else {
+            error!("couldn't get case number service!");
+            return Err(HardbanFailure::Unknown);
+        };
+
+        let case_number =
+            if let Some(case_number) = case_number_service.next_case_number(guild_id).await {
+                case_number
+            } else {
+                return Err(HardbanFailure::Unknown);
+            };
+
         notify_user_for_mod_action(
             http,
+            setting,
             target_user.id,
             ModActionKind::Hardban,
             &reason,
             now,
+            case_number,
             guild_name,
             mod_user_tag_and_id,
+            None,
         )
         .await;
 
@@ -73,6 +95,9 @@ impl HardbanService {
         {
             Ok(_) => (),
             Err(err) => {
+                case_number_service
+                    .release_case_number(guild_id, case_number)
+                    .await;
                 return match err.discord_error_code() {
                     Some(50013) => Err(HardbanFailure::Unauthorized),
                     _ => {
@@ -90,16 +115,15 @@ impl HardbanService {
             guild_id: guild_id.get() as i64,
             hardban_time: now as i64,
             reason: reason.clone(),
+            case_number,
+            mod_log_message_id: 0,
         };
 
-        let hardban_id = self
-            .insert_hardban(hardban_entry)
-            .await
-            .map(|hardban| hardban.id);
+        if let Some(hardban) = self.insert_hardban(hardban_entry).await {
+            record_evidence(services, guild_id, case_number, evidence).await;
 
-        if let Some(hardban_id) = hardban_id {
             if let Some(mod_log_channel_id) = mod_log_channel_id {
-                if let Err(err) = create_mod_log_entry(
+                match create_mod_log_entry(
                     http,
                     mod_log_channel_id,
                     channel_id,
@@ -107,12 +131,16 @@ impl HardbanService {
                     target_user,
                     ModLogAction::Hardban,
                     &reason,
-                    hardban_id,
+                    case_number,
                     now,
                 )
                 .await
                 {
-                    return Err(HardbanFailure::ModLogError(err));
+                    Ok(message_id) => {
+                        self.update_mod_log_message_id(hardban.id, message_id.get() as i64)
+                            .await;
+                    }
+                    Err(err) => return Err(HardbanFailure::ModLogError(err)),
                 }
             }
         }
@@ -120,12 +148,16 @@ impl HardbanService {
         Ok(())
     }
 
-    pub async fn fetch_hardban(&self, id: i32) -> Option<Hardban> {
+    pub async fn fetch_guild_hardban_with_case_number(
+        &self,
+        guild_id: GuildId,
+        case_number: i32,
+    ) -> Option<Hardban> {
         self.repository
-            .fetch_hardban(id)
+            .fetch_guild_hardban_with_case_number(guild_id.get() as i64, case_number)
             .await
             .map_err(|err| {
-                error!("failed to fetch guild hardban {:?}", err);
+                error!("failed to fetch hardban with case number {:?}", err);
                 err
             })
             .ok()
@@ -144,6 +176,22 @@ impl HardbanService {
             .unwrap_or_default()
     }
 
+    pub async fn fetch_guild_user_hardbans(
+        &self,
+        guild_id: GuildId,
+        user_id: UserId,
+    ) -> Vec<Hardban> {
+        self.repository
+            .fetch_guild_user_hardbans(guild_id.get() as i64, user_id.get() as i64)
+            .await
+            .map_err(|err| {
+                error!("failed to fetch guild user hardbans {:?}", err);
+                err
+            })
+            .ok()
+            .unwrap_or_default()
+    }
+
     pub async fn fetch_guild_hardban_count(&self, guild_id: GuildId) -> i64 {
         self.repository
             .fetch_guild_hardban_count(guild_id.get() as i64)
@@ -177,4 +225,15 @@ impl HardbanService {
             })
             .ok()
     }
+
+    pub async fn update_mod_log_message_id(&self, id: i32, mod_log_message_id: i64) {
+        let _ = self
+            .repository
+            .update_mod_log_message_id(id, mod_log_message_id)
+            .await
+            .map_err(|err| {
+                error!("failed to update hardban mod log message id {:?}", err);
+                err
+            });
+    }
 }
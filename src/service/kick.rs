@@ -7,10 +7,12 @@ use tracing::{error, warn};
 use typemap_rev::TypeMapKey;
 
 use crate::database::kicks::{Kick, KicksRepository};
-use crate::database::settings::{get_action_duration_for_auto_mod_action, Setting};
+use crate::database::settings::{Setting, ACTION_KICK};
 use crate::discord::util::mod_log::{create_mod_log_entry, CreateModLogEntryError, ModLogAction};
 use crate::discord::util::user_dm::{notify_user_for_mod_action, ModActionKind};
-use crate::discord::util::{execute_mod_action, SerenityErrorExt};
+use crate::discord::util::{escalate_mod_action, SerenityErrorExt};
+use crate::service::case_number::CaseNumberService;
+use crate::service::evidence::{record_evidence, EvidenceSnapshot};
 use crate::service::Services;
 use crate::util::now;
 
@@ -28,6 +30,14 @@ pub enum KickFailure {
     Unknown,
 }
 
+pub fn validate_kick_update(kick: &Kick, pardoned: bool) -> Result<(), &'static str> {
+    if kick.pardoned && !pardoned {
+        return Err("You can't un-pardon a kick!");
+    }
+
+    Ok(())
+}
+
 impl KickService {
     pub async fn issue_kick(
         &self,
@@ -41,6 +51,7 @@ impl KickService {
         mod_user_tag_and_id: &str,
         target_user: &User,
         reason: String,
+        evidence: &[EvidenceSnapshot],
         call_depth: i32,
     ) -> Result<(), KickFailure> {
         let now = now();
@@ -58,14 +69,31 @@ impl KickService {
             None
         };
 
+        let case_number_service = if let Some(service) = services.get::<CaseNumberService>() {
+            service
+        } else {
+            error!("couldn't get case number service!");
+            return Err(KickFailure::Unknown);
+        };
+
+        let case_number =
+            if let Some(case_number) = case_number_service.next_case_number(guild_id).await {
+                case_number
+            } else {
+                return Err(KickFailure::Unknown);
+            };
+
         notify_user_for_mod_action(
             http,
+            setting,
             target_user.id,
             ModActionKind::Kick,
             &reason,
             now,
+            case_number,
             guild_name,
             mod_user_tag_and_id,
+            None,
         )
         .await;
 
@@ -76,6 +104,9 @@ impl KickService {
         {
             Ok(_) => (),
             Err(err) => {
+                case_number_service
+                    .release_case_number(guild_id, case_number)
+                    .await;
                 return match err.discord_error_code() {
                     Some(50013) => Err(KickFailure::Unauthorized),
                     _ => {
@@ -94,13 +125,15 @@ impl KickService {
             kick_time: now as i64,
             reason: reason.clone(),
             pardoned: false,
+            case_number,
+            mod_log_message_id: 0,
         };
 
-        let kick_id = self.insert_kick(kick_entry).await.map(|kick| kick.id);
+        if let Some(kick) = self.insert_kick(kick_entry).await {
+            record_evidence(services, guild_id, case_number, evidence).await;
 
-        if let Some(kick_id) = kick_id {
             if let Some(mod_log_channel_id) = mod_log_channel_id {
-                if let Err(err) = create_mod_log_entry(
+                match create_mod_log_entry(
                     http,
                     mod_log_channel_id,
                     channel_id,
@@ -108,55 +141,49 @@ impl KickService {
                     target_user,
                     ModLogAction::Kick,
                     &reason,
-                    kick_id,
+                    case_number,
                     now,
                 )
                 .await
                 {
-                    return Err(KickFailure::ModLogError(err));
+                    Ok(message_id) => {
+                        self.update_mod_log_message_id(kick.id, message_id.get() as i64)
+                            .await;
+                    }
+                    Err(err) => return Err(KickFailure::ModLogError(err)),
                 }
             }
         }
 
-        if setting.kick_threshold != 0 {
-            let count = self
-                .fetch_actionable_kick_count(guild_id, target_user.id)
-                .await;
-            if count >= setting.kick_threshold.into() {
-                let duration = get_action_duration_for_auto_mod_action(
-                    setting.kick_action,
-                    setting.kick_action_duration_type,
-                    setting.kick_action_duration,
-                );
-
-                execute_mod_action(
-                    setting.kick_action,
-                    http,
-                    guild_id,
-                    guild_name,
-                    setting,
-                    services,
-                    channel_id,
-                    mod_user_id,
-                    mod_user_tag_and_id,
-                    target_user,
-                    reason,
-                    duration,
-                    call_depth,
-                )
-                .await;
-            }
-        }
+        escalate_mod_action(
+            ACTION_KICK,
+            http,
+            guild_id,
+            guild_name,
+            setting,
+            services,
+            channel_id,
+            mod_user_id,
+            mod_user_tag_and_id,
+            target_user,
+            reason,
+            call_depth,
+        )
+        .await;
 
         Ok(())
     }
 
-    pub async fn fetch_kick(&self, id: i32) -> Option<Kick> {
+    pub async fn fetch_guild_kick_with_case_number(
+        &self,
+        guild_id: GuildId,
+        case_number: i32,
+    ) -> Option<Kick> {
         self.repository
-            .fetch_kick(id)
+            .fetch_guild_kick_with_case_number(guild_id.get() as i64, case_number)
             .await
             .map_err(|err| {
-                error!("failed to fetch kick {:?}", err);
+                error!("failed to fetch kick with case number {:?}", err);
                 err
             })
             .ok()
@@ -175,6 +202,18 @@ impl KickService {
             .unwrap_or_default()
     }
 
+    pub async fn fetch_guild_user_kicks(&self, guild_id: GuildId, user_id: UserId) -> Vec<Kick> {
+        self.repository
+            .fetch_guild_user_kicks(guild_id.get() as i64, user_id.get() as i64)
+            .await
+            .map_err(|err| {
+                error!("failed to fetch guild user kicks {:?}", err);
+                err
+            })
+            .ok()
+            .unwrap_or_default()
+    }
+
     pub async fn fetch_guild_kick_count(&self, guild_id: GuildId) -> i64 {
         self.repository
             .fetch_guild_kick_count(guild_id.get() as i64)
@@ -187,9 +226,16 @@ impl KickService {
             .unwrap_or(0)
     }
 
-    pub async fn fetch_actionable_kick_count(&self, guild_id: GuildId, user_id: UserId) -> i64 {
+    pub async fn fetch_actionable_kick_count(
+        &self,
+        setting: &Setting,
+        guild_id: GuildId,
+        user_id: UserId,
+        since: u64,
+    ) -> i64 {
+        let since = since.max(setting.decay_cutoff(ACTION_KICK, now()));
         self.repository
-            .fetch_actionable_kick_count(guild_id.get() as i64, user_id.get() as i64)
+            .fetch_actionable_kick_count(guild_id.get() as i64, user_id.get() as i64, since as i64)
             .await
             .map_err(|err| {
                 error!("failed to fetch actionable kick count {:?}", err);
@@ -220,4 +266,15 @@ impl KickService {
             })
             .ok()
     }
+
+    pub async fn update_mod_log_message_id(&self, id: i32, mod_log_message_id: i64) {
+        let _ = self
+            .repository
+            .update_mod_log_message_id(id, mod_log_message_id)
+            .await
+            .map_err(|err| {
+                error!("failed to update kick mod log message id {:?}", err);
+                err
+            });
+    }
 }
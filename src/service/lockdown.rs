@@ -0,0 +1,256 @@
+use moka::future::{Cache, CacheBuilder};
+use serenity::http::Http;
+use serenity::model::channel::{GuildChannel, PermissionOverwrite, PermissionOverwriteType};
+use serenity::model::id::{ChannelId, GuildId, UserId};
+use serenity::model::Permissions;
+use std::num::NonZeroU64;
+use std::time::Duration;
+use tracing::{error, warn};
+use typemap_rev::TypeMapKey;
+
+use crate::database::lockdowns::{Lockdown, LockdownsRepository};
+use crate::discord::util::SerenityErrorExt;
+use crate::util::now;
+
+const LOCKED_PERMISSIONS: Permissions =
+    Permissions::SEND_MESSAGES.union(Permissions::SEND_MESSAGES_IN_THREADS);
+
+impl TypeMapKey for LockdownService {
+    type Value = LockdownService;
+}
+
+pub struct LockdownService {
+    pub repository: LockdownsRepository,
+    // lockdowns whose failed unlock was already reported, retries shouldn't flood the mod log
+    pub reported_unlock_failures: Cache<i32, ()>,
+}
+
+pub enum LockdownFailure {
+    AlreadyLocked,
+    Unauthorized,
+    Unknown,
+}
+
+pub enum UnlockFailure {
+    Unauthorized,
+    Unknown,
+}
+
+impl LockdownService {
+    pub fn new(repository: LockdownsRepository) -> LockdownService {
+        LockdownService {
+            repository,
+            reported_unlock_failures: CacheBuilder::new(1000)
+                .time_to_live(Duration::from_secs(60 * 60 * 24))
+                .build(),
+        }
+    }
+
+    // true only the first time a failed unlock of given lockdown is reported
+    pub async fn should_report_unlock_failure(&self, lockdown_id: i32) -> bool {
+        if self.reported_unlock_failures.contains_key(&lockdown_id) {
+            return false;
+        }
+
+        self.reported_unlock_failures.insert(lockdown_id, ()).await;
+        true
+    }
+
+    pub async fn lock_channel(
+        &self,
+        http: &Http,
+        guild_id: GuildId,
+        channel: &GuildChannel,
+        mod_user_id: UserId,
+        expire_time: Option<u64>,
+        reason: &str,
+    ) -> Result<(), LockdownFailure> {
+        // locking a channel twice would snapshot our own overwrite and make it permanent
+        match self
+            .repository
+            .fetch_active_channel_lockdown(guild_id.get() as i64, channel.id.get() as i64)
+            .await
+        {
+            Ok(None) => (),
+            Ok(Some(_)) => return Err(LockdownFailure::AlreadyLocked),
+            Err(err) => {
+                error!("failed to fetch channel lockdown {:?}", err);
+                return Err(LockdownFailure::Unknown);
+            }
+        }
+
+        let everyone = PermissionOverwriteType::Role(guild_id.everyone_role());
+        let previous_overwrite = channel
+            .permission_overwrites
+            .iter()
+            .find(|overwrite| overwrite.kind == everyone);
+
+        let (previous_allow, previous_deny) = previous_overwrite
+            .map(|overwrite| (overwrite.allow, overwrite.deny))
+            .unwrap_or_default();
+
+        let lockdown = Lockdown {
+            id: 0,
+            guild_id: guild_id.get() as i64,
+            channel_id: channel.id.get() as i64,
+            moderator_user_id: mod_user_id.get() as i64,
+            lock_time: now() as i64,
+            expire_time: expire_time.unwrap_or(0) as i64,
+            reason: reason.to_string(),
+            expires: expire_time.is_some(),
+            unlocked: false,
+            has_previous_overwrite: previous_overwrite.is_some(),
+            previous_allow: previous_allow.bits() as i64,
+            previous_deny: previous_deny.bits() as i64,
+            unlock_failed: false,
+        };
+
+        // snapshot is saved first, so the channel can still be restored if we crash in between
+        let lockdown = match self.repository.insert_lockdown(lockdown).await {
+            Ok(Some(lockdown)) => lockdown,
+            // another lockdown of this channel got in between the check above and the insert
+            Ok(None) => return Err(LockdownFailure::AlreadyLocked),
+            Err(err) => {
+                error!("failed to insert lockdown {:?}", err);
+                return Err(LockdownFailure::Unknown);
+            }
+        };
+
+        let overwrite = PermissionOverwrite {
+            allow: previous_allow - LOCKED_PERMISSIONS,
+            deny: previous_deny | LOCKED_PERMISSIONS,
+            kind: everyone,
+        };
+
+        if let Err(err) = channel
+            .id
+            .create_permission(http, overwrite, Some(reason))
+            .await
+        {
+            self.invalidate_lockdown(lockdown.id).await;
+
+            return match err.discord_error_code() {
+                Some(50013) => Err(LockdownFailure::Unauthorized),
+                _ => {
+                    error!(
+                        "failed to create lockdown permission override in guild: {} {}",
+                        guild_id, err
+                    );
+                    Err(LockdownFailure::Unknown)
+                }
+            };
+        }
+
+        Ok(())
+    }
+
+    pub async fn unlock_channel(
+        &self,
+        http: &Http,
+        guild_id: GuildId,
+        lockdown: &Lockdown,
+        reason: &str,
+    ) -> Result<ChannelId, UnlockFailure> {
+        let channel_id = if let Some(id) = NonZeroU64::new(lockdown.channel_id as u64) {
+            ChannelId::new(id.get())
+        } else {
+            warn!("found lockdown with invalid channel id! {:?}", lockdown);
+            self.invalidate_lockdown(lockdown.id).await;
+            return Err(UnlockFailure::Unknown);
+        };
+
+        let everyone = PermissionOverwriteType::Role(guild_id.everyone_role());
+
+        let result = if lockdown.has_previous_overwrite {
+            let overwrite = PermissionOverwrite {
+                allow: Permissions::from_bits_truncate(lockdown.previous_allow as u64),
+                deny: Permissions::from_bits_truncate(lockdown.previous_deny as u64),
+                kind: everyone,
+            };
+
+            channel_id
+                .create_permission(http, overwrite, Some(reason))
+                .await
+        } else {
+            channel_id
+                .delete_permission(http, everyone, Some(reason))
+                .await
+        };
+
+        if let Err(err) = result {
+            match err.discord_error_code() {
+                // channel was deleted while it was locked, there is nothing left to restore
+                _ if err.is_not_found() => (),
+                Some(50013) => return Err(UnlockFailure::Unauthorized),
+                _ => {
+                    error!(
+                        "failed to restore permission override after lockdown in guild: {} {}",
+                        guild_id, err
+                    );
+                    return Err(UnlockFailure::Unknown);
+                }
+            }
+        }
+
+        self.invalidate_lockdown(lockdown.id).await;
+        Ok(channel_id)
+    }
+
+    pub async fn fetch_active_guild_lockdowns(&self, guild_id: GuildId) -> Vec<Lockdown> {
+        self.repository
+            .fetch_active_guild_lockdowns(guild_id.get() as i64)
+            .await
+            .map_err(|err| {
+                error!("failed to fetch active guild lockdowns {:?}", err);
+                err
+            })
+            .ok()
+            .unwrap_or_default()
+    }
+
+    pub async fn fetch_active_channel_lockdown(
+        &self,
+        guild_id: GuildId,
+        channel_id: ChannelId,
+    ) -> Option<Lockdown> {
+        self.repository
+            .fetch_active_channel_lockdown(guild_id.get() as i64, channel_id.get() as i64)
+            .await
+            .map_err(|err| {
+                error!("failed to fetch active channel lockdown {:?}", err);
+                err
+            })
+            .ok()
+            .flatten()
+    }
+
+    pub async fn fetch_expired_lockdowns(&self) -> Vec<Lockdown> {
+        self.repository
+            .fetch_expired_lockdowns()
+            .await
+            .map_err(|err| {
+                error!("failed to fetch expired lockdowns {:?}", err);
+                err
+            })
+            .ok()
+            .unwrap_or_default()
+    }
+
+    pub async fn mark_unlock_failed(&self, id: i32) {
+        let _ = self.repository.mark_unlock_failed(id).await.map_err(|err| {
+            error!("failed to mark lockdown unlock as failed {:?}", err);
+            err
+        });
+    }
+
+    pub async fn invalidate_lockdown(&self, id: i32) {
+        let _ = self
+            .repository
+            .invalidate_lockdown(id)
+            .await
+            .map_err(|err| {
+                error!("failed to invalidate lockdown {:?}", err);
+                err
+            });
+    }
+}
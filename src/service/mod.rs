@@ -3,31 +3,47 @@ use std::sync::Arc;
 use sqlx::PgPool;
 use typemap_rev::TypeMap;
 
+use appeal::AppealService;
 use ban::BanService;
+use case_number::CaseNumberService;
+use escalation::EscalationService;
+use evidence::EvidenceService;
 use hardban::HardbanService;
 use iam_role::IAMRoleService;
 use invalid_uuid::InvalidUUIDService;
 use join::JoinService;
 use kick::KickService;
+use lockdown::LockdownService;
 use mute::MuteService;
+use note::NoteService;
 use reminder::ReminderService;
+use report::ReportService;
 use setting::SettingService;
 use softban::SoftbanService;
 use tag::TagService;
+use temp_role::TempRoleService;
 use user_secret::UserSecretService;
 use warn::WarnService;
 
+use crate::database::appeals::AppealsRepository;
 use crate::database::bans::BansRepository;
+use crate::database::case_numbers::CaseNumbersRepository;
+use crate::database::escalation_steps::EscalationStepsRepository;
+use crate::database::evidence::EvidenceRepository;
 use crate::database::hardbans::HardbansRepository;
 use crate::database::iam_roles::IAMRolesRepository;
 use crate::database::invalid_uuids::InvalidUUIDsRepository;
 use crate::database::joins::JoinsRepository;
 use crate::database::kicks::KicksRepository;
+use crate::database::lockdowns::LockdownsRepository;
 use crate::database::mutes::MutesRepository;
+use crate::database::notes::NotesRepository;
 use crate::database::reminders::RemindersRepository;
+use crate::database::reports::ReportsRepository;
 use crate::database::settings::SettingsRepository;
 use crate::database::softbans::SoftbansRepository;
 use crate::database::tags::TagsRepository;
+use crate::database::temp_roles::TempRolesRepository;
 use crate::database::user_secrets::UserSecretsRepository;
 use crate::database::warns::WarnsRepository;
 use crate::service::guild::GuildService;
@@ -35,7 +51,11 @@ use crate::service::guild_statistic::GuildStatisticService;
 use crate::service::watchdog::WatchdogService;
 use crate::Config;
 
+pub mod appeal;
 pub mod ban;
+pub mod case_number;
+pub mod escalation;
+pub mod evidence;
 pub mod guild;
 pub mod guild_statistic;
 pub mod hardban;
@@ -43,11 +63,15 @@ pub mod iam_role;
 pub mod invalid_uuid;
 pub mod join;
 pub mod kick;
+pub mod lockdown;
 pub mod mute;
+pub mod note;
 pub mod reminder;
+pub mod report;
 pub mod setting;
 pub mod softban;
 pub mod tag;
+pub mod temp_role;
 pub mod user_secret;
 pub mod warn;
 pub mod watchdog;
@@ -55,37 +79,65 @@ pub mod watchdog;
 pub type Services = TypeMap;
 
 pub async fn create_services(config: Arc<Config>, pool: Arc<PgPool>) -> anyhow::Result<Services> {
+    let appeals_repository = AppealsRepository(pool.clone());
     let bans_repository = BansRepository(pool.clone());
+    let case_numbers_repository = CaseNumbersRepository(pool.clone());
+    let escalation_steps_repository = EscalationStepsRepository(pool.clone());
+    let evidence_repository = EvidenceRepository(pool.clone());
     let hardbans_repository = HardbansRepository(pool.clone());
     let iam_roles_repository = IAMRolesRepository(pool.clone());
     let invalid_uuids_repository = InvalidUUIDsRepository(pool.clone());
     let joins_repository = JoinsRepository(pool.clone());
     let kicks_repository = KicksRepository(pool.clone());
+    let lockdowns_repository = LockdownsRepository(pool.clone());
     let mutes_repository = MutesRepository(pool.clone());
+    let notes_repository = NotesRepository(pool.clone());
     let reminders_repository = RemindersRepository(pool.clone());
+    let reports_repository = ReportsRepository(pool.clone());
     let settings_repository = SettingsRepository(pool.clone());
     let softbans_repository = SoftbansRepository(pool.clone());
     let tags_repository = TagsRepository(pool.clone());
+    let temp_roles_repository = TempRolesRepository(pool.clone());
     let user_secrets_repository = UserSecretsRepository(pool.clone());
     let warns_repository = WarnsRepository(pool.clone());
 
+    appeals_repository.initialize().await?;
     bans_repository.initialize().await?;
+    evidence_repository.initialize().await?;
     hardbans_repository.initialize().await?;
     iam_roles_repository.initialize().await?;
     invalid_uuids_repository.initialize().await?;
     joins_repository.initialize().await?;
     kicks_repository.initialize().await?;
+    lockdowns_repository.initialize().await?;
     mutes_repository.initialize().await?;
+    notes_repository.initialize().await?;
     reminders_repository.initialize().await?;
+    reports_repository.initialize().await?;
     settings_repository.initialize().await?;
     softbans_repository.initialize().await?;
     tags_repository.initialize().await?;
+    temp_roles_repository.initialize().await?;
     user_secrets_repository.initialize().await?;
     warns_repository.initialize().await?;
+    // numbers existing infractions, so it has to run after their tables are initialized
+    case_numbers_repository.initialize().await?;
+    // migrates the legacy threshold columns out of settings, so it has to run after settings are initialized
+    escalation_steps_repository.initialize().await?;
 
+    let appeal_service = AppealService::new(config.clone(), appeals_repository);
     let ban_service = BanService {
         repository: bans_repository,
     };
+    let case_number_service = CaseNumberService {
+        repository: case_numbers_repository,
+    };
+    let escalation_service = EscalationService {
+        repository: escalation_steps_repository,
+    };
+    let evidence_service = EvidenceService {
+        repository: evidence_repository,
+    };
     let hardban_service = HardbanService {
         repository: hardbans_repository,
     };
@@ -101,12 +153,19 @@ pub async fn create_services(config: Arc<Config>, pool: Arc<PgPool>) -> anyhow::
     let kick_service = KickService {
         repository: kicks_repository,
     };
+    let lockdown_service = LockdownService::new(lockdowns_repository);
     let mute_service = MuteService {
         repository: mutes_repository,
     };
+    let note_service = NoteService {
+        repository: notes_repository,
+    };
     let reminder_service = ReminderService {
         repository: reminders_repository,
     };
+    let report_service = ReportService {
+        repository: reports_repository,
+    };
     let setting_service = SettingService::new(settings_repository);
     let softban_service = SoftbanService {
         repository: softbans_repository,
@@ -114,6 +173,9 @@ pub async fn create_services(config: Arc<Config>, pool: Arc<PgPool>) -> anyhow::
     let tags_service = TagService {
         repository: tags_repository,
     };
+    let temp_role_service = TempRoleService {
+        repository: temp_roles_repository,
+    };
     let user_secrets_service = UserSecretService::new(config, user_secrets_repository);
     let warns_service = WarnService {
         repository: warns_repository,
@@ -123,17 +185,25 @@ pub async fn create_services(config: Arc<Config>, pool: Arc<PgPool>) -> anyhow::
     let watchdog_service = WatchdogService::new();
 
     let mut services = Services::new();
+    services.insert::<AppealService>(appeal_service);
     services.insert::<BanService>(ban_service);
+    services.insert::<CaseNumberService>(case_number_service);
+    services.insert::<EscalationService>(escalation_service);
+    services.insert::<EvidenceService>(evidence_service);
     services.insert::<HardbanService>(hardban_service);
     services.insert::<IAMRoleService>(iam_role_service);
     services.insert::<InvalidUUIDService>(invalid_uuid_service);
     services.insert::<JoinService>(join_service);
     services.insert::<KickService>(kick_service);
+    services.insert::<LockdownService>(lockdown_service);
     services.insert::<MuteService>(mute_service);
+    services.insert::<NoteService>(note_service);
     services.insert::<ReminderService>(reminder_service);
+    services.insert::<ReportService>(report_service);
     services.insert::<SettingService>(setting_service);
     services.insert::<SoftbanService>(softban_service);
     services.insert::<TagService>(tags_service);
+    services.insert::<TempRoleService>(temp_role_service);
     services.insert::<UserSecretService>(user_secrets_service);
     services.insert::<WarnService>(warns_service);
     services.insert::<GuildStatisticService>(guild_statistic_service);
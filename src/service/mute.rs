@@ -1,25 +1,34 @@
-use serenity::all::GenericChannelId;
-use serenity::builder::EditRole;
+use serenity::all::{Error, GenericChannelId};
+use serenity::builder::{EditMember, EditRole};
 use serenity::http::Http;
-use serenity::model::channel::{PermissionOverwrite, PermissionOverwriteType};
+use serenity::model::channel::{GuildChannel, PermissionOverwrite, PermissionOverwriteType};
 use serenity::model::id::{GuildId, RoleId, UserId};
 use serenity::model::user::User;
-use serenity::model::Permissions;
+use serenity::model::{Permissions, Timestamp};
+use std::collections::HashMap;
 use std::num::{NonZeroU32, NonZeroU64};
 use std::time::Duration;
 use tracing::{error, warn};
 use typemap_rev::TypeMapKey;
 
 use crate::database::mutes::{Mute, MutesRepository};
-use crate::database::settings::{get_action_duration_for_auto_mod_action, Setting};
+use crate::database::settings::{Setting, ACTION_MUTE, MUTE_BACKEND_ROLE, MUTE_BACKEND_TIMEOUT};
 use crate::discord::util::mod_log::{create_mod_log_entry, CreateModLogEntryError, ModLogAction};
 use crate::discord::util::user_dm::{notify_user_for_mod_action, ModActionKind};
-use crate::discord::util::{execute_mod_action, SerenityErrorExt};
-use crate::service::guild::{GetRolesFailure, GuildService};
+use crate::discord::util::{escalate_mod_action, SerenityErrorExt};
+use crate::service::appeal::AppealService;
+use crate::service::case_number::CaseNumberService;
+use crate::service::evidence::{record_evidence, EvidenceSnapshot};
+use crate::service::guild::{CachedRole, GetRolesFailure, GuildService};
+use crate::service::setting::SettingService;
 use crate::service::Services;
 use crate::util::now;
 
 const REASON: &str = "Safety Jim's mute function requires this role.";
+// discord rejects timeouts ending more than 28 days from now, leave some room for clock drift
+const MAX_TIMEOUT_DURATION: u64 = 60 * 60 * 24 * 28 - 60 * 5;
+// timeouts ending within this window get renewed if their mute outlives them
+pub const TIMEOUT_RENEWAL_WINDOW: u64 = 60 * 60 * 24;
 
 impl TypeMapKey for MuteService {
     type Value = MuteService;
@@ -44,11 +53,72 @@ pub enum UnmuteFailure {
     Unknown,
 }
 
+// guilds that haven't picked a mute role keep using the role called "Muted"
+fn find_mute_role_id(setting: &Setting, roles: &HashMap<RoleId, CachedRole>) -> Option<RoleId> {
+    let configured_role_id = setting
+        .mute_role_id
+        .and_then(|id| NonZeroU64::new(id as u64))
+        .map(|id| RoleId::new(id.get()))
+        .filter(|role_id| roles.contains_key(role_id));
+
+    configured_role_id.or_else(|| {
+        roles
+            .iter()
+            .find(|(_, role)| role.name == "Muted")
+            .map(|(id, _)| *id)
+    })
+}
+
+async fn create_mute_role_overwrite(
+    http: &Http,
+    channel: &GuildChannel,
+    role_id: RoleId,
+) -> Result<(), Error> {
+    channel
+        .id
+        .create_permission(
+            http,
+            PermissionOverwrite {
+                allow: Default::default(),
+                deny: Permissions::SEND_MESSAGES | Permissions::ADD_REACTIONS | Permissions::SPEAK,
+                kind: PermissionOverwriteType::Role(role_id),
+            },
+            Some(REASON),
+        )
+        .await
+}
+
+fn get_timeout_expire_time(now: u64, expiration_time: Option<u64>) -> u64 {
+    let max_expire_time = now + MAX_TIMEOUT_DURATION;
+    expiration_time
+        .map(|time| time.min(max_expire_time))
+        .unwrap_or(max_expire_time)
+}
+
+// once a mute is lifted its expiration can neither be undone nor moved
+pub fn validate_mute_update(
+    mute: &Mute,
+    unmuted: bool,
+    expire_time: i64,
+    pardoned: bool,
+) -> Result<(), &'static str> {
+    if mute.unmuted && (!unmuted || mute.expire_time != expire_time) {
+        return Err("You can't change expiration property after user has been unmuted.");
+    }
+
+    if mute.pardoned && !pardoned {
+        return Err("You can't un-pardon a mute!");
+    }
+
+    Ok(())
+}
+
 impl MuteService {
     pub async fn fetch_muted_role_id(
         &self,
         http: &Http,
         services: &Services,
+        setting: &Setting,
         guild_id: GuildId,
     ) -> Result<RoleId, MuteFailure> {
         let guild_service = if let Some(service) = services.get::<GuildService>() {
@@ -58,15 +128,25 @@ impl MuteService {
             return Err(MuteFailure::Unknown);
         };
 
+        let setting_service = if let Some(service) = services.get::<SettingService>() {
+            service
+        } else {
+            error!("couldn't get setting service!");
+            return Err(MuteFailure::Unknown);
+        };
+
         let roles = match guild_service.get_roles(guild_id).await {
             Ok(roles) => roles,
             Err(GetRolesFailure::FetchFailed) => return Err(MuteFailure::UnauthorizedFetchRoles),
         };
 
-        for (id, role) in roles.iter() {
-            if role.name == "Muted" {
-                return Ok(*id);
+        if let Some(role_id) = find_mute_role_id(setting, &roles) {
+            if setting.mute_role_id.is_none() {
+                // remember the role so renaming it doesn't break muting later
+                setting_service.update_mute_role_id(guild_id, role_id).await;
             }
+
+            return Ok(role_id);
         }
 
         let create_role_req = EditRole::default().name("Muted");
@@ -83,6 +163,21 @@ impl MuteService {
             }
         };
 
+        setting_service.update_mute_role_id(guild_id, role.id).await;
+
+        self.apply_mute_role_overwrites(http, guild_id, role.id)
+            .await?;
+
+        Ok(role.id)
+    }
+
+    // denies the mute role from talking in every channel, needed whenever a new mute role is set up
+    pub async fn apply_mute_role_overwrites(
+        &self,
+        http: &Http,
+        guild_id: GuildId,
+        role_id: RoleId,
+    ) -> Result<(), MuteFailure> {
         let channels = match guild_id.channels(http).await {
             Ok(channels) => channels,
             Err(err) => {
@@ -92,21 +187,7 @@ impl MuteService {
         };
 
         for channel in channels {
-            if let Err(err) = channel
-                .id
-                .create_permission(
-                    http,
-                    PermissionOverwrite {
-                        allow: Default::default(),
-                        deny: Permissions::SEND_MESSAGES
-                            | Permissions::ADD_REACTIONS
-                            | Permissions::SPEAK,
-                        kind: PermissionOverwriteType::Role(role.id),
-                    },
-                    Some(REASON),
-                )
-                .await
-            {
+            if let Err(err) = create_mute_role_overwrite(http, &channel, role_id).await {
                 return match err.discord_error_code() {
                     Some(50013) => Err(MuteFailure::UnauthorizedChannelOverride),
                     _ => {
@@ -120,7 +201,248 @@ impl MuteService {
             }
         }
 
-        Ok(role.id)
+        Ok(())
+    }
+
+    // channels created after the mute role was set up don't have its overwrite yet
+    pub async fn reconcile_channel_mute_overwrite(
+        &self,
+        http: &Http,
+        services: &Services,
+        channel: &GuildChannel,
+    ) {
+        let guild_id = channel.base.guild_id;
+
+        let setting_service = if let Some(service) = services.get::<SettingService>() {
+            service
+        } else {
+            return;
+        };
+
+        let guild_service = if let Some(service) = services.get::<GuildService>() {
+            service
+        } else {
+            return;
+        };
+
+        let setting = setting_service.get_setting(guild_id).await;
+        if setting.mute_backend != MUTE_BACKEND_ROLE {
+            return;
+        }
+
+        let roles = match guild_service.get_roles(guild_id).await {
+            Ok(roles) => roles,
+            Err(_) => return,
+        };
+
+        let role_id = if let Some(role_id) = find_mute_role_id(&setting, &roles) {
+            role_id
+        } else {
+            return;
+        };
+
+        let _ = create_mute_role_overwrite(http, channel, role_id)
+            .await
+            .map_err(|err| {
+                warn!(
+                    "failed to create mute role permission override for new channel in guild: {} {}",
+                    guild_id, err
+                );
+                err
+            });
+    }
+
+    async fn apply_timeout(
+        &self,
+        http: &Http,
+        guild_id: GuildId,
+        user_id: UserId,
+        timeout_expire_time: u64,
+        audit_log_reason: &str,
+    ) -> Result<(), MuteFailure> {
+        let timestamp = match Timestamp::from_unix_timestamp(timeout_expire_time as i64) {
+            Ok(t) => t,
+            Err(_) => {
+                warn!(
+                    "attempted to time out member with invalid timestamp! {}",
+                    timeout_expire_time
+                );
+                return Err(MuteFailure::Unknown);
+            }
+        };
+
+        let edit_member = EditMember::new()
+            .disable_communication_until(timestamp)
+            .audit_log_reason(audit_log_reason);
+
+        match guild_id.edit_member(http, user_id, edit_member).await {
+            Ok(_) => Ok(()),
+            Err(err) => match err.discord_error_code() {
+                Some(50013) => Err(MuteFailure::Unauthorized),
+                _ => {
+                    error!("failed to issue discord member timeout {}", err);
+                    Err(MuteFailure::Unknown)
+                }
+            },
+        }
+    }
+
+    // returns the end of the applied discord timeout, 0 if the mute role was used
+    pub async fn apply_mute(
+        &self,
+        http: &Http,
+        services: &Services,
+        setting: &Setting,
+        guild_id: GuildId,
+        user_id: UserId,
+        expiration_time: Option<u64>,
+        audit_log_reason: &str,
+    ) -> Result<u64, MuteFailure> {
+        if setting.mute_backend == MUTE_BACKEND_TIMEOUT {
+            let timeout_expire_time = get_timeout_expire_time(now(), expiration_time);
+            self.apply_timeout(
+                http,
+                guild_id,
+                user_id,
+                timeout_expire_time,
+                audit_log_reason,
+            )
+            .await?;
+
+            return Ok(timeout_expire_time);
+        }
+
+        let role_id = self
+            .fetch_muted_role_id(http, services, setting, guild_id)
+            .await?;
+
+        match http
+            .add_member_role(guild_id, user_id, role_id, Some(audit_log_reason))
+            .await
+        {
+            Ok(_) => Ok(0),
+            Err(err) => match err.discord_error_code() {
+                Some(50013) => Err(MuteFailure::Unauthorized),
+                _ => {
+                    error!("failed to issue discord member role add {}", err);
+                    Err(MuteFailure::Unknown)
+                }
+            },
+        }
+    }
+
+    // backend is passed separately since mutes are lifted the same way they were applied
+    pub async fn lift_mute(
+        &self,
+        http: &Http,
+        services: &Services,
+        setting: &Setting,
+        mute_backend: i32,
+        guild_id: GuildId,
+        user_id: UserId,
+        audit_log_reason: &str,
+    ) -> Result<(), UnmuteFailure> {
+        if mute_backend == MUTE_BACKEND_TIMEOUT {
+            let edit_member = EditMember::new()
+                .enable_communication()
+                .audit_log_reason(audit_log_reason);
+
+            return match guild_id.edit_member(http, user_id, edit_member).await {
+                Ok(_) => Ok(()),
+                Err(err) => match err.discord_error_code() {
+                    Some(50013) => Err(UnmuteFailure::Unauthorized),
+                    _ => {
+                        error!("failed to remove discord member timeout {}", err);
+                        Err(UnmuteFailure::Unknown)
+                    }
+                },
+            };
+        }
+
+        // use cached roles
+        let guild_service = if let Some(service) = services.get::<GuildService>() {
+            service
+        } else {
+            error!("couldn't get guild service!");
+            return Err(UnmuteFailure::Unknown);
+        };
+
+        let roles = match guild_service.get_roles(guild_id).await {
+            Ok(roles) => roles,
+            Err(GetRolesFailure::FetchFailed) => return Err(UnmuteFailure::Unknown),
+        };
+
+        let role_id = if let Some(role_id) = find_mute_role_id(setting, &roles) {
+            role_id
+        } else {
+            return Err(UnmuteFailure::RoleNotFound);
+        };
+
+        match http
+            .remove_member_role(guild_id, user_id, role_id, Some(audit_log_reason))
+            .await
+        {
+            Ok(_) => Ok(()),
+            Err(err) => match err.discord_error_code() {
+                Some(50013) => Err(UnmuteFailure::Unauthorized),
+                _ => {
+                    error!("failed to issue discord member role remove {}", err);
+                    Err(UnmuteFailure::Unknown)
+                }
+            },
+        }
+    }
+
+    // keeps timeouts of mutes longer than discord's limit going
+    pub async fn renew_timeout(&self, http: &Http, services: &Services, mute: &Mute) {
+        let (guild_id, user_id) = match (
+            NonZeroU64::new(mute.guild_id as u64),
+            NonZeroU64::new(mute.user_id as u64),
+        ) {
+            (Some(guild_id), Some(user_id)) => {
+                (GuildId::new(guild_id.get()), UserId::new(user_id.get()))
+            }
+            _ => {
+                warn!("found mute with invalid guild or user id! {:?}", mute);
+                self.invalidate_mute(mute.id).await;
+                return;
+            }
+        };
+
+        let guild_service = if let Some(service) = services.get::<GuildService>() {
+            service
+        } else {
+            error!("couldn't get guild service!");
+            return;
+        };
+
+        if guild_service.get_member(guild_id, user_id).await.is_err() {
+            // member left, timeout will be applied again in case they rejoin
+            self.update_timeout_expire_time(mute.id, 0).await;
+            return;
+        }
+
+        let expiration_time = if mute.expires {
+            Some(mute.expire_time as u64)
+        } else {
+            None
+        };
+        let timeout_expire_time = get_timeout_expire_time(now(), expiration_time);
+
+        if self
+            .apply_timeout(
+                http,
+                guild_id,
+                user_id,
+                timeout_expire_time,
+                "Renewing timeout of an ongoing mute",
+            )
+            .await
+            .is_ok()
+        {
+            self.update_timeout_expire_time(mute.id, timeout_expire_time as i64)
+                .await;
+        }
     }
 
     pub async fn issue_mute(
@@ -135,6 +457,7 @@ impl MuteService {
         mod_user_tag_and_id: &str,
         target_user: &User,
         reason: String,
+        evidence: &[EvidenceSnapshot],
         duration: Option<Duration>,
         call_depth: i32,
     ) -> Result<(), MuteFailure> {
@@ -154,40 +477,62 @@ impl MuteService {
             None
         };
 
+        let case_number_service = if let Some(service) = services.get::<CaseNumberService>() {
+            service
+        } else {
+            error!("couldn't get case number service!");
+            return Err(MuteFailure::Unknown);
+        };
+
+        let case_number =
+            if let Some(case_number) = case_number_service.next_case_number(guild_id).await {
+                case_number
+            } else {
+                return Err(MuteFailure::Unknown);
+            };
+
+        let appeal_url = if let Some(service) = services.get::<AppealService>() {
+            service.create_appeal_url(setting, guild_id, target_user.id, case_number)
+        } else {
+            error!("couldn't get appeal service!");
+            None
+        };
+
         notify_user_for_mod_action(
             http,
+            setting,
             target_user.id,
             ModActionKind::Mute { expiration_time },
             &reason,
             now,
+            case_number,
             guild_name,
             mod_user_tag_and_id,
+            appeal_url.as_deref(),
         )
         .await;
 
-        let role_id = match self.fetch_muted_role_id(http, services, guild_id).await {
-            Ok(role) => role,
-            Err(err) => {
-                return Err(err);
-            }
-        };
-
         let audit_log_reason = format!("Muted by {} - {}", mod_user_tag_and_id, reason);
-        match http
-            .add_member_role(guild_id, target_user.id, role_id, Some(&audit_log_reason))
+        let timeout_expire_time = match self
+            .apply_mute(
+                http,
+                services,
+                setting,
+                guild_id,
+                target_user.id,
+                expiration_time,
+                &audit_log_reason,
+            )
             .await
         {
-            Ok(_) => (),
-            Err(err) => {
-                return match err.discord_error_code() {
-                    Some(50013) => Err(MuteFailure::Unauthorized),
-                    _ => {
-                        error!("failed to issue discord member role add {}", err);
-                        Err(MuteFailure::Unknown)
-                    }
-                };
+            Ok(timeout_expire_time) => timeout_expire_time,
+            Err(failure) => {
+                case_number_service
+                    .release_case_number(guild_id, case_number)
+                    .await;
+                return Err(failure);
             }
-        }
+        };
 
         let mute_entry = Mute {
             id: 0,
@@ -200,15 +545,19 @@ impl MuteService {
             expires: duration.is_some(),
             unmuted: false,
             pardoned: false,
+            case_number,
+            timeout_expire_time: timeout_expire_time as i64,
+            mod_log_message_id: 0,
+            mute_backend: setting.mute_backend,
         };
 
         self.invalidate_previous_user_mutes(guild_id, target_user.id)
             .await;
-        let mute_id = self.insert_mute(mute_entry).await.map(|mute| mute.id);
+        if let Some(mute) = self.insert_mute(mute_entry).await {
+            record_evidence(services, guild_id, case_number, evidence).await;
 
-        if let Some(mute_id) = mute_id {
             if let Some(mod_log_channel_id) = mod_log_channel_id {
-                if let Err(err) = create_mod_log_entry(
+                match create_mod_log_entry(
                     http,
                     mod_log_channel_id,
                     channel_id,
@@ -216,45 +565,35 @@ impl MuteService {
                     target_user,
                     ModLogAction::Mute { expiration_time },
                     &reason,
-                    mute_id,
+                    case_number,
                     now,
                 )
                 .await
                 {
-                    return Err(MuteFailure::ModLogError(err));
+                    Ok(message_id) => {
+                        self.update_mod_log_message_id(mute.id, message_id.get() as i64)
+                            .await;
+                    }
+                    Err(err) => return Err(MuteFailure::ModLogError(err)),
                 }
             }
         }
 
-        if setting.mute_threshold != 0 {
-            let count = self
-                .fetch_actionable_mute_count(guild_id, target_user.id)
-                .await;
-            if count >= setting.mute_threshold.into() {
-                let duration = get_action_duration_for_auto_mod_action(
-                    setting.mute_action,
-                    setting.mute_action_duration_type,
-                    setting.mute_action_duration,
-                );
-
-                execute_mod_action(
-                    setting.mute_action,
-                    http,
-                    guild_id,
-                    guild_name,
-                    setting,
-                    services,
-                    channel_id,
-                    mod_user_id,
-                    mod_user_tag_and_id,
-                    target_user,
-                    reason,
-                    duration,
-                    call_depth,
-                )
-                .await;
-            }
-        }
+        escalate_mod_action(
+            ACTION_MUTE,
+            http,
+            guild_id,
+            guild_name,
+            setting,
+            services,
+            channel_id,
+            mod_user_id,
+            mod_user_tag_and_id,
+            target_user,
+            reason,
+            call_depth,
+        )
+        .await;
 
         Ok(())
     }
@@ -266,56 +605,116 @@ impl MuteService {
         guild_id: GuildId,
         target_user_id: UserId,
         mod_user_tag_and_id: &str,
+    ) -> Result<Vec<Mute>, UnmuteFailure> {
+        let setting_service = if let Some(service) = services.get::<SettingService>() {
+            service
+        } else {
+            error!("couldn't get setting service!");
+            return Err(UnmuteFailure::Unknown);
+        };
+
+        let setting = setting_service.get_setting(guild_id).await;
+
+        let audit_log_reason = format!("Unmuted by {}", mod_user_tag_and_id);
+
+        // returned so callers can log the cases that were lifted
+        let mut lifted_mutes = self.fetch_valid_mutes(guild_id, target_user_id).await;
+
+        // lift mutes the same way they were applied, the guild might have switched backends since,
+        // members without a recorded mute might still have been muted manually
+        let mut mute_backends = lifted_mutes
+            .iter()
+            .map(Mute::mute_backend)
+            .collect::<Vec<i32>>();
+        mute_backends.sort_unstable();
+        mute_backends.dedup();
+        if mute_backends.is_empty() {
+            mute_backends.push(setting.mute_backend);
+        }
+
+        for mute_backend in mute_backends {
+            self.lift_mute(
+                http,
+                services,
+                &setting,
+                mute_backend,
+                guild_id,
+                target_user_id,
+                &audit_log_reason,
+            )
+            .await?;
+        }
+
+        self.invalidate_previous_user_mutes(guild_id, target_user_id)
+            .await;
+
+        for mute in lifted_mutes.iter_mut() {
+            mute.unmuted = true;
+        }
+
+        Ok(lifted_mutes)
+    }
+
+    // lifts the given case only with the backend it was applied with, callers check that it's
+    // still active
+    pub async fn unmute_case(
+        &self,
+        http: &Http,
+        services: &Services,
+        mute: &Mute,
+        mod_user_tag_and_id: &str,
     ) -> Result<(), UnmuteFailure> {
-        // use cached roles
-        let guild_service = if let Some(service) = services.get::<GuildService>() {
+        let setting_service = if let Some(service) = services.get::<SettingService>() {
             service
         } else {
-            error!("couldn't get guild service!");
+            error!("couldn't get setting service!");
             return Err(UnmuteFailure::Unknown);
         };
 
-        let roles = match guild_service.get_roles(guild_id).await {
-            Ok(roles) => roles,
-            Err(GetRolesFailure::FetchFailed) => return Err(UnmuteFailure::Unknown),
+        let (guild_id, user_id) = match (
+            NonZeroU64::new(mute.guild_id as u64),
+            NonZeroU64::new(mute.user_id as u64),
+        ) {
+            (Some(guild_id), Some(user_id)) => {
+                (GuildId::new(guild_id.get()), UserId::new(user_id.get()))
+            }
+            _ => {
+                warn!("found mute with invalid guild or user id! {:?}", mute);
+                self.invalidate_mute(mute.id).await;
+                return Err(UnmuteFailure::Unknown);
+            }
         };
 
-        let muted_role = roles.iter().find(|(_, role)| role.name == "Muted");
+        let setting = setting_service.get_setting(guild_id).await;
 
         let audit_log_reason = format!("Unmuted by {}", mod_user_tag_and_id);
 
-        if let Some((role_id, _)) = muted_role {
-            match http
-                .remove_member_role(guild_id, target_user_id, *role_id, Some(&audit_log_reason))
-                .await
-            {
-                Ok(_) => (),
-                Err(err) => {
-                    return match err.discord_error_code() {
-                        Some(50013) => Err(UnmuteFailure::Unauthorized),
-                        _ => {
-                            error!("failed to issue discord member role remove {}", err);
-                            Err(UnmuteFailure::Unknown)
-                        }
-                    };
-                }
-            }
+        self.lift_mute(
+            http,
+            services,
+            &setting,
+            mute.mute_backend,
+            guild_id,
+            user_id,
+            &audit_log_reason,
+        )
+        .await?;
 
-            self.invalidate_previous_user_mutes(guild_id, target_user_id)
-                .await;
-        } else {
-            return Err(UnmuteFailure::RoleNotFound);
-        }
+        self.invalidate_mute(mute.id).await;
 
         Ok(())
     }
 
-    pub async fn fetch_mute(&self, id: i32) -> Option<Mute> {
+    pub async fn fetch_guild_mute_with_case_number(
+        &self,
+        guild_id: GuildId,
+        case_number: i32,
+    ) -> Option<Mute> {
         self.repository
-            .fetch_mute(id)
+            .fetch_guild_mute_with_case_number(guild_id.get() as i64, case_number)
             .await
             .map_err(|err| {
-                error!("failed to fetch mute {:?}", err);
+                error!("failed to fetch mute with case number {:?}", err);
                 err
             })
             .ok()
@@ -334,6 +733,18 @@ impl MuteService {
             .unwrap_or_default()
     }
 
+    pub async fn fetch_guild_user_mutes(&self, guild_id: GuildId, user_id: UserId) -> Vec<Mute> {
+        self.repository
+            .fetch_guild_user_mutes(guild_id.get() as i64, user_id.get() as i64)
+            .await
+            .map_err(|err| {
+                error!("failed to fetch guild user mutes {:?}", err);
+                err
+            })
+            .ok()
+            .unwrap_or_default()
+    }
+
     pub async fn fetch_guild_mute_count(&self, guild_id: GuildId) -> i64 {
         self.repository
             .fetch_guild_mute_count(guild_id.get() as i64)
@@ -358,6 +769,18 @@ impl MuteService {
             .unwrap_or_default()
     }
 
+    pub async fn fetch_mutes_with_expiring_timeout(&self) -> Vec<Mute> {
+        self.repository
+            .fetch_mutes_with_expiring_timeout((now() + TIMEOUT_RENEWAL_WINDOW) as i64)
+            .await
+            .map_err(|err| {
+                error!("failed to fetch mutes with expiring timeout {:?}", err);
+                err
+            })
+            .ok()
+            .unwrap_or_default()
+    }
+
     pub async fn fetch_valid_mutes(&self, guild_id: GuildId, user_id: UserId) -> Vec<Mute> {
         self.repository
             .fetch_valid_mutes(guild_id.get() as i64, user_id.get() as i64)
@@ -370,9 +793,16 @@ impl MuteService {
             .unwrap_or_default()
     }
 
-    pub async fn fetch_actionable_mute_count(&self, guild_id: GuildId, user_id: UserId) -> i64 {
+    pub async fn fetch_actionable_mute_count(
+        &self,
+        setting: &Setting,
+        guild_id: GuildId,
+        user_id: UserId,
+        since: u64,
+    ) -> i64 {
+        let since = since.max(setting.decay_cutoff(ACTION_MUTE, now()));
         self.repository
-            .fetch_actionable_mute_count(guild_id.get() as i64, user_id.get() as i64)
+            .fetch_actionable_mute_count(guild_id.get() as i64, user_id.get() as i64, since as i64)
             .await
             .map_err(|err| {
                 error!("failed to fetch actionable mute count {:?}", err);
@@ -404,6 +834,17 @@ impl MuteService {
             .ok()
     }
 
+    pub async fn update_mod_log_message_id(&self, id: i32, mod_log_message_id: i64) {
+        let _ = self
+            .repository
+            .update_mod_log_message_id(id, mod_log_message_id)
+            .await
+            .map_err(|err| {
+                error!("failed to update mute mod log message id {:?}", err);
+                err
+            });
+    }
+
     pub async fn invalidate_previous_user_mutes(&self, guild_id: GuildId, user_id: UserId) {
         let _ = self
             .repository
@@ -415,6 +856,28 @@ impl MuteService {
             });
     }
 
+    pub async fn update_timeout_expire_time(&self, id: i32, timeout_expire_time: i64) {
+        let _ = self
+            .repository
+            .update_timeout_expire_time(id, timeout_expire_time)
+            .await
+            .map_err(|err| {
+                error!("failed to update mute timeout expire time {:?}", err);
+                err
+            });
+    }
+
+    pub async fn update_mute_backend(&self, id: i32, mute_backend: i32, timeout_expire_time: i64) {
+        let _ = self
+            .repository
+            .update_mute_backend(id, mute_backend, timeout_expire_time)
+            .await
+            .map_err(|err| {
+                error!("failed to update mute backend {:?}", err);
+                err
+            });
+    }
+
     pub async fn invalidate_mute(&self, id: i32) {
         let _ = self.repository.invalidate_mute(id).await.map_err(|err| {
             error!("failed to invalidate mute {:?}", err);
@@ -0,0 +1,163 @@
+use std::num::NonZeroU32;
+
+use serenity::model::id::{GuildId, UserId};
+use tracing::error;
+use typemap_rev::TypeMapKey;
+
+use crate::database::notes::{Note, NotesRepository};
+use crate::util::now;
+
+pub const NOTE_MESSAGE_SIZE_LIMIT: usize = 1000;
+
+impl TypeMapKey for NoteService {
+    type Value = NoteService;
+}
+
+pub enum InsertNoteFailure {
+    MessageEmpty,
+    MessageTooBig,
+    Unknown,
+}
+
+pub enum RemoveNoteFailure {
+    NoteDoesNotExist,
+    Unknown,
+}
+
+// notes are only context for moderators, they are never sent to the user and escalation
+// steps don't look at them, unlike warns
+pub struct NoteService {
+    pub repository: NotesRepository,
+}
+
+impl NoteService {
+    pub async fn insert_note(
+        &self,
+        guild_id: GuildId,
+        user_id: UserId,
+        mod_user_id: UserId,
+        message: &str,
+    ) -> Result<Note, InsertNoteFailure> {
+        let message = message.trim();
+        if message.is_empty() {
+            return Err(InsertNoteFailure::MessageEmpty);
+        }
+
+        if message.chars().count() > NOTE_MESSAGE_SIZE_LIMIT {
+            return Err(InsertNoteFailure::MessageTooBig);
+        }
+
+        let note = Note {
+            id: 0,
+            guild_id: guild_id.get() as i64,
+            user_id: user_id.get() as i64,
+            moderator_user_id: mod_user_id.get() as i64,
+            note_time: now() as i64,
+            message: message.to_string(),
+        };
+
+        match self.repository.insert_note(note).await {
+            Ok(note) => Ok(note),
+            Err(err) => {
+                error!("failed to insert note {:?}", err);
+                Err(InsertNoteFailure::Unknown)
+            }
+        }
+    }
+
+    pub async fn remove_note(&self, guild_id: GuildId, id: i32) -> Result<(), RemoveNoteFailure> {
+        let note = match self
+            .repository
+            .fetch_guild_note(guild_id.get() as i64, id)
+            .await
+        {
+            Ok(Some(note)) => note,
+            Ok(None) => return Err(RemoveNoteFailure::NoteDoesNotExist),
+            Err(err) => {
+                error!("failed to fetch guild note {:?}", err);
+                return Err(RemoveNoteFailure::Unknown);
+            }
+        };
+
+        match self.repository.delete_note(note.id).await {
+            Ok(_) => Ok(()),
+            Err(err) => {
+                error!("failed to delete note {:?}", err);
+                Err(RemoveNoteFailure::Unknown)
+            }
+        }
+    }
+
+    pub async fn fetch_guild_note(&self, guild_id: GuildId, id: i32) -> Option<Note> {
+        self.repository
+            .fetch_guild_note(guild_id.get() as i64, id)
+            .await
+            .map_err(|err| {
+                error!("failed to fetch guild note {:?}", err);
+                err
+            })
+            .ok()
+            .flatten()
+    }
+
+    pub async fn fetch_guild_user_notes(&self, guild_id: GuildId, user_id: UserId) -> Vec<Note> {
+        self.repository
+            .fetch_guild_user_notes(guild_id.get() as i64, user_id.get() as i64)
+            .await
+            .map_err(|err| {
+                error!("failed to fetch guild user notes {:?}", err);
+                err
+            })
+            .ok()
+            .unwrap_or_default()
+    }
+
+    pub async fn fetch_guild_notes(
+        &self,
+        guild_id: GuildId,
+        user_id: Option<UserId>,
+        page: NonZeroU32,
+    ) -> Vec<Note> {
+        let result = if let Some(user_id) = user_id {
+            self.repository
+                .fetch_guild_user_notes_paginated(
+                    guild_id.get() as i64,
+                    user_id.get() as i64,
+                    page.get(),
+                )
+                .await
+        } else {
+            self.repository
+                .fetch_guild_notes(guild_id.get() as i64, page.get())
+                .await
+        };
+
+        result
+            .map_err(|err| {
+                error!("failed to fetch guild notes {:?}", err);
+                err
+            })
+            .ok()
+            .unwrap_or_default()
+    }
+
+    pub async fn fetch_guild_note_count(&self, guild_id: GuildId, user_id: Option<UserId>) -> i64 {
+        let result = if let Some(user_id) = user_id {
+            self.repository
+                .fetch_guild_user_note_count(guild_id.get() as i64, user_id.get() as i64)
+                .await
+        } else {
+            self.repository
+                .fetch_guild_note_count(guild_id.get() as i64)
+                .await
+        };
+
+        result
+            .map_err(|err| {
+                error!("failed to fetch guild note count {:?}", err);
+                err
+            })
+            .ok()
+            .unwrap_or(0)
+    }
+}
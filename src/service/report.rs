@@ -0,0 +1,186 @@
+use std::num::NonZeroU32;
+
+use serenity::model::channel::Message;
+use serenity::model::id::{GuildId, UserId};
+use tracing::error;
+use typemap_rev::TypeMapKey;
+
+use crate::database::reports::{Report, ReportsRepository, REPORT_STATUS_OPEN};
+use crate::util::now;
+
+impl TypeMapKey for ReportService {
+    type Value = ReportService;
+}
+
+pub struct ReportService {
+    pub repository: ReportsRepository,
+}
+
+pub enum SubmitReportFailure {
+    AlreadyReported,
+    Unknown,
+}
+
+pub enum UpdateReportFailure {
+    AlreadyHandled,
+    Unknown,
+}
+
+impl ReportService {
+    // reports of a message that is still in the queue are merged into the existing report, the
+    // returned bool is true if a new report was created
+    pub async fn submit_report(
+        &self,
+        guild_id: GuildId,
+        message: &Message,
+        reporter_user_id: UserId,
+    ) -> Result<(Report, bool), SubmitReportFailure> {
+        if let Some(report) = self.fetch_open_message_report(guild_id, message).await? {
+            return self.add_reporter(&report, reporter_user_id).await;
+        }
+
+        let now = now() as i64;
+        let report = Report {
+            id: 0,
+            guild_id: guild_id.get() as i64,
+            channel_id: message.channel_id.get() as i64,
+            message_id: message.id.get() as i64,
+            target_user_id: message.author.id.get() as i64,
+            reporter_user_ids: vec![reporter_user_id.get() as i64],
+            content: message.content.to_string(),
+            attachments: message
+                .attachments
+                .iter()
+                .map(|attachment| attachment.url.to_string())
+                .collect(),
+            message_time: message.timestamp.unix_timestamp(),
+            report_time: now,
+            status: REPORT_STATUS_OPEN,
+            moderator_user_id: 0,
+            update_time: now,
+            action_taken: false,
+            report_message_id: 0,
+        };
+
+        match self.repository.insert_report(report).await {
+            Ok(Some(report)) => Ok((report, true)),
+            // someone else reported the message at the same time, merge into their report
+            Ok(None) => match self.fetch_open_message_report(guild_id, message).await? {
+                Some(report) => self.add_reporter(&report, reporter_user_id).await,
+                None => Err(SubmitReportFailure::Unknown),
+            },
+            Err(err) => {
+                error!("failed to insert report {:?}", err);
+                Err(SubmitReportFailure::Unknown)
+            }
+        }
+    }
+
+    async fn fetch_open_message_report(
+        &self,
+        guild_id: GuildId,
+        message: &Message,
+    ) -> Result<Option<Report>, SubmitReportFailure> {
+        self.repository
+            .fetch_open_guild_message_report(guild_id.get() as i64, message.id.get() as i64)
+            .await
+            .map_err(|err| {
+                error!("failed to fetch open guild message report {:?}", err);
+                SubmitReportFailure::Unknown
+            })
+    }
+
+    async fn add_reporter(
+        &self,
+        report: &Report,
+        reporter_user_id: UserId,
+    ) -> Result<(Report, bool), SubmitReportFailure> {
+        match self
+            .repository
+            .add_reporter(report.id, reporter_user_id.get() as i64)
+            .await
+        {
+            Ok(Some(report)) => Ok((report, false)),
+            Ok(None) => Err(SubmitReportFailure::AlreadyReported),
+            Err(err) => {
+                error!("failed to add reporter to report {:?}", err);
+                Err(SubmitReportFailure::Unknown)
+            }
+        }
+    }
+
+    pub async fn fetch_report(&self, id: i32) -> Option<Report> {
+        self.repository
+            .fetch_report(id)
+            .await
+            .map_err(|err| {
+                error!("failed to fetch report {:?}", err);
+                err
+            })
+            .ok()
+            .flatten()
+    }
+
+    pub async fn fetch_guild_reports(
+        &self,
+        guild_id: GuildId,
+        status: Option<i32>,
+        target_user_id: Option<UserId>,
+        page: NonZeroU32,
+    ) -> Vec<Report> {
+        self.repository
+            .fetch_guild_reports(
+                guild_id.get() as i64,
+                status,
+                target_user_id.map(|id| id.get() as i64),
+                page.get(),
+            )
+            .await
+            .map_err(|err| {
+                error!("failed to fetch guild reports {:?}", err);
+                err
+            })
+            .ok()
+            .unwrap_or_default()
+    }
+
+    pub async fn fetch_guild_report_count(
+        &self,
+        guild_id: GuildId,
+        status: Option<i32>,
+        target_user_id: Option<UserId>,
+    ) -> i64 {
+        self.repository
+            .fetch_guild_report_count(
+                guild_id.get() as i64,
+                status,
+                target_user_id.map(|id| id.get() as i64),
+            )
+            .await
+            .map_err(|err| {
+                error!("failed to fetch guild report count {:?}", err);
+                err
+            })
+            .ok()
+            .unwrap_or(0)
+    }
+
+    pub async fn update_report(&self, report: &Report) -> Result<(), UpdateReportFailure> {
+        match self.repository.update_report(report).await {
+            Ok(Some(_)) => Ok(()),
+            // another moderator handled it in the meantime
+            Ok(None) => Err(UpdateReportFailure::AlreadyHandled),
+            Err(err) => {
+                error!("failed to update report {:?}", err);
+                Err(UpdateReportFailure::Unknown)
+            }
+        }
+    }
+
+    pub async fn reopen_report(&self, report: &Report) {
+        let _ = self.repository.reopen_report(report).await.map_err(|err| {
+            error!("failed to reopen report {:?}", err);
+            err
+        });
+    }
+}
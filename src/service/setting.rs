@@ -2,10 +2,11 @@ use std::sync::Arc;
 use std::time::Duration;
 
 use moka::future::{Cache, CacheBuilder};
-use serenity::model::id::GuildId;
+use serenity::model::id::{GuildId, RoleId};
 use tracing::error;
 use typemap_rev::TypeMapKey;
 
+use crate::database::escalation_steps::EscalationStep;
 use crate::database::settings::{Setting, SettingsRepository};
 
 impl TypeMapKey for SettingService {
@@ -76,14 +77,35 @@ impl SettingService {
             .map(Arc::new)
     }
 
-    pub async fn update_setting(&self, guild_id: GuildId, new_setting: Setting) {
-        let _ = self
+    pub async fn update_setting(
+        &self,
+        guild_id: GuildId,
+        new_setting: Setting,
+        escalation_steps: Option<Vec<EscalationStep>>,
+    ) -> Option<()> {
+        let result = self
             .repository
-            .update_setting(new_setting)
+            .update_setting(new_setting, escalation_steps)
             .await
             .map_err(|err| {
                 error!("failed to update setting {:?}", err);
                 err
+            })
+            .ok();
+
+        self.setting_cache.invalidate(&guild_id).await;
+
+        result
+    }
+
+    pub async fn update_mute_role_id(&self, guild_id: GuildId, role_id: RoleId) {
+        let _ = self
+            .repository
+            .update_mute_role_id(guild_id.get() as i64, role_id.get() as i64)
+            .await
+            .map_err(|err| {
+                error!("failed to update mute role id {:?}", err);
+                err
             });
 
         self.setting_cache.invalidate(&guild_id).await;
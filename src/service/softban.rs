@@ -6,11 +6,13 @@ use std::num::{NonZeroU32, NonZeroU64};
 use tracing::{error, warn};
 use typemap_rev::TypeMapKey;
 
-use crate::database::settings::{get_action_duration_for_auto_mod_action, Setting};
+use crate::database::settings::{Setting, ACTION_SOFTBAN};
 use crate::database::softbans::{Softban, SoftbansRepository};
 use crate::discord::util::mod_log::{create_mod_log_entry, CreateModLogEntryError, ModLogAction};
 use crate::discord::util::user_dm::{notify_user_for_mod_action, ModActionKind};
-use crate::discord::util::{execute_mod_action, SerenityErrorExt};
+use crate::discord::util::{escalate_mod_action, SerenityErrorExt};
+use crate::service::case_number::CaseNumberService;
+use crate::service::evidence::{record_evidence, EvidenceSnapshot};
 use crate::service::Services;
 use crate::util::now;
 
@@ -28,6 +30,14 @@ pub enum SoftbanFailure {
     Unknown,
 }
 
+pub fn validate_softban_update(softban: &Softban, pardoned: bool) -> Result<(), &'static str> {
+    if softban.pardoned && !pardoned {
+        return Err("You can't un-pardon a softban!");
+    }
+
+    Ok(())
+}
+
 impl SoftbanService {
     pub async fn issue_softban(
         &self,
@@ -41,6 +51,7 @@ impl SoftbanService {
         mod_user_tag_and_id: &str,
         target_user: &User,
         reason: String,
+        evidence: &[EvidenceSnapshot],
         days: u32,
         call_depth: i32,
     ) -> Result<(), SoftbanFailure> {
@@ -59,14 +70,31 @@ impl SoftbanService {
             None
         };
 
+        let case_number_service = if let Some(service) = services.get::<CaseNumberService>() {
+            service
+        } else {
+            error!("couldn't get case number service!");
+            return Err(SoftbanFailure::Unknown);
+        };
+
+        let case_number =
+            if let Some(case_number) = case_number_service.next_case_number(guild_id).await {
+                case_number
+            } else {
+                return Err(SoftbanFailure::Unknown);
+            };
+
         notify_user_for_mod_action(
             http,
+            setting,
             target_user.id,
             ModActionKind::Softban,
             &reason,
             now,
+            case_number,
             guild_name,
             mod_user_tag_and_id,
+            None,
         )
         .await;
 
@@ -82,6 +110,9 @@ impl SoftbanService {
         {
             Ok(_) => (),
             Err(err) => {
+                case_number_service
+                    .release_case_number(guild_id, case_number)
+                    .await;
                 return match err.discord_error_code() {
                     Some(50013) => Err(SoftbanFailure::Unauthorized),
                     _ => {
@@ -116,16 +147,15 @@ impl SoftbanService {
             softban_time: now as i64,
             reason: reason.clone(),
             pardoned: false,
+            case_number,
+            mod_log_message_id: 0,
         };
 
-        let softban_id = self
-            .insert_softban(softban_entry)
-            .await
-            .map(|softban| softban.id);
+        if let Some(softban) = self.insert_softban(softban_entry).await {
+            record_evidence(services, guild_id, case_number, evidence).await;
 
-        if let Some(softban_id) = softban_id {
             if let Some(mod_log_channel_id) = mod_log_channel_id {
-                if let Err(err) = create_mod_log_entry(
+                match create_mod_log_entry(
                     http,
                     mod_log_channel_id,
                     channel_id,
@@ -133,55 +163,49 @@ impl SoftbanService {
                     target_user,
                     ModLogAction::Softban,
                     &reason,
-                    softban_id,
+                    case_number,
                     now,
                 )
                 .await
                 {
-                    return Err(SoftbanFailure::ModLogError(err));
+                    Ok(message_id) => {
+                        self.update_mod_log_message_id(softban.id, message_id.get() as i64)
+                            .await;
+                    }
+                    Err(err) => return Err(SoftbanFailure::ModLogError(err)),
                 }
             }
         }
 
-        if setting.softban_threshold != 0 {
-            let count = self
-                .fetch_actionable_softban_count(guild_id, target_user.id)
-                .await;
-            if count >= setting.softban_threshold.into() {
-                let duration = get_action_duration_for_auto_mod_action(
-                    setting.softban_action,
-                    setting.softban_action_duration_type,
-                    setting.softban_action_duration,
-                );
-
-                execute_mod_action(
-                    setting.softban_action,
-                    http,
-                    guild_id,
-                    guild_name,
-                    setting,
-                    services,
-                    channel_id,
-                    mod_user_id,
-                    mod_user_tag_and_id,
-                    target_user,
-                    reason,
-                    duration,
-                    call_depth,
-                )
-                .await;
-            }
-        }
+        escalate_mod_action(
+            ACTION_SOFTBAN,
+            http,
+            guild_id,
+            guild_name,
+            setting,
+            services,
+            channel_id,
+            mod_user_id,
+            mod_user_tag_and_id,
+            target_user,
+            reason,
+            call_depth,
+        )
+        .await;
 
         Ok(())
     }
 
-    pub async fn fetch_softban(&self, id: i32) -> Option<Softban> {
+    pub async fn fetch_guild_softban_with_case_number(
+        &self,
+        guild_id: GuildId,
+        case_number: i32,
+    ) -> Option<Softban> {
         self.repository
-            .fetch_softban(id)
+            .fetch_guild_softban_with_case_number(guild_id.get() as i64, case_number)
             .await
             .map_err(|err| {
-                error!("failed to fetch softban {:?}", err);
+                error!("failed to fetch softban with case number {:?}", err);
                 err
             })
             .ok()
@@ -200,6 +224,22 @@ impl SoftbanService {
             .unwrap_or_default()
     }
 
+    pub async fn fetch_guild_user_softbans(
+        &self,
+        guild_id: GuildId,
+        user_id: UserId,
+    ) -> Vec<Softban> {
+        self.repository
+            .fetch_guild_user_softbans(guild_id.get() as i64, user_id.get() as i64)
+            .await
+            .map_err(|err| {
+                error!("failed to fetch guild user softbans {:?}", err);
+                err
+            })
+            .ok()
+            .unwrap_or_default()
+    }
+
     pub async fn fetch_guild_softban_count(&self, guild_id: GuildId) -> i64 {
         self.repository
             .fetch_guild_softban_count(guild_id.get() as i64)
@@ -212,9 +252,20 @@ impl SoftbanService {
             .unwrap_or(0)
     }
 
-    pub async fn fetch_actionable_softban_count(&self, guild_id: GuildId, user_id: UserId) -> i64 {
+    pub async fn fetch_actionable_softban_count(
+        &self,
+        setting: &Setting,
+        guild_id: GuildId,
+        user_id: UserId,
+        since: u64,
+    ) -> i64 {
+        let since = since.max(setting.decay_cutoff(ACTION_SOFTBAN, now()));
         self.repository
-            .fetch_actionable_softban_count(guild_id.get() as i64, user_id.get() as i64)
+            .fetch_actionable_softban_count(
+                guild_id.get() as i64,
+                user_id.get() as i64,
+                since as i64,
+            )
             .await
             .map_err(|err| {
                 error!("failed to fetch actionable softban count {:?}", err);
@@ -245,4 +296,15 @@ impl SoftbanService {
             })
             .ok()
     }
+
+    pub async fn update_mod_log_message_id(&self, id: i32, mod_log_message_id: i64) {
+        let _ = self
+            .repository
+            .update_mod_log_message_id(id, mod_log_message_id)
+            .await
+            .map_err(|err| {
+                error!("failed to update softban mod log message id {:?}", err);
+                err
+            });
+    }
 }
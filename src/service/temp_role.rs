@@ -0,0 +1,269 @@
+use serenity::all::Error;
+use serenity::http::Http;
+use serenity::model::id::{GuildId, RoleId, UserId};
+use std::num::NonZeroU64;
+use std::time::Duration;
+use tracing::{error, warn};
+use typemap_rev::TypeMapKey;
+
+use crate::database::temp_roles::{TempRole, TempRolesRepository};
+use crate::discord::util::SerenityErrorExt;
+use crate::util::now;
+
+impl TypeMapKey for TempRoleService {
+    type Value = TempRoleService;
+}
+
+pub struct TempRoleService {
+    pub repository: TempRolesRepository,
+}
+
+pub enum GrantTempRoleFailure {
+    AlreadyHasRole,
+    RoleNotFound,
+    Unauthorized,
+    Unknown,
+}
+
+pub enum CancelTempRoleFailure {
+    Unauthorized,
+    Unknown,
+}
+
+impl TempRoleService {
+    pub async fn grant_temp_role(
+        &self,
+        http: &Http,
+        guild_id: GuildId,
+        user_id: UserId,
+        role_id: RoleId,
+        mod_user_id: UserId,
+        member_has_role: bool,
+        duration: Duration,
+    ) -> Result<TempRole, GrantTempRoleFailure> {
+        let now = now();
+
+        // the role only counts as temporary if we granted it, otherwise expiring the grant would
+        // take away a role the member had all along
+        if member_has_role
+            && !self
+                .fetch_active_guild_temp_roles(guild_id, Some(user_id))
+                .await
+                .iter()
+                .any(|temp_role| temp_role.role_id == role_id.get() as i64)
+        {
+            return Err(GrantTempRoleFailure::AlreadyHasRole);
+        }
+
+        if let Err(err) = http
+            .add_member_role(guild_id, user_id, role_id, Some("Granting temporary role"))
+            .await
+        {
+            return match err.discord_error_code() {
+                Some(10011) => Err(GrantTempRoleFailure::RoleNotFound),
+                Some(50013) => Err(GrantTempRoleFailure::Unauthorized),
+                _ => {
+                    error!("failed to issue discord member role add {}", err);
+                    Err(GrantTempRoleFailure::Unknown)
+                }
+            };
+        }
+
+        // granting the same role again only extends or shortens the existing grant
+        let _ = self
+            .repository
+            .invalidate_previous_user_temp_roles(
+                guild_id.get() as i64,
+                user_id.get() as i64,
+                role_id.get() as i64,
+            )
+            .await
+            .map_err(|err| {
+                error!("failed to invalidate previous temp roles {:?}", err);
+                err
+            });
+
+        let temp_role = TempRole {
+            id: 0,
+            guild_id: guild_id.get() as i64,
+            user_id: user_id.get() as i64,
+            role_id: role_id.get() as i64,
+            moderator_user_id: mod_user_id.get() as i64,
+            grant_time: now as i64,
+            expire_time: (now + duration.as_secs()) as i64,
+            removed: false,
+        };
+
+        match self.repository.insert_temp_role(temp_role).await {
+            Ok(temp_role) => Ok(temp_role),
+            Err(err) => {
+                error!("failed to insert temp role {:?}", err);
+                Err(GrantTempRoleFailure::Unknown)
+            }
+        }
+    }
+
+    pub async fn cancel_temp_role(
+        &self,
+        http: &Http,
+        temp_role: &TempRole,
+    ) -> Result<(), CancelTempRoleFailure> {
+        match self
+            .remove_temp_role(http, temp_role, "Temporary role grant cancelled")
+            .await
+        {
+            Ok(_) => {
+                self.invalidate_temp_role(temp_role.id).await;
+                Ok(())
+            }
+            Err(err) => match err.discord_error_code() {
+                Some(50013) => Err(CancelTempRoleFailure::Unauthorized),
+                _ => {
+                    error!("failed to issue discord member role remove {}", err);
+                    Err(CancelTempRoleFailure::Unknown)
+                }
+            },
+        }
+    }
+
+    // removes the role of an expired grant, grants with broken ids are only invalidated
+    pub async fn expire_temp_role(&self, http: &Http, temp_role: &TempRole) {
+        let _ = self
+            .remove_temp_role(
+                http,
+                temp_role,
+                "Removing temporary role because duration expired",
+            )
+            .await
+            .map_err(|err| {
+                error!("failed to issue discord member role remove {}", err);
+                err
+            });
+
+        self.invalidate_temp_role(temp_role.id).await;
+    }
+
+    // members leaving and rejoining shouldn't be a way to get rid of restrictive roles early
+    pub async fn reapply_temp_roles(&self, http: &Http, guild_id: GuildId, user_id: UserId) {
+        let temp_roles = self
+            .fetch_active_guild_temp_roles(guild_id, Some(user_id))
+            .await;
+
+        for temp_role in temp_roles {
+            let role_id = if let Some(id) = NonZeroU64::new(temp_role.role_id as u64) {
+                RoleId::new(id.get())
+            } else {
+                warn!("found temp role with invalid role id! {:?}", temp_role);
+                self.invalidate_temp_role(temp_role.id).await;
+                continue;
+            };
+
+            let _ = http
+                .add_member_role(
+                    guild_id,
+                    user_id,
+                    role_id,
+                    Some("Reapplying temporary role"),
+                )
+                .await
+                .map_err(|err| {
+                    error!("failed to issue discord member role add {}", err);
+                    err
+                });
+        }
+    }
+
+    pub async fn fetch_guild_temp_role(&self, guild_id: GuildId, id: i32) -> Option<TempRole> {
+        self.repository
+            .fetch_guild_temp_role(guild_id.get() as i64, id)
+            .await
+            .map_err(|err| {
+                error!("failed to fetch guild temp role {:?}", err);
+                err
+            })
+            .ok()
+            .flatten()
+    }
+
+    pub async fn fetch_active_guild_temp_roles(
+        &self,
+        guild_id: GuildId,
+        user_id: Option<UserId>,
+    ) -> Vec<TempRole> {
+        let result = if let Some(user_id) = user_id {
+            self.repository
+                .fetch_active_guild_user_temp_roles(guild_id.get() as i64, user_id.get() as i64)
+                .await
+        } else {
+            self.repository
+                .fetch_active_guild_temp_roles(guild_id.get() as i64)
+                .await
+        };
+
+        result
+            .map_err(|err| {
+                error!("failed to fetch active guild temp roles {:?}", err);
+                err
+            })
+            .ok()
+            .unwrap_or_default()
+    }
+
+    pub async fn fetch_expired_temp_roles(&self) -> Vec<TempRole> {
+        self.repository
+            .fetch_expired_temp_roles()
+            .await
+            .map_err(|err| {
+                error!("failed to fetch expired temp roles {:?}", err);
+                err
+            })
+            .ok()
+            .unwrap_or_default()
+    }
+
+    pub async fn invalidate_temp_role(&self, id: i32) {
+        let _ = self
+            .repository
+            .invalidate_temp_role(id)
+            .await
+            .map_err(|err| {
+                error!("failed to invalidate temp role {:?}", err);
+                err
+            });
+    }
+
+    async fn remove_temp_role(
+        &self,
+        http: &Http,
+        temp_role: &TempRole,
+        reason: &str,
+    ) -> Result<(), Error> {
+        let ids = (
+            NonZeroU64::new(temp_role.guild_id as u64),
+            NonZeroU64::new(temp_role.user_id as u64),
+            NonZeroU64::new(temp_role.role_id as u64),
+        );
+
+        let (guild_id, user_id, role_id) =
+            if let (Some(guild_id), Some(user_id), Some(role_id)) = ids {
+                (
+                    GuildId::new(guild_id.get()),
+                    UserId::new(user_id.get()),
+                    RoleId::new(role_id.get()),
+                )
+            } else {
+                warn!("found temp role with invalid ids! {:?}", temp_role);
+                return Ok(());
+            };
+
+        match http
+            .remove_member_role(guild_id, user_id, role_id, Some(reason))
+            .await
+        {
+            Ok(_) => Ok(()),
+            // member left or the role was deleted, either way they no longer have it
+            Err(err) if matches!(err.discord_error_code(), Some(10007) | Some(10011)) => Ok(()),
+            Err(err) => Err(err),
+        }
+    }
+}
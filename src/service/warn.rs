@@ -6,11 +6,13 @@ use std::num::{NonZeroU32, NonZeroU64};
 use tracing::{error, warn};
 use typemap_rev::TypeMapKey;
 
-use crate::database::settings::{get_action_duration_for_auto_mod_action, Setting};
+use crate::database::settings::{Setting, ACTION_WARN};
 use crate::database::warns::{Warn, WarnsRepository};
-use crate::discord::util::execute_mod_action;
+use crate::discord::util::escalate_mod_action;
 use crate::discord::util::mod_log::{create_mod_log_entry, CreateModLogEntryError, ModLogAction};
 use crate::discord::util::user_dm::{notify_user_for_mod_action, ModActionKind};
+use crate::service::case_number::CaseNumberService;
+use crate::service::evidence::{record_evidence, EvidenceSnapshot};
 use crate::service::Services;
 use crate::util::now;
 
@@ -24,6 +26,15 @@ pub struct WarnService {
 
 pub enum WarnFailure {
     ModLogError(CreateModLogEntryError),
+    Unknown,
+}
+
+pub fn validate_warn_update(warn: &Warn, pardoned: bool) -> Result<(), &'static str> {
+    if warn.pardoned && !pardoned {
+        return Err("You can't un-pardon a warn!");
+    }
+
+    Ok(())
 }
 
 impl WarnService {
@@ -39,6 +50,7 @@ impl WarnService {
         mod_user_tag_and_id: &str,
         target_user: &User,
         reason: String,
+        evidence: &[EvidenceSnapshot],
         call_depth: i32,
     ) -> Result<(), WarnFailure> {
         let now = now();
@@ -56,14 +68,31 @@ impl WarnService {
             None
         };
 
+        let case_number_service = if let Some(service) = services.get::<CaseNumberService>() {
+            service
+        } else {
+            error!("couldn't get case number service!");
+            return Err(WarnFailure::Unknown);
+        };
+
+        let case_number =
+            if let Some(case_number) = case_number_service.next_case_number(guild_id).await {
+                case_number
+            } else {
+                return Err(WarnFailure::Unknown);
+            };
+
         notify_user_for_mod_action(
             http,
+            setting,
             target_user.id,
             ModActionKind::Warn,
             &reason,
             now,
+            case_number,
             guild_name,
             mod_user_tag_and_id,
+            None,
         )
         .await;
 
@@ -75,13 +104,15 @@ impl WarnService {
             warn_time: now as i64,
             reason: reason.clone(),
             pardoned: false,
+            case_number,
+            mod_log_message_id: 0,
         };
 
-        let warn_id = self.insert_warn(warn_entry).await.map(|warn| warn.id);
+        if let Some(warn) = self.insert_warn(warn_entry).await {
+            record_evidence(services, guild_id, case_number, evidence).await;
 
-        if let Some(warn_id) = warn_id {
             if let Some(mod_log_channel_id) = mod_log_channel_id {
-                if let Err(err) = create_mod_log_entry(
+                match create_mod_log_entry(
                     http,
                     mod_log_channel_id,
                     channel_id,
@@ -89,55 +120,49 @@ impl WarnService {
                     target_user,
                     ModLogAction::Warn,
                     &reason,
-                    warn_id,
+                    case_number,
                     now,
                 )
                 .await
                 {
-                    return Err(WarnFailure::ModLogError(err));
+                    Ok(message_id) => {
+                        self.update_mod_log_message_id(warn.id, message_id.get() as i64)
+                            .await;
+                    }
+                    Err(err) => return Err(WarnFailure::ModLogError(err)),
                 }
             }
         }
 
-        if setting.warn_threshold != 0 {
-            let count = self
-                .fetch_actionable_warn_count(guild_id, target_user.id)
-                .await;
-            if count >= setting.warn_threshold.into() {
-                let duration = get_action_duration_for_auto_mod_action(
-                    setting.warn_action,
-                    setting.warn_action_duration_type,
-                    setting.warn_action_duration,
-                );
-
-                execute_mod_action(
-                    setting.warn_action,
-                    http,
-                    guild_id,
-                    guild_name,
-                    setting,
-                    services,
-                    channel_id,
-                    mod_user_id,
-                    mod_user_tag_and_id,
-                    target_user,
-                    reason,
-                    duration,
-                    call_depth,
-                )
-                .await;
-            }
-        }
+        escalate_mod_action(
+            ACTION_WARN,
+            http,
+            guild_id,
+            guild_name,
+            setting,
+            services,
+            channel_id,
+            mod_user_id,
+            mod_user_tag_and_id,
+            target_user,
+            reason,
+            call_depth,
+        )
+        .await;
 
         Ok(())
     }
 
-    pub async fn fetch_warn(&self, id: i32) -> Option<Warn> {
+    pub async fn fetch_guild_warn_with_case_number(
+        &self,
+        guild_id: GuildId,
+        case_number: i32,
+    ) -> Option<Warn> {
         self.repository
-            .fetch_warn(id)
+            .fetch_guild_warn_with_case_number(guild_id.get() as i64, case_number)
             .await
             .map_err(|err| {
-                error!("failed to fetch warn {:?}", err);
+                error!("failed to fetch warn with case number {:?}", err);
                 err
             })
             .ok()
@@ -156,6 +181,18 @@ impl WarnService {
             .unwrap_or_default()
     }
 
+    pub async fn fetch_guild_user_warns(&self, guild_id: GuildId, user_id: UserId) -> Vec<Warn> {
+        self.repository
+            .fetch_guild_user_warns(guild_id.get() as i64, user_id.get() as i64)
+            .await
+            .map_err(|err| {
+                error!("failed to fetch guild user warns {:?}", err);
+                err
+            })
+            .ok()
+            .unwrap_or_default()
+    }
+
     pub async fn fetch_guild_warn_count(&self, guild_id: GuildId) -> i64 {
         self.repository
             .fetch_guild_warn_count(guild_id.get() as i64)
@@ -168,9 +205,16 @@ impl WarnService {
             .unwrap_or(0)
     }
 
-    pub async fn fetch_actionable_warn_count(&self, guild_id: GuildId, user_id: UserId) -> i64 {
+    pub async fn fetch_actionable_warn_count(
+        &self,
+        setting: &Setting,
+        guild_id: GuildId,
+        user_id: UserId,
+        since: u64,
+    ) -> i64 {
+        let since = since.max(setting.decay_cutoff(ACTION_WARN, now()));
         self.repository
-            .fetch_actionable_warn_count(guild_id.get() as i64, user_id.get() as i64)
+            .fetch_actionable_warn_count(guild_id.get() as i64, user_id.get() as i64, since as i64)
             .await
             .map_err(|err| {
                 error!("failed to fetch actionable warn count {:?}", err);
@@ -201,4 +245,15 @@ impl WarnService {
             })
             .ok()
     }
+
+    pub async fn update_mod_log_message_id(&self, id: i32, mod_log_message_id: i64) {
+        let _ = self
+            .repository
+            .update_mod_log_message_id(id, mod_log_message_id)
+            .await
+            .map_err(|err| {
+                error!("failed to update warn mod log message id {:?}", err);
+                err
+            });
+    }
 }
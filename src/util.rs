@@ -29,3 +29,12 @@ pub fn now() -> u64 {
         .expect("failed to get current time somehow")
         .as_secs()
 }
+
+pub fn normalize_reason(reason: &str) -> String {
+    let reason = reason.trim();
+    if reason.is_empty() {
+        "No reason specified".to_string()
+    } else {
+        reason.to_string()
+    }
+}